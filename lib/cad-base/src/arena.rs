@@ -45,12 +45,14 @@ macro_rules! index_impl {
 #[derive(Debug, Clone)]
 pub struct Gen {
     /// mapped internal id of current tags.
+    #[allow(dead_code)]
     current_generation: HashMap<Tag, u64>,
 
     /// current index of generation. This will be reset when reset call.
     current: u64,
 
     /// The generation of arena
+    #[allow(dead_code)]
     generation: u64,
 }
 
@@ -74,6 +76,7 @@ impl Gen {
     /// Re-generate an index for the given tag. This will increment the current index and return a new index.
     ///
     /// This keeps same index while in the same generation by a tag
+    #[allow(dead_code)]
     pub fn regen<I: Index>(&mut self, tag: Tag) -> I {
         if let Some(v) = self.current_generation.get(&tag) {
             return I::from(*v);
@@ -86,6 +89,7 @@ impl Gen {
     }
 
     /// Reset generation.
+    #[allow(dead_code)]
     pub fn next_generation(&mut self) {
         self.current_generation.clear();
         self.current = 0;
@@ -151,7 +151,7 @@ mod tests {
 
     fn make_operation() -> Operation {
         let eq: Equation = 10.0.into();
-        Pad::new(&eq).into()
+        Pad::new(&eq).unwrap().into()
     }
 
     fn make_sketch_id() -> SketchId {
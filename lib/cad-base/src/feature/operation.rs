@@ -1,5 +1,6 @@
+use color_eyre::eyre::{Result, eyre};
 use immutable::Im;
-use solver::equation::Equation;
+use solver::{Dimension, environment::Environment, equation::Equation};
 
 /// Operation definition. Each operations have some special parameters for its own.
 #[derive(Debug, Clone, PartialEq)]
@@ -24,20 +25,22 @@ pub struct Pad {
     /// Pad direction. Normal is
     pub direction: Im<PadDirection>,
 
-    /// The equation to compute size of pad.
+    /// The equation to compute size of pad. The size is a length in millimeter.
     pub size: Im<Equation>,
 
     _immutable: (),
 }
 
 impl Pad {
-    /// Get new operation
-    pub fn new(equation: &Equation) -> Self {
-        Pad {
+    /// Get new operation. Returns an error if the size is not a length.
+    pub fn new(equation: &Equation) -> Result<Self> {
+        Self::validate_size(equation)?;
+
+        Ok(Pad {
             direction: (PadDirection::default()).into(),
             size: equation.clone().into(),
             _immutable: (),
-        }
+        })
     }
 
     /// Update direction with [direction]
@@ -45,9 +48,29 @@ impl Pad {
         self.direction = direction.clone().into();
     }
 
-    /// Update size of padding
-    pub fn change_size(&mut self, equation: &Equation) {
-        self.size = equation.clone().into()
+    /// Update size of padding. Returns an error if the size is not a length.
+    pub fn change_size(&mut self, equation: &Equation) -> Result<()> {
+        Self::validate_size(equation)?;
+
+        self.size = equation.clone().into();
+        Ok(())
+    }
+
+    /// Size must be a length, or a number without unit that is treated as millimeter.
+    ///
+    /// The size is evaluated without variables, so sizes with variables, such as `x * 2`, are
+    /// rejected as well as sizes of other dimension.
+    fn validate_size(equation: &Equation) -> Result<()> {
+        let (_, dimension) = equation
+            .evaluate_with_dimension(&Environment::empty())
+            .map_err(|e| eyre!("Can not evaluate size of pad: {:?}", e))?;
+
+        match dimension {
+            Some(d) if d != Dimension::LENGTH && d != Dimension::NONE => {
+                Err(eyre!("Size of pad must be a length, but {}", d))
+            }
+            _ => Ok(()),
+        }
     }
 }
 
@@ -57,3 +80,71 @@ impl From<Pad> for Operation {
         Operation::Pad(pad)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+    use solver::equation::parse;
+
+    #[rstest]
+    #[case("10")]
+    #[case("10[mm] + 1[in]")]
+    #[case("2 * 3[cm]")]
+    fn new_accepts_length_size(#[case] input: &str) {
+        // Arrange
+        let equation = parse(input).unwrap();
+
+        // Act
+        let result = Pad::new(&equation);
+
+        // Assert
+        assert!(result.is_ok());
+    }
+
+    #[rstest]
+    #[case("10[deg]")]
+    #[case("10[mm] + 10[deg]")]
+    #[case("2[mm] * 3[mm]")]
+    fn new_rejects_size_other_than_length(#[case] input: &str) {
+        // Arrange
+        let equation = parse(input).unwrap();
+
+        // Act
+        let result = Pad::new(&equation);
+
+        // Assert
+        assert!(result.is_err());
+    }
+
+    #[rstest]
+    #[case("x")]
+    #[case("x * 2")]
+    #[case("x * 1[deg]")]
+    #[case("x + 10[deg]")]
+    fn new_rejects_size_with_variables(#[case] input: &str) {
+        // Arrange
+        let equation = parse(input).unwrap();
+
+        // Act
+        let result = Pad::new(&equation);
+
+        // Assert
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn change_size_keeps_current_size_on_error() {
+        // Arrange
+        let size = parse("10[mm]").unwrap();
+        let mut pad = Pad::new(&size).unwrap();
+
+        // Act
+        let result = pad.change_size(&parse("90[deg]").unwrap());
+
+        // Assert
+        assert!(result.is_err());
+        assert_eq!(*pad.size, size);
+    }
+}
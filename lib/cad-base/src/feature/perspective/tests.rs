@@ -11,7 +11,7 @@ use super::FeaturePerspective;
 
 fn make_operation() -> Operation {
     let eq: Equation = 10.0.into();
-    Pad::new(&eq).into()
+    Pad::new(&eq).unwrap().into()
 }
 
 fn make_sketch_id() -> SketchId {
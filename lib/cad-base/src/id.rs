@@ -22,6 +22,10 @@ pub struct GeometryId(u64);
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, MakeId)]
 pub struct ConstraintId(u64);
 
+/// Internal id for dimension management in sketch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, MakeId)]
+pub struct DimensionId(u64);
+
 /// id for Body
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, MakeId)]
 pub struct BodyId(u64);
@@ -12,6 +12,10 @@ pub mod tag;
 pub mod transaction;
 pub mod vector3;
 
+use std::collections::HashMap;
+
+use solver::{Unit, equation::Equation};
+
 use crate::{
     body::{Body, BodyPerspective, BodyReader, PlaneRef},
     feature::FeaturePerspective,
@@ -25,6 +29,9 @@ use crate::{
 pub struct CadEngine {
     /// central registry. can not mutable out of this crate.
     registry: PerspectiveRegistry,
+
+    /// Unit to display lengths of the document. Values are always kept in millimeter.
+    display_unit: Unit,
 }
 
 impl CadEngine {
@@ -34,7 +41,49 @@ impl CadEngine {
         registry.register(BodyPerspective::new());
         registry.register(SketchPerspective::new());
         registry.register(FeaturePerspective::new());
-        Self { registry }
+        Self {
+            registry,
+            display_unit: Unit::Millimeter,
+        }
+    }
+
+    /// Get the unit to display lengths
+    pub fn display_unit(&self) -> Unit {
+        self.display_unit
+    }
+
+    /// Change the unit to display lengths. Returns an error if the unit is not for length.
+    pub fn set_display_unit(&mut self, unit: Unit) -> color_eyre::eyre::Result<()> {
+        if unit.dimension() != solver::Dimension::LENGTH {
+            return Err(color_eyre::eyre::eyre!("{} is not a unit of length", unit));
+        }
+
+        self.display_unit = unit;
+        Ok(())
+    }
+
+    /// Convert a length in millimeter into the display unit
+    pub fn to_display(&self, value: f64) -> f64 {
+        self.display_unit.from_base(value)
+    }
+
+    /// Convert a length in the display unit into millimeter
+    pub fn from_display(&self, value: f64) -> f64 {
+        self.display_unit.to_base(value)
+    }
+
+    /// Parse a length typed by user. Numbers without unit are read in the display unit.
+    pub fn parse_length(&self, input: &str) -> color_eyre::eyre::Result<Equation> {
+        let equation = solver::equation::parse(input)
+            .map_err(|e| color_eyre::eyre::eyre!("Invalid length {}: {e}", input))?;
+        let length = equation.with_default_unit(self.display_unit, &HashMap::new())?;
+
+        match length.dimension(&HashMap::new())? {
+            Some(d) if d != solver::Dimension::LENGTH => {
+                Err(color_eyre::eyre::eyre!("{} is not a length", input))
+            }
+            _ => Ok(length),
+        }
     }
 
     /// A simple transaction undo
@@ -77,3 +126,69 @@ impl<'a> Resolve<'a, PlaneRef, PlaneScope<'a>> for Baseline {
             .map(|b| PlaneScope::new(b, ref_))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+    use solver::{environment::Environment, equation::Evaluate};
+
+    #[test]
+    fn display_unit_converts_lengths_from_millimeter() {
+        // Arrange
+        let mut engine = CadEngine::new();
+
+        // Act
+        engine.set_display_unit(Unit::Inch).unwrap();
+
+        // Assert
+        assert_eq!(engine.display_unit(), Unit::Inch);
+        assert_relative_eq!(engine.to_display(50.8), 2.0);
+        assert_relative_eq!(engine.from_display(2.0), 50.8);
+    }
+
+    #[test]
+    fn display_unit_rejects_unit_of_angle() {
+        // Arrange
+        let mut engine = CadEngine::new();
+
+        // Act
+        let result = engine.set_display_unit(Unit::Degree);
+
+        // Assert
+        assert!(result.is_err());
+        assert_eq!(engine.display_unit(), Unit::Millimeter);
+    }
+
+    #[rstest]
+    #[case("10", 254.0)]
+    #[case("2 * 5", 254.0)]
+    #[case("10 + 1[mm]", 255.0)]
+    #[case("1[cm]", 10.0)]
+    fn parse_length_reads_numbers_in_display_unit(#[case] input: &str, #[case] expected: f64) {
+        // Arrange
+        let mut engine = CadEngine::new();
+        engine.set_display_unit(Unit::Inch).unwrap();
+
+        // Act
+        let result = engine.parse_length(input).unwrap();
+
+        // Assert
+        let value = result.evaluate(&Environment::empty());
+        assert_relative_eq!(value.unwrap(), expected, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn parse_length_rejects_angle() {
+        // Arrange
+        let engine = CadEngine::new();
+
+        // Act
+        let result = engine.parse_length("10[deg]");
+
+        // Assert
+        assert!(result.is_err());
+    }
+}
@@ -1,10 +1,15 @@
 #[cfg(test)]
 mod tests;
 
-use std::marker::PhantomData;
+use std::{cmp::Ordering, marker::PhantomData};
 
 use color_eyre::eyre::Result;
-use epsilon::{DefaultEpsilon, Epsilon, approx_zero};
+use epsilon::{
+    DefaultEpsilon, Epsilon,
+    dual::{Dual, Dual3},
+    exact::sign_of_sum_of_products,
+    interval::Predicate,
+};
 use immutable::Im;
 use tracing::instrument;
 
@@ -76,29 +81,43 @@ impl<E: Epsilon> Plane<E> {
     }
 
     /// Check the [point] on the plane or not
+    ///
+    /// The distance from the plane must be less than the epsilon. The result is exact for
+    /// coordinates, and exact arithmetic is used only when [`Plane::on_plane`] is uncertain.
     pub fn is_on_plane(&self, point: &Point) -> bool {
-        let r0: Vector3 = (*self.r0).clone().into();
-        let r: Vector3 = point.into();
-
-        let ret = self.normal.dot(&(r0 - r));
-
-        approx_zero::<E>(ret.abs())
+        self.on_plane(point).resolve(|| {
+            let (n, r0, r) = (&*self.normal, &*self.r0, point);
+            let distance = [
+                (n.x, *r0.x),
+                (n.y, *r0.y),
+                (n.z, *r0.z),
+                (-n.x, *r.x),
+                (-n.y, *r.y),
+                (-n.z, *r.z),
+            ];
+            let below = [distance.as_slice(), &[(-E::EPSILON, 1.0)]].concat();
+            let above = [distance.as_slice(), &[(E::EPSILON, 1.0)]].concat();
+
+            sign_of_sum_of_products(&below) == Ordering::Less
+                && sign_of_sum_of_products(&above) == Ordering::Greater
+        })
     }
 
-    /// Get the nearest vector to avoid shrink cross
-    fn nearest_normal(&self) -> Vector3 {
-        if self.normal.x <= self.normal.y && self.normal.x <= self.normal.z {
-            Vector3::new_x_unit()
-        } else if self.normal.y <= self.normal.x && self.normal.y <= self.normal.z {
-            Vector3::new_y_unit()
-        } else {
-            Vector3::new_z_unit()
-        }
+    /// Same as [`Plane::is_on_plane`] with interval arithmetic. This is [`Predicate::Uncertain`]
+    /// when the distance is nearly the epsilon.
+    pub fn on_plane(&self, point: &Point) -> Predicate {
+        let r0 = Vector3::from(&*self.r0).to_interval();
+        let r = Vector3::from(point).to_interval();
+
+        self.normal
+            .to_interval()
+            .dot(&(r0 - r))
+            .is_within(E::EPSILON)
     }
 
     /// Project [`Point2`] to [`Point`] on this plane
     pub fn point_from_2d(&self, point: &Point2) -> Point {
-        let e1 = self.normal.cross(&self.nearest_normal()).unit();
+        let e1 = self.normal.cross(&nearest_normal(&self.normal)).unit();
         let e2 = self.normal.cross(&e1);
 
         let u = e1 * *point.x;
@@ -107,4 +126,80 @@ impl<E: Epsilon> Plane<E> {
         let r = Vector3::from(&*self.r0) + u + v;
         Point::from_vector3(&r)
     }
+
+    /// Get the plane as dual numbers that do not depend on the parameter
+    pub fn to_dual(&self) -> DualPlane {
+        DualPlane {
+            normal: self.normal.to_dual(),
+            r0: self.r0.to_dual(),
+        }
+    }
+}
+
+/// Get the nearest vector to avoid shrink cross
+fn nearest_normal(normal: &Vector3) -> Vector3 {
+    if normal.x <= normal.y && normal.x <= normal.z {
+        Vector3::new_x_unit()
+    } else if normal.y <= normal.x && normal.y <= normal.z {
+        Vector3::new_y_unit()
+    } else {
+        Vector3::new_z_unit()
+    }
+}
+
+/// [`Plane`] over dual numbers, to get the derivative of the plane and points on it with respect
+/// to a parameter.
+///
+/// Calculations are same as [`Plane`], so values of results are same as the ones of [`Plane`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct DualPlane {
+    /// unit normal vector of the plane
+    pub normal: Dual3,
+
+    /// point on the plane
+    pub r0: Dual3,
+}
+
+impl DualPlane {
+    /// Same as [`Plane::new`] over dual numbers
+    pub fn new(edge1: (&Dual3, &Dual3), edge2: (&Dual3, &Dual3)) -> Result<Self> {
+        let v1 = *edge1.1 - *edge1.0;
+        let v2 = *edge2.1 - *edge2.0;
+
+        let crossed = v1.cross(&v2);
+
+        if crossed.norm2().value().abs() < 1e-5 {
+            Err(color_eyre::eyre::eyre!(
+                "Can not define plane from same edges"
+            ))
+        } else {
+            Ok(DualPlane {
+                normal: crossed.unit(),
+                r0: *edge1.0,
+            })
+        }
+    }
+
+    /// Get the plane of values
+    pub fn value<E: Epsilon>(&self) -> Plane<E> {
+        Plane::with_parametric(
+            &Vector3::from(self.normal.value()),
+            &Point::from(self.r0.value()),
+        )
+    }
+
+    /// Get the signed distance of the point from the plane, positive on the side of the normal
+    pub fn distance(&self, point: &Dual3) -> Dual {
+        self.normal.dot(&(*point - self.r0))
+    }
+
+    /// Same as [`Plane::point_from_2d`] over dual numbers
+    pub fn point_from_2d(&self, x: Dual, y: Dual) -> Dual3 {
+        // the choice of the axis is not differentiable, so it is decided by values
+        let axis = nearest_normal(&Vector3::from(self.normal.value())).to_dual();
+        let e1 = self.normal.cross(&axis).unit();
+        let e2 = self.normal.cross(&e1);
+
+        self.r0 + e1 * x + e2 * y
+    }
 }
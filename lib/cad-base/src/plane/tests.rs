@@ -2,11 +2,11 @@ use super::*;
 use crate::body::BodyPerspective;
 use approx::assert_relative_eq;
 
-fn p(x: f32, y: f32, z: f32) -> Point {
+fn p(x: f64, y: f64, z: f64) -> Point {
     Point::new(x, y, z)
 }
 
-fn edge(x1: f32, y1: f32, z1: f32, x2: f32, y2: f32, z2: f32) -> (Point, Point) {
+fn edge(x1: f64, y1: f64, z1: f64, x2: f64, y2: f64, z2: f64) -> (Point, Point) {
     (p(x1, y1, z1), p(x2, y2, z2))
 }
 
@@ -218,7 +218,7 @@ mod properties {
 mod point_from_2d {
     use super::*;
 
-    fn pt2(x: f32, y: f32) -> Point2 {
+    fn pt2(x: f64, y: f64) -> Point2 {
         Point2::new(x, y)
     }
 
@@ -568,3 +568,143 @@ mod is_on_plane {
         assert!(!result);
     }
 }
+
+mod on_plane {
+    use super::*;
+
+    #[test]
+    fn point_near_plane_is_certain() {
+        // Arrange
+        let plane = Plane::new_xy();
+
+        // Act
+        let on = plane.on_plane(&p(3.0, 4.0, 1e-7));
+        let off = plane.on_plane(&p(3.0, 4.0, 1.0));
+
+        // Assert
+        assert_eq!(on, Predicate::True);
+        assert_eq!(off, Predicate::False);
+    }
+
+    #[test]
+    fn point_at_epsilon_distance_is_decided_exactly() {
+        // Arrange
+        let plane = Plane::with_parametric(&Vector3::new(1.0, 1.0, 0.0), &p(0.0, 0.0, 0.0));
+        // distances are nearly the epsilon, and the rounded one of inside is equal to it
+        let inside = p(7.071067811865476e-06, 7.071067811865476e-06, 0.0);
+        let outside = p(7.071067811865477e-06, 7.071067811865477e-06, 0.0);
+
+        // Act
+        let predicates = (plane.on_plane(&inside), plane.on_plane(&outside));
+        let results = (plane.is_on_plane(&inside), plane.is_on_plane(&outside));
+
+        // Assert
+        assert_eq!(predicates, (Predicate::Uncertain, Predicate::Uncertain));
+        assert_eq!(results, (true, false));
+    }
+}
+
+mod dual {
+    use super::*;
+    use solver::{environment::Environment, equation::parse};
+
+    /// Edges of a plane that leans with `t`
+    fn edges(t: Dual) -> ((Dual3, Dual3), (Dual3, Dual3)) {
+        let zero = Dual::constant(0.0);
+        let one = Dual::constant(1.0);
+        (
+            (Dual3::from((0.0, 0.0, 0.0)), Dual3::new(one, zero, t)),
+            (Dual3::from((0.0, 0.0, 0.0)), Dual3::new(zero, one, t * t)),
+        )
+    }
+
+    fn plane_at(t: f64) -> Plane {
+        let ((a, b), (c, d)) = edges(Dual::constant(t));
+        DualPlane::new((&a, &b), (&c, &d)).unwrap().value()
+    }
+
+    #[test]
+    fn value_is_same_as_plane() {
+        // Arrange
+        let e1 = edge(1.0, 2.0, 3.0, 4.0, 0.0, 1.0);
+        let e2 = edge(1.0, 2.0, 3.0, -1.0, 5.0, 2.0);
+        let expected = Plane::new((&e1.0, &e1.1), (&e2.0, &e2.1)).unwrap();
+
+        // Act
+        let plane = DualPlane::new(
+            (&e1.0.to_dual(), &e1.1.to_dual()),
+            (&e2.0.to_dual(), &e2.1.to_dual()),
+        )
+        .unwrap();
+
+        // Assert
+        assert_eq!(plane.value::<DefaultEpsilon>(), expected);
+        assert_eq!(plane.normal.derivative(), (0.0, 0.0, 0.0));
+        assert_eq!(expected.to_dual(), plane);
+    }
+
+    #[test]
+    fn normal_derivative_matches_central_difference() {
+        // Arrange
+        let t = 0.7;
+        let h = 1e-6;
+        let ((a, b), (c, d)) = edges(Dual::variable(t));
+
+        // Act
+        let plane = DualPlane::new((&a, &b), (&c, &d)).unwrap();
+
+        // Assert
+        let (dx, dy, dz) = plane.normal.derivative();
+        let (before, after) = (plane_at(t - h), plane_at(t + h));
+        let numeric = (*after.normal - *before.normal) / (2.0 * h);
+        assert_relative_eq!(dx, numeric.x, epsilon = 1e-6);
+        assert_relative_eq!(dy, numeric.y, epsilon = 1e-6);
+        assert_relative_eq!(dz, numeric.z, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn point_from_2d_is_same_as_plane_and_moves_with_parameter() {
+        // Arrange
+        let t = 0.7;
+        let h = 1e-6;
+        let ((a, b), (c, d)) = edges(Dual::variable(t));
+        let plane = DualPlane::new((&a, &b), (&c, &d)).unwrap();
+
+        // Act
+        let point = plane.point_from_2d(Dual::constant(3.0), Dual::constant(-2.0));
+
+        // Assert
+        let expected = plane_at(t).point_from_2d(&Point2::new(3.0, -2.0));
+        let (x, y, z) = point.value();
+        assert_relative_eq!(x, *expected.x, epsilon = 1e-12);
+        assert_relative_eq!(y, *expected.y, epsilon = 1e-12);
+        assert_relative_eq!(z, *expected.z, epsilon = 1e-12);
+
+        let (before, after) = (
+            plane_at(t - h).point_from_2d(&Point2::new(3.0, -2.0)),
+            plane_at(t + h).point_from_2d(&Point2::new(3.0, -2.0)),
+        );
+        let (dx, _, dz) = point.derivative();
+        assert_relative_eq!(dx, (*after.x - *before.x) / (2.0 * h), epsilon = 1e-6);
+        assert_relative_eq!(dz, (*after.z - *before.z) / (2.0 * h), epsilon = 1e-6);
+    }
+
+    #[test]
+    fn distance_derivative_with_respect_to_named_parameter() {
+        // Arrange
+        let env = Environment::from_tuples(&[("height", 3.0), ("width", 1.0)]);
+        let z = parse("2 * height + width").unwrap();
+        let point = Dual3::new(
+            Dual::constant(1.0),
+            Dual::constant(1.0),
+            z.evaluate_dual(&env, "height").unwrap(),
+        );
+        let plane = Plane::new_xy().to_dual();
+
+        // Act
+        let distance = plane.distance(&point);
+
+        // Assert
+        assert_eq!(distance, Dual::new(7.0, 2.0));
+    }
+}
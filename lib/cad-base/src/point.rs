@@ -1,5 +1,6 @@
 use std::fmt::{Display, Formatter};
 
+use epsilon::dual::Dual3;
 use immutable::Im;
 
 use crate::vector3::Vector3;
@@ -9,15 +10,15 @@ use crate::vector3::Vector3;
 /// This type is totally immutable
 #[derive(Debug, Clone, PartialEq)]
 pub struct Point {
-    pub x: Im<f32>,
-    pub y: Im<f32>,
-    pub z: Im<f32>,
+    pub x: Im<f64>,
+    pub y: Im<f64>,
+    pub z: Im<f64>,
     _immutable: (),
 }
 
 impl Point {
     /// Get a new [Point]
-    pub fn new(x: f32, y: f32, z: f32) -> Self {
+    pub fn new(x: f64, y: f64, z: f64) -> Self {
         Point {
             x: x.into(),
             y: y.into(),
@@ -35,15 +36,20 @@ impl Point {
     pub fn zero() -> Self {
         Point::new(0.0, 0.0, 0.0)
     }
+
+    /// Get the point as dual numbers that do not depend on the parameter
+    pub fn to_dual(&self) -> Dual3 {
+        Dual3::from((*self.x, *self.y, *self.z))
+    }
 }
 
-impl From<(f32, f32, f32)> for Point {
-    fn from(value: (f32, f32, f32)) -> Self {
+impl From<(f64, f64, f64)> for Point {
+    fn from(value: (f64, f64, f64)) -> Self {
         Point::new(value.0, value.1, value.2)
     }
 }
 
-impl From<Point> for (f32, f32, f32) {
+impl From<Point> for (f64, f64, f64) {
     fn from(value: Point) -> Self {
         (*value.x, *value.y, *value.z)
     }
@@ -97,7 +103,7 @@ mod tests {
         let point = Point::new(1.0, 2.0, 3.0);
 
         // Act
-        let tuple: (f32, f32, f32) = point.into();
+        let tuple: (f64, f64, f64) = point.into();
 
         // Assert
         assert_eq!(tuple, (1.0, 2.0, 3.0));
@@ -2,10 +2,28 @@ use std::collections::HashSet;
 
 use color_eyre::eyre::Result;
 use immutable::Im;
-use solver::equation::{Equation, Evaluate};
+use solver::{
+    environment::Environment,
+    equation::{Equation, Evaluate},
+};
+use thiserror::Error;
 use tracing::instrument;
 
-use crate::sketch::scope::{VariableArena, VariableIndex};
+use crate::sketch::scope::{ConstraintIndex, VariableArena, VariableIndex};
+
+/// Errors of removing a constraint from a sketch
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum RemoveConstraintError {
+    #[error("Constraint {0} not found")]
+    NotFound(ConstraintIndex),
+
+    /// The constraint is made by a geometric constraint, a dimension or a geometry
+    #[error("Constraint {constraint} is made by {owner}, remove the owner instead")]
+    Owned {
+        constraint: ConstraintIndex,
+        owner: String,
+    },
+}
 
 /// Constraint between variables
 #[derive(Debug, Clone)]
@@ -35,7 +53,24 @@ impl Constraint {
     /// * `Result<Constraint>` - Returns a Result containing the newly created Constraint or an error if any variable in the equation is not found in the scope.
     #[instrument(err)]
     pub fn new(name: &str, equation: Equation, scope: &VariableArena) -> Result<Self> {
-        let mut vars: HashSet<String> = HashSet::from_iter(equation.related_variables());
+        Constraint::with_parameters(name, equation, scope, &Environment::empty())
+    }
+
+    /// Same as [`Constraint::new`], but the equation can also refer named values in `parameters`.
+    /// Parameters are not related variables, because they are constant while solving.
+    #[instrument(err)]
+    pub fn with_parameters(
+        name: &str,
+        equation: Equation,
+        scope: &VariableArena,
+        parameters: &Environment,
+    ) -> Result<Self> {
+        let mut vars: HashSet<String> = HashSet::from_iter(
+            equation
+                .related_variables()
+                .into_iter()
+                .filter(|v| parameters.get(v).is_none()),
+        );
 
         let env = scope.to_id_name_map();
         let mut related = vec![];
@@ -187,4 +222,21 @@ mod tests {
         assert_eq!(constraint.related_variables.len(), 1);
         assert_eq!(constraint.related_variables[0], var1);
     }
+
+    #[test]
+    fn parameters_are_not_related_variables() {
+        // Arrange
+        let mut scope = VariableArena::new();
+        let var1 = scope.register(1.0);
+        let parameters = Environment::from_tuples(&[("width", 4.0)]);
+        let equation = parse(&format!("{} - width / 2", var1)).unwrap();
+
+        // Act
+        let result =
+            Constraint::with_parameters("parameter_constraint", equation, &scope, &parameters);
+
+        // Assert
+        let constraint = result.unwrap();
+        assert_eq!(*constraint.related_variables, vec![var1]);
+    }
 }
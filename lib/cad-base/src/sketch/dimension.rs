@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+
+use color_eyre::eyre::{Result, eyre};
+use immutable::Im;
+use solver::equation::{Equation, parse};
+
+use crate::{
+    id::GeometryId,
+    sketch::{
+        geometric::{coordinates, direction, line_of, point_of, radius_of},
+        geometry::{Geometry, GeometryPoint},
+    },
+};
+
+/// What a dimension measures
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DimensionKind {
+    /// Distance between two points
+    Distance(GeometryPoint, GeometryPoint),
+
+    /// Distance from the point to the infinite line through the line segment
+    PointLineDistance(GeometryPoint, GeometryId),
+
+    /// Distance between two parallel lines, measured from the start of the second line
+    LineDistance(GeometryId, GeometryId),
+
+    /// Signed distance along X-axis from the first point to the second one
+    HorizontalDistance(GeometryPoint, GeometryPoint),
+
+    /// Signed distance along Y-axis from the first point to the second one
+    VerticalDistance(GeometryPoint, GeometryPoint),
+
+    /// Counterclockwise angle from the first line to the second one, in radians
+    Angle(GeometryId, GeometryId),
+
+    /// Radius of the circle or the arc
+    Radius(GeometryId),
+
+    /// Diameter of the circle or the arc
+    Diameter(GeometryId),
+}
+
+impl DimensionKind {
+    /// Get all geometries that this dimension refers
+    pub fn geometries(&self) -> Vec<GeometryId> {
+        match self {
+            DimensionKind::Distance(p1, p2)
+            | DimensionKind::HorizontalDistance(p1, p2)
+            | DimensionKind::VerticalDistance(p1, p2) => vec![p1.geometry, p2.geometry],
+            DimensionKind::PointLineDistance(p, line) => vec![p.geometry, *line],
+            DimensionKind::LineDistance(l1, l2) | DimensionKind::Angle(l1, l2) => vec![*l1, *l2],
+            DimensionKind::Radius(circle) | DimensionKind::Diameter(circle) => vec![*circle],
+        }
+    }
+
+    /// Make an equation of the measured value over variables of `geometries`.
+    ///
+    /// # Errors
+    /// Returns error when referred geometries or points do not exist, or the geometry is not a line
+    /// or a circle that the dimension needs.
+    pub(crate) fn measure(&self, geometries: &HashMap<GeometryId, Geometry>) -> Result<Equation> {
+        let point = |p: &GeometryPoint| point_of(geometries, p).map(coordinates);
+        let line = |id: &GeometryId| line_of(geometries, id);
+        let point_line = |(px, py): (Equation, Equation), l: &GeometryId| -> Result<Equation> {
+            let line = line(l)?;
+            let ((sx, sy), (dx, dy)) = (coordinates(line.0), direction(line));
+            Ok((&dx * (py - sy) - &dy * (px - sx)).abs() / (dx.powi(2) + dy.powi(2)).sqrt())
+        };
+
+        Ok(match self {
+            DimensionKind::Distance(p1, p2) => {
+                let ((x1, y1), (x2, y2)) = (point(p1)?, point(p2)?);
+                ((x2 - x1).powi(2) + (y2 - y1).powi(2)).sqrt()
+            }
+            DimensionKind::PointLineDistance(p, l) => point_line(point(p)?, l)?,
+            DimensionKind::LineDistance(l1, l2) => point_line(coordinates(line(l2)?.0), l1)?,
+            DimensionKind::HorizontalDistance(p1, p2) => {
+                let ((x1, _), (x2, _)) = (point(p1)?, point(p2)?);
+                x2 - x1
+            }
+            DimensionKind::VerticalDistance(p1, p2) => {
+                let ((_, y1), (_, y2)) = (point(p1)?, point(p2)?);
+                y2 - y1
+            }
+            DimensionKind::Angle(l1, l2) => {
+                let ((dx1, dy1), (dx2, dy2)) = (direction(line(l1)?), direction(line(l2)?));
+                (&dx1 * &dy2 - &dy1 * &dx2).atan2(&(dx1 * dx2 + dy1 * dy2))
+            }
+            DimensionKind::Radius(circle) => radius_of(geometries, circle)?,
+            DimensionKind::Diameter(circle) => Equation::from(2.0) * radius_of(geometries, circle)?,
+        })
+    }
+}
+
+/// Whether a dimension drives geometries or not
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DimensionMode {
+    /// The dimension is a constraint, so geometries follow the value
+    Driving,
+
+    /// The dimension only measures geometries
+    Reference,
+}
+
+/// A dimension of a sketch.
+///
+/// The value is an expression as the user wrote, such as `width / 2`. It can refer parameters of
+/// the sketch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SketchDimension {
+    pub kind: DimensionKind,
+
+    /// Expression of the value
+    pub expression: Im<String>,
+
+    pub mode: DimensionMode,
+}
+
+impl SketchDimension {
+    /// Make a new driving dimension
+    pub fn driving(kind: DimensionKind, expression: &str) -> Self {
+        SketchDimension {
+            kind,
+            expression: expression.to_string().into(),
+            mode: DimensionMode::Driving,
+        }
+    }
+
+    /// Make a new reference dimension. The expression is used when it becomes driving.
+    pub fn reference(kind: DimensionKind, expression: &str) -> Self {
+        SketchDimension {
+            kind,
+            expression: expression.to_string().into(),
+            mode: DimensionMode::Reference,
+        }
+    }
+
+    /// Get the parsed expression of the value
+    pub fn value(&self) -> Result<Equation> {
+        parse(&self.expression).map_err(|e| eyre!("Invalid expression {}: {e}", *self.expression))
+    }
+
+    /// Make an equation `measured - value` of the constraint of this dimension
+    pub(crate) fn equation(&self, geometries: &HashMap<GeometryId, Geometry>) -> Result<Equation> {
+        Ok(self.kind.measure(geometries)? - self.value()?)
+    }
+}
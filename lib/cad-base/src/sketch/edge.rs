@@ -1,11 +1,25 @@
+use std::f64::consts::TAU;
+
 use immutable::Im;
 
 use crate::sketch::Point2;
 
+/// Shape of [SketchEdge] between the start and the end
+#[derive(Debug, Clone, PartialEq)]
+pub enum EdgeCurve {
+    /// Straight line from the start to the end
+    Line,
+
+    /// Counterclockwise circular arc from the start to the end. A full circle has same start and
+    /// end.
+    Arc { center: Point2, radius: f64 },
+}
+
 /// Edge is generated by sketch. it is 3D based on sketch's attached target.
 pub struct SketchEdge {
     pub start: Im<Point2>,
     pub end: Im<Point2>,
+    pub curve: Im<EdgeCurve>,
 
     _immutable: (),
 }
@@ -16,7 +30,77 @@ impl SketchEdge {
         SketchEdge {
             start: start.clone().into(),
             end: end.clone().into(),
+            curve: EdgeCurve::Line.into(),
+            _immutable: (),
+        }
+    }
+
+    /// Get a new counterclockwise arc edge. The radius is the distance from the center to the start.
+    pub fn arc(center: &Point2, start: &Point2, end: &Point2) -> Self {
+        let radius = (*start.x - *center.x).hypot(*start.y - *center.y);
+
+        SketchEdge {
+            start: start.clone().into(),
+            end: end.clone().into(),
+            curve: EdgeCurve::Arc {
+                center: center.clone(),
+                radius,
+            }
+            .into(),
+            _immutable: (),
+        }
+    }
+
+    /// Get a new circle edge. It starts and ends at the rightmost point of the circle.
+    pub fn circle(center: &Point2, radius: f64) -> Self {
+        let start = Point2::new(*center.x + radius, *center.y);
+
+        SketchEdge {
+            start: start.clone().into(),
+            end: start.into(),
+            curve: EdgeCurve::Arc {
+                center: center.clone(),
+                radius,
+            }
+            .into(),
             _immutable: (),
         }
     }
+
+    /// Get the counterclockwise angle from the start to the end of the arc, in `(0, 2π]`. `None`
+    /// for lines.
+    pub fn sweep(&self) -> Option<f64> {
+        let EdgeCurve::Arc { center, .. } = &*self.curve else {
+            return None;
+        };
+
+        let angle_of = |p: &Point2| (*p.y - *center.y).atan2(*p.x - *center.x);
+        let sweep = (angle_of(&self.end) - angle_of(&self.start)).rem_euclid(TAU);
+
+        Some(if sweep == 0.0 { TAU } else { sweep })
+    }
+
+    /// Get points along the edge from the start to the end, both inclusive. Arcs are divided into
+    /// `segments_per_turn` segments per full turn, at least one.
+    pub fn tessellate(&self, segments_per_turn: usize) -> Vec<Point2> {
+        let (EdgeCurve::Arc { center, radius }, Some(sweep)) = (&*self.curve, self.sweep()) else {
+            return vec![(*self.start).clone(), (*self.end).clone()];
+        };
+
+        let segments = ((segments_per_turn as f64 * sweep / TAU).ceil() as usize).max(1);
+        let start = (*self.start.y - *center.y).atan2(*self.start.x - *center.x);
+
+        let mut points = (0..segments)
+            .map(|i| {
+                let angle = start + sweep * i as f64 / segments as f64;
+                Point2::new(
+                    *center.x + radius * angle.cos(),
+                    *center.y + radius * angle.sin(),
+                )
+            })
+            .collect::<Vec<_>>();
+        points.push((*self.end).clone());
+
+        points
+    }
 }
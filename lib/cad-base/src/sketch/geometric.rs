@@ -0,0 +1,259 @@
+use std::collections::HashMap;
+
+use color_eyre::eyre::{Result, eyre};
+use solver::equation::Equation;
+
+use crate::{
+    id::GeometryId,
+    sketch::{
+        Point2,
+        geometry::{Geometry, GeometryPoint, LineSegment},
+        scope::VariableIndex,
+    },
+};
+
+/// A geometric constraint between geometries of a sketch.
+///
+/// Each constraint is a set of equations over variables of geometries. Lines are referred by
+/// [`GeometryId`], and points by [`GeometryPoint`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum SketchConstraint {
+    /// Two points are at same position
+    Coincident(GeometryPoint, GeometryPoint),
+
+    /// The line is parallel to X-axis
+    Horizontal(GeometryId),
+
+    /// The line is parallel to Y-axis
+    Vertical(GeometryId),
+
+    /// Two lines are parallel
+    Parallel(GeometryId, GeometryId),
+
+    /// Two lines are perpendicular
+    Perpendicular(GeometryId, GeometryId),
+
+    /// Two lines have same length
+    EqualLength(GeometryId, GeometryId),
+
+    /// The point is at the middle of the line
+    Midpoint(GeometryPoint, GeometryId),
+
+    /// Two points are symmetric about the line
+    Symmetric(GeometryPoint, GeometryPoint, GeometryId),
+
+    /// The point is fixed at the position
+    Fixed(GeometryPoint, Point2),
+
+    /// The infinite line through the line segment touches the circle or the arc
+    Tangent(GeometryId, GeometryId),
+
+    /// The angle from the first line to the second one is same as the angle from the second line
+    /// to the third one
+    EqualAngle(GeometryId, GeometryId, GeometryId),
+
+    /// The second point is the first point rotated around the third point by the angle in radians,
+    /// counterclockwise
+    Rotated(GeometryPoint, GeometryPoint, GeometryPoint, f64),
+}
+
+impl SketchConstraint {
+    /// Get the name of the kind of this constraint
+    pub fn name(&self) -> &'static str {
+        match self {
+            SketchConstraint::Coincident(..) => "coincident",
+            SketchConstraint::Horizontal(..) => "horizontal",
+            SketchConstraint::Vertical(..) => "vertical",
+            SketchConstraint::Parallel(..) => "parallel",
+            SketchConstraint::Perpendicular(..) => "perpendicular",
+            SketchConstraint::EqualLength(..) => "equal_length",
+            SketchConstraint::Midpoint(..) => "midpoint",
+            SketchConstraint::Symmetric(..) => "symmetric",
+            SketchConstraint::Fixed(..) => "fixed",
+            SketchConstraint::Tangent(..) => "tangent",
+            SketchConstraint::EqualAngle(..) => "equal_angle",
+            SketchConstraint::Rotated(..) => "rotated",
+        }
+    }
+
+    /// Get all geometries that this constraint refers
+    pub fn geometries(&self) -> Vec<GeometryId> {
+        match self {
+            SketchConstraint::Coincident(p1, p2) => vec![p1.geometry, p2.geometry],
+            SketchConstraint::Horizontal(line) | SketchConstraint::Vertical(line) => vec![*line],
+            SketchConstraint::Parallel(l1, l2)
+            | SketchConstraint::Perpendicular(l1, l2)
+            | SketchConstraint::EqualLength(l1, l2)
+            | SketchConstraint::Tangent(l1, l2) => vec![*l1, *l2],
+            SketchConstraint::Midpoint(p, line) => vec![p.geometry, *line],
+            SketchConstraint::Symmetric(p1, p2, line) => vec![p1.geometry, p2.geometry, *line],
+            SketchConstraint::Fixed(p, _) => vec![p.geometry],
+            SketchConstraint::EqualAngle(l1, l2, l3) => vec![*l1, *l2, *l3],
+            SketchConstraint::Rotated(from, to, center, _) => {
+                vec![from.geometry, to.geometry, center.geometry]
+            }
+        }
+    }
+
+    /// Make equations `f = 0` of this constraint over variables of `geometries`.
+    ///
+    /// # Errors
+    /// Returns error when referred geometries or points do not exist, or the geometry is not a line
+    /// or a circle that the constraint needs.
+    pub(crate) fn equations(
+        &self,
+        geometries: &HashMap<GeometryId, Geometry>,
+    ) -> Result<Vec<Equation>> {
+        let point = |p: &GeometryPoint| point_of(geometries, p).map(coordinates);
+        let line = |id: &GeometryId| line_of(geometries, id);
+
+        let equations = match self {
+            SketchConstraint::Coincident(p1, p2) => {
+                let ((x1, y1), (x2, y2)) = (point(p1)?, point(p2)?);
+                vec![x1 - x2, y1 - y2]
+            }
+            SketchConstraint::Horizontal(l) => {
+                let ((_, sy), (_, ey)) = line(l)?;
+                vec![Equation::from(ey) - Equation::from(sy)]
+            }
+            SketchConstraint::Vertical(l) => {
+                let ((sx, _), (ex, _)) = line(l)?;
+                vec![Equation::from(ex) - Equation::from(sx)]
+            }
+            SketchConstraint::Parallel(l1, l2) => {
+                let ((dx1, dy1), (dx2, dy2)) = (direction(line(l1)?), direction(line(l2)?));
+                vec![dx1 * dy2 - dy1 * dx2]
+            }
+            SketchConstraint::Perpendicular(l1, l2) => {
+                let ((dx1, dy1), (dx2, dy2)) = (direction(line(l1)?), direction(line(l2)?));
+                vec![dx1 * dx2 + dy1 * dy2]
+            }
+            SketchConstraint::EqualLength(l1, l2) => {
+                let ((dx1, dy1), (dx2, dy2)) = (direction(line(l1)?), direction(line(l2)?));
+                vec![dx1.powi(2) + dy1.powi(2) - dx2.powi(2) - dy2.powi(2)]
+            }
+            SketchConstraint::Midpoint(p, l) => {
+                let ((px, py), (s, e)) = (point(p)?, line(l)?);
+                let ((sx, sy), (ex, ey)) = (coordinates(s), coordinates(e));
+                vec![
+                    Equation::from(2.0) * px - sx - ex,
+                    Equation::from(2.0) * py - sy - ey,
+                ]
+            }
+            SketchConstraint::Symmetric(p1, p2, l) => {
+                let ((x1, y1), (x2, y2)) = (point(p1)?, point(p2)?);
+                let line = line(l)?;
+                let ((sx, sy), (dx, dy)) = (coordinates(line.0), direction(line));
+                let two = Equation::from(2.0);
+                vec![
+                    // the middle of points is on the line
+                    &dx * (&y1 + &y2 - &two * sy) - &dy * (&x1 + &x2 - &two * sx),
+                    // points are on a perpendicular of the line
+                    dx * (x2 - x1) + dy * (y2 - y1),
+                ]
+            }
+            SketchConstraint::Fixed(p, at) => {
+                let (x, y) = point(p)?;
+                vec![x - Equation::from(*at.x), y - Equation::from(*at.y)]
+            }
+            SketchConstraint::Tangent(l, curve) => {
+                let radius = radius_of(geometries, curve)?;
+                let (cx, cy) = point(&GeometryPoint::center(*curve))?;
+                let line = line(l)?;
+                let ((sx, sy), (dx, dy)) = (coordinates(line.0), direction(line));
+                // the distance from the center to the line is the radius
+                vec![
+                    (&dx * (cy - sy) - &dy * (cx - sx)).powi(2)
+                        - radius.powi(2) * (dx.powi(2) + dy.powi(2)),
+                ]
+            }
+            SketchConstraint::EqualAngle(l1, l2, l3) => {
+                let (d1, d2, d3) = (
+                    direction(line(l1)?),
+                    direction(line(l2)?),
+                    direction(line(l3)?),
+                );
+                let cross = |(x1, y1): &Direction, (x2, y2): &Direction| x1 * y2 - y1 * x2;
+                let dot = |(x1, y1): &Direction, (x2, y2): &Direction| x1 * x2 + y1 * y2;
+                // sine of the difference of angles is zero
+                vec![cross(&d1, &d2) * dot(&d2, &d3) - dot(&d1, &d2) * cross(&d2, &d3)]
+            }
+            SketchConstraint::Rotated(from, to, center, angle) => {
+                let ((x1, y1), (x2, y2), (cx, cy)) = (point(from)?, point(to)?, point(center)?);
+                let (cos, sin) = (Equation::from(angle.cos()), Equation::from(angle.sin()));
+                let (rx, ry) = (x1 - &cx, y1 - &cy);
+                vec![
+                    x2 - cx - &cos * &rx + &sin * &ry,
+                    y2 - cy - sin * rx - cos * ry,
+                ]
+            }
+        };
+
+        Ok(equations)
+    }
+}
+
+pub(super) type PointVariables = (VariableIndex, VariableIndex);
+
+/// Get variables of the point
+pub(super) fn point_of(
+    geometries: &HashMap<GeometryId, Geometry>,
+    point: &GeometryPoint,
+) -> Result<PointVariables> {
+    geometries
+        .get(&point.geometry)
+        .ok_or_else(|| eyre!("Geometry {} not found", point.geometry))?
+        .point(point.kind)
+        .ok_or_else(|| eyre!("Geometry {} does not have {:?}", point.geometry, point.kind))
+}
+
+/// Get variables of start and end points of the line
+pub(super) fn line_of(
+    geometries: &HashMap<GeometryId, Geometry>,
+    id: &GeometryId,
+) -> Result<(PointVariables, PointVariables)> {
+    match geometries.get(id) {
+        Some(Geometry::LineSegment(LineSegment {
+            start_points,
+            end_points,
+        })) => Ok((**start_points, **end_points)),
+        Some(_) => Err(eyre!("Geometry {} is not a line", id)),
+        None => Err(eyre!("Geometry {} not found", id)),
+    }
+}
+
+/// Direction of a line as equations of X and Y
+pub(super) type Direction = (Equation, Equation);
+
+/// Get equations of coordinates of the point
+pub(super) fn coordinates((x, y): PointVariables) -> (Equation, Equation) {
+    (x.into(), y.into())
+}
+
+/// Get the direction of the line as equations
+pub(super) fn direction(line: (PointVariables, PointVariables)) -> Direction {
+    let ((sx, sy), (ex, ey)) = (coordinates(line.0), coordinates(line.1));
+
+    (ex - sx, ey - sy)
+}
+
+/// Get the equation of the radius of the circle or the arc
+pub(super) fn radius_of(
+    geometries: &HashMap<GeometryId, Geometry>,
+    id: &GeometryId,
+) -> Result<Equation> {
+    match geometries.get(id) {
+        Some(Geometry::Circle(circle)) => Ok((*circle.radius).into()),
+        Some(Geometry::Arc(arc)) => {
+            let ((cx, cy), (sx, sy)) = (
+                coordinates(*arc.center_points),
+                coordinates(*arc.start_points),
+            );
+            Ok(((sx - cx).powi(2) + (sy - cy).powi(2)).sqrt())
+        }
+        Some(Geometry::LineSegment(_) | Geometry::Point(_)) => {
+            Err(eyre!("Geometry {} is not a circle", id))
+        }
+        None => Err(eyre!("Geometry {} not found", id)),
+    }
+}
@@ -1,8 +1,14 @@
+use color_eyre::eyre::{Result, eyre};
 use immutable::Im;
+use solver::equation::Equation;
 
-use crate::sketch::{
-    Point2,
-    scope::{VariableArena, VariableIndex},
+use crate::{
+    id::GeometryId,
+    sketch::{
+        Point2,
+        geometric::coordinates,
+        scope::{VariableArena, VariableIndex},
+    },
 };
 
 /// A basic structure of the sketch. This is representation of a line and points.
@@ -26,7 +32,210 @@ impl LineSegment {
     }
 }
 
+/// A circle with the center and the radius.
+#[derive(Debug, Clone)]
+pub struct Circle {
+    pub center_points: Im<(VariableIndex, VariableIndex)>,
+    pub radius: Im<VariableIndex>,
+}
+
+impl Circle {
+    /// Make a new circle with the center and the radius.
+    pub fn new(center: &Point2, radius: f64, registrar: &mut VariableArena) -> Self {
+        let center_ids = (registrar.register(*center.x), registrar.register(*center.y));
+
+        Circle {
+            center_points: center_ids.into(),
+            radius: registrar.register(radius).into(),
+        }
+    }
+}
+
+/// A counterclockwise circular arc from the start to the end around the center.
+///
+/// The radius is the distance from the center to the start. The end is kept on the circle by a
+/// constraint that the sketch adds with the arc.
+#[derive(Debug, Clone)]
+pub struct Arc {
+    pub center_points: Im<(VariableIndex, VariableIndex)>,
+    pub start_points: Im<(VariableIndex, VariableIndex)>,
+    pub end_points: Im<(VariableIndex, VariableIndex)>,
+}
+
+impl Arc {
+    /// Make a new arc with the center, the start and the end. The end is moved on the circle.
+    pub fn from_points(
+        center: &Point2,
+        start: &Point2,
+        end: &Point2,
+        registrar: &mut VariableArena,
+    ) -> Self {
+        let radius = (*start.x - *center.x).hypot(*start.y - *center.y);
+        let angle = (*end.y - *center.y).atan2(*end.x - *center.x);
+        let end = Point2::new(
+            *center.x + radius * angle.cos(),
+            *center.y + radius * angle.sin(),
+        );
+
+        let center_ids = (registrar.register(*center.x), registrar.register(*center.y));
+        let start_ids = (registrar.register(*start.x), registrar.register(*start.y));
+        let end_ids = (registrar.register(*end.x), registrar.register(*end.y));
+
+        Arc {
+            center_points: center_ids.into(),
+            start_points: start_ids.into(),
+            end_points: end_ids.into(),
+        }
+    }
+
+    /// Make a new arc through three points, from `start` to `end` via `middle`.
+    ///
+    /// # Errors
+    /// Returns error when points are on a line.
+    pub fn from_three_points(
+        start: &Point2,
+        middle: &Point2,
+        end: &Point2,
+        registrar: &mut VariableArena,
+    ) -> Result<Self> {
+        let (ax, ay) = (*start.x, *start.y);
+        let (bx, by) = (*middle.x, *middle.y);
+        let (cx, cy) = (*end.x, *end.y);
+
+        let d = 2.0 * (ax * (by - cy) + bx * (cy - ay) + cx * (ay - by));
+        if d.abs() < 1e-12 {
+            return Err(eyre!("Can not make arc from points on a line"));
+        }
+
+        let (a2, b2, c2) = (ax * ax + ay * ay, bx * bx + by * by, cx * cx + cy * cy);
+        let center = Point2::new(
+            (a2 * (by - cy) + b2 * (cy - ay) + c2 * (ay - by)) / d,
+            (a2 * (cx - bx) + b2 * (ax - cx) + c2 * (bx - ax)) / d,
+        );
+
+        // arcs are counterclockwise, so swap the start and the end for clockwise points
+        if d > 0.0 {
+            Ok(Arc::from_points(&center, start, end, registrar))
+        } else {
+            Ok(Arc::from_points(&center, end, start, registrar))
+        }
+    }
+}
+
+/// A standalone point, such as the center of a shape. The point is [`PointKind::Center`] of it.
+#[derive(Debug, Clone)]
+pub struct SketchPoint {
+    pub points: Im<(VariableIndex, VariableIndex)>,
+}
+
+impl SketchPoint {
+    /// Make a new point at the position.
+    pub fn new(point: &Point2, registrar: &mut VariableArena) -> Self {
+        let ids = (registrar.register(*point.x), registrar.register(*point.y));
+
+        SketchPoint { points: ids.into() }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum Geometry {
     LineSegment(LineSegment),
+    Circle(Circle),
+    Arc(Arc),
+    Point(SketchPoint),
+}
+
+impl Geometry {
+    /// Get variables of the point of this geometry. `None` when this geometry does not have it.
+    pub fn point(&self, kind: PointKind) -> Option<(VariableIndex, VariableIndex)> {
+        match (self, kind) {
+            (Geometry::LineSegment(line), PointKind::Start) => Some(*line.start_points),
+            (Geometry::LineSegment(line), PointKind::End) => Some(*line.end_points),
+            (Geometry::LineSegment(_), PointKind::Center) => None,
+            (Geometry::Circle(circle), PointKind::Center) => Some(*circle.center_points),
+            (Geometry::Circle(_), PointKind::Start | PointKind::End) => None,
+            (Geometry::Arc(arc), PointKind::Start) => Some(*arc.start_points),
+            (Geometry::Arc(arc), PointKind::End) => Some(*arc.end_points),
+            (Geometry::Arc(arc), PointKind::Center) => Some(*arc.center_points),
+            (Geometry::Point(point), PointKind::Center) => Some(*point.points),
+            (Geometry::Point(_), PointKind::Start | PointKind::End) => None,
+        }
+    }
+
+    /// Get all variables of this geometry
+    pub(crate) fn variables(&self) -> Vec<VariableIndex> {
+        let points = match self {
+            Geometry::LineSegment(line) => vec![*line.start_points, *line.end_points],
+            Geometry::Circle(circle) => vec![*circle.center_points],
+            Geometry::Arc(arc) => vec![*arc.center_points, *arc.start_points, *arc.end_points],
+            Geometry::Point(point) => vec![*point.points],
+        };
+        let radius = match self {
+            Geometry::Circle(circle) => Some(*circle.radius),
+            _ => None,
+        };
+
+        points
+            .into_iter()
+            .flat_map(|(x, y)| [x, y])
+            .chain(radius)
+            .collect()
+    }
+
+    /// Get equations `f = 0` that the geometry needs to keep its shape
+    pub(crate) fn equations(&self) -> Vec<Equation> {
+        match self {
+            Geometry::LineSegment(_) | Geometry::Circle(_) | Geometry::Point(_) => vec![],
+            Geometry::Arc(arc) => {
+                let (cx, cy) = coordinates(*arc.center_points);
+                let ((sx, sy), (ex, ey)) =
+                    (coordinates(*arc.start_points), coordinates(*arc.end_points));
+
+                vec![
+                    (ex - &cx).powi(2) + (ey - &cy).powi(2) - (sx - cx).powi(2) - (sy - cy).powi(2),
+                ]
+            }
+        }
+    }
+}
+
+/// Kinds of points that a geometry has
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PointKind {
+    Start,
+    End,
+    Center,
+}
+
+/// A point of a geometry in a sketch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GeometryPoint {
+    pub geometry: GeometryId,
+    pub kind: PointKind,
+}
+
+impl GeometryPoint {
+    /// Get the start point of the geometry
+    pub fn start(geometry: GeometryId) -> Self {
+        GeometryPoint {
+            geometry,
+            kind: PointKind::Start,
+        }
+    }
+
+    /// Get the end point of the geometry
+    pub fn end(geometry: GeometryId) -> Self {
+        GeometryPoint {
+            geometry,
+            kind: PointKind::End,
+        }
+    }
+
+    /// Get the center point of the geometry
+    pub fn center(geometry: GeometryId) -> Self {
+        GeometryPoint {
+            geometry,
+            kind: PointKind::Center,
+        }
+    }
 }
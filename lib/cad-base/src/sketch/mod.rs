@@ -2,31 +2,42 @@
 mod tests;
 
 mod constraint;
+mod dimension;
 pub mod edge;
+mod geometric;
 mod geometry;
 mod perspective;
 mod point2;
 mod scope;
+mod shape;
 
 pub use constraint::*;
+pub use dimension::*;
+pub use geometric::*;
 pub use geometry::*;
 pub use perspective::*;
 pub use point2::*;
+pub use scope::{ConstraintIndex, VariableArena, VariableIndex};
+pub use shape::*;
+use solver::{
+    DefaultEquationIdGenerator, DimensionSpecificationStatus, SolveError, SolveMode, SolveReport,
+    Solver,
+    environment::Environment,
+    equation::{Equation, Evaluate},
+};
 use tracing::instrument;
 
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::OnceLock};
 
 use crate::{
-    id::{BodyId, GeometryId, IdStore},
+    id::{BodyId, ConstraintId, DimensionId, GeometryId, IdStore},
     plane::Plane,
     refs::{FaceRef, PlaneRef, PlaneScope, Resolve},
-    sketch::{
-        edge::SketchEdge,
-        scope::{ConstraintArena, VariableArena, VariableIndex},
-    },
+    sketch::{edge::SketchEdge, scope::ConstraintArena},
 };
 
 use color_eyre::eyre::{Result, eyre};
+use epsilon::DefaultEpsilon;
 use immutable::Im;
 
 /// Target of sketch attachment.
@@ -55,25 +66,36 @@ impl AttachableTarget {
         }
     }
 
-    /// Make plane from target.
+    /// Make plane from target. `None` for faces, because the reader only resolves planes and
+    /// planes of faces come from evaluated solids.
     pub fn to_plane<'a, T: Resolve<'a, PlaneRef, PlaneScope<'a>>>(
         &self,
         reader: &'a T,
     ) -> Option<Plane> {
         match self {
             AttachableTarget::Plane(plane_ref) => reader.resolve(*plane_ref).map(|s| s.to_plane()),
-            AttachableTarget::Face(_face_ref) => todo!(),
+            AttachableTarget::Face(_) => None,
         }
     }
 }
 
+/// Result of [`Sketch::solve`]
+#[derive(Debug, Clone)]
+pub struct SketchSolveReport {
+    /// Status of constraints before solving
+    pub status: DimensionSpecificationStatus,
+
+    /// Report of the solver. Names of variables are ones of [`VariableIndex`].
+    pub report: SolveReport,
+}
+
 /// The sketch of base of modeling.
 ///
 /// [Sketch] has these values:
 ///
 /// - geometries defined as some basic geometres
 /// - attached Plane with plane id.
-/// - constraints equations for points (not implemented yet)
+/// - constraints equations for variables of geometries
 ///
 #[derive(Debug, Clone)]
 pub struct Sketch {
@@ -88,12 +110,31 @@ pub struct Sketch {
     /// Geometries in this sketch
     geometries: HashMap<GeometryId, Geometry>,
 
+    /// Indices of constraints that geometries need to keep their shapes
+    geometry_constraints: HashMap<GeometryId, Vec<ConstraintIndex>>,
+
     /// variable scope.
     variables: VariableArena,
 
     /// Constraint scope
     constraints: ConstraintArena,
 
+    sketch_constraint_id_gen: IdStore,
+
+    /// Geometric constraints, with indices of constraints of their equations
+    sketch_constraints: HashMap<ConstraintId, (SketchConstraint, Vec<ConstraintIndex>)>,
+
+    dimension_id_gen: IdStore,
+
+    /// Dimensions, with the index of the constraint of driving ones
+    dimensions: HashMap<DimensionId, (SketchDimension, Option<ConstraintIndex>)>,
+
+    /// Named values that constraints and dimensions can refer. These are constant while solving.
+    parameters: Environment,
+
+    /// Status of constraints, cleared when geometries, constraints or values are changed
+    status: OnceLock<DimensionSpecificationStatus>,
+
     /// A plane atteched to sketch
     pub attach_target: Im<AttachableTarget>,
 }
@@ -106,8 +147,15 @@ impl Sketch {
             name: name.to_string().into(),
             geometory_id_gen: IdStore::of(),
             geometries: HashMap::new(),
+            geometry_constraints: HashMap::new(),
             variables: VariableArena::new(),
             constraints: ConstraintArena::new(),
+            sketch_constraint_id_gen: IdStore::of(),
+            sketch_constraints: HashMap::new(),
+            dimension_id_gen: IdStore::of(),
+            dimensions: HashMap::new(),
+            parameters: Environment::empty(),
+            status: OnceLock::new(),
             attach_target: attach_target.clone().into(),
         }
     }
@@ -127,23 +175,576 @@ impl Sketch {
         Ok(())
     }
 
-    /// Add a geometry to this sketch with a geometry maker function
-    pub fn add_geometry<F>(&mut self, maker: F) -> GeometryId
+    /// Add a geometry to this sketch with a geometry maker function. Constraints that the geometry
+    /// needs, such as the end of an arc on its circle, are added with it.
+    ///
+    /// # Errors
+    /// Returns error when constraints of the geometry can not be made. Nothing is added on errors.
+    #[instrument(err, skip_all)]
+    pub fn add_geometry<F>(&mut self, maker: F) -> Result<GeometryId>
     where
         F: FnOnce(&mut VariableArena) -> Geometry,
     {
+        self.status.take();
         let geometry = maker(&mut self.variables);
+        let constraints = geometry
+            .equations()
+            .into_iter()
+            .map(|equation| Constraint::new("geometry", equation, &self.variables))
+            .collect::<Result<Vec<_>>>();
+        let constraints = match constraints {
+            Ok(constraints) => constraints,
+            Err(e) => {
+                for index in geometry.variables() {
+                    self.variables.deregister(&index);
+                }
+                return Err(e);
+            }
+        };
 
+        let indices = constraints
+            .into_iter()
+            .map(|constraint| self.constraints.register(constraint))
+            .collect::<Vec<_>>();
         let id = self.geometory_id_gen.generate();
         self.geometries.insert(id, geometry);
-        id
+        if !indices.is_empty() {
+            self.geometry_constraints.insert(id, indices);
+        }
+        Ok(id)
     }
 
-    /// Remove a geometry from this sketch
+    /// Remove a geometry from this sketch, and geometric constraints and dimensions that refer it
     pub fn remove_geometry(&mut self, id: &GeometryId) -> Option<Geometry> {
+        self.status.take();
+        let referring = self
+            .sketch_constraints
+            .iter()
+            .filter(|(_, (c, _))| c.geometries().contains(id))
+            .map(|(k, _)| *k)
+            .collect::<Vec<_>>();
+        for constraint in referring {
+            self.remove_sketch_constraint(&constraint);
+        }
+
+        let referring = self
+            .dimensions
+            .iter()
+            .filter(|(_, (d, _))| d.kind.geometries().contains(id))
+            .map(|(k, _)| *k)
+            .collect::<Vec<_>>();
+        for dimension in referring {
+            self.remove_dimension(&dimension);
+        }
+
+        for index in self.geometry_constraints.remove(id).unwrap_or_default() {
+            self.constraints.deregister(&index);
+        }
+
         self.geometries.remove(id)
     }
 
+    /// Get a geometry of the id
+    pub fn geometry(&self, id: &GeometryId) -> Option<&Geometry> {
+        self.geometries.get(id)
+    }
+
+    /// Get current variables of geometries
+    pub fn variables(&self) -> &VariableArena {
+        &self.variables
+    }
+
+    /// Get named values of this sketch
+    pub fn parameters(&self) -> &Environment {
+        &self.parameters
+    }
+
+    /// Set a named value that constraints and dimensions can refer. Call [`Sketch::solve`] to
+    /// apply the new value to geometries.
+    ///
+    /// # Errors
+    /// Returns error when the name is empty or same as a variable of geometries.
+    #[instrument(err, skip(self))]
+    pub fn set_parameter(&mut self, name: &str, value: f64) -> Result<()> {
+        self.status.take();
+        if name.trim().is_empty() {
+            return Err(eyre!("Do not allow empty string"));
+        }
+        if self.variables.to_id_name_map().contains_key(name) {
+            return Err(eyre!("Parameter {} conflicts with a variable", name));
+        }
+
+        match self.parameters.get_mut(name) {
+            Some(parameter) => parameter.set_value(value),
+            None => self
+                .parameters
+                .add_variable(solver::variable::Variable::new(name, value)),
+        }
+        Ok(())
+    }
+
+    /// Add a constraint `equation = 0` over variables and parameters of this sketch.
+    ///
+    /// # Errors
+    /// Returns error when the equation has variables not in this sketch, or an equivalent
+    /// constraint is already added.
+    #[instrument(err, skip(self))]
+    pub fn add_constraint(&mut self, name: &str, equation: Equation) -> Result<ConstraintIndex> {
+        self.status.take();
+        if let Some(id) = self.constraints.find_equivalent(&equation) {
+            return Err(eyre!("Equivalent constraint {} already exists", id));
+        }
+
+        let constraint =
+            Constraint::with_parameters(name, equation, &self.variables, &self.parameters)?;
+        Ok(self.constraints.register(constraint))
+    }
+
+    /// Replace the equation of a constraint, keeping its index and name.
+    ///
+    /// # Errors
+    /// Returns error when the constraint does not exist, the constraint is made by a
+    /// [`SketchConstraint`], a [`SketchDimension`] or a geometry, or the equation has variables not
+    /// in this sketch.
+    #[instrument(err, skip(self))]
+    pub fn update_constraint(&mut self, id: &ConstraintIndex, equation: Equation) -> Result<()> {
+        self.status.take();
+        if let Some(owner) = self.owner_of(id) {
+            return Err(eyre!("Constraint {} is made by {}", id, owner));
+        }
+
+        let Some(constraint) = self.constraints.get(id) else {
+            return Err(eyre!("Constraint {} not found", id));
+        };
+
+        let constraint = Constraint::with_parameters(
+            &constraint.name,
+            equation,
+            &self.variables,
+            &self.parameters,
+        )?;
+        self.constraints.replace(id, constraint);
+        Ok(())
+    }
+
+    /// Get a constraint of the index
+    pub fn constraint(&self, id: &ConstraintIndex) -> Option<&Constraint> {
+        self.constraints.get(id)
+    }
+
+    /// Remove a constraint from this sketch.
+    ///
+    /// # Errors
+    /// Returns [`RemoveConstraintError::Owned`] when the constraint is made by a
+    /// [`SketchConstraint`], a [`SketchDimension`] or a geometry, remove the owner instead.
+    pub fn remove_constraint(
+        &mut self,
+        id: &ConstraintIndex,
+    ) -> Result<Constraint, RemoveConstraintError> {
+        if let Some(owner) = self.owner_of(id) {
+            return Err(RemoveConstraintError::Owned {
+                constraint: *id,
+                owner,
+            });
+        }
+
+        self.status.take();
+        self.constraints
+            .deregister(id)
+            .ok_or(RemoveConstraintError::NotFound(*id))
+    }
+
+    /// Get all constraints, ordered by the index. This contains equations of geometric
+    /// constraints and driving dimensions.
+    pub fn constraints(&self) -> Vec<(ConstraintIndex, &Constraint)> {
+        self.constraints.iter().collect()
+    }
+
+    /// Add a geometric constraint. Its equations are added as constraints of this sketch.
+    ///
+    /// # Errors
+    /// Returns error when referred geometries do not exist, or some of equations are equivalent to
+    /// existing constraints.
+    #[instrument(err, skip(self))]
+    pub fn add_sketch_constraint(&mut self, constraint: SketchConstraint) -> Result<ConstraintId> {
+        self.status.take();
+        let indices = self.register_equations(&constraint, &[])?;
+
+        let id = self.sketch_constraint_id_gen.generate();
+        self.sketch_constraints.insert(id, (constraint, indices));
+        Ok(id)
+    }
+
+    /// Add lines and arcs of the shape, with geometric constraints to keep the shape. Returns ids of
+    /// geometries in counterclockwise order around the shape.
+    ///
+    /// # Errors
+    /// Returns error when the shape has no area, or its constraints can not be added. Nothing is
+    /// added on errors.
+    #[instrument(err, skip(self))]
+    pub fn add_shape(&mut self, shape: &SketchShape) -> Result<Vec<GeometryId>> {
+        let geometries = shape.geometries(&mut self.variables)?;
+        let variables = geometries
+            .iter()
+            .flat_map(Geometry::variables)
+            .collect::<Vec<_>>();
+
+        let mut ids = vec![];
+        if let Err(e) = self.add_shape_geometries(shape, geometries, &mut ids) {
+            for id in &ids {
+                self.remove_geometry(id);
+            }
+            for index in &variables {
+                self.variables.deregister(index);
+            }
+            return Err(e);
+        }
+
+        Ok(ids)
+    }
+
+    /// Add geometries and constraints of the shape. Ids of added geometries are pushed to `ids`
+    /// even on errors, to remove them.
+    fn add_shape_geometries(
+        &mut self,
+        shape: &SketchShape,
+        geometries: Vec<Geometry>,
+        ids: &mut Vec<GeometryId>,
+    ) -> Result<()> {
+        for geometry in geometries {
+            ids.push(self.add_geometry(|_| geometry)?);
+        }
+        for constraint in shape.constraints(ids) {
+            self.add_sketch_constraint(constraint)?;
+        }
+
+        Ok(())
+    }
+
+    /// Replace a geometric constraint and its equations, keeping its id.
+    ///
+    /// # Errors
+    /// Same as [`Sketch::add_sketch_constraint`], or the constraint does not exist. Current
+    /// equations are kept on errors.
+    #[instrument(err, skip(self))]
+    pub fn update_sketch_constraint(
+        &mut self,
+        id: &ConstraintId,
+        constraint: SketchConstraint,
+    ) -> Result<()> {
+        self.status.take();
+        let Some((_, current)) = self.sketch_constraints.get(id) else {
+            return Err(eyre!("Sketch constraint {} not found", id));
+        };
+
+        let current = current.clone();
+        let indices = self.register_equations(&constraint, &current)?;
+        for index in &current {
+            self.constraints.deregister(index);
+        }
+
+        self.sketch_constraints.insert(*id, (constraint, indices));
+        Ok(())
+    }
+
+    /// Remove a geometric constraint and its equations
+    pub fn remove_sketch_constraint(&mut self, id: &ConstraintId) -> Option<SketchConstraint> {
+        self.status.take();
+        let (constraint, indices) = self.sketch_constraints.remove(id)?;
+        for index in &indices {
+            self.constraints.deregister(index);
+        }
+
+        Some(constraint)
+    }
+
+    /// Get a geometric constraint of the id
+    pub fn sketch_constraint(&self, id: &ConstraintId) -> Option<&SketchConstraint> {
+        self.sketch_constraints.get(id).map(|(c, _)| c)
+    }
+
+    /// Get indices of constraints made by the geometric constraint
+    pub fn equations_of(&self, id: &ConstraintId) -> Option<&[ConstraintIndex]> {
+        self.sketch_constraints.get(id).map(|(_, i)| i.as_slice())
+    }
+
+    /// Get all geometric constraints, ordered by the id
+    pub fn sketch_constraints(&self) -> Vec<(ConstraintId, &SketchConstraint)> {
+        let mut constraints = self
+            .sketch_constraints
+            .iter()
+            .map(|(k, (c, _))| (*k, c))
+            .collect::<Vec<_>>();
+        constraints.sort_by_key(|(k, _)| u64::from(*k));
+
+        constraints
+    }
+
+    /// Get the geometric constraint, the dimension or the geometry that made the constraint
+    fn owner_of(&self, index: &ConstraintIndex) -> Option<String> {
+        let constraint = self
+            .sketch_constraints
+            .iter()
+            .find(|(_, (_, indices))| indices.contains(index))
+            .map(|(k, _)| k.to_string());
+        let dimension = || {
+            self.dimensions
+                .iter()
+                .find(|(_, (_, i))| i.as_ref() == Some(index))
+                .map(|(k, _)| k.to_string())
+        };
+        let geometry = || {
+            self.geometry_constraints
+                .iter()
+                .find(|(_, indices)| indices.contains(index))
+                .map(|(k, _)| k.to_string())
+        };
+
+        constraint.or_else(dimension).or_else(geometry)
+    }
+
+    /// Register equations of the geometric constraint. Constraints in `replaced` are ignored to
+    /// find equivalent ones, because they will be removed.
+    fn register_equations(
+        &mut self,
+        constraint: &SketchConstraint,
+        replaced: &[ConstraintIndex],
+    ) -> Result<Vec<ConstraintIndex>> {
+        let mut canonicals = vec![];
+        let mut made = vec![];
+
+        for equation in constraint.equations(&self.geometries)? {
+            if let Some(id) = self
+                .constraints
+                .find_equivalent(&equation)
+                .filter(|id| !replaced.contains(id))
+            {
+                return Err(eyre!("Equivalent constraint {} already exists", id));
+            }
+
+            let canonical = equation.canonical_constraint().ok();
+            if canonical.is_some() && canonicals.contains(&canonical) {
+                return Err(eyre!(
+                    "Equations of {} are not independent",
+                    constraint.name()
+                ));
+            }
+            canonicals.push(canonical);
+            made.push(Constraint::new(
+                constraint.name(),
+                equation,
+                &self.variables,
+            )?);
+        }
+
+        Ok(made
+            .into_iter()
+            .map(|c| self.constraints.register(c))
+            .collect())
+    }
+
+    /// Add a dimension. A driving dimension adds a constraint of its value.
+    ///
+    /// # Errors
+    /// Returns error when referred geometries do not exist, the expression is invalid or refers
+    /// unknown names, or the constraint is equivalent to an existing one.
+    #[instrument(err, skip(self))]
+    pub fn add_dimension(&mut self, dimension: SketchDimension) -> Result<DimensionId> {
+        self.status.take();
+        let index = self.register_dimension(&dimension, None)?;
+
+        let id = self.dimension_id_gen.generate();
+        self.dimensions.insert(id, (dimension, index));
+        Ok(id)
+    }
+
+    /// Replace a dimension, and solve the sketch again to follow the new value.
+    ///
+    /// # Errors
+    /// Same as [`Sketch::add_dimension`], the dimension does not exist, or the sketch can not be
+    /// solved to satisfy all constraints, such as when the new value conflicts with others. The
+    /// current dimension and geometries are kept on errors.
+    #[instrument(err, skip(self))]
+    pub fn update_dimension(
+        &mut self,
+        id: &DimensionId,
+        dimension: SketchDimension,
+    ) -> Result<SketchSolveReport> {
+        self.status.take();
+        let Some((current, current_index)) = self.dimensions.get(id).cloned() else {
+            return Err(eyre!("Dimension {} not found", id));
+        };
+
+        let index = self.register_dimension(&dimension, current_index)?;
+        let removed = current_index.and_then(|i| self.constraints.deregister(&i));
+        self.dimensions.insert(*id, (dimension, index));
+        let variables = self.variables.clone();
+
+        let reason = match self.solve() {
+            Ok(report) if report.report.converged() => return Ok(report),
+            Ok(_) => "constraints are not satisfied".to_string(),
+            Err(e) => e.to_string(),
+        };
+
+        if let Some(index) = index {
+            self.constraints.deregister(&index);
+        }
+        let restored = removed.map(|c| self.constraints.register(c));
+        self.dimensions.insert(*id, (current, restored));
+        self.variables = variables;
+        // the solve cached the status of the rejected dimension
+        self.status.take();
+
+        Err(eyre!(
+            "Can not solve the sketch with dimension {}: {}",
+            id,
+            reason
+        ))
+    }
+
+    /// Change the expression of a dimension, and solve the sketch again. See
+    /// [`Sketch::update_dimension`].
+    pub fn set_dimension_expression(
+        &mut self,
+        id: &DimensionId,
+        expression: &str,
+    ) -> Result<SketchSolveReport> {
+        let Some((dimension, _)) = self.dimensions.get(id) else {
+            return Err(eyre!("Dimension {} not found", id));
+        };
+
+        let dimension = SketchDimension {
+            expression: expression.to_string().into(),
+            ..dimension.clone()
+        };
+        self.update_dimension(id, dimension)
+    }
+
+    /// Remove a dimension and its constraint
+    pub fn remove_dimension(&mut self, id: &DimensionId) -> Option<SketchDimension> {
+        self.status.take();
+        let (dimension, index) = self.dimensions.remove(id)?;
+        if let Some(index) = index {
+            self.constraints.deregister(&index);
+        }
+
+        Some(dimension)
+    }
+
+    /// Get a dimension of the id
+    pub fn dimension(&self, id: &DimensionId) -> Option<&SketchDimension> {
+        self.dimensions.get(id).map(|(d, _)| d)
+    }
+
+    /// Get all dimensions, ordered by the id
+    pub fn dimensions(&self) -> Vec<(DimensionId, &SketchDimension)> {
+        let mut dimensions = self
+            .dimensions
+            .iter()
+            .map(|(k, (d, _))| (*k, d))
+            .collect::<Vec<_>>();
+        dimensions.sort_by_key(|(k, _)| u64::from(*k));
+
+        dimensions
+    }
+
+    /// Measure the current value of a dimension from geometries, in both modes
+    #[instrument(err, skip(self))]
+    pub fn measure(&self, id: &DimensionId) -> Result<f64> {
+        let Some((dimension, _)) = self.dimensions.get(id) else {
+            return Err(eyre!("Dimension {} not found", id));
+        };
+
+        dimension
+            .kind
+            .measure(&self.geometries)?
+            .evaluate(&self.variables.to_environment())
+            .map_err(|e| eyre!("Can not measure dimension {}: {:?}", id, e))
+    }
+
+    /// Register the constraint of the dimension if it is driving. The constraint `replaced` is
+    /// ignored to find equivalent ones, because it will be removed.
+    fn register_dimension(
+        &mut self,
+        dimension: &SketchDimension,
+        replaced: Option<ConstraintIndex>,
+    ) -> Result<Option<ConstraintIndex>> {
+        if dimension.mode == DimensionMode::Reference {
+            dimension.kind.measure(&self.geometries)?;
+            dimension.value()?;
+            return Ok(None);
+        }
+
+        let equation = dimension.equation(&self.geometries)?;
+        if let Some(id) = self
+            .constraints
+            .find_equivalent(&equation)
+            .filter(|id| Some(*id) != replaced)
+        {
+            return Err(eyre!("Equivalent constraint {} already exists", id));
+        }
+
+        let constraint =
+            Constraint::with_parameters("dimension", equation, &self.variables, &self.parameters)?;
+        Ok(Some(self.constraints.register(constraint)))
+    }
+
+    /// Get the status of constraints of this sketch. The status is kept until this sketch is
+    /// changed.
+    pub fn status(&self) -> DimensionSpecificationStatus {
+        *self.status.get_or_init(|| self.make_solver().status())
+    }
+
+    /// Solve constraints, and write solved values back into variables.
+    ///
+    /// Well-constrained sketches are solved with Newton-Raphson method. Others are solved with least
+    /// squares method, so variables that constraints do not decide stay close to current values.
+    /// Variables are not changed when solving failed. A sketch without constraints is not solved.
+    #[instrument(err, skip(self))]
+    pub fn solve(&mut self) -> std::result::Result<SketchSolveReport, SolveError> {
+        let mut solver = self.make_solver();
+        let status = *self.status.get_or_init(|| solver.status());
+        if self.constraints.iter().next().is_none() {
+            let report = SolveReport {
+                variables: self.variables.to_environment(),
+                clusters: vec![],
+                violations: vec![],
+                relaxed: vec![],
+            };
+            return Ok(SketchSolveReport { status, report });
+        }
+        if status != DimensionSpecificationStatus::WellConstrained {
+            solver.set_mode(SolveMode::LeastSquares);
+        }
+
+        let report = solver.solve()?;
+        self.status.take();
+        for (name, index) in self.variables.to_id_name_map() {
+            if let (Some(solved), Some(variable)) =
+                (report.variables.get(&name), self.variables.get_mut(&index))
+            {
+                variable.set_value(*solved.value);
+            }
+        }
+
+        Ok(SketchSolveReport { status, report })
+    }
+
+    /// Make a solver with variables and constraints of this sketch
+    fn make_solver(&self) -> Solver {
+        let mut solver =
+            Solver::new::<DefaultEpsilon>(Box::new(DefaultEquationIdGenerator::default()));
+        solver.update_variables(&self.variables.to_environment());
+        solver.update_dimensions(&self.parameters);
+        solver.add_equations(
+            self.constraints
+                .iter()
+                .map(|(_, constraint)| (*constraint.equation).clone()),
+        );
+
+        solver
+    }
+
     /// Get a point2 from raw point.
     #[tracing::instrument(err)]
     fn resolve_point(&self, raw_point: &Im<(VariableIndex, VariableIndex)>) -> Result<Point2> {
@@ -171,6 +772,23 @@ impl Sketch {
 
                     ret.push(SketchEdge::new(&start, &end));
                 }
+                Geometry::Circle(circle) => {
+                    let center = self.resolve_point(&circle.center_points)?;
+                    let Some(radius) = self.variables.get(&circle.radius) else {
+                        return Err(eyre!("Do not found variable for {}", *circle.radius));
+                    };
+
+                    ret.push(SketchEdge::circle(&center, *radius.value));
+                }
+                Geometry::Arc(arc) => {
+                    let center = self.resolve_point(&arc.center_points)?;
+                    let start = self.resolve_point(&arc.start_points)?;
+                    let end = self.resolve_point(&arc.end_points)?;
+
+                    ret.push(SketchEdge::arc(&center, &start, &end));
+                }
+                // points do not make edges
+                Geometry::Point(_) => (),
             }
         }
 
@@ -3,9 +3,14 @@ mod tests;
 
 use std::collections::HashMap;
 
+use solver::{SolveError, equation::Equation};
+
 use crate::{
-    id::{BodyId, IdStore, SketchId},
-    sketch::{AttachableTarget, Sketch},
+    id::{BodyId, ConstraintId, DimensionId, GeometryId, IdStore, SketchId},
+    sketch::{
+        AttachableTarget, Constraint, ConstraintIndex, Sketch, SketchConstraint, SketchDimension,
+        SketchShape, SketchSolveReport,
+    },
 };
 
 use color_eyre::eyre::{Result, eyre};
@@ -74,4 +79,136 @@ impl SketchPerspective {
 
         sketch.set_name(new_name)
     }
+
+    /// Add a constraint to the sketch. See [`Sketch::add_constraint`].
+    #[instrument(err, skip(self))]
+    pub fn add_constraint(
+        &mut self,
+        id: &SketchId,
+        name: &str,
+        equation: Equation,
+    ) -> Result<ConstraintIndex> {
+        let sketch = self
+            .get_mut(id)
+            .ok_or_else(|| eyre!("Sketch with id {id} not found"))?;
+
+        sketch.add_constraint(name, equation)
+    }
+
+    /// Remove a constraint from the sketch. See [`Sketch::remove_constraint`].
+    #[instrument(err, skip(self))]
+    pub fn remove_constraint(
+        &mut self,
+        id: &SketchId,
+        constraint: &ConstraintIndex,
+    ) -> Result<Constraint> {
+        let sketch = self
+            .get_mut(id)
+            .ok_or_else(|| eyre!("Sketch with id {id} not found"))?;
+
+        Ok(sketch.remove_constraint(constraint)?)
+    }
+
+    /// Get all constraints of the sketch, ordered by the index
+    pub fn constraints(&self, id: &SketchId) -> Option<Vec<(ConstraintIndex, &Constraint)>> {
+        self.get(id).map(|s| s.constraints())
+    }
+
+    /// Add a geometric constraint to the sketch. See [`Sketch::add_sketch_constraint`].
+    #[instrument(err, skip(self))]
+    pub fn add_sketch_constraint(
+        &mut self,
+        id: &SketchId,
+        constraint: SketchConstraint,
+    ) -> Result<ConstraintId> {
+        let sketch = self
+            .get_mut(id)
+            .ok_or_else(|| eyre!("Sketch with id {id} not found"))?;
+
+        sketch.add_sketch_constraint(constraint)
+    }
+
+    /// Add a shape to the sketch. See [`Sketch::add_shape`].
+    #[instrument(err, skip(self))]
+    pub fn add_shape(&mut self, id: &SketchId, shape: &SketchShape) -> Result<Vec<GeometryId>> {
+        let sketch = self
+            .get_mut(id)
+            .ok_or_else(|| eyre!("Sketch with id {id} not found"))?;
+
+        sketch.add_shape(shape)
+    }
+
+    /// Replace a geometric constraint of the sketch. See [`Sketch::update_sketch_constraint`].
+    #[instrument(err, skip(self))]
+    pub fn update_sketch_constraint(
+        &mut self,
+        id: &SketchId,
+        constraint_id: &ConstraintId,
+        constraint: SketchConstraint,
+    ) -> Result<()> {
+        let sketch = self
+            .get_mut(id)
+            .ok_or_else(|| eyre!("Sketch with id {id} not found"))?;
+
+        sketch.update_sketch_constraint(constraint_id, constraint)
+    }
+
+    /// Remove a geometric constraint from the sketch. `None` when the sketch or the constraint does
+    /// not exist.
+    pub fn remove_sketch_constraint(
+        &mut self,
+        id: &SketchId,
+        constraint_id: &ConstraintId,
+    ) -> Option<SketchConstraint> {
+        self.get_mut(id)?.remove_sketch_constraint(constraint_id)
+    }
+
+    /// Add a dimension to the sketch. See [`Sketch::add_dimension`].
+    #[instrument(err, skip(self))]
+    pub fn add_dimension(
+        &mut self,
+        id: &SketchId,
+        dimension: SketchDimension,
+    ) -> Result<DimensionId> {
+        let sketch = self
+            .get_mut(id)
+            .ok_or_else(|| eyre!("Sketch with id {id} not found"))?;
+
+        sketch.add_dimension(dimension)
+    }
+
+    /// Change the expression of a dimension, and solve the sketch again. See
+    /// [`Sketch::set_dimension_expression`].
+    #[instrument(err, skip(self))]
+    pub fn set_dimension_expression(
+        &mut self,
+        id: &SketchId,
+        dimension_id: &DimensionId,
+        expression: &str,
+    ) -> Result<SketchSolveReport> {
+        let sketch = self
+            .get_mut(id)
+            .ok_or_else(|| eyre!("Sketch with id {id} not found"))?;
+
+        sketch.set_dimension_expression(dimension_id, expression)
+    }
+
+    /// Remove a dimension from the sketch. `None` when the sketch or the dimension does not exist.
+    pub fn remove_dimension(
+        &mut self,
+        id: &SketchId,
+        dimension_id: &DimensionId,
+    ) -> Option<SketchDimension> {
+        self.get_mut(id)?.remove_dimension(dimension_id)
+    }
+
+    /// Solve all sketches, and get the result for each sketch. See [`Sketch::solve`].
+    pub fn solve(
+        &mut self,
+    ) -> HashMap<SketchId, std::result::Result<SketchSolveReport, SolveError>> {
+        self.sketches
+            .iter_mut()
+            .map(|(id, sketch)| (*id, sketch.solve()))
+            .collect()
+    }
 }
@@ -275,4 +275,258 @@ mod sketch_perspective {
             );
         }
     }
+
+    mod constraints {
+        use super::*;
+        use crate::sketch::{Geometry, LineSegment, Point2, VariableIndex};
+        use approx::assert_relative_eq;
+        use pretty_assertions::assert_eq;
+        use solver::equation::parse;
+
+        fn add_sketch_with_line(
+            perspective: &mut SketchPerspective,
+            body_id: BodyId,
+            plane_ref: crate::body::PlaneRef,
+        ) -> (SketchId, VariableIndex) {
+            let sketch_id = perspective.add_sketch(body_id, &AttachableTarget::Plane(plane_ref));
+            let sketch = perspective.get_mut(&sketch_id).unwrap();
+            let id = sketch
+                .add_geometry(|scope| {
+                    Geometry::LineSegment(LineSegment::from_points(
+                        &Point2::new(1.0, 2.0),
+                        &Point2::new(3.0, 4.0),
+                        scope,
+                    ))
+                })
+                .unwrap();
+            let Some(Geometry::LineSegment(line)) = sketch.geometry(&id) else {
+                unreachable!()
+            };
+            (sketch_id, line.start_points.0)
+        }
+
+        #[test]
+        fn add_constraint_adds_to_the_sketch() {
+            // Arrange
+            let mut perspective = SketchPerspective::new();
+            let (_bodies, body_id, plane_ref) = make_plane_ref();
+            let (sketch_id, x) = add_sketch_with_line(&mut perspective, body_id, plane_ref);
+
+            // Act
+            let id = perspective
+                .add_constraint(&sketch_id, "c", parse(&format!("{} - 5", x)).unwrap())
+                .unwrap();
+
+            // Assert
+            let constraints = perspective.constraints(&sketch_id).unwrap();
+            assert_eq!(constraints.len(), 1);
+            assert_eq!(constraints[0].0, id);
+        }
+
+        #[test]
+        fn add_constraint_fails_for_nonexistent_sketch() {
+            // Arrange
+            let mut perspective = SketchPerspective::new();
+            let nonexistent_id = SketchId::new(999);
+
+            // Act
+            let result = perspective.add_constraint(&nonexistent_id, "c", parse("1").unwrap());
+
+            // Assert
+            assert!(result.is_err());
+            assert_eq!(
+                result.unwrap_err().to_string(),
+                format!("Sketch with id {} not found", nonexistent_id)
+            );
+        }
+
+        #[test]
+        fn remove_constraint_removes_from_the_sketch() {
+            // Arrange
+            let mut perspective = SketchPerspective::new();
+            let (_bodies, body_id, plane_ref) = make_plane_ref();
+            let (sketch_id, x) = add_sketch_with_line(&mut perspective, body_id, plane_ref);
+            let id = perspective
+                .add_constraint(&sketch_id, "c", parse(&format!("{} - 5", x)).unwrap())
+                .unwrap();
+
+            // Act
+            let result = perspective.remove_constraint(&sketch_id, &id);
+
+            // Assert
+            assert!(result.is_ok());
+            assert!(perspective.constraints(&sketch_id).unwrap().is_empty());
+            assert!(
+                perspective
+                    .remove_constraint(&SketchId::new(999), &id)
+                    .is_err()
+            );
+        }
+
+        #[test]
+        fn solve_solves_each_sketch() {
+            // Arrange
+            let mut perspective = SketchPerspective::new();
+            let (_bodies, body_id, plane_ref) = make_plane_ref();
+            let (sketch1, x1) = add_sketch_with_line(&mut perspective, body_id, plane_ref);
+            let (sketch2, x2) = add_sketch_with_line(&mut perspective, body_id, plane_ref);
+            perspective
+                .add_constraint(&sketch1, "c", parse(&format!("{} - 5", x1)).unwrap())
+                .unwrap();
+
+            // Act
+            let result = perspective.solve();
+
+            // Assert
+            assert_eq!(result.len(), 2);
+            assert!(result[&sketch1].is_ok());
+            assert!(result[&sketch2].is_ok());
+            let variables = perspective.get(&sketch1).unwrap().variables();
+            assert_relative_eq!(*variables.get(&x1).unwrap().value, 5.0, epsilon = 1e-6);
+            let variables = perspective.get(&sketch2).unwrap().variables();
+            assert_eq!(*variables.get(&x2).unwrap().value, 1.0);
+        }
+    }
+
+    mod sketch_constraints {
+        use super::*;
+        use crate::sketch::{Geometry, LineSegment, Point2, SketchConstraint};
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn add_update_and_remove_sketch_constraint() {
+            // Arrange
+            let mut perspective = SketchPerspective::new();
+            let (_bodies, body_id, plane_ref) = make_plane_ref();
+            let sketch_id = perspective.add_sketch(body_id, &AttachableTarget::Plane(plane_ref));
+            let line = perspective
+                .get_mut(&sketch_id)
+                .unwrap()
+                .add_geometry(|scope| {
+                    Geometry::LineSegment(LineSegment::from_points(
+                        &Point2::new(0.0, 0.0),
+                        &Point2::new(1.0, 1.0),
+                        scope,
+                    ))
+                })
+                .unwrap();
+
+            // Act
+            let id = perspective
+                .add_sketch_constraint(&sketch_id, SketchConstraint::Horizontal(line))
+                .unwrap();
+            perspective
+                .update_sketch_constraint(&sketch_id, &id, SketchConstraint::Vertical(line))
+                .unwrap();
+            let removed = perspective.remove_sketch_constraint(&sketch_id, &id);
+
+            // Assert
+            assert_eq!(removed, Some(SketchConstraint::Vertical(line)));
+            assert!(perspective.constraints(&sketch_id).unwrap().is_empty());
+        }
+
+        #[test]
+        fn add_sketch_constraint_fails_for_nonexistent_sketch() {
+            // Arrange
+            let mut perspective = SketchPerspective::new();
+            let nonexistent_id = SketchId::new(999);
+
+            // Act
+            let result = perspective.add_sketch_constraint(
+                &nonexistent_id,
+                SketchConstraint::Horizontal(crate::id::GeometryId::from(1)),
+            );
+
+            // Assert
+            assert!(result.is_err());
+            assert_eq!(
+                result.unwrap_err().to_string(),
+                format!("Sketch with id {} not found", nonexistent_id)
+            );
+        }
+    }
+
+    mod shapes {
+        use super::*;
+        use crate::sketch::{Point2, SketchShape};
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn add_shape_adds_geometries_with_constraints() {
+            // Arrange
+            let mut perspective = SketchPerspective::new();
+            let (_bodies, body_id, plane_ref) = make_plane_ref();
+            let sketch_id = perspective.add_sketch(body_id, &AttachableTarget::Plane(plane_ref));
+            let shape = SketchShape::Rectangle(Point2::new(0.0, 0.0), Point2::new(2.0, 1.0));
+
+            // Act
+            let ids = perspective.add_shape(&sketch_id, &shape).unwrap();
+
+            // Assert
+            let sketch = perspective.get(&sketch_id).unwrap();
+            assert_eq!(ids.len(), 4);
+            assert_eq!(sketch.resolve_edges().unwrap().len(), 4);
+            assert_eq!(sketch.sketch_constraints().len(), 4);
+        }
+
+        #[test]
+        fn add_shape_fails_for_nonexistent_sketch() {
+            // Arrange
+            let mut perspective = SketchPerspective::new();
+            let nonexistent_id = SketchId::new(999);
+            let shape = SketchShape::Rectangle(Point2::new(0.0, 0.0), Point2::new(2.0, 1.0));
+
+            // Act
+            let result = perspective.add_shape(&nonexistent_id, &shape);
+
+            // Assert
+            assert!(result.is_err());
+            assert_eq!(
+                result.unwrap_err().to_string(),
+                format!("Sketch with id {} not found", nonexistent_id)
+            );
+        }
+    }
+
+    mod dimensions {
+        use super::*;
+        use crate::sketch::{
+            DimensionKind, Geometry, GeometryPoint, LineSegment, Point2, SketchDimension,
+        };
+        use approx::assert_relative_eq;
+
+        #[test]
+        fn set_dimension_expression_drives_the_sketch() {
+            // Arrange
+            let mut perspective = SketchPerspective::new();
+            let (_bodies, body_id, plane_ref) = make_plane_ref();
+            let sketch_id = perspective.add_sketch(body_id, &AttachableTarget::Plane(plane_ref));
+            let line = perspective
+                .get_mut(&sketch_id)
+                .unwrap()
+                .add_geometry(|scope| {
+                    Geometry::LineSegment(LineSegment::from_points(
+                        &Point2::new(0.0, 0.0),
+                        &Point2::new(3.0, 4.0),
+                        scope,
+                    ))
+                })
+                .unwrap();
+            let kind =
+                DimensionKind::Distance(GeometryPoint::start(line), GeometryPoint::end(line));
+            let id = perspective
+                .add_dimension(&sketch_id, SketchDimension::driving(kind, "5"))
+                .unwrap();
+
+            // Act
+            perspective
+                .set_dimension_expression(&sketch_id, &id, "2 * 4")
+                .unwrap();
+
+            // Assert
+            let measured = perspective.get(&sketch_id).unwrap().measure(&id).unwrap();
+            assert_relative_eq!(measured, 8.0, epsilon = 1e-4);
+            assert!(perspective.remove_dimension(&sketch_id, &id).is_some());
+        }
+    }
 }
@@ -1,22 +1,26 @@
 use std::cmp::Ordering;
 
-use epsilon::Epsilon;
+use epsilon::{
+    Epsilon,
+    exact::sign_of_sum_of_products,
+    interval::{Interval2, Predicate},
+};
 use immutable::Im;
 
 /// A 2D point in sketch space with immutable coordinates.
 ///
 /// Once created, the x and y coordinates cannot be modified in place.
-/// Use [`Point2::new`] or convert from a `(f32, f32)` tuple to create an instance.
+/// Use [`Point2::new`] or convert from a `(f64, f64)` tuple to create an instance.
 #[derive(Debug, Clone, PartialEq)]
 pub struct Point2 {
-    pub x: Im<f32>,
-    pub y: Im<f32>,
+    pub x: Im<f64>,
+    pub y: Im<f64>,
     _immutable: (),
 }
 
 impl Point2 {
     /// Create a new [`Point2`] from the given x and y coordinates.
-    pub fn new(x: f32, y: f32) -> Self {
+    pub fn new(x: f64, y: f64) -> Self {
         Point2 {
             x: x.into(),
             y: y.into(),
@@ -25,7 +29,7 @@ impl Point2 {
     }
 
     /// Compute the Euclidean distance between `self` and `other`.
-    pub fn distance(&self, other: &Point2) -> f32 {
+    pub fn distance(&self, other: &Point2) -> f64 {
         let x = *other.x - *self.x;
         let y = *other.y - *self.y;
 
@@ -49,22 +53,53 @@ impl Point2 {
         epsilon::approx_total_cmp::<E>(*self.y, *other.y)
     }
 
+    /// Get the point as intervals
+    pub fn to_interval(&self) -> Interval2 {
+        Interval2::from((*self.x, *self.y))
+    }
+
     /// Return `true` if the path `self` -> `o1` -> `o2` makes a
     /// counter-clockwise (CCW) turn.
     ///
     /// Uses the cross-product of vectors (`self` -> `o1`) and (`self` -> `o2`).
     /// Returns `false` when the points are collinear or clockwise.
+    ///
+    /// The result is exact for coordinates. [`Point2::is_ccw`] decides most cases, and exact
+    /// arithmetic is used only when it is uncertain.
     pub fn detect_ccw(&self, o1: &Point2, o2: &Point2) -> bool {
-        let (ax, ay) = (*self.x, *self.y);
-        let (bx, by) = (*o1.x, *o1.y);
-        let (cx, cy) = (*o2.x, *o2.y);
+        self.is_ccw(o1, o2).resolve(|| {
+            let (ax, ay) = (*self.x, *self.y);
+            let (bx, by) = (*o1.x, *o1.y);
+            let (cx, cy) = (*o2.x, *o2.y);
+
+            // expanded (bx - ax) * (cy - ay) - (cx - ax) * (by - ay)
+            let terms = [
+                (bx, cy),
+                (-bx, ay),
+                (-ax, cy),
+                (ax, ay),
+                (-cx, by),
+                (cx, ay),
+                (ax, by),
+                (-ax, ay),
+            ];
+            sign_of_sum_of_products(&terms) == Ordering::Greater
+        })
+    }
+
+    /// Same as [`Point2::detect_ccw`] with interval arithmetic. This is
+    /// [`Predicate::Uncertain`] when the points are nearly collinear.
+    pub fn is_ccw(&self, o1: &Point2, o2: &Point2) -> Predicate {
+        let a = self.to_interval();
 
-        (bx - ax) * (cy - ay) > (cx - ax) * (by - ay)
+        (o1.to_interval() - a)
+            .cross(&(o2.to_interval() - a))
+            .is_positive()
     }
 }
 
-impl From<(f32, f32)> for Point2 {
-    fn from(value: (f32, f32)) -> Self {
+impl From<(f64, f64)> for Point2 {
+    fn from(value: (f64, f64)) -> Self {
         Point2 {
             x: value.0.into(),
             y: value.1.into(),
@@ -73,7 +108,7 @@ impl From<(f32, f32)> for Point2 {
     }
 }
 
-impl From<Point2> for (f32, f32) {
+impl From<Point2> for (f64, f64) {
     fn from(value: Point2) -> Self {
         (*value.x, *value.y)
     }
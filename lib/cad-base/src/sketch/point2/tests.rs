@@ -130,7 +130,7 @@ fn test_from_point2d_to_tuple() {
     let point = Point2::new(3.3, 6.6);
 
     // Act
-    let tuple: (f32, f32) = point.into();
+    let tuple: (f64, f64) = point.into();
 
     // Assert
     assert_relative_eq!(tuple.0, 3.3);
@@ -272,6 +272,40 @@ fn test_detect_ccw(
     assert_eq!(result, expected);
 }
 
+#[test]
+fn test_detect_ccw_is_exact_for_nearly_collinear_points() {
+    // Arrange
+    // rounded cross product is 0, but the exact one is positive
+    let p = Point2::new(0.49999999999999944, 0.5000000000000002);
+    let o1 = Point2::new(12.0, 12.0);
+    let o2 = Point2::new(24.0, 24.0);
+
+    // Act
+    let predicate = p.is_ccw(&o1, &o2);
+    let result = p.detect_ccw(&o1, &o2);
+
+    // Assert
+    assert_eq!(predicate, Predicate::Uncertain);
+    assert_eq!(result, true);
+    assert_eq!(o1.detect_ccw(&p, &o2), false);
+}
+
+#[test]
+fn test_is_ccw_is_certain_for_clear_turn() {
+    // Arrange
+    let p = Point2::new(0.0, 0.0);
+    let o1 = Point2::new(1.0, 0.0);
+    let o2 = Point2::new(0.0, 1.0);
+
+    // Act
+    let ccw = p.is_ccw(&o1, &o2);
+    let cw = p.is_ccw(&o2, &o1);
+
+    // Assert
+    assert_eq!(ccw, Predicate::True);
+    assert_eq!(cw, Predicate::False);
+}
+
 #[test]
 fn test_round_trip_conversion() {
     // Arrange
@@ -279,7 +313,7 @@ fn test_round_trip_conversion() {
 
     // Act
     let point: Point2 = original_tuple.into();
-    let result_tuple: (f32, f32) = point.into();
+    let result_tuple: (f64, f64) = point.into();
 
     // Assert
     assert_eq!(result_tuple, original_tuple);
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use solver::{environment::Environment, variable::Variable};
+use solver::{environment::Environment, equation::Equation, variable::Variable};
 
 use crate::{arena::Gen, index_impl, sketch::constraint::Constraint};
 
@@ -8,6 +8,12 @@ use crate::{arena::Gen, index_impl, sketch::constraint::Constraint};
 pub struct VariableIndex(u64);
 index_impl!(VariableIndex);
 
+impl From<VariableIndex> for Equation {
+    fn from(value: VariableIndex) -> Self {
+        Equation::variable(&value.to_string())
+    }
+}
+
 /// Scoping defined variable.
 #[derive(Debug, Clone)]
 pub struct VariableArena {
@@ -42,7 +48,7 @@ impl VariableArena {
     }
 
     /// Register a variable.
-    pub fn register(&mut self, value: f32) -> VariableIndex {
+    pub fn register(&mut self, value: f64) -> VariableIndex {
         let id: VariableIndex = self.id_gen.next();
         let v = Variable::new(&id.to_string(), value);
 
@@ -66,6 +72,12 @@ impl VariableArena {
     }
 }
 
+impl Default for VariableArena {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct ConstraintIndex(u64);
 index_impl!(ConstraintIndex);
@@ -76,6 +88,9 @@ pub struct ConstraintArena {
     id_gen: Gen,
 
     constraints: HashMap<ConstraintIndex, Constraint>,
+
+    /// Constraints by the text of the canonical form of the equation, to find equivalent ones
+    canonicals: HashMap<String, Vec<ConstraintIndex>>,
 }
 
 impl ConstraintArena {
@@ -84,19 +99,38 @@ impl ConstraintArena {
         Self {
             id_gen: Gen::new(),
             constraints: HashMap::new(),
+            canonicals: HashMap::new(),
         }
     }
 
     /// Register a constraint
     pub fn register(&mut self, constraint: Constraint) -> ConstraintIndex {
         let id = self.id_gen.next();
-        self.constraints.insert(id, constraint);
+        self.insert(id, constraint);
         id
     }
 
     /// Deregister a constraint
     pub fn deregister(&mut self, id: &ConstraintIndex) -> Option<Constraint> {
-        self.constraints.remove(id)
+        let constraint = self.constraints.remove(id)?;
+
+        if let Some(key) = canonical_key(&constraint.equation)
+            && let Some(ids) = self.canonicals.get_mut(&key)
+        {
+            ids.retain(|i| i != id);
+            if ids.is_empty() {
+                self.canonicals.remove(&key);
+            }
+        }
+        Some(constraint)
+    }
+
+    /// Replace the constraint of the id. Returns the old constraint, or `None` if the id is not
+    /// registered.
+    pub fn replace(&mut self, id: &ConstraintIndex, constraint: Constraint) -> Option<Constraint> {
+        let old = self.deregister(id)?;
+        self.insert(*id, constraint);
+        Some(old)
     }
 
     /// Get a constraint by id
@@ -104,12 +138,47 @@ impl ConstraintArena {
         self.constraints.get(id)
     }
 
-    /// Get a mutable constraint by id
-    pub fn get_mut(&mut self, id: &ConstraintIndex) -> Option<&mut Constraint> {
-        self.constraints.get_mut(id)
+    /// Get all constraints, ordered by id
+    pub fn iter(&self) -> impl Iterator<Item = (ConstraintIndex, &Constraint)> {
+        let mut constraints: Vec<_> = self.constraints.iter().map(|(k, v)| (*k, v)).collect();
+        constraints.sort_by_key(|(k, _)| u64::from(*k));
+
+        constraints.into_iter()
+    }
+
+    /// Find a constraint that has an equation equivalent to the given one, to avoid registering
+    /// duplicated constraints. Constraints are `equation = 0`, so equations that differ only in sign
+    /// or scale are equivalent.
+    pub fn find_equivalent(&self, equation: &Equation) -> Option<ConstraintIndex> {
+        self.canonicals
+            .get(&canonical_key(equation)?)
+            .and_then(|ids| ids.first())
+            .copied()
+    }
+
+    fn insert(&mut self, id: ConstraintIndex, constraint: Constraint) {
+        if let Some(key) = canonical_key(&constraint.equation) {
+            self.canonicals.entry(key).or_default().push(id);
+        }
+        self.constraints.insert(id, constraint);
+    }
+}
+
+impl Default for ConstraintArena {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
+/// Text of the canonical form of the constraint equation. The text is lossless, so equal texts are
+/// equal canonical forms.
+fn canonical_key(equation: &Equation) -> Option<String> {
+    equation
+        .canonical_constraint()
+        .ok()
+        .map(|canonical| canonical.to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -356,4 +425,81 @@ mod tests {
         assert_eq!(map.get(&id2.to_string()), Some(&id2));
         assert_eq!(map.get(&id1.to_string()), None);
     }
+
+    #[test]
+    fn find_equivalent_returns_constraint_with_same_canonical_form() {
+        // Arrange
+        let mut variables = VariableArena::new();
+        let x = variables.register(1.0);
+        let y = variables.register(2.0);
+        let mut constraints = ConstraintArena::new();
+        let equation = solver::equation::parse(&format!("{} - 2 * {}", x, y)).unwrap();
+        let id = constraints.register(Constraint::new("c", equation, &variables).unwrap());
+        let other = solver::equation::parse(&format!("{} * 2", y)).unwrap();
+        constraints.register(Constraint::new("d", other, &variables).unwrap());
+
+        // Act
+        let equivalent = constraints
+            .find_equivalent(&solver::equation::parse(&format!("-{} - {} + {}", y, y, x)).unwrap());
+        let different = constraints
+            .find_equivalent(&solver::equation::parse(&format!("{} + 2 * {}", x, y)).unwrap());
+
+        // Assert
+        assert_eq!(equivalent, Some(id));
+        assert_eq!(different, None);
+    }
+
+    #[test]
+    fn find_equivalent_ignores_sign_and_scale_of_equation() {
+        // Arrange
+        let mut variables = VariableArena::new();
+        let x = variables.register(1.0);
+        let y = variables.register(2.0);
+        let mut constraints = ConstraintArena::new();
+        let equation = solver::equation::parse(&format!("{} - 2 * {} + 1", x, y)).unwrap();
+        let id = constraints.register(Constraint::new("c", equation, &variables).unwrap());
+        let parse = |input: String| solver::equation::parse(&input).unwrap();
+
+        // Act
+        let negated = constraints.find_equivalent(&parse(format!("2 * {} - {} - 1", y, x)));
+        let scaled = constraints.find_equivalent(&parse(format!("3 * {} - 6 * {} + 3", x, y)));
+        let divided = constraints.find_equivalent(&parse(format!("1 / 0 + {}", x)));
+
+        // Assert
+        assert_eq!(negated, Some(id));
+        assert_eq!(scaled, Some(id));
+        assert_eq!(divided, None);
+    }
+
+    #[test]
+    fn find_equivalent_follows_replaced_and_deregistered_constraints() {
+        // Arrange
+        let mut variables = VariableArena::new();
+        let x = variables.register(1.0);
+        let mut constraints = ConstraintArena::new();
+        let parse = |input: String| solver::equation::parse(&input).unwrap();
+        let id = constraints
+            .register(Constraint::new("c", parse(format!("{} - 1", x)), &variables).unwrap());
+        let kept = constraints
+            .register(Constraint::new("d", parse(format!("{} - 3", x)), &variables).unwrap());
+
+        // Act
+        constraints.replace(
+            &id,
+            Constraint::new("c", parse(format!("{} - 2", x)), &variables).unwrap(),
+        );
+        let old = constraints.find_equivalent(&parse(format!("{} - 1", x)));
+        let new = constraints.find_equivalent(&parse(format!("2 * {} - 4", x)));
+        constraints.deregister(&id);
+        let removed = constraints.find_equivalent(&parse(format!("{} - 2", x)));
+
+        // Assert
+        assert_eq!(old, None);
+        assert_eq!(new, Some(id));
+        assert_eq!(removed, None);
+        assert_eq!(
+            constraints.find_equivalent(&parse(format!("{} - 3", x))),
+            Some(kept)
+        );
+    }
 }
@@ -0,0 +1,243 @@
+use std::f64::consts::{PI, TAU};
+
+use color_eyre::eyre::{Result, eyre};
+
+use crate::{
+    id::GeometryId,
+    sketch::{
+        Point2,
+        geometric::{PointVariables, SketchConstraint},
+        geometry::{Arc, Geometry, GeometryPoint, LineSegment, SketchPoint},
+        scope::VariableArena,
+    },
+};
+
+/// A composite shape that is made from lines and arcs with constraints to keep the shape.
+///
+/// Geometries of a shape share variables of their corners, so corners are coincident without
+/// constraints.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SketchShape {
+    /// Rectangle along axes from a corner to the opposite corner
+    Rectangle(Point2, Point2),
+
+    /// Rectangle along axes from the center to a corner
+    CenterRectangle { center: Point2, corner: Point2 },
+
+    /// Regular polygon around the center, with a vertex and the number of sides
+    Polygon {
+        center: Point2,
+        vertex: Point2,
+        sides: usize,
+    },
+
+    /// Slot around the line segment from the start to the end, with the radius of its ends
+    Slot {
+        start: Point2,
+        end: Point2,
+        radius: f64,
+    },
+}
+
+impl SketchShape {
+    /// Get the name of the kind of this shape
+    pub fn name(&self) -> &'static str {
+        match self {
+            SketchShape::Rectangle(..) => "rectangle",
+            SketchShape::CenterRectangle { .. } => "center_rectangle",
+            SketchShape::Polygon { .. } => "polygon",
+            SketchShape::Slot { .. } => "slot",
+        }
+    }
+
+    /// Make geometries of this shape. Geometries are in counterclockwise order around the shape,
+    /// and the center of a center rectangle or a polygon is a point after them.
+    ///
+    /// # Errors
+    /// Returns error when the shape has no area.
+    pub(crate) fn geometries(&self, registrar: &mut VariableArena) -> Result<Vec<Geometry>> {
+        match self {
+            SketchShape::Rectangle(p1, p2) => rectangle(p1, p2, registrar),
+            SketchShape::CenterRectangle { center, corner } => {
+                let opposite =
+                    Point2::new(2.0 * *center.x - *corner.x, 2.0 * *center.y - *corner.y);
+                let mut geometries = rectangle(&opposite, corner, registrar)?;
+                geometries.push(Geometry::Point(SketchPoint::new(center, registrar)));
+                Ok(geometries)
+            }
+            SketchShape::Polygon {
+                center,
+                vertex,
+                sides,
+            } => {
+                let radius = (*vertex.x - *center.x).hypot(*vertex.y - *center.y);
+                if *sides < 3 {
+                    return Err(eyre!("Polygon needs at least 3 sides"));
+                }
+                if radius == 0.0 {
+                    return Err(eyre!("Vertex of polygon must not be the center"));
+                }
+
+                let start = (*vertex.y - *center.y).atan2(*vertex.x - *center.x);
+                let vertices = (0..*sides)
+                    .map(|i| {
+                        let angle = start + TAU * i as f64 / *sides as f64;
+                        Point2::new(
+                            *center.x + radius * angle.cos(),
+                            *center.y + radius * angle.sin(),
+                        )
+                    })
+                    .collect::<Vec<_>>();
+
+                let mut geometries = closed_lines(&vertices, registrar);
+                geometries.push(Geometry::Point(SketchPoint::new(center, registrar)));
+                Ok(geometries)
+            }
+            SketchShape::Slot { start, end, radius } => {
+                let (dx, dy) = (*end.x - *start.x, *end.y - *start.y);
+                let length = dx.hypot(dy);
+                if length == 0.0 || *radius <= 0.0 {
+                    return Err(eyre!("Slot must have length and positive radius"));
+                }
+
+                // normal of the center line, to the left side
+                let (nx, ny) = (-dy / length * radius, dx / length * radius);
+                let at = |p: &Point2, sign: f64| Point2::new(*p.x + sign * nx, *p.y + sign * ny);
+                let register = |p: &Point2, registrar: &mut VariableArena| -> PointVariables {
+                    (registrar.register(*p.x), registrar.register(*p.y))
+                };
+
+                let start_right = register(&at(start, -1.0), registrar);
+                let end_right = register(&at(end, -1.0), registrar);
+                let end_left = register(&at(end, 1.0), registrar);
+                let start_left = register(&at(start, 1.0), registrar);
+                let end_center = register(end, registrar);
+                let start_center = register(start, registrar);
+
+                Ok(vec![
+                    line(start_right, end_right),
+                    arc(end_center, end_right, end_left),
+                    line(end_left, start_left),
+                    arc(start_center, start_left, start_right),
+                ])
+            }
+        }
+    }
+
+    /// Make constraints to keep this shape. `ids` are ids of geometries in order of
+    /// [`SketchShape::geometries`].
+    pub(crate) fn constraints(&self, ids: &[GeometryId]) -> Vec<SketchConstraint> {
+        match self {
+            SketchShape::Rectangle(..) => axis_aligned(ids),
+            SketchShape::CenterRectangle { .. } => {
+                let [bottom, _, top, _, center] = ids else {
+                    return vec![];
+                };
+
+                // the opposite corner is the corner turned by a half around the center, so the
+                // center is the middle of the diagonal
+                let mut constraints = axis_aligned(&ids[..4]);
+                constraints.push(SketchConstraint::Rotated(
+                    GeometryPoint::start(*bottom),
+                    GeometryPoint::start(*top),
+                    GeometryPoint::center(*center),
+                    PI,
+                ));
+                constraints
+            }
+            SketchShape::Polygon { sides, .. } => {
+                let Some((center, lines)) = ids.split_last() else {
+                    return vec![];
+                };
+
+                // each vertex is the previous one turned around the center, so vertices stay on a
+                // circle in counterclockwise order. The last turn follows from others.
+                let angle = TAU / *sides as f64;
+                lines
+                    .windows(2)
+                    .map(|w| {
+                        SketchConstraint::Rotated(
+                            GeometryPoint::start(w[0]),
+                            GeometryPoint::start(w[1]),
+                            GeometryPoint::center(*center),
+                            angle,
+                        )
+                    })
+                    .collect()
+            }
+            SketchShape::Slot { .. } => {
+                let [right, end, left, start] = ids else {
+                    return vec![];
+                };
+
+                vec![
+                    SketchConstraint::Parallel(*right, *left),
+                    SketchConstraint::Tangent(*right, *end),
+                    SketchConstraint::Tangent(*left, *end),
+                    SketchConstraint::Tangent(*left, *start),
+                    SketchConstraint::Tangent(*right, *start),
+                ]
+            }
+        }
+    }
+}
+
+/// Make horizontal and vertical constraints of lines of a rectangle from the bottom
+fn axis_aligned(ids: &[GeometryId]) -> Vec<SketchConstraint> {
+    ids.iter()
+        .enumerate()
+        .map(|(i, id)| {
+            if i % 2 == 0 {
+                SketchConstraint::Horizontal(*id)
+            } else {
+                SketchConstraint::Vertical(*id)
+            }
+        })
+        .collect()
+}
+
+/// Make lines of a rectangle from the bottom, in counterclockwise
+fn rectangle(p1: &Point2, p2: &Point2, registrar: &mut VariableArena) -> Result<Vec<Geometry>> {
+    let (left, right) = (p1.x.min(*p2.x), p1.x.max(*p2.x));
+    let (bottom, top) = (p1.y.min(*p2.y), p1.y.max(*p2.y));
+    if left == right || bottom == top {
+        return Err(eyre!("Rectangle must have width and height"));
+    }
+
+    Ok(closed_lines(
+        &[
+            Point2::new(left, bottom),
+            Point2::new(right, bottom),
+            Point2::new(right, top),
+            Point2::new(left, top),
+        ],
+        registrar,
+    ))
+}
+
+/// Make lines through vertices and back to the first one. Adjacent lines share variables.
+fn closed_lines(vertices: &[Point2], registrar: &mut VariableArena) -> Vec<Geometry> {
+    let points = vertices
+        .iter()
+        .map(|p| (registrar.register(*p.x), registrar.register(*p.y)))
+        .collect::<Vec<_>>();
+
+    (0..points.len())
+        .map(|i| line(points[i], points[(i + 1) % points.len()]))
+        .collect()
+}
+
+fn line(start: PointVariables, end: PointVariables) -> Geometry {
+    Geometry::LineSegment(LineSegment {
+        start_points: start.into(),
+        end_points: end.into(),
+    })
+}
+
+fn arc(center: PointVariables, start: PointVariables, end: PointVariables) -> Geometry {
+    Geometry::Arc(Arc {
+        center_points: center.into(),
+        start_points: start.into(),
+        end_points: end.into(),
+    })
+}
@@ -34,7 +34,7 @@ mod attachable_target {
 
     fn make_operation() -> Operation {
         let eq: solver::equation::Equation = 10.0.into();
-        Pad::new(&eq).into()
+        Pad::new(&eq).unwrap().into()
     }
 
     struct OneSolidEvaluator;
@@ -121,13 +121,30 @@ mod attachable_target {
         assert!(result.is_none());
     }
 
+    #[test]
+    fn to_plane_returns_none_for_face_variant() {
+        // Arrange
+        let (engine, _, _) = make_engine_with_body_and_solid();
+        let baseline = engine.baseline();
+        let target = AttachableTarget::Face(crate::refs::FaceRef::new(
+            crate::id::FeatureId::from(1),
+            crate::tag::FaceTag::from(1),
+        ));
+
+        // Act
+        let result = target.to_plane(&baseline);
+
+        // Assert
+        assert!(result.is_none());
+    }
+
     #[test]
     fn to_plane_ref_returns_some_for_plane_variant() {
         // Arrange
         let mut bodies = BodyPerspective::new();
         let body_id = bodies.add_body();
         let plane_ref = bodies.to_x_plane_ref(&body_id).unwrap();
-        let target = AttachableTarget::Plane(plane_ref.clone());
+        let target = AttachableTarget::Plane(plane_ref);
 
         // Act
         let result = target.to_plane_ref();
@@ -154,6 +171,8 @@ mod attachable_target {
 
 mod sketch {
     use super::*;
+    use crate::sketch::edge::EdgeCurve;
+    use crate::sketch::geometry::{Arc, Circle};
 
     mod add_geometry {
         use super::*;
@@ -166,9 +185,11 @@ mod sketch {
             let end = Point2::new(1.0, 1.0);
 
             // Act
-            let geometry_id = sketch.add_geometry(|scope| {
-                Geometry::LineSegment(LineSegment::from_points(&start, &end, scope))
-            });
+            let geometry_id = sketch
+                .add_geometry(|scope| {
+                    Geometry::LineSegment(LineSegment::from_points(&start, &end, scope))
+                })
+                .unwrap();
 
             // Assert
             assert!(sketch.remove_geometry(&geometry_id).is_some());
@@ -182,16 +203,42 @@ mod sketch {
             let end = Point2::new(1.0, 1.0);
 
             // Act
-            let geometry_id1 = sketch.add_geometry(|scope| {
-                Geometry::LineSegment(LineSegment::from_points(&start, &end, scope))
-            });
-            let geometry_id2 = sketch.add_geometry(|scope| {
-                Geometry::LineSegment(LineSegment::from_points(&start, &end, scope))
-            });
+            let geometry_id1 = sketch
+                .add_geometry(|scope| {
+                    Geometry::LineSegment(LineSegment::from_points(&start, &end, scope))
+                })
+                .unwrap();
+            let geometry_id2 = sketch
+                .add_geometry(|scope| {
+                    Geometry::LineSegment(LineSegment::from_points(&start, &end, scope))
+                })
+                .unwrap();
 
             // Assert
             assert_ne!(geometry_id1, geometry_id2);
         }
+
+        #[test]
+        fn add_geometry_fails_when_constraints_of_geometry_can_not_be_made() {
+            // Arrange
+            let mut sketch = Sketch::new("TestSketch", BodyId::from(1), &make_attach_target());
+            let mut other = VariableArena::new();
+            let arc = crate::sketch::Arc::from_points(
+                &Point2::new(0.0, 0.0),
+                &Point2::new(1.0, 0.0),
+                &Point2::new(0.0, 1.0),
+                &mut other,
+            );
+
+            // Act
+            // variables of the arc are not in the sketch, so the constraint of the arc is invalid
+            let result = sketch.add_geometry(|_| Geometry::Arc(arc));
+
+            // Assert
+            assert!(result.is_err());
+            assert!(sketch.resolve_edges().unwrap().is_empty());
+            assert!(sketch.constraints().is_empty());
+        }
     }
 
     mod resolve_edges {
@@ -217,9 +264,11 @@ mod sketch {
             let mut sketch = Sketch::new("TestSketch", BodyId::from(1), &make_attach_target());
             let start = Point2::new(1.0, 2.0);
             let end = Point2::new(3.0, 4.0);
-            sketch.add_geometry(|scope| {
-                Geometry::LineSegment(LineSegment::from_points(&start, &end, scope))
-            });
+            sketch
+                .add_geometry(|scope| {
+                    Geometry::LineSegment(LineSegment::from_points(&start, &end, scope))
+                })
+                .unwrap();
 
             // Act
             let edges = sketch.resolve_edges().unwrap();
@@ -236,27 +285,33 @@ mod sketch {
         fn resolves_multiple_line_segments() {
             // Arrange
             let mut sketch = Sketch::new("TestSketch", BodyId::from(1), &make_attach_target());
-            sketch.add_geometry(|scope| {
-                Geometry::LineSegment(LineSegment::from_points(
-                    &Point2::new(0.0, 0.0),
-                    &Point2::new(1.0, 0.0),
-                    scope,
-                ))
-            });
-            sketch.add_geometry(|scope| {
-                Geometry::LineSegment(LineSegment::from_points(
-                    &Point2::new(1.0, 0.0),
-                    &Point2::new(1.0, 1.0),
-                    scope,
-                ))
-            });
-            sketch.add_geometry(|scope| {
-                Geometry::LineSegment(LineSegment::from_points(
-                    &Point2::new(1.0, 1.0),
-                    &Point2::new(0.0, 0.0),
-                    scope,
-                ))
-            });
+            sketch
+                .add_geometry(|scope| {
+                    Geometry::LineSegment(LineSegment::from_points(
+                        &Point2::new(0.0, 0.0),
+                        &Point2::new(1.0, 0.0),
+                        scope,
+                    ))
+                })
+                .unwrap();
+            sketch
+                .add_geometry(|scope| {
+                    Geometry::LineSegment(LineSegment::from_points(
+                        &Point2::new(1.0, 0.0),
+                        &Point2::new(1.0, 1.0),
+                        scope,
+                    ))
+                })
+                .unwrap();
+            sketch
+                .add_geometry(|scope| {
+                    Geometry::LineSegment(LineSegment::from_points(
+                        &Point2::new(1.0, 1.0),
+                        &Point2::new(0.0, 0.0),
+                        scope,
+                    ))
+                })
+                .unwrap();
 
             // Act
             let edges = sketch.resolve_edges().unwrap();
@@ -269,13 +324,15 @@ mod sketch {
         fn does_not_resolve_removed_geometry() {
             // Arrange
             let mut sketch = Sketch::new("TestSketch", BodyId::from(1), &make_attach_target());
-            let id = sketch.add_geometry(|scope| {
-                Geometry::LineSegment(LineSegment::from_points(
-                    &Point2::new(0.0, 0.0),
-                    &Point2::new(1.0, 1.0),
-                    scope,
-                ))
-            });
+            let id = sketch
+                .add_geometry(|scope| {
+                    Geometry::LineSegment(LineSegment::from_points(
+                        &Point2::new(0.0, 0.0),
+                        &Point2::new(1.0, 1.0),
+                        scope,
+                    ))
+                })
+                .unwrap();
             sketch.remove_geometry(&id);
 
             // Act
@@ -289,13 +346,15 @@ mod sketch {
         fn resolves_edges_with_negative_coordinates() {
             // Arrange
             let mut sketch = Sketch::new("TestSketch", BodyId::from(1), &make_attach_target());
-            sketch.add_geometry(|scope| {
-                Geometry::LineSegment(LineSegment::from_points(
-                    &Point2::new(-5.0, -3.0),
-                    &Point2::new(-1.0, -2.0),
-                    scope,
-                ))
-            });
+            sketch
+                .add_geometry(|scope| {
+                    Geometry::LineSegment(LineSegment::from_points(
+                        &Point2::new(-5.0, -3.0),
+                        &Point2::new(-1.0, -2.0),
+                        scope,
+                    ))
+                })
+                .unwrap();
 
             // Act
             let edges = sketch.resolve_edges().unwrap();
@@ -307,6 +366,58 @@ mod sketch {
             assert_relative_eq!(*edges[0].end.x, -1.0);
             assert_relative_eq!(*edges[0].end.y, -2.0);
         }
+
+        #[test]
+        fn resolves_circle_as_closed_arc() {
+            // Arrange
+            let mut sketch = Sketch::new("TestSketch", BodyId::from(1), &make_attach_target());
+            sketch
+                .add_geometry(|scope| {
+                    Geometry::Circle(Circle::new(&Point2::new(1.0, 2.0), 3.0, scope))
+                })
+                .unwrap();
+
+            // Act
+            let edges = sketch.resolve_edges().unwrap();
+
+            // Assert
+            assert_eq!(edges.len(), 1);
+            assert_eq!(
+                *edges[0].curve,
+                EdgeCurve::Arc {
+                    center: Point2::new(1.0, 2.0),
+                    radius: 3.0
+                }
+            );
+            assert_relative_eq!(*edges[0].start.x, 4.0);
+            assert_relative_eq!(*edges[0].start.y, 2.0);
+            assert_relative_eq!(edges[0].sweep().unwrap(), std::f64::consts::TAU);
+        }
+
+        #[test]
+        fn resolves_arc_with_end_on_circle() {
+            // Arrange
+            let mut sketch = Sketch::new("TestSketch", BodyId::from(1), &make_attach_target());
+            sketch
+                .add_geometry(|scope| {
+                    Geometry::Arc(Arc::from_points(
+                        &Point2::new(0.0, 0.0),
+                        &Point2::new(2.0, 0.0),
+                        &Point2::new(0.0, 5.0),
+                        scope,
+                    ))
+                })
+                .unwrap();
+
+            // Act
+            let edges = sketch.resolve_edges().unwrap();
+
+            // Assert
+            assert_eq!(edges.len(), 1);
+            assert_relative_eq!(*edges[0].end.x, 0.0, epsilon = 1e-12);
+            assert_relative_eq!(*edges[0].end.y, 2.0);
+            assert_relative_eq!(edges[0].sweep().unwrap(), std::f64::consts::FRAC_PI_2);
+        }
     }
 
     mod remove_geometry {
@@ -318,9 +429,11 @@ mod sketch {
             let mut sketch = Sketch::new("TestSketch", BodyId::from(1), &make_attach_target());
             let start = Point2::new(0.0, 0.0);
             let end = Point2::new(1.0, 1.0);
-            let geometry_id = sketch.add_geometry(|scope| {
-                Geometry::LineSegment(LineSegment::from_points(&start, &end, scope))
-            });
+            let geometry_id = sketch
+                .add_geometry(|scope| {
+                    Geometry::LineSegment(LineSegment::from_points(&start, &end, scope))
+                })
+                .unwrap();
 
             // Act
             let result = sketch.remove_geometry(&geometry_id);
@@ -349,15 +462,21 @@ mod sketch {
             let mut sketch = Sketch::new("TestSketch", BodyId::from(1), &make_attach_target());
             let start = Point2::new(0.0, 0.0);
             let end = Point2::new(1.0, 1.0);
-            let geometry_id1 = sketch.add_geometry(|scope| {
-                Geometry::LineSegment(LineSegment::from_points(&start, &end, scope))
-            });
-            let geometry_id2 = sketch.add_geometry(|scope| {
-                Geometry::LineSegment(LineSegment::from_points(&start, &end, scope))
-            });
-            let geometry_id3 = sketch.add_geometry(|scope| {
-                Geometry::LineSegment(LineSegment::from_points(&start, &end, scope))
-            });
+            let geometry_id1 = sketch
+                .add_geometry(|scope| {
+                    Geometry::LineSegment(LineSegment::from_points(&start, &end, scope))
+                })
+                .unwrap();
+            let geometry_id2 = sketch
+                .add_geometry(|scope| {
+                    Geometry::LineSegment(LineSegment::from_points(&start, &end, scope))
+                })
+                .unwrap();
+            let geometry_id3 = sketch
+                .add_geometry(|scope| {
+                    Geometry::LineSegment(LineSegment::from_points(&start, &end, scope))
+                })
+                .unwrap();
 
             // Act
             sketch.remove_geometry(&geometry_id2);
@@ -369,3 +488,1648 @@ mod sketch {
         }
     }
 }
+
+mod constraints {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use solver::equation::parse;
+
+    fn make_sketch() -> (Sketch, LineSegment) {
+        let mut sketch = Sketch::new("TestSketch", BodyId::from(1), &make_attach_target());
+        let id = sketch
+            .add_geometry(|scope| {
+                Geometry::LineSegment(LineSegment::from_points(
+                    &Point2::new(1.0, 2.0),
+                    &Point2::new(3.0, 4.0),
+                    scope,
+                ))
+            })
+            .unwrap();
+        let Some(Geometry::LineSegment(line)) = sketch.geometry(&id).cloned() else {
+            unreachable!()
+        };
+        (sketch, line)
+    }
+
+    #[test]
+    fn add_constraint_registers_constraint() {
+        // Arrange
+        let (mut sketch, line) = make_sketch();
+        let equation = parse(&format!("{} - 5", line.start_points.0)).unwrap();
+
+        // Act
+        let id = sketch.add_constraint("fix x", equation.clone()).unwrap();
+
+        // Assert
+        let constraint = sketch.constraint(&id).unwrap();
+        assert_eq!(*constraint.name, "fix x");
+        assert_eq!(*constraint.equation, equation);
+        assert_eq!(*constraint.related_variables, vec![line.start_points.0]);
+    }
+
+    #[test]
+    fn add_constraint_fails_with_unknown_variable() {
+        // Arrange
+        let (mut sketch, _) = make_sketch();
+        let equation = parse("VariableIndex999 - 5").unwrap();
+
+        // Act
+        let result = sketch.add_constraint("unknown", equation);
+
+        // Assert
+        assert!(result.is_err());
+        assert!(sketch.constraints().is_empty());
+    }
+
+    #[test]
+    fn add_constraint_rejects_equivalent_constraint() {
+        // Arrange
+        let (mut sketch, line) = make_sketch();
+        let x = line.start_points.0;
+        sketch
+            .add_constraint("c", parse(&format!("{} - 5", x)).unwrap())
+            .unwrap();
+
+        // Act
+        let result = sketch.add_constraint("d", parse(&format!("2 * {} - 10", x)).unwrap());
+
+        // Assert
+        assert!(result.is_err());
+        assert_eq!(sketch.constraints().len(), 1);
+    }
+
+    #[test]
+    fn update_constraint_keeps_index_and_name() {
+        // Arrange
+        let (mut sketch, line) = make_sketch();
+        let id = sketch
+            .add_constraint("c", parse(&format!("{} - 5", line.start_points.0)).unwrap())
+            .unwrap();
+        let equation = parse(&format!("{} - 1", line.end_points.1)).unwrap();
+
+        // Act
+        sketch.update_constraint(&id, equation.clone()).unwrap();
+
+        // Assert
+        let constraint = sketch.constraint(&id).unwrap();
+        assert_eq!(*constraint.name, "c");
+        assert_eq!(*constraint.equation, equation);
+        assert_eq!(*constraint.related_variables, vec![line.end_points.1]);
+    }
+
+    #[test]
+    fn update_constraint_fails_for_nonexistent_constraint() {
+        // Arrange
+        let (mut sketch, line) = make_sketch();
+        let equation = parse(&format!("{} - 5", line.start_points.0)).unwrap();
+
+        // Act
+        let result = sketch.update_constraint(&ConstraintIndex::from(999), equation);
+
+        // Assert
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn remove_constraint_returns_removed_constraint() {
+        // Arrange
+        let (mut sketch, line) = make_sketch();
+        let id = sketch
+            .add_constraint("c", parse(&format!("{} - 5", line.start_points.0)).unwrap())
+            .unwrap();
+
+        // Act
+        let result = sketch.remove_constraint(&id);
+
+        // Assert
+        assert!(result.is_ok());
+        assert!(sketch.constraint(&id).is_none());
+        assert_eq!(
+            sketch.remove_constraint(&id).err(),
+            Some(RemoveConstraintError::NotFound(id))
+        );
+    }
+
+    #[test]
+    fn constraints_are_ordered_by_index() {
+        // Arrange
+        let (mut sketch, line) = make_sketch();
+        let ids = [line.start_points.0, line.start_points.1, line.end_points.0]
+            .iter()
+            .map(|v| {
+                sketch
+                    .add_constraint(&v.to_string(), parse(&format!("{} - 5", v)).unwrap())
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        // Act
+        let result = sketch
+            .constraints()
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect::<Vec<_>>();
+
+        // Assert
+        assert_eq!(result, ids);
+    }
+}
+
+mod solve {
+    use super::*;
+    use approx::assert_relative_eq;
+    use pretty_assertions::assert_eq;
+    use solver::{DimensionSpecificationStatus, equation::parse};
+
+    fn make_sketch() -> (Sketch, LineSegment) {
+        let mut sketch = Sketch::new("TestSketch", BodyId::from(1), &make_attach_target());
+        let id = sketch
+            .add_geometry(|scope| {
+                Geometry::LineSegment(LineSegment::from_points(
+                    &Point2::new(1.0, 2.0),
+                    &Point2::new(3.0, 4.0),
+                    scope,
+                ))
+            })
+            .unwrap();
+        let Some(Geometry::LineSegment(line)) = sketch.geometry(&id).cloned() else {
+            unreachable!()
+        };
+        (sketch, line)
+    }
+
+    #[test]
+    fn solve_writes_values_back_to_geometries() {
+        // Arrange
+        let (mut sketch, line) = make_sketch();
+        let (sx, sy) = *line.start_points;
+        let (ex, ey) = *line.end_points;
+        for equation in [
+            format!("{} - 5", sx),
+            format!("{} - {}", sy, sx),
+            format!("{} - {} - 2", ex, sx),
+            format!("{} - 1", ey),
+        ] {
+            sketch
+                .add_constraint(&equation, parse(&equation).unwrap())
+                .unwrap();
+        }
+
+        // Act
+        let result = sketch.solve().unwrap();
+
+        // Assert
+        assert_eq!(result.status, DimensionSpecificationStatus::WellConstrained);
+        let edges = sketch.resolve_edges().unwrap();
+        assert_relative_eq!(*edges[0].start.x, 5.0, epsilon = 1e-6);
+        assert_relative_eq!(*edges[0].start.y, 5.0, epsilon = 1e-6);
+        assert_relative_eq!(*edges[0].end.x, 7.0, epsilon = 1e-6);
+        assert_relative_eq!(*edges[0].end.y, 1.0, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn solve_keeps_free_variables_of_under_constrained_sketch() {
+        // Arrange
+        let (mut sketch, line) = make_sketch();
+        let equation = format!("{} - 5", line.start_points.0);
+        sketch
+            .add_constraint("x", parse(&equation).unwrap())
+            .unwrap();
+
+        // Act
+        let result = sketch.solve().unwrap();
+
+        // Assert
+        assert_eq!(
+            result.status,
+            DimensionSpecificationStatus::UnderConstrained
+        );
+        let edges = sketch.resolve_edges().unwrap();
+        assert_relative_eq!(*edges[0].start.x, 5.0, epsilon = 1e-6);
+        assert_relative_eq!(*edges[0].start.y, 2.0, epsilon = 1e-6);
+        assert_relative_eq!(*edges[0].end.x, 3.0, epsilon = 1e-6);
+        assert_relative_eq!(*edges[0].end.y, 4.0, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn solve_does_not_change_variables_when_failed() {
+        // Arrange
+        let (mut sketch, line) = make_sketch();
+        let (x, sy) = *line.start_points;
+        let (ex, ey) = *line.end_points;
+        for equation in [
+            format!("{}^2 + 1", x),
+            format!("{} - 1", sy),
+            format!("{} - 1", ex),
+            format!("{} - 1", ey),
+        ] {
+            sketch
+                .add_constraint(&equation, parse(&equation).unwrap())
+                .unwrap();
+        }
+
+        // Act
+        let result = sketch.solve();
+
+        // Assert
+        assert!(result.is_err());
+        assert_eq!(*sketch.variables().get(&x).unwrap().value, 1.0);
+    }
+
+    #[test]
+    fn status_of_sketch_without_constraints() {
+        // Arrange
+        let (sketch, _) = make_sketch();
+
+        // Act
+        let result = sketch.status();
+
+        // Assert
+        assert_eq!(result, DimensionSpecificationStatus::UnderConstrained);
+    }
+
+    #[test]
+    fn status_follows_changes_of_sketch() {
+        // Arrange
+        let (mut sketch, line) = make_sketch();
+        let (sx, sy) = *line.start_points;
+        let (ex, ey) = *line.end_points;
+        let before = sketch.status();
+
+        // Act
+        let mut ids = vec![];
+        for equation in [
+            format!("{} - 5", sx),
+            format!("{} - 1", sy),
+            format!("{} - 2", ex),
+            format!("{} - 1", ey),
+        ] {
+            ids.push(
+                sketch
+                    .add_constraint(&equation, parse(&equation).unwrap())
+                    .unwrap(),
+            );
+        }
+        let added = sketch.status();
+        sketch.remove_constraint(&ids[0]).unwrap();
+        let removed = sketch.status();
+
+        // Assert
+        assert_eq!(before, DimensionSpecificationStatus::UnderConstrained);
+        assert_eq!(added, DimensionSpecificationStatus::WellConstrained);
+        assert_eq!(removed, DimensionSpecificationStatus::UnderConstrained);
+    }
+}
+
+mod sketch_constraints {
+    use super::*;
+    use crate::id::ConstraintId;
+    use crate::sketch::{GeometryPoint, SketchConstraint};
+    use approx::assert_relative_eq;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+    use solver::equation::parse;
+
+    fn add_line(sketch: &mut Sketch, start: (f64, f64), end: (f64, f64)) -> GeometryId {
+        sketch
+            .add_geometry(|scope| {
+                Geometry::LineSegment(LineSegment::from_points(
+                    &Point2::new(start.0, start.1),
+                    &Point2::new(end.0, end.1),
+                    scope,
+                ))
+            })
+            .unwrap()
+    }
+
+    fn value_of(sketch: &Sketch, point: GeometryPoint) -> (f64, f64) {
+        let (x, y) = sketch
+            .geometry(&point.geometry)
+            .unwrap()
+            .point(point.kind)
+            .unwrap();
+        let variables = sketch.variables();
+
+        (
+            *variables.get(&x).unwrap().value,
+            *variables.get(&y).unwrap().value,
+        )
+    }
+
+    fn direction_of(sketch: &Sketch, line: GeometryId) -> (f64, f64) {
+        let start = value_of(sketch, GeometryPoint::start(line));
+        let end = value_of(sketch, GeometryPoint::end(line));
+
+        (end.0 - start.0, end.1 - start.1)
+    }
+
+    fn make_sketch() -> (Sketch, GeometryId, GeometryId) {
+        let mut sketch = Sketch::new("TestSketch", BodyId::from(1), &make_attach_target());
+        let line1 = add_line(&mut sketch, (0.0, 0.0), (2.0, 0.3));
+        let line2 = add_line(&mut sketch, (2.2, 0.5), (2.5, 3.0));
+        (sketch, line1, line2)
+    }
+
+    #[test]
+    fn coincident_joins_points() {
+        // Arrange
+        let (mut sketch, line1, line2) = make_sketch();
+        let (p1, p2) = (GeometryPoint::end(line1), GeometryPoint::start(line2));
+
+        // Act
+        sketch
+            .add_sketch_constraint(SketchConstraint::Coincident(p1, p2))
+            .unwrap();
+        sketch.solve().unwrap();
+
+        // Assert
+        let (v1, v2) = (value_of(&sketch, p1), value_of(&sketch, p2));
+        assert_relative_eq!(v1.0, v2.0, epsilon = 1e-4);
+        assert_relative_eq!(v1.1, v2.1, epsilon = 1e-4);
+    }
+
+    #[rstest]
+    #[case(SketchConstraint::Horizontal, |d: (f64, f64)| d.1)]
+    #[case(SketchConstraint::Vertical, |d: (f64, f64)| d.0)]
+    fn axis_aligned_constraint_aligns_line(
+        #[case] make: fn(GeometryId) -> SketchConstraint,
+        #[case] component: fn((f64, f64)) -> f64,
+    ) {
+        // Arrange
+        let (mut sketch, line1, _) = make_sketch();
+
+        // Act
+        sketch.add_sketch_constraint(make(line1)).unwrap();
+        sketch.solve().unwrap();
+
+        // Assert
+        assert_relative_eq!(component(direction_of(&sketch, line1)), 0.0, epsilon = 1e-4);
+    }
+
+    #[rstest]
+    #[case(SketchConstraint::Parallel, |a: (f64, f64), b: (f64, f64)| a.0 * b.1 - a.1 * b.0)]
+    #[case(SketchConstraint::Perpendicular, |a: (f64, f64), b: (f64, f64)| a.0 * b.0 + a.1 * b.1)]
+    #[case(
+        SketchConstraint::EqualLength,
+        |a: (f64, f64), b: (f64, f64)| a.0.hypot(a.1) - b.0.hypot(b.1)
+    )]
+    fn constraint_between_lines_is_satisfied(
+        #[case] make: fn(GeometryId, GeometryId) -> SketchConstraint,
+        #[case] residual: fn((f64, f64), (f64, f64)) -> f64,
+    ) {
+        // Arrange
+        let (mut sketch, line1, line2) = make_sketch();
+
+        // Act
+        sketch.add_sketch_constraint(make(line1, line2)).unwrap();
+        sketch.solve().unwrap();
+
+        // Assert
+        let (d1, d2) = (direction_of(&sketch, line1), direction_of(&sketch, line2));
+        assert_relative_eq!(residual(d1, d2), 0.0, epsilon = 1e-4);
+    }
+
+    #[test]
+    fn midpoint_puts_point_at_middle_of_line() {
+        // Arrange
+        let (mut sketch, line1, line2) = make_sketch();
+        let point = GeometryPoint::start(line2);
+
+        // Act
+        sketch
+            .add_sketch_constraint(SketchConstraint::Midpoint(point, line1))
+            .unwrap();
+        sketch.solve().unwrap();
+
+        // Assert
+        let start = value_of(&sketch, GeometryPoint::start(line1));
+        let end = value_of(&sketch, GeometryPoint::end(line1));
+        let result = value_of(&sketch, point);
+        assert_relative_eq!(result.0, (start.0 + end.0) / 2.0, epsilon = 1e-4);
+        assert_relative_eq!(result.1, (start.1 + end.1) / 2.0, epsilon = 1e-4);
+    }
+
+    #[test]
+    fn symmetric_mirrors_points_about_line() {
+        // Arrange
+        let mut sketch = Sketch::new("TestSketch", BodyId::from(1), &make_attach_target());
+        let axis = add_line(&mut sketch, (0.0, 0.0), (0.0, 2.0));
+        let line = add_line(&mut sketch, (-1.0, 0.5), (1.2, 0.7));
+        let (p1, p2) = (GeometryPoint::start(line), GeometryPoint::end(line));
+        sketch
+            .add_sketch_constraint(SketchConstraint::Vertical(axis))
+            .unwrap();
+        sketch
+            .add_sketch_constraint(SketchConstraint::Fixed(
+                GeometryPoint::start(axis),
+                Point2::new(0.0, 0.0),
+            ))
+            .unwrap();
+
+        // Act
+        sketch
+            .add_sketch_constraint(SketchConstraint::Symmetric(p1, p2, axis))
+            .unwrap();
+        sketch.solve().unwrap();
+
+        // Assert
+        let (v1, v2) = (value_of(&sketch, p1), value_of(&sketch, p2));
+        assert_relative_eq!(v1.0, -v2.0, epsilon = 1e-4);
+        assert_relative_eq!(v1.1, v2.1, epsilon = 1e-4);
+    }
+
+    #[test]
+    fn fixed_moves_point_to_position() {
+        // Arrange
+        let (mut sketch, line1, _) = make_sketch();
+        let point = GeometryPoint::end(line1);
+
+        // Act
+        sketch
+            .add_sketch_constraint(SketchConstraint::Fixed(point, Point2::new(3.0, -1.5)))
+            .unwrap();
+        sketch.solve().unwrap();
+
+        // Assert
+        let result = value_of(&sketch, point);
+        assert_relative_eq!(result.0, 3.0, epsilon = 1e-4);
+        assert_relative_eq!(result.1, -1.5, epsilon = 1e-4);
+    }
+
+    #[test]
+    fn tangent_makes_line_touch_circle() {
+        // Arrange
+        let (mut sketch, line1, _) = make_sketch();
+        let circle = sketch
+            .add_geometry(|scope| {
+                Geometry::Circle(crate::sketch::Circle::new(
+                    &Point2::new(1.0, 2.0),
+                    1.0,
+                    scope,
+                ))
+            })
+            .unwrap();
+
+        // Act
+        sketch
+            .add_sketch_constraint(SketchConstraint::Tangent(line1, circle))
+            .unwrap();
+        sketch.solve().unwrap();
+
+        // Assert - the distance from the center to the line is the radius
+        let (sx, sy) = value_of(&sketch, GeometryPoint::start(line1));
+        let (dx, dy) = direction_of(&sketch, line1);
+        let (cx, cy) = value_of(&sketch, GeometryPoint::center(circle));
+        let Some(Geometry::Circle(c)) = sketch.geometry(&circle) else {
+            unreachable!()
+        };
+        let radius = *sketch.variables().get(&c.radius).unwrap().value;
+        let distance = (dx * (cy - sy) - dy * (cx - sx)).abs() / dx.hypot(dy);
+        assert_relative_eq!(distance, radius, epsilon = 1e-4);
+    }
+
+    #[test]
+    fn tangent_fails_for_line_instead_of_circle() {
+        // Arrange
+        let (mut sketch, line1, line2) = make_sketch();
+
+        // Act
+        let result = sketch.add_sketch_constraint(SketchConstraint::Tangent(line1, line2));
+
+        // Assert
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn equal_angle_makes_same_angles_between_lines() {
+        // Arrange
+        let (mut sketch, line1, line2) = make_sketch();
+        let line3 = add_line(&mut sketch, (2.5, 3.0), (1.0, 4.5));
+        let angle =
+            |a: (f64, f64), b: (f64, f64)| (a.0 * b.1 - a.1 * b.0).atan2(a.0 * b.0 + a.1 * b.1);
+
+        // Act
+        sketch
+            .add_sketch_constraint(SketchConstraint::EqualAngle(line1, line2, line3))
+            .unwrap();
+        sketch.solve().unwrap();
+
+        // Assert
+        let (d1, d2, d3) = (
+            direction_of(&sketch, line1),
+            direction_of(&sketch, line2),
+            direction_of(&sketch, line3),
+        );
+        assert_relative_eq!(angle(d1, d2), angle(d2, d3), epsilon = 1e-4);
+    }
+
+    #[test]
+    fn rotated_turns_point_around_center() {
+        // Arrange
+        let (mut sketch, line1, line2) = make_sketch();
+        let (from, to, center) = (
+            GeometryPoint::end(line1),
+            GeometryPoint::end(line2),
+            GeometryPoint::start(line1),
+        );
+
+        // Act
+        sketch
+            .add_sketch_constraint(SketchConstraint::Rotated(
+                from,
+                to,
+                center,
+                std::f64::consts::FRAC_PI_2,
+            ))
+            .unwrap();
+        sketch.solve().unwrap();
+
+        // Assert
+        let (f, t, c) = (
+            value_of(&sketch, from),
+            value_of(&sketch, to),
+            value_of(&sketch, center),
+        );
+        assert_relative_eq!(t.0 - c.0, -(f.1 - c.1), epsilon = 1e-4);
+        assert_relative_eq!(t.1 - c.1, f.0 - c.0, epsilon = 1e-4);
+    }
+
+    #[test]
+    fn add_sketch_constraint_registers_equations() {
+        // Arrange
+        let (mut sketch, line1, line2) = make_sketch();
+
+        // Act
+        let id = sketch
+            .add_sketch_constraint(SketchConstraint::Midpoint(
+                GeometryPoint::start(line2),
+                line1,
+            ))
+            .unwrap();
+
+        // Assert
+        let equations = sketch.equations_of(&id).unwrap();
+        assert_eq!(equations.len(), 2);
+        for index in equations {
+            assert_eq!(*sketch.constraint(index).unwrap().name, "midpoint");
+        }
+        assert_eq!(sketch.constraints().len(), 2);
+    }
+
+    #[test]
+    fn add_sketch_constraint_fails_for_nonexistent_geometry() {
+        // Arrange
+        let (mut sketch, _, _) = make_sketch();
+
+        // Act
+        let result =
+            sketch.add_sketch_constraint(SketchConstraint::Horizontal(GeometryId::from(999)));
+
+        // Assert
+        assert!(result.is_err());
+        assert!(sketch.sketch_constraints().is_empty());
+    }
+
+    #[test]
+    fn add_sketch_constraint_rejects_equivalent_equations() {
+        // Arrange
+        let (mut sketch, line1, line2) = make_sketch();
+        sketch
+            .add_sketch_constraint(SketchConstraint::Parallel(line1, line2))
+            .unwrap();
+
+        // Act
+        let result = sketch.add_sketch_constraint(SketchConstraint::Parallel(line2, line1));
+
+        // Assert
+        assert!(result.is_err());
+        assert_eq!(sketch.sketch_constraints().len(), 1);
+        assert_eq!(sketch.constraints().len(), 1);
+    }
+
+    #[test]
+    fn add_sketch_constraint_rejects_coincident_of_same_point() {
+        // Arrange
+        let (mut sketch, line1, _) = make_sketch();
+        let point = GeometryPoint::start(line1);
+
+        // Act
+        let result = sketch.add_sketch_constraint(SketchConstraint::Coincident(point, point));
+
+        // Assert
+        assert!(result.is_err());
+        assert!(sketch.constraints().is_empty());
+    }
+
+    #[test]
+    fn sketch_constraints_are_ordered_by_id() {
+        // Arrange
+        let (mut sketch, line1, line2) = make_sketch();
+        let id1 = sketch
+            .add_sketch_constraint(SketchConstraint::Horizontal(line1))
+            .unwrap();
+        let id2 = sketch
+            .add_sketch_constraint(SketchConstraint::Vertical(line2))
+            .unwrap();
+
+        // Act
+        let result = sketch.sketch_constraints();
+
+        // Assert
+        assert_eq!(
+            result,
+            vec![
+                (id1, &SketchConstraint::Horizontal(line1)),
+                (id2, &SketchConstraint::Vertical(line2)),
+            ]
+        );
+    }
+
+    #[test]
+    fn update_sketch_constraint_replaces_equations() {
+        // Arrange
+        let (mut sketch, line1, _) = make_sketch();
+        let id = sketch
+            .add_sketch_constraint(SketchConstraint::Horizontal(line1))
+            .unwrap();
+        let old = sketch.equations_of(&id).unwrap().to_vec();
+
+        // Act
+        sketch
+            .update_sketch_constraint(&id, SketchConstraint::Vertical(line1))
+            .unwrap();
+
+        // Assert
+        assert_eq!(
+            sketch.sketch_constraint(&id),
+            Some(&SketchConstraint::Vertical(line1))
+        );
+        assert!(sketch.constraint(&old[0]).is_none());
+        assert_eq!(sketch.constraints().len(), 1);
+        assert_eq!(*sketch.constraints()[0].1.name, "vertical");
+    }
+
+    #[test]
+    fn update_sketch_constraint_keeps_current_on_error() {
+        // Arrange
+        let (mut sketch, line1, _) = make_sketch();
+        let id = sketch
+            .add_sketch_constraint(SketchConstraint::Horizontal(line1))
+            .unwrap();
+        let old = sketch.equations_of(&id).unwrap().to_vec();
+
+        // Act
+        let result =
+            sketch.update_sketch_constraint(&id, SketchConstraint::Vertical(GeometryId::from(999)));
+
+        // Assert
+        assert!(result.is_err());
+        assert_eq!(
+            sketch.sketch_constraint(&id),
+            Some(&SketchConstraint::Horizontal(line1))
+        );
+        assert_eq!(sketch.equations_of(&id).unwrap(), old.as_slice());
+    }
+
+    #[test]
+    fn update_sketch_constraint_fails_for_nonexistent_constraint() {
+        // Arrange
+        let (mut sketch, line1, _) = make_sketch();
+
+        // Act
+        let result = sketch
+            .update_sketch_constraint(&ConstraintId::from(999), SketchConstraint::Vertical(line1));
+
+        // Assert
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn remove_sketch_constraint_removes_equations() {
+        // Arrange
+        let (mut sketch, line1, line2) = make_sketch();
+        let id = sketch
+            .add_sketch_constraint(SketchConstraint::Coincident(
+                GeometryPoint::end(line1),
+                GeometryPoint::start(line2),
+            ))
+            .unwrap();
+
+        // Act
+        let result = sketch.remove_sketch_constraint(&id);
+
+        // Assert
+        assert!(result.is_some());
+        assert!(sketch.sketch_constraint(&id).is_none());
+        assert!(sketch.constraints().is_empty());
+    }
+
+    #[test]
+    fn equations_of_sketch_constraint_can_not_be_changed_directly() {
+        // Arrange
+        let (mut sketch, line1, _) = make_sketch();
+        let id = sketch
+            .add_sketch_constraint(SketchConstraint::Horizontal(line1))
+            .unwrap();
+        let index = sketch.equations_of(&id).unwrap()[0];
+        let (x, _) = sketch
+            .geometry(&line1)
+            .unwrap()
+            .point(PointKind::Start)
+            .unwrap();
+
+        // Act
+        let updated = sketch.update_constraint(&index, parse(&format!("{} - 1", x)).unwrap());
+        let removed = sketch.remove_constraint(&index);
+
+        // Assert
+        assert!(updated.is_err());
+        assert!(matches!(removed, Err(RemoveConstraintError::Owned { .. })));
+        assert!(sketch.constraint(&index).is_some());
+    }
+
+    #[test]
+    fn remove_geometry_removes_referring_sketch_constraints() {
+        // Arrange
+        let (mut sketch, line1, line2) = make_sketch();
+        sketch
+            .add_sketch_constraint(SketchConstraint::Parallel(line1, line2))
+            .unwrap();
+        let kept = sketch
+            .add_sketch_constraint(SketchConstraint::Horizontal(line1))
+            .unwrap();
+
+        // Act
+        sketch.remove_geometry(&line2);
+
+        // Assert
+        let ids = sketch
+            .sketch_constraints()
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec![kept]);
+        assert_eq!(sketch.constraints().len(), 1);
+    }
+}
+
+mod dimensions {
+    use super::*;
+    use crate::sketch::{DimensionKind, DimensionMode, GeometryPoint, PointKind, SketchDimension};
+    use approx::assert_relative_eq;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+    use solver::equation::Evaluate;
+
+    fn add_line(sketch: &mut Sketch, start: (f64, f64), end: (f64, f64)) -> GeometryId {
+        sketch
+            .add_geometry(|scope| {
+                Geometry::LineSegment(LineSegment::from_points(
+                    &Point2::new(start.0, start.1),
+                    &Point2::new(end.0, end.1),
+                    scope,
+                ))
+            })
+            .unwrap()
+    }
+
+    fn make_sketch() -> (Sketch, GeometryId, GeometryId) {
+        let mut sketch = Sketch::new("TestSketch", BodyId::from(1), &make_attach_target());
+        let line1 = add_line(&mut sketch, (0.0, 0.0), (3.0, 4.0));
+        let line2 = add_line(&mut sketch, (1.0, 2.0), (4.0, 6.5));
+        (sketch, line1, line2)
+    }
+
+    #[rstest]
+    #[case(|l1, _| DimensionKind::Distance(GeometryPoint::start(l1), GeometryPoint::end(l1)), "10")]
+    #[case(|l1, l2| DimensionKind::PointLineDistance(GeometryPoint::end(l2), l1), "2")]
+    #[case(|l1, l2| DimensionKind::LineDistance(l1, l2), "1.5")]
+    #[case(
+        |l1, l2| DimensionKind::HorizontalDistance(GeometryPoint::start(l1), GeometryPoint::end(l2)),
+        "-2"
+    )]
+    #[case(
+        |l1, l2| DimensionKind::VerticalDistance(GeometryPoint::start(l1), GeometryPoint::end(l2)),
+        "7"
+    )]
+    #[case(|l1, l2| DimensionKind::Angle(l1, l2), "30[deg]")]
+    fn driving_dimension_drives_geometries(
+        #[case] make: fn(GeometryId, GeometryId) -> DimensionKind,
+        #[case] expression: &str,
+    ) {
+        // Arrange
+        let (mut sketch, line1, line2) = make_sketch();
+        let id = sketch
+            .add_dimension(SketchDimension::driving(make(line1, line2), expression))
+            .unwrap();
+
+        // Act
+        sketch.solve().unwrap();
+
+        // Assert
+        let expected = solver::equation::parse(expression)
+            .unwrap()
+            .evaluate(&solver::environment::Environment::empty())
+            .unwrap();
+        assert_relative_eq!(sketch.measure(&id).unwrap(), expected, epsilon = 1e-4);
+    }
+
+    #[test]
+    fn driving_dimension_refers_parameters() {
+        // Arrange
+        let (mut sketch, line1, _) = make_sketch();
+        sketch.set_parameter("width", 8.0).unwrap();
+        let kind = DimensionKind::Distance(GeometryPoint::start(line1), GeometryPoint::end(line1));
+        let id = sketch
+            .add_dimension(SketchDimension::driving(kind, "width / 2"))
+            .unwrap();
+
+        // Act
+        sketch.solve().unwrap();
+
+        // Assert
+        assert_relative_eq!(sketch.measure(&id).unwrap(), 4.0, epsilon = 1e-4);
+    }
+
+    #[test]
+    fn changed_parameter_drives_geometries_by_solving() {
+        // Arrange
+        let (mut sketch, line1, _) = make_sketch();
+        sketch.set_parameter("width", 8.0).unwrap();
+        let kind = DimensionKind::HorizontalDistance(
+            GeometryPoint::start(line1),
+            GeometryPoint::end(line1),
+        );
+        let id = sketch
+            .add_dimension(SketchDimension::driving(kind, "width"))
+            .unwrap();
+        sketch.solve().unwrap();
+
+        // Act
+        sketch.set_parameter("width", 5.0).unwrap();
+        sketch.solve().unwrap();
+
+        // Assert
+        assert_eq!(*sketch.parameters().get("width").unwrap().value, 5.0);
+        assert_relative_eq!(sketch.measure(&id).unwrap(), 5.0, epsilon = 1e-4);
+    }
+
+    #[test]
+    fn set_parameter_rejects_name_of_variable() {
+        // Arrange
+        let (mut sketch, line1, _) = make_sketch();
+        let (x, _) = sketch
+            .geometry(&line1)
+            .unwrap()
+            .point(PointKind::Start)
+            .unwrap();
+
+        // Act
+        let result = sketch.set_parameter(&x.to_string(), 1.0);
+
+        // Assert
+        assert!(result.is_err());
+        assert!(sketch.parameters().get(&x.to_string()).is_none());
+    }
+
+    #[test]
+    fn add_dimension_fails_with_unknown_name_in_expression() {
+        // Arrange
+        let (mut sketch, line1, _) = make_sketch();
+        let kind = DimensionKind::Distance(GeometryPoint::start(line1), GeometryPoint::end(line1));
+
+        // Act
+        let result = sketch.add_dimension(SketchDimension::driving(kind, "height * 2"));
+
+        // Assert
+        assert!(result.is_err());
+        assert!(sketch.dimensions().is_empty());
+        assert!(sketch.constraints().is_empty());
+    }
+
+    #[test]
+    fn add_dimension_fails_with_invalid_expression() {
+        // Arrange
+        let (mut sketch, line1, _) = make_sketch();
+        let kind = DimensionKind::Distance(GeometryPoint::start(line1), GeometryPoint::end(line1));
+
+        // Act
+        let result = sketch.add_dimension(SketchDimension::reference(kind, "2 +"));
+
+        // Assert
+        assert!(result.is_err());
+        assert!(sketch.dimensions().is_empty());
+    }
+
+    #[test]
+    fn reference_dimension_only_measures() {
+        // Arrange
+        let (mut sketch, line1, _) = make_sketch();
+        let kind = DimensionKind::Distance(GeometryPoint::start(line1), GeometryPoint::end(line1));
+
+        // Act
+        let id = sketch
+            .add_dimension(SketchDimension::reference(kind, "10"))
+            .unwrap();
+        sketch.solve().unwrap();
+
+        // Assert
+        assert!(sketch.constraints().is_empty());
+        assert_relative_eq!(sketch.measure(&id).unwrap(), 5.0);
+    }
+
+    #[test]
+    fn set_dimension_expression_solves_again() {
+        // Arrange
+        let (mut sketch, line1, _) = make_sketch();
+        let kind = DimensionKind::Distance(GeometryPoint::start(line1), GeometryPoint::end(line1));
+        let id = sketch
+            .add_dimension(SketchDimension::driving(kind, "10"))
+            .unwrap();
+        sketch.solve().unwrap();
+
+        // Act
+        let result = sketch.set_dimension_expression(&id, "6 + 1");
+
+        // Assert
+        assert!(result.is_ok());
+        assert_eq!(*sketch.dimension(&id).unwrap().expression, "6 + 1");
+        assert_relative_eq!(sketch.measure(&id).unwrap(), 7.0, epsilon = 1e-4);
+        assert_eq!(sketch.constraints().len(), 1);
+    }
+
+    #[test]
+    fn set_dimension_expression_keeps_current_on_error() {
+        // Arrange
+        let (mut sketch, line1, _) = make_sketch();
+        let kind = DimensionKind::Distance(GeometryPoint::start(line1), GeometryPoint::end(line1));
+        let id = sketch
+            .add_dimension(SketchDimension::driving(kind, "10"))
+            .unwrap();
+        sketch.solve().unwrap();
+
+        // Act
+        let result = sketch.set_dimension_expression(&id, "unknown");
+
+        // Assert
+        assert!(result.is_err());
+        assert_eq!(*sketch.dimension(&id).unwrap().expression, "10");
+        assert_eq!(sketch.constraints().len(), 1);
+        assert_relative_eq!(sketch.measure(&id).unwrap(), 10.0, epsilon = 1e-4);
+    }
+
+    #[rstest]
+    #[case::conflicting("20")]
+    #[case::diverging("10^400")]
+    fn update_dimension_keeps_status_when_new_value_conflicts(#[case] expression: &str) {
+        // Arrange
+        let (mut sketch, line1, _) = make_sketch();
+        let (start, end) = (GeometryPoint::start(line1), GeometryPoint::end(line1));
+        let kind = DimensionKind::Distance(start, end);
+        sketch
+            .add_dimension(SketchDimension::driving(
+                DimensionKind::HorizontalDistance(start, end),
+                "6",
+            ))
+            .unwrap();
+        sketch
+            .add_dimension(SketchDimension::driving(
+                DimensionKind::VerticalDistance(start, end),
+                "8",
+            ))
+            .unwrap();
+        let id = sketch
+            .add_dimension(SketchDimension::reference(kind, "10"))
+            .unwrap();
+        sketch.solve().unwrap();
+        let before = sketch.status();
+
+        // Act
+        let result = sketch.update_dimension(&id, SketchDimension::driving(kind, expression));
+
+        // Assert
+        assert!(result.is_err());
+        assert_eq!(sketch.status(), before);
+        assert_eq!(
+            sketch.dimension(&id).unwrap().mode,
+            DimensionMode::Reference
+        );
+        assert_relative_eq!(sketch.measure(&id).unwrap(), 10.0, epsilon = 1e-4);
+    }
+
+    #[test]
+    fn update_dimension_switches_to_driving() {
+        // Arrange
+        let (mut sketch, line1, _) = make_sketch();
+        let kind = DimensionKind::Distance(GeometryPoint::start(line1), GeometryPoint::end(line1));
+        let id = sketch
+            .add_dimension(SketchDimension::reference(kind, "10"))
+            .unwrap();
+
+        // Act
+        sketch
+            .update_dimension(&id, SketchDimension::driving(kind, "10"))
+            .unwrap();
+
+        // Assert
+        assert_eq!(sketch.dimension(&id).unwrap().mode, DimensionMode::Driving);
+        assert_eq!(sketch.constraints().len(), 1);
+        assert_relative_eq!(sketch.measure(&id).unwrap(), 10.0, epsilon = 1e-4);
+    }
+
+    #[test]
+    fn remove_dimension_removes_constraint() {
+        // Arrange
+        let (mut sketch, line1, _) = make_sketch();
+        let kind = DimensionKind::Distance(GeometryPoint::start(line1), GeometryPoint::end(line1));
+        let id = sketch
+            .add_dimension(SketchDimension::driving(kind, "10"))
+            .unwrap();
+
+        // Act
+        let result = sketch.remove_dimension(&id);
+
+        // Assert
+        assert_eq!(result, Some(SketchDimension::driving(kind, "10")));
+        assert!(sketch.dimension(&id).is_none());
+        assert!(sketch.constraints().is_empty());
+    }
+
+    #[test]
+    fn constraint_of_dimension_can_not_be_removed_directly() {
+        // Arrange
+        let (mut sketch, line1, _) = make_sketch();
+        let kind = DimensionKind::Distance(GeometryPoint::start(line1), GeometryPoint::end(line1));
+        sketch
+            .add_dimension(SketchDimension::driving(kind, "10"))
+            .unwrap();
+        let index = sketch.constraints()[0].0;
+
+        // Act
+        let result = sketch.remove_constraint(&index);
+
+        // Assert
+        assert!(matches!(result, Err(RemoveConstraintError::Owned { .. })));
+        assert_eq!(sketch.constraints().len(), 1);
+    }
+
+    #[test]
+    fn remove_geometry_removes_referring_dimensions() {
+        // Arrange
+        let (mut sketch, line1, line2) = make_sketch();
+        sketch
+            .add_dimension(SketchDimension::driving(
+                DimensionKind::Angle(line1, line2),
+                "1",
+            ))
+            .unwrap();
+
+        // Act
+        sketch.remove_geometry(&line2);
+
+        // Assert
+        assert!(sketch.dimensions().is_empty());
+        assert!(sketch.constraints().is_empty());
+    }
+}
+
+mod curves {
+    use super::*;
+    use crate::sketch::edge::SketchEdge;
+    use crate::sketch::geometry::{Arc, Circle};
+    use crate::sketch::{DimensionKind, GeometryPoint, SketchDimension};
+    use approx::assert_relative_eq;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    fn value_of(sketch: &Sketch, point: GeometryPoint) -> (f64, f64) {
+        let (x, y) = sketch
+            .geometry(&point.geometry)
+            .unwrap()
+            .point(point.kind)
+            .unwrap();
+        let variables = sketch.variables();
+
+        (
+            *variables.get(&x).unwrap().value,
+            *variables.get(&y).unwrap().value,
+        )
+    }
+
+    #[test]
+    fn arc_from_three_points_goes_counterclockwise() {
+        // Arrange
+        let mut variables = VariableArena::new();
+
+        // Act
+        let arc = Arc::from_three_points(
+            &Point2::new(-1.0, 0.0),
+            &Point2::new(0.0, 1.0),
+            &Point2::new(1.0, 0.0),
+            &mut variables,
+        )
+        .unwrap();
+
+        // Assert
+        let value = |(x, y): (VariableIndex, VariableIndex)| {
+            (
+                *variables.get(&x).unwrap().value,
+                *variables.get(&y).unwrap().value,
+            )
+        };
+        let (cx, cy) = value(*arc.center_points);
+        let (sx, sy) = value(*arc.start_points);
+        let (ex, ey) = value(*arc.end_points);
+        assert_relative_eq!(cx, 0.0);
+        assert_relative_eq!(cy, 0.0);
+        assert_relative_eq!(sx, 1.0);
+        assert_relative_eq!(sy, 0.0);
+        assert_relative_eq!(ex, -1.0);
+        assert_relative_eq!(ey, 0.0);
+    }
+
+    #[test]
+    fn arc_from_three_points_on_a_line_is_error() {
+        // Arrange
+        let mut variables = VariableArena::new();
+
+        // Act
+        let result = Arc::from_three_points(
+            &Point2::new(0.0, 0.0),
+            &Point2::new(1.0, 1.0),
+            &Point2::new(2.0, 2.0),
+            &mut variables,
+        );
+
+        // Assert
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn arc_keeps_end_on_circle_by_solving() {
+        // Arrange
+        let mut sketch = Sketch::new("TestSketch", BodyId::from(1), &make_attach_target());
+        let arc = sketch
+            .add_geometry(|scope| {
+                Geometry::Arc(Arc::from_points(
+                    &Point2::new(0.0, 0.0),
+                    &Point2::new(2.0, 0.0),
+                    &Point2::new(0.0, 2.0),
+                    scope,
+                ))
+            })
+            .unwrap();
+        sketch
+            .add_dimension(SketchDimension::driving(DimensionKind::Radius(arc), "3"))
+            .unwrap();
+
+        // Act
+        sketch.solve().unwrap();
+
+        // Assert
+        let (ex, ey) = value_of(&sketch, GeometryPoint::end(arc));
+        let (cx, cy) = value_of(&sketch, GeometryPoint::center(arc));
+        assert_relative_eq!((ex - cx).hypot(ey - cy), 3.0, epsilon = 1e-4);
+    }
+
+    #[test]
+    fn constraint_of_arc_can_not_be_removed_directly() {
+        // Arrange
+        let mut sketch = Sketch::new("TestSketch", BodyId::from(1), &make_attach_target());
+        sketch
+            .add_geometry(|scope| {
+                Geometry::Arc(Arc::from_points(
+                    &Point2::new(0.0, 0.0),
+                    &Point2::new(2.0, 0.0),
+                    &Point2::new(0.0, 2.0),
+                    scope,
+                ))
+            })
+            .unwrap();
+        let index = sketch.constraints()[0].0;
+
+        // Act
+        let result = sketch.remove_constraint(&index);
+
+        // Assert
+        assert!(matches!(result, Err(RemoveConstraintError::Owned { .. })));
+        assert_eq!(sketch.constraints().len(), 1);
+    }
+
+    #[test]
+    fn remove_arc_removes_its_constraint() {
+        // Arrange
+        let mut sketch = Sketch::new("TestSketch", BodyId::from(1), &make_attach_target());
+        let arc = sketch
+            .add_geometry(|scope| {
+                Geometry::Arc(Arc::from_points(
+                    &Point2::new(0.0, 0.0),
+                    &Point2::new(2.0, 0.0),
+                    &Point2::new(0.0, 2.0),
+                    scope,
+                ))
+            })
+            .unwrap();
+
+        // Act
+        sketch.remove_geometry(&arc);
+
+        // Assert
+        assert!(sketch.constraints().is_empty());
+    }
+
+    #[rstest]
+    #[case(DimensionKind::Radius, "2.5", 2.5)]
+    #[case(DimensionKind::Diameter, "7", 7.0)]
+    fn radius_and_diameter_drive_circle(
+        #[case] make: fn(GeometryId) -> DimensionKind,
+        #[case] expression: &str,
+        #[case] expected: f64,
+    ) {
+        // Arrange
+        let mut sketch = Sketch::new("TestSketch", BodyId::from(1), &make_attach_target());
+        let circle = sketch
+            .add_geometry(|scope| Geometry::Circle(Circle::new(&Point2::new(0.0, 0.0), 1.0, scope)))
+            .unwrap();
+        let id = sketch
+            .add_dimension(SketchDimension::driving(make(circle), expression))
+            .unwrap();
+
+        // Act
+        sketch.solve().unwrap();
+
+        // Assert
+        assert_relative_eq!(sketch.measure(&id).unwrap(), expected, epsilon = 1e-4);
+    }
+
+    #[test]
+    fn radius_of_line_is_error() {
+        // Arrange
+        let mut sketch = Sketch::new("TestSketch", BodyId::from(1), &make_attach_target());
+        let line = sketch
+            .add_geometry(|scope| {
+                Geometry::LineSegment(LineSegment::from_points(
+                    &Point2::new(0.0, 0.0),
+                    &Point2::new(1.0, 0.0),
+                    scope,
+                ))
+            })
+            .unwrap();
+
+        // Act
+        let result =
+            sketch.add_dimension(SketchDimension::driving(DimensionKind::Radius(line), "1"));
+
+        // Assert
+        assert!(result.is_err());
+    }
+
+    #[rstest]
+    #[case(SketchEdge::circle(&Point2::new(0.0, 0.0), 1.0), 65)]
+    #[case(
+        SketchEdge::arc(&Point2::new(0.0, 0.0), &Point2::new(1.0, 0.0), &Point2::new(-1.0, 0.0)),
+        33
+    )]
+    #[case(SketchEdge::new(&Point2::new(0.0, 0.0), &Point2::new(1.0, 0.0)), 2)]
+    fn tessellate_includes_both_ends(#[case] edge: SketchEdge, #[case] expected: usize) {
+        // Act
+        let points = edge.tessellate(64);
+
+        // Assert
+        assert_eq!(points.len(), expected);
+        assert_eq!(points.first().unwrap(), &*edge.start);
+        assert_eq!(points.last().unwrap(), &*edge.end);
+    }
+}
+
+mod shapes {
+    use super::*;
+    use crate::sketch::{
+        DimensionKind, GeometryPoint, PointKind, SketchConstraint, SketchDimension, SketchShape,
+    };
+    use approx::assert_relative_eq;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+    use solver::equation::parse;
+
+    fn make_sketch() -> Sketch {
+        Sketch::new("TestSketch", BodyId::from(1), &make_attach_target())
+    }
+
+    fn value_of(sketch: &Sketch, point: GeometryPoint) -> (f64, f64) {
+        let (x, y) = sketch
+            .geometry(&point.geometry)
+            .unwrap()
+            .point(point.kind)
+            .unwrap();
+        let variables = sketch.variables();
+
+        (
+            *variables.get(&x).unwrap().value,
+            *variables.get(&y).unwrap().value,
+        )
+    }
+
+    fn length_of(sketch: &Sketch, line: GeometryId) -> f64 {
+        let (sx, sy) = value_of(sketch, GeometryPoint::start(line));
+        let (ex, ey) = value_of(sketch, GeometryPoint::end(line));
+        (ex - sx).hypot(ey - sy)
+    }
+
+    #[test]
+    fn rectangle_shares_corners_of_lines() {
+        // Arrange
+        let mut sketch = make_sketch();
+
+        // Act
+        let ids = sketch
+            .add_shape(&SketchShape::Rectangle(
+                Point2::new(3.0, 2.0),
+                Point2::new(0.0, 0.0),
+            ))
+            .unwrap();
+
+        // Assert
+        assert_eq!(ids.len(), 4);
+        for (i, id) in ids.iter().enumerate() {
+            let next = ids[(i + 1) % ids.len()];
+            assert_eq!(
+                sketch.geometry(id).unwrap().point(PointKind::End),
+                sketch.geometry(&next).unwrap().point(PointKind::Start)
+            );
+        }
+        assert_eq!(value_of(&sketch, GeometryPoint::start(ids[0])), (0.0, 0.0));
+        assert_eq!(value_of(&sketch, GeometryPoint::start(ids[2])), (3.0, 2.0));
+        assert_eq!(
+            sketch
+                .sketch_constraints()
+                .iter()
+                .map(|(_, c)| (*c).clone())
+                .collect::<Vec<_>>(),
+            vec![
+                SketchConstraint::Horizontal(ids[0]),
+                SketchConstraint::Vertical(ids[1]),
+                SketchConstraint::Horizontal(ids[2]),
+                SketchConstraint::Vertical(ids[3]),
+            ]
+        );
+    }
+
+    #[test]
+    fn center_rectangle_is_around_the_center() {
+        // Arrange
+        let mut sketch = make_sketch();
+
+        // Act
+        let ids = sketch
+            .add_shape(&SketchShape::CenterRectangle {
+                center: Point2::new(1.0, 1.0),
+                corner: Point2::new(3.0, 2.0),
+            })
+            .unwrap();
+
+        // Assert
+        assert_eq!(ids.len(), 5);
+        assert_eq!(value_of(&sketch, GeometryPoint::start(ids[0])), (-1.0, 0.0));
+        assert_eq!(value_of(&sketch, GeometryPoint::start(ids[2])), (3.0, 2.0));
+        assert_eq!(value_of(&sketch, GeometryPoint::center(ids[4])), (1.0, 1.0));
+        assert_eq!(sketch.resolve_edges().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn center_rectangle_keeps_center_when_width_changes() {
+        // Arrange
+        let mut sketch = make_sketch();
+        let ids = sketch
+            .add_shape(&SketchShape::CenterRectangle {
+                center: Point2::new(1.0, 1.0),
+                corner: Point2::new(3.0, 2.0),
+            })
+            .unwrap();
+        let kind = DimensionKind::HorizontalDistance(
+            GeometryPoint::start(ids[0]),
+            GeometryPoint::end(ids[0]),
+        );
+        sketch
+            .add_dimension(SketchDimension::driving(kind, "10"))
+            .unwrap();
+
+        // Act
+        sketch.solve().unwrap();
+
+        // Assert - the center is the middle of the diagonal
+        let (x0, y0) = value_of(&sketch, GeometryPoint::start(ids[0]));
+        let (x2, y2) = value_of(&sketch, GeometryPoint::start(ids[2]));
+        let (cx, cy) = value_of(&sketch, GeometryPoint::center(ids[4]));
+        assert_relative_eq!(x2 - x0, 10.0, epsilon = 1e-4);
+        assert_relative_eq!(x0 + x2, 2.0 * cx, epsilon = 1e-4);
+        assert_relative_eq!(y0 + y2, 2.0 * cy, epsilon = 1e-4);
+    }
+
+    #[test]
+    fn rectangle_stays_rectangle_when_width_changes() {
+        // Arrange
+        let mut sketch = make_sketch();
+        let ids = sketch
+            .add_shape(&SketchShape::Rectangle(
+                Point2::new(0.0, 0.0),
+                Point2::new(3.0, 2.0),
+            ))
+            .unwrap();
+        let kind = DimensionKind::HorizontalDistance(
+            GeometryPoint::start(ids[0]),
+            GeometryPoint::end(ids[0]),
+        );
+        sketch
+            .add_dimension(SketchDimension::driving(kind, "10"))
+            .unwrap();
+
+        // Act
+        sketch.solve().unwrap();
+
+        // Assert
+        let (x0, y0) = value_of(&sketch, GeometryPoint::start(ids[0]));
+        let (x1, y1) = value_of(&sketch, GeometryPoint::start(ids[1]));
+        let (x2, y2) = value_of(&sketch, GeometryPoint::start(ids[2]));
+        let (x3, y3) = value_of(&sketch, GeometryPoint::start(ids[3]));
+        assert_relative_eq!(x1 - x0, 10.0, epsilon = 1e-4);
+        assert_relative_eq!(x2 - x3, 10.0, epsilon = 1e-4);
+        assert_relative_eq!(y0, y1, epsilon = 1e-4);
+        assert_relative_eq!(y2, y3, epsilon = 1e-4);
+        assert_relative_eq!(x1, x2, epsilon = 1e-4);
+        assert_relative_eq!(x0, x3, epsilon = 1e-4);
+    }
+
+    #[rstest]
+    #[case(3)]
+    #[case(4)]
+    #[case(6)]
+    fn polygon_stays_regular_when_side_changes(#[case] sides: usize) {
+        // Arrange
+        let mut sketch = make_sketch();
+        let ids = sketch
+            .add_shape(&SketchShape::Polygon {
+                center: Point2::new(1.0, 1.0),
+                vertex: Point2::new(3.0, 1.0),
+                sides,
+            })
+            .unwrap();
+        let kind =
+            DimensionKind::Distance(GeometryPoint::start(ids[0]), GeometryPoint::end(ids[0]));
+        sketch
+            .add_dimension(SketchDimension::driving(kind, "5"))
+            .unwrap();
+
+        // Act
+        sketch.solve().unwrap();
+
+        // Assert
+        assert_eq!(ids.len(), sides + 1);
+        assert_regular_polygon(&sketch, &ids);
+        assert_relative_eq!(length_of(&sketch, ids[0]), 5.0, epsilon = 1e-4);
+    }
+
+    #[rstest]
+    #[case::along_side((2.5, 0.5))]
+    #[case::across_center((-3.0, 1.0))]
+    fn polygon_stays_convex_and_regular_when_vertex_is_dragged(#[case] target: (f64, f64)) {
+        // Arrange
+        let mut sketch = make_sketch();
+        let ids = sketch
+            .add_shape(&SketchShape::Polygon {
+                center: Point2::new(0.0, 0.0),
+                vertex: Point2::new(2.0, 0.0),
+                sides: 5,
+            })
+            .unwrap();
+        sketch
+            .add_sketch_constraint(SketchConstraint::Fixed(
+                GeometryPoint::start(ids[1]),
+                Point2::new(target.0, target.1),
+            ))
+            .unwrap();
+
+        // Act
+        sketch.solve().unwrap();
+
+        // Assert
+        let (x, y) = value_of(&sketch, GeometryPoint::start(ids[1]));
+        assert_relative_eq!(x, target.0, epsilon = 1e-4);
+        assert_relative_eq!(y, target.1, epsilon = 1e-4);
+        assert_regular_polygon(&sketch, &ids);
+    }
+
+    /// Assert that sides have same length, vertices are on a circle around the center point, and
+    /// vertices turn counterclockwise
+    fn assert_regular_polygon(sketch: &Sketch, ids: &[GeometryId]) {
+        let (center, lines) = ids.split_last().unwrap();
+        let (cx, cy) = value_of(sketch, GeometryPoint::center(*center));
+        let vertices = lines
+            .iter()
+            .map(|id| value_of(sketch, GeometryPoint::start(*id)))
+            .collect::<Vec<_>>();
+        let radius = (vertices[0].0 - cx).hypot(vertices[0].1 - cy);
+        let side = length_of(sketch, lines[0]);
+        assert!(radius > 1e-4);
+
+        for (i, (x, y)) in vertices.iter().enumerate() {
+            let (nx, ny) = vertices[(i + 1) % vertices.len()];
+            let (px, py) = vertices[(i + vertices.len() - 1) % vertices.len()];
+            assert_relative_eq!(length_of(sketch, lines[i]), side, epsilon = 1e-4);
+            assert_relative_eq!((x - cx).hypot(y - cy), radius, epsilon = 1e-4);
+            // the turn at each vertex is to the left
+            assert!((x - px) * (ny - y) - (y - py) * (nx - x) > 0.0);
+        }
+    }
+
+    #[test]
+    fn slot_stays_slot_when_radius_changes() {
+        // Arrange
+        let mut sketch = make_sketch();
+        let ids = sketch
+            .add_shape(&SketchShape::Slot {
+                start: Point2::new(0.0, 0.0),
+                end: Point2::new(4.0, 0.0),
+                radius: 1.0,
+            })
+            .unwrap();
+        sketch
+            .add_dimension(SketchDimension::driving(DimensionKind::Radius(ids[1]), "2"))
+            .unwrap();
+
+        // Act
+        sketch.solve().unwrap();
+
+        // Assert - both ends are half circles of the radius
+        let edges = sketch.resolve_edges().unwrap();
+        assert_eq!(edges.len(), 4);
+        for id in [ids[1], ids[3]] {
+            let (cx, cy) = value_of(&sketch, GeometryPoint::center(id));
+            let (sx, sy) = value_of(&sketch, GeometryPoint::start(id));
+            let (ex, ey) = value_of(&sketch, GeometryPoint::end(id));
+            assert_relative_eq!((sx - cx).hypot(sy - cy), 2.0, epsilon = 1e-4);
+            assert_relative_eq!((ex - cx).hypot(ey - cy), 2.0, epsilon = 1e-4);
+            assert_relative_eq!(sx + ex, 2.0 * cx, epsilon = 1e-4);
+            assert_relative_eq!(sy + ey, 2.0 * cy, epsilon = 1e-4);
+        }
+        assert_relative_eq!(
+            length_of(&sketch, ids[0]),
+            length_of(&sketch, ids[2]),
+            epsilon = 1e-4
+        );
+    }
+
+    #[rstest]
+    #[case(SketchShape::Rectangle(Point2::new(0.0, 0.0), Point2::new(0.0, 1.0)))]
+    #[case(SketchShape::CenterRectangle { center: Point2::new(1.0, 1.0), corner: Point2::new(2.0, 1.0) })]
+    #[case(SketchShape::Polygon { center: Point2::new(0.0, 0.0), vertex: Point2::new(1.0, 0.0), sides: 2 })]
+    #[case(SketchShape::Polygon { center: Point2::new(0.0, 0.0), vertex: Point2::new(0.0, 0.0), sides: 5 })]
+    #[case(SketchShape::Slot { start: Point2::new(0.0, 0.0), end: Point2::new(0.0, 0.0), radius: 1.0 })]
+    #[case(SketchShape::Slot { start: Point2::new(0.0, 0.0), end: Point2::new(1.0, 0.0), radius: 0.0 })]
+    fn shape_without_area_adds_nothing(#[case] shape: SketchShape) {
+        // Arrange
+        let mut sketch = make_sketch();
+
+        // Act
+        let result = sketch.add_shape(&shape);
+
+        // Assert
+        assert!(result.is_err());
+        assert!(sketch.resolve_edges().unwrap().is_empty());
+        assert!(sketch.sketch_constraints().is_empty());
+    }
+
+    #[test]
+    fn shape_with_failing_constraint_adds_nothing() {
+        // Arrange
+        let mut sketch = make_sketch();
+        let shape = SketchShape::Rectangle(Point2::new(0.0, 0.0), Point2::new(3.0, 2.0));
+        // the same horizontal constraint of the bottom line exists before the rectangle
+        let mut probe = sketch.clone();
+        let ids = probe.add_shape(&shape).unwrap();
+        let bottom = probe.geometry(&ids[0]).unwrap();
+        let (_, sy) = bottom.point(PointKind::Start).unwrap();
+        let (_, ey) = bottom.point(PointKind::End).unwrap();
+        let equation = parse(&format!("{} - {}", ey, sy)).unwrap();
+        let existing = sketch
+            .constraints
+            .register(Constraint::new("c", equation, probe.variables()).unwrap());
+
+        // Act
+        let result = sketch.add_shape(&shape);
+
+        // Assert
+        assert!(result.is_err());
+        assert!(sketch.resolve_edges().unwrap().is_empty());
+        assert!(sketch.sketch_constraints().is_empty());
+        assert!(sketch.variables().to_environment().variables().is_empty());
+        assert_eq!(
+            sketch
+                .constraints()
+                .iter()
+                .map(|(k, _)| *k)
+                .collect::<Vec<_>>(),
+            vec![existing]
+        );
+    }
+}
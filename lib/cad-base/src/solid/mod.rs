@@ -141,7 +141,7 @@ mod tests {
         },
     };
 
-    fn v(x: f32, y: f32, z: f32) -> Vertex {
+    fn v(x: f64, y: f64, z: f64) -> Vertex {
         Point::new(x, y, z).into()
     }
 
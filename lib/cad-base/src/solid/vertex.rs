@@ -45,7 +45,7 @@ mod tests {
     #[case(0.0, 0.0, 0.0)]
     #[case(1.0, 2.0, 3.0)]
     #[case(-1.5, 0.0, 99.9)]
-    fn new_creates_vertex_with_given_coordinates(#[case] x: f32, #[case] y: f32, #[case] z: f32) {
+    fn new_creates_vertex_with_given_coordinates(#[case] x: f64, #[case] y: f64, #[case] z: f64) {
         // Act
         let v = Vertex::new(&Point::new(x, y, z));
 
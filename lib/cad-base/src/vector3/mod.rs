@@ -3,19 +3,21 @@ mod tests;
 
 use std::ops::{Add, Div, Mul, Sub};
 
+use epsilon::{dual::Dual3, interval::Interval3};
+
 use crate::point::Point;
 
-/// f32-specialized 3D vector
+/// f64-specialized 3D vector
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub struct Vector3 {
-    pub x: f32,
-    pub y: f32,
-    pub z: f32,
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
     _immutable: (),
 }
 
 impl Vector3 {
-    pub fn new(x: f32, y: f32, z: f32) -> Self {
+    pub fn new(x: f64, y: f64, z: f64) -> Self {
         Vector3 {
             x,
             y,
@@ -39,8 +41,18 @@ impl Vector3 {
         Vector3::new(0.0, 0.0, 1.0)
     }
 
+    /// Get the vector as intervals
+    pub fn to_interval(&self) -> Interval3 {
+        Interval3::from((self.x, self.y, self.z))
+    }
+
+    /// Get the vector as dual numbers that do not depend on the parameter
+    pub fn to_dual(&self) -> Dual3 {
+        Dual3::from((self.x, self.y, self.z))
+    }
+
     /// Get dot product with another vector
-    pub fn dot(&self, other: &Vector3) -> f32 {
+    pub fn dot(&self, other: &Vector3) -> f64 {
         self.x * other.x + self.y * other.y + self.z * other.z
     }
 
@@ -54,7 +66,7 @@ impl Vector3 {
     }
 
     /// Return squared norm of the vector
-    pub fn norm2(&self) -> f32 {
+    pub fn norm2(&self) -> f64 {
         self.x * self.x + self.y * self.y + self.z * self.z
     }
 
@@ -81,21 +93,21 @@ impl Vector3 {
         Vector3::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
     }
 
-    pub fn multiply(&self, rhs: f32) -> Self {
+    pub fn multiply(&self, rhs: f64) -> Self {
         Vector3::new(self.x * rhs, self.y * rhs, self.z * rhs)
     }
-    pub fn divide(&self, rhs: f32) -> Self {
+    pub fn divide(&self, rhs: f64) -> Self {
         Vector3::new(self.x / rhs, self.y / rhs, self.z / rhs)
     }
 }
 
-impl From<(f32, f32, f32)> for Vector3 {
-    fn from(value: (f32, f32, f32)) -> Self {
+impl From<(f64, f64, f64)> for Vector3 {
+    fn from(value: (f64, f64, f64)) -> Self {
         Vector3::new(value.0, value.1, value.2)
     }
 }
 
-impl From<Vector3> for (f32, f32, f32) {
+impl From<Vector3> for (f64, f64, f64) {
     fn from(value: Vector3) -> Self {
         (value.x, value.y, value.z)
     }
@@ -113,7 +125,7 @@ impl From<&Point> for Vector3 {
     }
 }
 
-/// operations
+// operations
 
 // Add
 impl Add<&Vector3> for &Vector3 {
@@ -181,18 +193,18 @@ impl Sub<&Vector3> for Vector3 {
 }
 
 // scalar operations
-impl Mul<f32> for &Vector3 {
+impl Mul<f64> for &Vector3 {
     type Output = Vector3;
 
-    fn mul(self, rhs: f32) -> Self::Output {
+    fn mul(self, rhs: f64) -> Self::Output {
         self.multiply(rhs)
     }
 }
 
-impl Mul<f32> for Vector3 {
+impl Mul<f64> for Vector3 {
     type Output = Vector3;
 
-    fn mul(self, rhs: f32) -> Self::Output {
+    fn mul(self, rhs: f64) -> Self::Output {
         self.multiply(rhs)
     }
 }
@@ -201,7 +213,7 @@ impl Mul<i32> for Vector3 {
     type Output = Vector3;
 
     fn mul(self, rhs: i32) -> Self::Output {
-        self * (rhs as f32)
+        self * (rhs as f64)
     }
 }
 
@@ -209,14 +221,14 @@ impl Mul<i32> for &Vector3 {
     type Output = Vector3;
 
     fn mul(self, rhs: i32) -> Self::Output {
-        self * (rhs as f32)
+        self * (rhs as f64)
     }
 }
 
-impl Div<f32> for &Vector3 {
+impl Div<f64> for &Vector3 {
     type Output = Vector3;
 
-    fn div(self, rhs: f32) -> Self::Output {
+    fn div(self, rhs: f64) -> Self::Output {
         self.divide(rhs)
     }
 }
@@ -228,10 +240,10 @@ impl Default for Vector3 {
     }
 }
 
-impl Div<f32> for Vector3 {
+impl Div<f64> for Vector3 {
     type Output = Vector3;
 
-    fn div(self, rhs: f32) -> Self::Output {
+    fn div(self, rhs: f64) -> Self::Output {
         self.divide(rhs)
     }
 }
@@ -240,7 +252,7 @@ impl Div<i32> for Vector3 {
     type Output = Vector3;
 
     fn div(self, rhs: i32) -> Self::Output {
-        self / (rhs as f32)
+        self / (rhs as f64)
     }
 }
 
@@ -248,6 +260,6 @@ impl Div<i32> for &Vector3 {
     type Output = Vector3;
 
     fn div(self, rhs: i32) -> Self::Output {
-        self / (rhs as f32)
+        self / (rhs as f64)
     }
 }
@@ -39,7 +39,7 @@ mod construction {
         let v = Vector3::new(1.0, 2.0, 3.0);
 
         // Act
-        let tuple: (f32, f32, f32) = v.into();
+        let tuple: (f64, f64, f64) = v.into();
 
         // Assert
         assert_eq!(tuple, (1.0, 2.0, 3.0));
@@ -353,7 +353,7 @@ mod scalar_multiplication {
     use approx::assert_relative_eq;
 
     #[test]
-    fn mul_ref_by_f32() {
+    fn mul_ref_by_f64() {
         // Arrange
         let v = Vector3::new(1.0, 2.0, 3.0);
 
@@ -367,7 +367,7 @@ mod scalar_multiplication {
     }
 
     #[test]
-    fn mul_owned_by_f32() {
+    fn mul_owned_by_f64() {
         // Arrange
         let v = Vector3::new(1.0, 2.0, 3.0);
 
@@ -429,7 +429,7 @@ mod scalar_division {
     use approx::assert_relative_eq;
 
     #[test]
-    fn div_ref_by_f32() {
+    fn div_ref_by_f64() {
         // Arrange
         let v = Vector3::new(2.0, 4.0, 6.0);
 
@@ -443,7 +443,7 @@ mod scalar_division {
     }
 
     #[test]
-    fn div_owned_by_f32() {
+    fn div_owned_by_f64() {
         // Arrange
         let v = Vector3::new(2.0, 4.0, 6.0);
 
@@ -30,7 +30,7 @@ fn compute_moved_face(
     builder: &mut SolidBuilder,
     curve: &JordanCurve,
     plane: &Plane,
-    length: f32,
+    length: f64,
 ) -> (Vec<VertexId>, Vec<EdgeId>) {
     let moved_points: Vec<_> = curve
         .points
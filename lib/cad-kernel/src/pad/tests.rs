@@ -27,19 +27,21 @@ fn make_pentagon_sketch() -> Sketch {
     let target = make_plane_attach_target();
     let mut sketch = Sketch::new("pentagon", BodyId::from(1), &target);
     for (s, e) in [
-        ((0.0_f32, 0.0_f32), (2.0_f32, 0.0_f32)),
+        ((0.0_f64, 0.0_f64), (2.0_f64, 0.0_f64)),
         ((2.0, 0.0), (3.0, 1.0)),
         ((3.0, 1.0), (1.5, 2.0)),
         ((1.5, 2.0), (0.0, 1.0)),
         ((0.0, 1.0), (0.0, 0.0)),
     ] {
-        sketch.add_geometry(|vars| {
-            Geometry::LineSegment(LineSegment::from_points(
-                &Point2::new(s.0, s.1),
-                &Point2::new(e.0, e.1),
-                vars,
-            ))
-        });
+        sketch
+            .add_geometry(|vars| {
+                Geometry::LineSegment(LineSegment::from_points(
+                    &Point2::new(s.0, s.1),
+                    &Point2::new(e.0, e.1),
+                    vars,
+                ))
+            })
+            .unwrap();
     }
     sketch
 }
@@ -48,32 +50,36 @@ fn make_pentagon_sketch() -> Sketch {
 fn make_open_sketch() -> Sketch {
     let target = make_plane_attach_target();
     let mut sketch = Sketch::new("open", BodyId::from(1), &target);
-    sketch.add_geometry(|vars| {
-        Geometry::LineSegment(LineSegment::from_points(
-            &Point2::new(0.0, 0.0),
-            &Point2::new(1.0, 0.0),
-            vars,
-        ))
-    });
-    sketch.add_geometry(|vars| {
-        Geometry::LineSegment(LineSegment::from_points(
-            &Point2::new(1.0, 0.0),
-            &Point2::new(1.0, 1.0),
-            vars,
-        ))
-    });
+    sketch
+        .add_geometry(|vars| {
+            Geometry::LineSegment(LineSegment::from_points(
+                &Point2::new(0.0, 0.0),
+                &Point2::new(1.0, 0.0),
+                vars,
+            ))
+        })
+        .unwrap();
+    sketch
+        .add_geometry(|vars| {
+            Geometry::LineSegment(LineSegment::from_points(
+                &Point2::new(1.0, 0.0),
+                &Point2::new(1.0, 1.0),
+                vars,
+            ))
+        })
+        .unwrap();
     sketch
 }
 
-fn make_feature(eq_value: f32) -> Feature {
+fn make_feature(eq_value: f64) -> Feature {
     let eq: Equation = eq_value.into();
-    let op = Operation::Pad(Pad::new(&eq));
+    let op = Operation::Pad(Pad::new(&eq).unwrap());
     Feature::new("Pad1", BodyId::from(1), SketchId::from(1), &op).unwrap()
 }
 
-fn make_feature_with_direction(eq_value: f32, direction: &PadDirection) -> Feature {
+fn make_feature_with_direction(eq_value: f64, direction: &PadDirection) -> Feature {
     let eq: Equation = eq_value.into();
-    let mut pad = Pad::new(&eq);
+    let mut pad = Pad::new(&eq).unwrap();
     pad.change_direction(direction);
     let op = Operation::Pad(pad);
     Feature::new("Pad1", BodyId::from(1), SketchId::from(1), &op).unwrap()
@@ -191,7 +197,7 @@ fn normal_pad_vertices_are_offset_along_normal() {
     // Arrange
     let sketch = make_pentagon_sketch();
     let plane = Plane::<DefaultEpsilon>::new_xz();
-    let length = 5.0_f32;
+    let length = 5.0_f64;
     let feature = make_feature(length);
     let context = make_context(&sketch, &plane);
 
@@ -201,7 +207,7 @@ fn normal_pad_vertices_are_offset_along_normal() {
     // Assert
     // XZ plane normal is (0,1,0). First face stays at y=0, second face at y=length.
     let solid = &solids[0];
-    let y_values: Vec<f32> = solid.vertices.values().map(|v| *v.y).collect();
+    let y_values: Vec<f64> = solid.vertices.values().map(|v| *v.y).collect();
     for y in &y_values {
         assert!(
             (*y - 0.0).abs() < 1e-5 || (*y - length).abs() < 1e-5,
@@ -215,7 +221,7 @@ fn symmetric_pad_vertices_are_offset_both_directions() {
     // Arrange
     let sketch = make_pentagon_sketch();
     let plane = Plane::<DefaultEpsilon>::new_xz();
-    let length = 5.0_f32;
+    let length = 5.0_f64;
     let feature = make_feature_with_direction(length, &PadDirection::Symmetric);
     let context = make_context(&sketch, &plane);
 
@@ -225,7 +231,7 @@ fn symmetric_pad_vertices_are_offset_both_directions() {
     // Assert
     // Symmetric: first face offset along +normal (y=+length), second along -normal (y=-length).
     let solid = &solids[0];
-    let y_values: Vec<f32> = solid.vertices.values().map(|v| *v.y).collect();
+    let y_values: Vec<f64> = solid.vertices.values().map(|v| *v.y).collect();
     for y in &y_values {
         assert!(
             (*y - length).abs() < 1e-5 || (*y + length).abs() < 1e-5,
@@ -239,7 +245,7 @@ fn inverted_normal_pad_vertices_are_offset_in_negative_direction() {
     // Arrange
     let sketch = make_pentagon_sketch();
     let plane = Plane::<DefaultEpsilon>::new_xz();
-    let length = 5.0_f32;
+    let length = 5.0_f64;
     let feature = make_feature_with_direction(length, &PadDirection::InveredNormal);
     let context = make_context(&sketch, &plane);
 
@@ -249,7 +255,7 @@ fn inverted_normal_pad_vertices_are_offset_in_negative_direction() {
     // Assert
     // InveredNormal: first face at y=0, second face offset along -normal (y=-length).
     let solid = &solids[0];
-    let y_values: Vec<f32> = solid.vertices.values().map(|v| *v.y).collect();
+    let y_values: Vec<f64> = solid.vertices.values().map(|v| *v.y).collect();
     for y in &y_values {
         assert!(
             (*y - 0.0).abs() < 1e-5 || (*y + length).abs() < 1e-5,
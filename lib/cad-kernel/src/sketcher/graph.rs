@@ -1,14 +1,14 @@
 use std::collections::HashSet;
 
-use cad_base::sketch::{Point2, edge::SketchEdge};
+use cad_base::sketch::edge::SketchEdge;
 use color_eyre::eyre::{Result, eyre};
 use epsilon::Epsilon;
 
 /// A internal Graph representation
 pub struct Graph {
-    // adjacent list. An index is the start point, and value is next indices from the start.
-    adj: Vec<Vec<usize>>,
-    points: Vec<Point2>,
+    // adjacent list. An index is the start point, and value is next indices from the start with
+    // indices of edges between them.
+    adj: Vec<Vec<(usize, usize)>>,
 }
 
 impl Graph {
@@ -27,9 +27,9 @@ impl Graph {
         all_points.dedup_by(|o1, o2| o1.approx_eq::<E>(o2));
 
         // make adjacent list
-        let mut adj: Vec<Vec<usize>> = vec![vec![]; all_points.len()];
+        let mut adj: Vec<Vec<(usize, usize)>> = vec![vec![]; all_points.len()];
 
-        for edge in edges {
+        for (index, edge) in edges.iter().enumerate() {
             let start = all_points
                 .iter()
                 .position(|v| v.approx_eq::<E>(&edge.start));
@@ -37,9 +37,9 @@ impl Graph {
 
             if let (Some(start), Some(end)) = (start, end) {
                 if let Some(ends) = adj.get_mut(start) {
-                    ends.push(end);
+                    ends.push((end, index));
                 } else {
-                    adj[start] = vec![end];
+                    adj[start] = vec![(end, index)];
                 }
             }
         }
@@ -48,14 +48,13 @@ impl Graph {
             return Err(eyre!("Points in edge are not identical"));
         }
 
-        Ok(Self {
-            adj,
-            points: all_points.into_iter().map(|v| (*v).clone()).collect(),
-        })
+        Ok(Self { adj })
     }
 
-    /// Get all closed loops. Detect the branch in the loop, the loop and related points ignores.
-    pub fn jordan_curves(&self) -> Option<Vec<Vec<Point2>>> {
+    /// Get all closed loops as start points with indices of edges from them. Detect the branch in
+    /// the loop, the loop and related points ignores. An edge from a point to itself, such as a
+    /// circle, is a loop.
+    pub fn jordan_loops(&self) -> Option<Vec<Vec<(usize, usize)>>> {
         let mut loops = vec![];
         let indices: HashSet<usize> =
             HashSet::from_iter(self.adj.iter().enumerate().map(|(i, _)| i));
@@ -64,7 +63,6 @@ impl Graph {
         let mut start = 0;
         let mut in_loop = vec![];
         while through_points.len() < indices.len() {
-            in_loop.push(start);
             through_points.insert(start);
 
             let nexts = self.adj.get(start).expect("Should be success");
@@ -73,16 +71,11 @@ impl Graph {
             if nexts.len() != 1 {
                 return None;
             }
+            in_loop.push((start, nexts[0].1));
 
             // Detecting the closed loop, reset
-            if in_loop[0] == nexts[0] {
-                let points: Vec<Point2> = in_loop
-                    .iter()
-                    .filter_map(|v| self.points.get(*v))
-                    .cloned()
-                    .collect();
-                loops.push(points);
-                in_loop.clear();
+            if in_loop[0].0 == nexts[0].0 {
+                loops.push(std::mem::take(&mut in_loop));
 
                 let diff: Vec<_> = indices.difference(&through_points).collect();
                 if let Some(next) = diff.first() {
@@ -92,7 +85,7 @@ impl Graph {
                 // if no diff == all point has been throughed, continue and break.
             } else {
                 // Go next loop with next.
-                start = nexts[0];
+                start = nexts[0].0;
             }
         }
 
@@ -107,7 +100,7 @@ mod tests {
     use epsilon::DefaultEpsilon;
     use pretty_assertions::assert_eq;
 
-    fn make_edge(start: (f32, f32), end: (f32, f32)) -> SketchEdge {
+    fn make_edge(start: (f64, f64), end: (f64, f64)) -> SketchEdge {
         SketchEdge::new(&Point2::new(start.0, start.1), &Point2::new(end.0, end.1))
     }
 
@@ -134,7 +127,7 @@ mod tests {
 
         // Act
         let graph = Graph::new::<DefaultEpsilon>(&edges).expect("should build graph");
-        let result = graph.jordan_curves();
+        let result = graph.jordan_loops();
 
         // Assert
         let loops = result.expect("should have loops");
@@ -154,7 +147,7 @@ mod tests {
 
         // Act
         let graph = Graph::new::<DefaultEpsilon>(&edges).expect("should build graph");
-        let result = graph.jordan_curves();
+        let result = graph.jordan_loops();
 
         // Assert
         let loops = result.expect("should have loops");
@@ -172,7 +165,7 @@ mod tests {
 
         // Act
         let graph = Graph::new::<DefaultEpsilon>(&edges).expect("should build graph");
-        let result = graph.jordan_curves();
+        let result = graph.jordan_loops();
 
         // Assert
         assert!(result.is_none());
@@ -192,7 +185,7 @@ mod tests {
 
         // Act
         let graph = Graph::new::<DefaultEpsilon>(&edges).expect("should build graph");
-        let result = graph.jordan_curves();
+        let result = graph.jordan_loops();
 
         // Assert
         let loops = result.expect("should have loops");
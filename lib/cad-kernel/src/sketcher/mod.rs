@@ -6,11 +6,17 @@ mod tests;
 use cad_base::{
     feature::AttachedTarget,
     point::Point,
-    sketch::{AttachableTarget, Point2, Sketch, edge::SketchEdge},
+    sketch::{
+        AttachableTarget, Point2, Sketch,
+        edge::{EdgeCurve, SketchEdge},
+    },
 };
 use color_eyre::eyre::{Result, eyre};
 use epsilon::{DefaultEpsilon, Epsilon};
 
+/// Number of segments to approximate a full circle
+const SEGMENTS_PER_TURN: usize = 64;
+
 /// struct of representation of Jordan Curve.
 ///
 /// Currently, all sketch needs to construct valid jordan curves.
@@ -76,12 +82,12 @@ impl Sketcher<'_> {
             return Err(SketcherError::SketchNotHaveEdge);
         };
 
-        let Some(curves) = graph.jordan_curves() else {
+        let Some(loops) = graph.jordan_loops() else {
             return Err(SketcherError::SketchHasNoJordanCurve);
         };
 
         let mut ret: Vec<JordanCurve> = vec![];
-        for curve in &curves {
+        for curve in loops.iter().map(|l| points_of_loop(&edges, l)) {
             let edges = Vec::from_iter((0..(curve.len() - 1)).map(|v| (v, v + 1)));
 
             let plane = match self.target {
@@ -100,6 +106,20 @@ impl Sketcher<'_> {
     }
 }
 
+/// Get points along edges of the loop. Curved edges are approximated by segments, because faces of
+/// solids are planar.
+fn points_of_loop(edges: &[SketchEdge], edge_loop: &[(usize, usize)]) -> Vec<Point2> {
+    edge_loop
+        .iter()
+        .flat_map(|(_, edge)| {
+            let mut points = edges[*edge].tessellate(SEGMENTS_PER_TURN);
+            // the end is the start of the next edge
+            points.pop();
+            points
+        })
+        .collect()
+}
+
 /// Check segment `(p1,p2)` and `(p3, p4)` intersection.
 fn segment_intersect(p1: &Point2, p2: &Point2, p3: &Point2, p4: &Point2) -> bool {
     (p1.detect_ccw(p3, p4) != p2.detect_ccw(p3, p4))
@@ -113,6 +133,13 @@ fn all_edges_not_crossed(edges: &[SketchEdge]) -> bool {
             let ei = &edges[i];
             let ej = &edges[j];
 
+            if *ei.curve != EdgeCurve::Line || *ej.curve != EdgeCurve::Line {
+                if curves_intersect(ei, ej) {
+                    return false;
+                }
+                continue;
+            }
+
             // exclude edges that they have shared point
             if ei.start.approx_eq::<DefaultEpsilon>(&ej.start)
                 || ei.start.approx_eq::<DefaultEpsilon>(&ej.end)
@@ -130,3 +157,120 @@ fn all_edges_not_crossed(edges: &[SketchEdge]) -> bool {
 
     true
 }
+
+/// Check intersection of edges that some of them are curved. Intersections at points that both
+/// edges end are ignored, because they are joints of edges.
+fn curves_intersect(e1: &SketchEdge, e2: &SketchEdge) -> bool {
+    let is_end_of = |e: &SketchEdge, p: &Point2| {
+        e.start.approx_eq::<DefaultEpsilon>(p) || e.end.approx_eq::<DefaultEpsilon>(p)
+    };
+
+    intersections(e1, e2)
+        .iter()
+        .any(|p| !(is_end_of(e1, p) && is_end_of(e2, p)))
+}
+
+/// Get intersection points of edges. Overlapping arcs on a same circle give a point in the overlap.
+fn intersections(e1: &SketchEdge, e2: &SketchEdge) -> Vec<Point2> {
+    match (&*e1.curve, &*e2.curve) {
+        (EdgeCurve::Line, EdgeCurve::Line) => vec![],
+        (EdgeCurve::Line, EdgeCurve::Arc { .. }) => line_arc_intersections(e1, e2),
+        (EdgeCurve::Arc { .. }, EdgeCurve::Line) => line_arc_intersections(e2, e1),
+        (
+            EdgeCurve::Arc {
+                center: c1,
+                radius: r1,
+            },
+            EdgeCurve::Arc {
+                center: c2,
+                radius: r2,
+            },
+        ) => {
+            let (dx, dy) = (*c2.x - *c1.x, *c2.y - *c1.y);
+            let d = dx.hypot(dy);
+
+            if d < DefaultEpsilon::EPSILON {
+                if (r1 - r2).abs() >= DefaultEpsilon::EPSILON {
+                    return vec![];
+                }
+
+                return [middle_of_arc(e1), middle_of_arc(e2)]
+                    .into_iter()
+                    .filter(|p| is_on_arc(e1, p) && is_on_arc(e2, p))
+                    .collect();
+            }
+            if d > r1 + r2 + DefaultEpsilon::EPSILON
+                || d < (r1 - r2).abs() - DefaultEpsilon::EPSILON
+            {
+                return vec![];
+            }
+
+            // the foot of the chord between intersections, and the half of the chord
+            let a = (r1 * r1 - r2 * r2 + d * d) / (2.0 * d);
+            let h = (r1 * r1 - a * a).max(0.0).sqrt();
+            let (ux, uy) = (dx / d, dy / d);
+            let (fx, fy) = (*c1.x + a * ux, *c1.y + a * uy);
+
+            [
+                Point2::new(fx - h * uy, fy + h * ux),
+                Point2::new(fx + h * uy, fy - h * ux),
+            ]
+            .into_iter()
+            .filter(|p| is_on_arc(e1, p) && is_on_arc(e2, p))
+            .collect()
+        }
+    }
+}
+
+/// Get intersection points of the line and the arc
+fn line_arc_intersections(line: &SketchEdge, arc: &SketchEdge) -> Vec<Point2> {
+    let EdgeCurve::Arc { center, radius } = &*arc.curve else {
+        return vec![];
+    };
+
+    // solve |start + t * d - center| = radius for t in [0, 1]
+    let (dx, dy) = (*line.end.x - *line.start.x, *line.end.y - *line.start.y);
+    let (fx, fy) = (*line.start.x - *center.x, *line.start.y - *center.y);
+    let a = dx * dx + dy * dy;
+    let b = 2.0 * (fx * dx + fy * dy);
+    let c = fx * fx + fy * fy - radius * radius;
+    let discriminant = b * b - 4.0 * a * c;
+    if a == 0.0 || discriminant < 0.0 {
+        return vec![];
+    }
+
+    let tolerance = DefaultEpsilon::EPSILON / a.sqrt();
+    let root = discriminant.sqrt();
+    [(-b - root) / (2.0 * a), (-b + root) / (2.0 * a)]
+        .into_iter()
+        .filter(|t| (-tolerance..=1.0 + tolerance).contains(t))
+        .map(|t| Point2::new(*line.start.x + t * dx, *line.start.y + t * dy))
+        .filter(|p| is_on_arc(arc, p))
+        .collect()
+}
+
+/// Check the point on the circle of the arc is in the range of the arc
+fn is_on_arc(arc: &SketchEdge, point: &Point2) -> bool {
+    let (EdgeCurve::Arc { center, radius }, Some(sweep)) = (&*arc.curve, arc.sweep()) else {
+        return false;
+    };
+
+    let angle_of = |p: &Point2| (*p.y - *center.y).atan2(*p.x - *center.x);
+    let from_start = (angle_of(point) - angle_of(&arc.start)).rem_euclid(std::f64::consts::TAU);
+    let tolerance = DefaultEpsilon::EPSILON / radius;
+
+    from_start <= sweep + tolerance || from_start >= std::f64::consts::TAU - tolerance
+}
+
+/// Get the point at the middle of the arc
+fn middle_of_arc(arc: &SketchEdge) -> Point2 {
+    let (EdgeCurve::Arc { center, radius }, Some(sweep)) = (&*arc.curve, arc.sweep()) else {
+        return (*arc.start).clone();
+    };
+
+    let angle = (*arc.start.y - *center.y).atan2(*arc.start.x - *center.x) + sweep / 2.0;
+    Point2::new(
+        *center.x + radius * angle.cos(),
+        *center.y + radius * angle.sin(),
+    )
+}
@@ -4,7 +4,7 @@ use cad_base::{
     id::{BodyId, FeatureId},
     plane::Plane,
     refs::FaceRef,
-    sketch::{AttachableTarget, Geometry, LineSegment, Point2, Sketch},
+    sketch::{Arc, AttachableTarget, Circle, Geometry, LineSegment, Point2, Sketch, SketchShape},
     tag::FaceTag,
 };
 use epsilon::DefaultEpsilon;
@@ -24,14 +24,16 @@ fn face_sketch() -> Sketch {
     Sketch::new("test", BodyId::new(1), &target)
 }
 
-fn add_segment(sketch: &mut Sketch, start: (f32, f32), end: (f32, f32)) {
-    sketch.add_geometry(|vars| {
-        Geometry::LineSegment(LineSegment::from_points(
-            &Point2::new(start.0, start.1),
-            &Point2::new(end.0, end.1),
-            vars,
-        ))
-    });
+fn add_segment(sketch: &mut Sketch, start: (f64, f64), end: (f64, f64)) {
+    sketch
+        .add_geometry(|vars| {
+            Geometry::LineSegment(LineSegment::from_points(
+                &Point2::new(start.0, start.1),
+                &Point2::new(end.0, end.1),
+                vars,
+            ))
+        })
+        .unwrap();
 }
 
 fn triangle_sketch() -> Sketch {
@@ -327,4 +329,127 @@ mod calculate_jordan_curves {
             "concave L-shape should not produce crossing edges"
         );
     }
+
+    #[test]
+    fn circle_returns_one_curve_of_tessellated_points() {
+        // Arrange
+        let mut sketch = plane_sketch();
+        sketch
+            .add_geometry(|vars| Geometry::Circle(Circle::new(&Point2::new(0.0, 0.0), 2.0, vars)))
+            .unwrap();
+
+        let plane = Plane::<DefaultEpsilon>::new_xy();
+        let target = AttachedTarget::Plane(&plane);
+        let sketcher = Sketcher::new(&sketch, &target).expect("should create sketcher");
+
+        // Act
+        let curves = sketcher
+            .calculate_jordan_corves::<DefaultEpsilon>()
+            .expect("should calculate curves");
+
+        // Assert – all points are on the circle
+        assert_eq!(curves.len(), 1);
+        assert_eq!(curves[0].points.len(), 64);
+        for point in &curves[0].points {
+            assert_relative_eq!(point.x.hypot(*point.y), 2.0, epsilon = 1e-5);
+        }
+    }
+
+    #[test]
+    fn half_disc_of_arc_and_line_succeeds() {
+        // Arrange – arc (1,0)→(-1,0) counterclockwise, and line (-1,0)→(1,0)
+        let mut sketch = plane_sketch();
+        sketch
+            .add_geometry(|vars| {
+                Geometry::Arc(Arc::from_points(
+                    &Point2::new(0.0, 0.0),
+                    &Point2::new(1.0, 0.0),
+                    &Point2::new(-1.0, 0.0),
+                    vars,
+                ))
+            })
+            .unwrap();
+        add_segment(&mut sketch, (-1.0, 0.0), (1.0, 0.0));
+
+        let plane = Plane::<DefaultEpsilon>::new_xy();
+        let target = AttachedTarget::Plane(&plane);
+        let sketcher = Sketcher::new(&sketch, &target).expect("should create sketcher");
+
+        // Act
+        let curves = sketcher
+            .calculate_jordan_corves::<DefaultEpsilon>()
+            .expect("should calculate curves");
+
+        // Assert – half of the turn is 32 segments, and the line adds its start
+        assert_eq!(curves.len(), 1);
+        assert_eq!(curves[0].points.len(), 33);
+    }
+
+    #[test]
+    fn line_crossing_circle_returns_error() {
+        // Arrange
+        let mut sketch = plane_sketch();
+        sketch
+            .add_geometry(|vars| Geometry::Circle(Circle::new(&Point2::new(0.0, 0.0), 1.0, vars)))
+            .unwrap();
+        add_segment(&mut sketch, (-2.0, 0.5), (2.0, 0.5));
+
+        let plane = Plane::<DefaultEpsilon>::new_xy();
+        let target = AttachedTarget::Plane(&plane);
+        let sketcher = Sketcher::new(&sketch, &target).expect("should create sketcher");
+
+        // Act
+        let result = sketcher.calculate_jordan_corves::<DefaultEpsilon>();
+
+        // Assert
+        assert!(matches!(result, Err(SketcherError::SketchHasNoJordanCurve)));
+    }
+
+    #[test]
+    fn overlapping_circles_return_error() {
+        // Arrange
+        let mut sketch = plane_sketch();
+        sketch
+            .add_geometry(|vars| Geometry::Circle(Circle::new(&Point2::new(0.0, 0.0), 1.0, vars)))
+            .unwrap();
+        sketch
+            .add_geometry(|vars| Geometry::Circle(Circle::new(&Point2::new(1.0, 0.0), 1.0, vars)))
+            .unwrap();
+
+        let plane = Plane::<DefaultEpsilon>::new_xy();
+        let target = AttachedTarget::Plane(&plane);
+        let sketcher = Sketcher::new(&sketch, &target).expect("should create sketcher");
+
+        // Act
+        let result = sketcher.calculate_jordan_corves::<DefaultEpsilon>();
+
+        // Assert
+        assert!(matches!(result, Err(SketcherError::SketchHasNoJordanCurve)));
+    }
+
+    #[test]
+    fn slot_returns_one_curve() {
+        // Arrange
+        let mut sketch = plane_sketch();
+        sketch
+            .add_shape(&SketchShape::Slot {
+                start: Point2::new(0.0, 0.0),
+                end: Point2::new(3.0, 0.0),
+                radius: 1.0,
+            })
+            .expect("should add slot");
+
+        let plane = Plane::<DefaultEpsilon>::new_xy();
+        let target = AttachedTarget::Plane(&plane);
+        let sketcher = Sketcher::new(&sketch, &target).expect("should create sketcher");
+
+        // Act
+        let curves = sketcher
+            .calculate_jordan_corves::<DefaultEpsilon>()
+            .expect("should calculate curves");
+
+        // Assert – each half circle is 32 segments, and each line adds its start
+        assert_eq!(curves.len(), 1);
+        assert_eq!(curves[0].points.len(), 66);
+    }
 }
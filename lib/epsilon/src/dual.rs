@@ -0,0 +1,429 @@
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// A dual number `value + derivative * e` where `e * e = 0`, for forward-mode automatic
+/// differentiation.
+///
+/// Seed the parameter to differentiate with [`Dual::variable`] and others with
+/// [`Dual::constant`], then the derivative of any calculation with respect to the parameter is
+/// carried along the value. Derivatives of functions that are not differentiable at the value,
+/// such as `abs` at zero, are one-sided ones.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Dual {
+    value: f64,
+    derivative: f64,
+}
+
+impl Dual {
+    pub fn new(value: f64, derivative: f64) -> Self {
+        Dual { value, derivative }
+    }
+
+    /// Get a constant, that is its derivative is zero
+    pub fn constant(value: f64) -> Self {
+        Dual::new(value, 0.0)
+    }
+
+    /// Get the parameter to differentiate with respect to, that is its derivative is one
+    pub fn variable(value: f64) -> Self {
+        Dual::new(value, 1.0)
+    }
+
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    pub fn derivative(&self) -> f64 {
+        self.derivative
+    }
+
+    /// Apply the function with `value` of it and `slope` that is the derivative at the value
+    fn chain(&self, value: f64, slope: f64) -> Self {
+        Dual::new(value, slope * self.derivative)
+    }
+
+    /// Get the square
+    pub fn sqr(&self) -> Self {
+        *self * *self
+    }
+
+    /// Get the integer power
+    pub fn powi(&self, exponent: i32) -> Self {
+        if exponent == 0 {
+            return Dual::constant(1.0);
+        }
+
+        self.chain(
+            self.value.powi(exponent),
+            exponent as f64 * self.value.powi(exponent - 1),
+        )
+    }
+
+    /// Get the power. The exponent must be constant for non-positive bases.
+    pub fn powf(&self, exponent: Dual) -> Self {
+        if exponent.derivative == 0.0 {
+            if exponent.value.fract() == 0.0 && exponent.value.abs() <= i32::MAX as f64 {
+                return self.powi(exponent.value as i32);
+            }
+            return self.chain(
+                self.value.powf(exponent.value),
+                exponent.value * self.value.powf(exponent.value - 1.0),
+            );
+        }
+
+        // d(a^b) = a^b * (b' * ln(a) + b * a' / a)
+        let value = self.value.powf(exponent.value);
+        Dual::new(
+            value,
+            value
+                * (exponent.derivative * self.value.ln()
+                    + exponent.value * self.derivative / self.value),
+        )
+    }
+
+    /// Get the square root
+    pub fn sqrt(&self) -> Self {
+        let value = self.value.sqrt();
+
+        self.chain(value, 0.5 / value)
+    }
+
+    /// Get the natural logarithm
+    pub fn ln(&self) -> Self {
+        self.chain(self.value.ln(), 1.0 / self.value)
+    }
+
+    /// Get the absolute value
+    pub fn abs(&self) -> Self {
+        if self.value.is_sign_negative() {
+            -*self
+        } else {
+            *self
+        }
+    }
+
+    /// Get the minimum of two values, and the derivative of the selected one
+    pub fn min(&self, other: Dual) -> Self {
+        if other.value < self.value {
+            other
+        } else {
+            *self
+        }
+    }
+
+    /// Get the maximum of two values, and the derivative of the selected one
+    pub fn max(&self, other: Dual) -> Self {
+        if other.value > self.value {
+            other
+        } else {
+            *self
+        }
+    }
+
+    /// Get the sign of the value, `0` for zero. The derivative is always zero.
+    pub fn sign(&self) -> Self {
+        let sign = if self.value > 0.0 {
+            1.0
+        } else if self.value < 0.0 {
+            -1.0
+        } else {
+            0.0
+        };
+
+        Dual::constant(sign)
+    }
+
+    /// Get the sine
+    pub fn sin(&self) -> Self {
+        self.chain(self.value.sin(), self.value.cos())
+    }
+
+    /// Get the cosine
+    pub fn cos(&self) -> Self {
+        self.chain(self.value.cos(), -self.value.sin())
+    }
+
+    /// Get the tangent
+    pub fn tan(&self) -> Self {
+        let cos = self.value.cos();
+
+        self.chain(self.value.tan(), 1.0 / (cos * cos))
+    }
+
+    /// Get `atan2(self, x)`, that is the angle of the point `(x, self)`
+    pub fn atan2(&self, x: Dual) -> Self {
+        let y = self;
+        let r2 = x.value * x.value + y.value * y.value;
+
+        Dual::new(
+            y.value.atan2(x.value),
+            (x.value * y.derivative - y.value * x.derivative) / r2,
+        )
+    }
+}
+
+impl From<f64> for Dual {
+    fn from(value: f64) -> Self {
+        Dual::constant(value)
+    }
+}
+
+impl Add for Dual {
+    type Output = Dual;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Dual::new(self.value + rhs.value, self.derivative + rhs.derivative)
+    }
+}
+
+impl Sub for Dual {
+    type Output = Dual;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Dual::new(self.value - rhs.value, self.derivative - rhs.derivative)
+    }
+}
+
+impl Mul for Dual {
+    type Output = Dual;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Dual::new(
+            self.value * rhs.value,
+            self.derivative * rhs.value + self.value * rhs.derivative,
+        )
+    }
+}
+
+impl Div for Dual {
+    type Output = Dual;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        Dual::new(
+            self.value / rhs.value,
+            (self.derivative * rhs.value - self.value * rhs.derivative) / (rhs.value * rhs.value),
+        )
+    }
+}
+
+impl Mul<f64> for Dual {
+    type Output = Dual;
+
+    fn mul(self, rhs: f64) -> Self::Output {
+        Dual::new(self.value * rhs, self.derivative * rhs)
+    }
+}
+
+impl Div<f64> for Dual {
+    type Output = Dual;
+
+    fn div(self, rhs: f64) -> Self::Output {
+        Dual::new(self.value / rhs, self.derivative / rhs)
+    }
+}
+
+impl Neg for Dual {
+    type Output = Dual;
+
+    fn neg(self) -> Self::Output {
+        Dual::new(-self.value, -self.derivative)
+    }
+}
+
+/// A 3D vector of dual numbers
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Dual3 {
+    pub x: Dual,
+    pub y: Dual,
+    pub z: Dual,
+}
+
+impl Dual3 {
+    pub fn new(x: Dual, y: Dual, z: Dual) -> Self {
+        Dual3 { x, y, z }
+    }
+
+    /// Get values of components
+    pub fn value(&self) -> (f64, f64, f64) {
+        (self.x.value, self.y.value, self.z.value)
+    }
+
+    /// Get derivatives of components
+    pub fn derivative(&self) -> (f64, f64, f64) {
+        (self.x.derivative, self.y.derivative, self.z.derivative)
+    }
+
+    /// Get the dot product
+    pub fn dot(&self, other: &Dual3) -> Dual {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    /// Get the cross product
+    pub fn cross(&self, other: &Dual3) -> Dual3 {
+        Dual3::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+
+    /// Get the squared norm
+    pub fn norm2(&self) -> Dual {
+        self.dot(self)
+    }
+
+    /// Get the unit vector
+    pub fn unit(&self) -> Dual3 {
+        *self / self.norm2().sqrt()
+    }
+}
+
+impl From<(f64, f64, f64)> for Dual3 {
+    fn from(value: (f64, f64, f64)) -> Self {
+        Dual3::new(value.0.into(), value.1.into(), value.2.into())
+    }
+}
+
+impl Add for Dual3 {
+    type Output = Dual3;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Dual3::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl Sub for Dual3 {
+    type Output = Dual3;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Dual3::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl Mul<Dual> for Dual3 {
+    type Output = Dual3;
+
+    fn mul(self, rhs: Dual) -> Self::Output {
+        Dual3::new(self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+impl Div<Dual> for Dual3 {
+    type Output = Dual3;
+
+    fn div(self, rhs: Dual) -> Self::Output {
+        Dual3::new(self.x / rhs, self.y / rhs, self.z / rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    /// Central difference of `f` at `x`
+    fn numeric(f: impl Fn(f64) -> f64, x: f64) -> f64 {
+        let h = 1e-6;
+        (f(x + h) - f(x - h)) / (2.0 * h)
+    }
+
+    #[test]
+    fn test_product_rule() {
+        // Arrange
+        let x = Dual::variable(3.0);
+
+        // Act
+        let result = x * x * Dual::constant(2.0) + x;
+
+        // Assert
+        assert_eq!(result, Dual::new(21.0, 13.0));
+    }
+
+    #[test]
+    fn test_quotient_rule() {
+        // Arrange
+        let x = Dual::variable(2.0);
+
+        // Act
+        let result = Dual::constant(1.0) / x;
+
+        // Assert
+        assert_eq!(result, Dual::new(0.5, -0.25));
+    }
+
+    #[rstest]
+    #[case(|x: Dual| x.sqrt(), |x: f64| x.sqrt())]
+    #[case(|x: Dual| x.ln(), |x: f64| x.ln())]
+    #[case(|x: Dual| x.sin(), |x: f64| x.sin())]
+    #[case(|x: Dual| x.cos(), |x: f64| x.cos())]
+    #[case(|x: Dual| x.tan(), |x: f64| x.tan())]
+    #[case(|x: Dual| x.powi(-3), |x: f64| x.powi(-3))]
+    #[case(|x: Dual| x.powf(Dual::constant(0.5)), |x: f64| x.powf(0.5))]
+    #[case(|x: Dual| x.powf(x), |x: f64| x.powf(x))]
+    #[case(|x: Dual| Dual::constant(2.0).powf(x), |x: f64| 2.0f64.powf(x))]
+    #[case(|x: Dual| x.atan2(Dual::constant(-0.5)), |x: f64| x.atan2(-0.5))]
+    #[case(|x: Dual| Dual::constant(0.3).atan2(x), |x: f64| 0.3f64.atan2(x))]
+    #[case(|x: Dual| (-x).abs(), |x: f64| (-x).abs())]
+    #[case(|x: Dual| x.sqr().min(x), |x: f64| (x * x).min(x))]
+    #[case(|x: Dual| x.sqr().max(x), |x: f64| (x * x).max(x))]
+    fn test_derivative_matches_central_difference(
+        #[case] f: fn(Dual) -> Dual,
+        #[case] g: fn(f64) -> f64,
+    ) {
+        for x in [0.7, 1.3, 2.5] {
+            // Arrange
+            let seed = Dual::variable(x);
+
+            // Act
+            let result = f(seed);
+
+            // Assert
+            assert_relative_eq!(result.value(), g(x));
+            assert_relative_eq!(result.derivative(), numeric(g, x), epsilon = 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_sign_is_constant() {
+        // Arrange
+        let x = Dual::variable(-2.0);
+
+        // Act
+        let result = x.sign();
+
+        // Assert
+        assert_eq!(result, Dual::constant(-1.0));
+    }
+
+    #[test]
+    fn test_unit_vector_derivative_is_orthogonal_to_it() {
+        // Arrange
+        let t = Dual::variable(0.5);
+        let v = Dual3::new(t, t * t, Dual::constant(1.0));
+
+        // Act
+        let unit = v.unit();
+
+        // Assert
+        let (x, y, z) = unit.value();
+        let (dx, dy, dz) = unit.derivative();
+        assert_relative_eq!(x * x + y * y + z * z, 1.0);
+        assert_relative_eq!(x * dx + y * dy + z * dz, 0.0, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_cross_product_derivative() {
+        // Arrange
+        let t = Dual::variable(2.0);
+        let a = Dual3::new(t, Dual::constant(0.0), Dual::constant(0.0));
+        let b = Dual3::from((0.0, 1.0, 0.0));
+
+        // Act
+        let result = a.cross(&b);
+
+        // Assert
+        assert_eq!(result.value(), (0.0, 0.0, 2.0));
+        assert_eq!(result.derivative(), (0.0, 0.0, 1.0));
+    }
+}
@@ -0,0 +1,127 @@
+use std::cmp::Ordering;
+
+/// Get the rounded sum of `a` and `b`, and the rounding error of it.
+///
+/// `a + b` equals exactly to the sum of returned values.
+pub fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let sum = a + b;
+    let b_virtual = sum - a;
+    let a_virtual = sum - b_virtual;
+    let error = (a - a_virtual) + (b - b_virtual);
+
+    (sum, error)
+}
+
+/// Get the rounded product of `a` and `b`, and the rounding error of it.
+///
+/// `a * b` equals exactly to the sum of returned values, unless the product overflows or underflows.
+pub fn two_product(a: f64, b: f64) -> (f64, f64) {
+    let product = a * b;
+
+    (product, a.mul_add(b, -product))
+}
+
+/// Get the exact sign of `sum(a * b)` of `terms`.
+///
+/// Products and sums are kept as a non-overlapping expansion, so there is no rounding error. The
+/// result is exact as long as products do not overflow or underflow. When some of terms are not
+/// finite, the sign of the rounded sum is returned.
+pub fn sign_of_sum_of_products(terms: &[(f64, f64)]) -> Ordering {
+    let mut expansion: Vec<f64> = Vec::with_capacity(terms.len() * 2);
+    for (a, b) in terms {
+        let (product, error) = two_product(*a, *b);
+        if !product.is_finite() || !error.is_finite() {
+            let rounded: f64 = terms.iter().map(|(a, b)| a * b).sum();
+            return rounded.partial_cmp(&0.0).unwrap_or(Ordering::Equal);
+        }
+
+        grow_expansion(&mut expansion, error);
+        grow_expansion(&mut expansion, product);
+    }
+
+    // components are ordered by magnitude, so the largest non-zero one decides the sign
+    expansion
+        .iter()
+        .rev()
+        .find(|v| **v != 0.0)
+        .map(|v| v.total_cmp(&0.0))
+        .unwrap_or(Ordering::Equal)
+}
+
+/// Add `value` to the non-overlapping expansion that is ordered by magnitude
+fn grow_expansion(expansion: &mut Vec<f64>, value: f64) {
+    let mut q = value;
+    for component in expansion.iter_mut() {
+        let (sum, error) = two_sum(q, *component);
+        *component = error;
+        q = sum;
+    }
+    expansion.push(q);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    #[test]
+    fn test_two_sum_keeps_rounding_error() {
+        // Arrange
+        let a = 1.0;
+        let b = 1e-20;
+
+        // Act
+        let (sum, error) = two_sum(a, b);
+
+        // Assert
+        assert_eq!(sum, 1.0);
+        assert_eq!(error, 1e-20);
+    }
+
+    #[test]
+    fn test_two_product_keeps_rounding_error() {
+        // Arrange
+        let a = 1.0 + f64::EPSILON;
+        let b = 1.0 - f64::EPSILON;
+
+        // Act
+        let (product, error) = two_product(a, b);
+
+        // Assert
+        // exact product is 1 - EPSILON^2, that is not representable
+        assert_eq!(product, 1.0);
+        assert_eq!(error, -f64::EPSILON * f64::EPSILON);
+    }
+
+    #[rstest]
+    #[case(&[(2.0, 3.0), (-1.0, 6.0)], Ordering::Equal)]
+    #[case(&[(2.0, 3.0), (-1.0, 5.0)], Ordering::Greater)]
+    #[case(&[(2.0, 3.0), (-1.0, 7.0)], Ordering::Less)]
+    #[case(&[], Ordering::Equal)]
+    // 1e20 + 1 - 1e20 is 0 with rounded arithmetic
+    #[case(&[(1e20, 1.0), (1.0, 1.0), (-1e20, 1.0)], Ordering::Greater)]
+    // (1 + EPSILON)(1 - EPSILON) - 1 is 0 with rounded arithmetic
+    #[case(&[(1.0 + f64::EPSILON, 1.0 - f64::EPSILON), (-1.0, 1.0)], Ordering::Less)]
+    fn test_sign_of_sum_of_products(#[case] terms: &[(f64, f64)], #[case] expected: Ordering) {
+        // Arrange - inputs provided by rstest
+
+        // Act
+        let result = sign_of_sum_of_products(terms);
+
+        // Assert
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_sign_of_sum_of_products_with_infinity_uses_rounded_sum() {
+        // Arrange
+        let terms = [(f64::INFINITY, 1.0), (-1.0, 1.0)];
+
+        // Act
+        let result = sign_of_sum_of_products(&terms);
+
+        // Assert
+        assert_eq!(result, Ordering::Greater);
+    }
+}
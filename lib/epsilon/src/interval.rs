@@ -0,0 +1,765 @@
+use std::{
+    f64::consts::{FRAC_PI_2, PI},
+    ops::{Add, Div, Mul, Neg, Sub},
+};
+
+/// Result of a predicate that is evaluated with rounding errors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Predicate {
+    True,
+    False,
+    /// Rounding errors are too large to decide
+    Uncertain,
+}
+
+impl Predicate {
+    /// Get the result when it is certain
+    pub fn certain(self) -> Option<bool> {
+        match self {
+            Predicate::True => Some(true),
+            Predicate::False => Some(false),
+            Predicate::Uncertain => None,
+        }
+    }
+
+    /// Get the result, or the result of `fallback` when it is uncertain. `fallback` is for exact
+    /// and slower evaluation.
+    pub fn resolve(self, fallback: impl FnOnce() -> bool) -> bool {
+        self.certain().unwrap_or_else(fallback)
+    }
+}
+
+impl From<bool> for Predicate {
+    fn from(value: bool) -> Self {
+        if value {
+            Predicate::True
+        } else {
+            Predicate::False
+        }
+    }
+}
+
+/// A closed interval of real numbers that contains the exact result of a calculation.
+///
+/// Each operation rounds the bounds outward, so the exact value is always in the interval even if
+/// floating-point operations round. An operation that is not defined for some values in the
+/// interval, such as division by an interval containing zero, returns [`Interval::entire`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Interval {
+    lo: f64,
+    hi: f64,
+}
+
+impl Interval {
+    /// Get a new interval `[lo, hi]`. Bounds are swapped when `lo > hi`, and the entire interval is
+    /// returned when some bounds are NaN.
+    pub fn new(lo: f64, hi: f64) -> Self {
+        if lo.is_nan() || hi.is_nan() {
+            Interval::entire()
+        } else {
+            Interval {
+                lo: lo.min(hi),
+                hi: lo.max(hi),
+            }
+        }
+    }
+
+    /// Get an interval that has only `value`
+    pub fn point(value: f64) -> Self {
+        Interval::new(value, value)
+    }
+
+    /// Get the interval of all real numbers
+    pub fn entire() -> Self {
+        Interval {
+            lo: f64::NEG_INFINITY,
+            hi: f64::INFINITY,
+        }
+    }
+
+    /// Lower bound
+    pub fn lo(&self) -> f64 {
+        self.lo
+    }
+
+    /// Upper bound
+    pub fn hi(&self) -> f64 {
+        self.hi
+    }
+
+    /// Width of the interval
+    pub fn width(&self) -> f64 {
+        self.hi - self.lo
+    }
+
+    /// Return `true` if the interval contains `value`
+    pub fn contains(&self, value: f64) -> bool {
+        self.lo <= value && value <= self.hi
+    }
+
+    /// Return `true` if the interval is the entire interval
+    pub fn is_entire(&self) -> bool {
+        self.lo == f64::NEG_INFINITY && self.hi == f64::INFINITY
+    }
+
+    /// Is the value greater than zero
+    pub fn is_positive(&self) -> Predicate {
+        if self.lo > 0.0 {
+            Predicate::True
+        } else if self.hi <= 0.0 {
+            Predicate::False
+        } else {
+            Predicate::Uncertain
+        }
+    }
+
+    /// Is the value less than zero
+    pub fn is_negative(&self) -> Predicate {
+        (-*self).is_positive()
+    }
+
+    /// Is the value zero
+    pub fn is_zero(&self) -> Predicate {
+        if self.lo == 0.0 && self.hi == 0.0 {
+            Predicate::True
+        } else if self.contains(0.0) {
+            Predicate::Uncertain
+        } else {
+            Predicate::False
+        }
+    }
+
+    /// Is the absolute value less than `tolerance`
+    pub fn is_within(&self, tolerance: f64) -> Predicate {
+        if -tolerance < self.lo && self.hi < tolerance {
+            Predicate::True
+        } else if self.hi <= -tolerance || tolerance <= self.lo {
+            Predicate::False
+        } else {
+            Predicate::Uncertain
+        }
+    }
+
+    /// Get the square
+    pub fn sqr(&self) -> Self {
+        self.powi(2)
+    }
+
+    /// Get the power with the integer exponent
+    pub fn powi(&self, exponent: i32) -> Self {
+        if exponent < 0 {
+            return Interval::point(1.0) / self.powi(-exponent);
+        }
+
+        // even powers are the same as powers of the absolute value
+        let base = if exponent % 2 == 0 { self.abs() } else { *self };
+        (0..exponent).fold(Interval::point(1.0), |acc, _| acc * base)
+    }
+
+    /// Get the power with the exponent. Bases must not be negative unless the exponent is an
+    /// integer.
+    pub fn powf(&self, exponent: Interval) -> Self {
+        if exponent.lo == exponent.hi
+            && exponent.lo.fract() == 0.0
+            && exponent.lo.abs() <= i32::MAX as f64
+        {
+            return self.powi(exponent.lo as i32);
+        }
+        if self.lo < 0.0 {
+            return Interval::entire();
+        }
+
+        // powers of non-negative bases are monotonic for both of base and exponent
+        hull_of(
+            [
+                (self.lo, exponent.lo),
+                (self.lo, exponent.hi),
+                (self.hi, exponent.lo),
+                (self.hi, exponent.hi),
+            ]
+            .map(|(b, e)| b.powf(e)),
+        )
+        .widen(LIBM_ULPS)
+    }
+
+    /// Get the square root. Negative values are ignored.
+    pub fn sqrt(&self) -> Self {
+        if self.hi < 0.0 {
+            return Interval::entire();
+        }
+
+        Interval::new(self.lo.max(0.0).sqrt(), self.hi.sqrt()).widen(1)
+    }
+
+    /// Get the natural logarithm. Non-positive values are ignored.
+    pub fn ln(&self) -> Self {
+        if self.hi <= 0.0 {
+            return Interval::entire();
+        }
+
+        let lo = if self.lo <= 0.0 {
+            f64::NEG_INFINITY
+        } else {
+            self.lo.ln()
+        };
+        Interval::new(lo, self.hi.ln()).widen(LIBM_ULPS)
+    }
+
+    /// Get the absolute value
+    pub fn abs(&self) -> Self {
+        if self.lo >= 0.0 {
+            *self
+        } else if self.hi <= 0.0 {
+            -*self
+        } else {
+            Interval::new(0.0, self.hi.max(-self.lo))
+        }
+    }
+
+    /// Get the minimum of two values
+    pub fn min(&self, other: Interval) -> Self {
+        Interval::new(self.lo.min(other.lo), self.hi.min(other.hi))
+    }
+
+    /// Get the maximum of two values
+    pub fn max(&self, other: Interval) -> Self {
+        Interval::new(self.lo.max(other.lo), self.hi.max(other.hi))
+    }
+
+    /// Get the sign of the value, `0` for zero
+    pub fn sign(&self) -> Self {
+        let sign = |v: f64| {
+            if v > 0.0 {
+                1.0
+            } else if v < 0.0 {
+                -1.0
+            } else {
+                0.0
+            }
+        };
+
+        Interval::new(sign(self.lo), sign(self.hi))
+    }
+
+    /// Get the sine
+    pub fn sin(&self) -> Self {
+        periodic(self, f64::sin, FRAC_PI_2, -FRAC_PI_2)
+    }
+
+    /// Get the cosine
+    pub fn cos(&self) -> Self {
+        periodic(self, f64::cos, 0.0, PI)
+    }
+
+    /// Get the tangent. The entire interval is returned when the interval may contain a pole.
+    pub fn tan(&self) -> Self {
+        if !self.lo.is_finite() || !self.hi.is_finite() || self.width() >= PI {
+            return Interval::entire();
+        }
+        if contains_periodic(self, FRAC_PI_2, PI) {
+            return Interval::entire();
+        }
+
+        Interval::new(self.lo.tan(), self.hi.tan()).widen(LIBM_ULPS)
+    }
+
+    /// Get the angle of the point `(x, y)`, same as [`f64::atan2`] of `self` as `y`.
+    pub fn atan2(&self, x: Interval) -> Self {
+        let y = self;
+        // atan2 is continuous and monotonic for each coordinates unless the region crosses the
+        // negative x axis, so corners have extremes
+        if x.lo > 0.0 || y.lo > 0.0 || y.hi < 0.0 {
+            hull_of(
+                [(y.lo, x.lo), (y.lo, x.hi), (y.hi, x.lo), (y.hi, x.hi)].map(|(y, x)| y.atan2(x)),
+            )
+            .widen(LIBM_ULPS)
+        } else {
+            Interval::new(-PI, PI).widen(LIBM_ULPS)
+        }
+    }
+
+    /// Widen bounds by `ulps` units in the last place
+    fn widen(&self, ulps: usize) -> Self {
+        let (mut lo, mut hi) = (self.lo, self.hi);
+        for _ in 0..ulps {
+            lo = lo.next_down();
+            hi = hi.next_up();
+        }
+
+        Interval::new(lo, hi)
+    }
+}
+
+/// Units in the last place that results of libm functions can differ from exact ones. They are not
+/// correctly rounded.
+const LIBM_ULPS: usize = 2;
+
+/// Get the smallest interval that contains all values, rounded outward by one ulp
+fn hull_of<const N: usize>(values: [f64; N]) -> Interval {
+    if values.iter().any(|v| v.is_nan()) {
+        return Interval::entire();
+    }
+
+    let lo = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let hi = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    Interval::new(lo.next_down(), hi.next_up())
+}
+
+/// Return `true` if the interval may contain `offset + k * period` for some integer `k`. This is
+/// conservative to rounding errors of the multiple of the period.
+fn contains_periodic(interval: &Interval, offset: f64, period: f64) -> bool {
+    let k = ((interval.lo - offset) / period).floor();
+    let point = offset + k * period;
+    let slack = 1e-12 * (1.0 + point.abs());
+
+    point + period <= interval.hi + slack || interval.lo - slack <= point
+}
+
+/// Evaluate a function that has period `2 pi`, maximum `1` at `max` and minimum `-1` at `min`
+fn periodic(interval: &Interval, f: fn(f64) -> f64, max: f64, min: f64) -> Interval {
+    if !interval.lo.is_finite() || !interval.hi.is_finite() || interval.width() >= 2.0 * PI {
+        return Interval::new(-1.0, 1.0);
+    }
+
+    let ends = Interval::new(f(interval.lo), f(interval.hi)).widen(LIBM_ULPS);
+    let hi = if contains_periodic(interval, max, 2.0 * PI) {
+        1.0
+    } else {
+        ends.hi.min(1.0)
+    };
+    let lo = if contains_periodic(interval, min, 2.0 * PI) {
+        -1.0
+    } else {
+        ends.lo.max(-1.0)
+    };
+
+    Interval::new(lo, hi)
+}
+
+impl From<f64> for Interval {
+    fn from(value: f64) -> Self {
+        Interval::point(value)
+    }
+}
+
+impl Neg for Interval {
+    type Output = Interval;
+
+    fn neg(self) -> Self::Output {
+        Interval::new(-self.hi, -self.lo)
+    }
+}
+
+impl Add for Interval {
+    type Output = Interval;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Interval::new((self.lo + rhs.lo).next_down(), (self.hi + rhs.hi).next_up())
+    }
+}
+
+impl Sub for Interval {
+    type Output = Interval;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + -rhs
+    }
+}
+
+impl Mul for Interval {
+    type Output = Interval;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        hull_of([
+            self.lo * rhs.lo,
+            self.lo * rhs.hi,
+            self.hi * rhs.lo,
+            self.hi * rhs.hi,
+        ])
+    }
+}
+
+impl Div for Interval {
+    type Output = Interval;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        if rhs.contains(0.0) {
+            return Interval::entire();
+        }
+
+        hull_of([
+            self.lo / rhs.lo,
+            self.lo / rhs.hi,
+            self.hi / rhs.lo,
+            self.hi / rhs.hi,
+        ])
+    }
+}
+
+/// A 2D point or vector with interval coordinates
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Interval2 {
+    pub x: Interval,
+    pub y: Interval,
+}
+
+impl Interval2 {
+    pub fn new(x: Interval, y: Interval) -> Self {
+        Interval2 { x, y }
+    }
+
+    /// Get the dot product
+    pub fn dot(&self, other: &Interval2) -> Interval {
+        self.x * other.x + self.y * other.y
+    }
+
+    /// Get the z component of the cross product
+    pub fn cross(&self, other: &Interval2) -> Interval {
+        self.x * other.y - self.y * other.x
+    }
+}
+
+impl From<(f64, f64)> for Interval2 {
+    fn from(value: (f64, f64)) -> Self {
+        Interval2::new(value.0.into(), value.1.into())
+    }
+}
+
+impl Add for Interval2 {
+    type Output = Interval2;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Interval2::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl Sub for Interval2 {
+    type Output = Interval2;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Interval2::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl Mul<Interval> for Interval2 {
+    type Output = Interval2;
+
+    fn mul(self, rhs: Interval) -> Self::Output {
+        Interval2::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+/// A 3D point or vector with interval coordinates
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Interval3 {
+    pub x: Interval,
+    pub y: Interval,
+    pub z: Interval,
+}
+
+impl Interval3 {
+    pub fn new(x: Interval, y: Interval, z: Interval) -> Self {
+        Interval3 { x, y, z }
+    }
+
+    /// Get the dot product
+    pub fn dot(&self, other: &Interval3) -> Interval {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    /// Get the cross product
+    pub fn cross(&self, other: &Interval3) -> Interval3 {
+        Interval3::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+
+    /// Get the squared norm
+    pub fn norm2(&self) -> Interval {
+        self.x.sqr() + self.y.sqr() + self.z.sqr()
+    }
+}
+
+impl From<(f64, f64, f64)> for Interval3 {
+    fn from(value: (f64, f64, f64)) -> Self {
+        Interval3::new(value.0.into(), value.1.into(), value.2.into())
+    }
+}
+
+impl Add for Interval3 {
+    type Output = Interval3;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Interval3::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl Sub for Interval3 {
+    type Output = Interval3;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Interval3::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl Mul<Interval> for Interval3 {
+    type Output = Interval3;
+
+    fn mul(self, rhs: Interval) -> Self::Output {
+        Interval3::new(self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    #[test]
+    fn test_new_swaps_reversed_bounds() {
+        // Arrange & Act
+        let interval = Interval::new(2.0, -1.0);
+
+        // Assert
+        assert_eq!((interval.lo(), interval.hi()), (-1.0, 2.0));
+    }
+
+    #[test]
+    fn test_new_with_nan_is_entire() {
+        // Arrange & Act
+        let interval = Interval::new(f64::NAN, 1.0);
+
+        // Assert
+        assert!(interval.is_entire());
+    }
+
+    #[test]
+    fn test_add_contains_exact_sum_that_rounds() {
+        // Arrange
+        let a = Interval::point(0.1);
+        let b = Interval::point(0.2);
+
+        // Act
+        let sum = a + b;
+
+        // Assert
+        // 0.1 + 0.2 rounds to 0.30000000000000004, and the exact sum is lesser than it
+        assert!(sum.lo() < 0.1 + 0.2);
+        assert!(sum.hi() > 0.1 + 0.2);
+        assert!(sum.contains(0.3));
+    }
+
+    #[rstest]
+    #[case(Interval::new(1.0, 2.0), Interval::new(3.0, 4.0), 3.0, 8.0)]
+    #[case(Interval::new(-1.0, 2.0), Interval::new(3.0, 4.0), -4.0, 8.0)]
+    #[case(Interval::new(-2.0, -1.0), Interval::new(-4.0, 3.0), -6.0, 8.0)]
+    fn test_mul_contains_all_products(
+        #[case] a: Interval,
+        #[case] b: Interval,
+        #[case] lo: f64,
+        #[case] hi: f64,
+    ) {
+        // Arrange - inputs provided by rstest
+
+        // Act
+        let product = a * b;
+
+        // Assert
+        assert!(product.lo() <= lo && lo - product.lo() < 1e-12);
+        assert!(product.hi() >= hi && product.hi() - hi < 1e-12);
+    }
+
+    #[test]
+    fn test_div_by_interval_containing_zero_is_entire() {
+        // Arrange
+        let a = Interval::point(1.0);
+        let b = Interval::new(-1.0, 1.0);
+
+        // Act
+        let result = a / b;
+
+        // Assert
+        assert!(result.is_entire());
+    }
+
+    #[test]
+    fn test_even_power_is_not_negative() {
+        // Arrange
+        let a = Interval::new(-1.0, 2.0);
+
+        // Act
+        let result = a.sqr();
+
+        // Assert
+        assert!(result.lo() <= 0.0 && result.lo() > -1e-12);
+        assert!(result.contains(4.0));
+        assert!(!result.contains(-0.5));
+    }
+
+    #[test]
+    fn test_powf_with_integer_exponent_accepts_negative_base() {
+        // Arrange
+        let a = Interval::new(-2.0, -1.0);
+
+        // Act
+        let result = a.powf(Interval::point(3.0));
+
+        // Assert
+        assert!(result.contains(-8.0));
+        assert!(result.contains(-1.0));
+        assert!(!result.contains(0.0));
+    }
+
+    #[rstest]
+    #[case(Interval::new(0.0, 1.0), 0.0, 1.0f64.sin())]
+    #[case(Interval::new(1.0, 2.0), 1.0f64.sin(), 1.0)] // contains pi/2
+    #[case(Interval::new(4.0, 5.0), -1.0, 4.0f64.sin())] // contains 3pi/2
+    #[case(Interval::new(-10.0, 10.0), -1.0, 1.0)]
+    fn test_sin_contains_extremes(#[case] x: Interval, #[case] lo: f64, #[case] hi: f64) {
+        // Arrange - inputs provided by rstest
+
+        // Act
+        let result = x.sin();
+
+        // Assert
+        assert!(result.contains(lo));
+        assert!(result.contains(hi));
+        assert!(result.lo() >= -1.0 && result.hi() <= 1.0);
+    }
+
+    #[test]
+    fn test_cos_does_not_contain_values_out_of_range() {
+        // Arrange
+        let x = Interval::new(0.5, 1.0);
+
+        // Act
+        let result = x.cos();
+
+        // Assert
+        assert!(result.contains(0.5f64.cos()));
+        assert!(result.contains(1.0f64.cos()));
+        assert!(!result.contains(1.0));
+        assert!(!result.contains(0.5));
+    }
+
+    #[test]
+    fn test_tan_over_pole_is_entire() {
+        // Arrange
+        let x = Interval::new(1.0, 2.0);
+
+        // Act
+        let result = x.tan();
+
+        // Assert
+        assert!(result.is_entire());
+    }
+
+    #[test]
+    fn test_atan2_over_negative_x_axis_is_whole_circle() {
+        // Arrange
+        let y = Interval::new(-1.0, 1.0);
+        let x = Interval::point(-1.0);
+
+        // Act
+        let result = y.atan2(x);
+
+        // Assert
+        assert!(result.contains(PI));
+        assert!(result.contains(-PI));
+    }
+
+    #[test]
+    fn test_atan2_in_first_quadrant() {
+        // Arrange
+        let y = Interval::new(1.0, 2.0);
+        let x = Interval::new(1.0, 2.0);
+
+        // Act
+        let result = y.atan2(x);
+
+        // Assert
+        assert!(result.contains(0.5f64.atan()));
+        assert!(result.contains(2.0f64.atan()));
+        assert!(!result.contains(0.0));
+    }
+
+    #[rstest]
+    #[case(Interval::new(1.0, 2.0), Predicate::True)]
+    #[case(Interval::new(-2.0, 0.0), Predicate::False)]
+    #[case(Interval::new(-1.0, 1.0), Predicate::Uncertain)]
+    fn test_is_positive(#[case] x: Interval, #[case] expected: Predicate) {
+        // Arrange - inputs provided by rstest
+
+        // Act
+        let result = x.is_positive();
+
+        // Assert
+        assert_eq!(result, expected);
+    }
+
+    #[rstest]
+    #[case(Interval::new(-0.5, 0.5), Predicate::True)]
+    #[case(Interval::new(1.0, 2.0), Predicate::False)]
+    #[case(Interval::new(0.5, 1.5), Predicate::Uncertain)]
+    fn test_is_within(#[case] x: Interval, #[case] expected: Predicate) {
+        // Arrange - inputs provided by rstest
+
+        // Act
+        let result = x.is_within(1.0);
+
+        // Assert
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_resolve_uses_fallback_only_when_uncertain() {
+        // Arrange
+        let mut called = 0;
+
+        // Act
+        let certain = Predicate::False.resolve(|| {
+            called += 1;
+            true
+        });
+        let uncertain = Predicate::Uncertain.resolve(|| {
+            called += 1;
+            true
+        });
+
+        // Assert
+        assert_eq!((certain, uncertain, called), (false, true, 1));
+    }
+
+    #[test]
+    fn test_cross_of_interval2_contains_exact_value() {
+        // Arrange
+        let a = Interval2::from((0.1, 0.2));
+        let b = Interval2::from((0.3, 0.6));
+
+        // Act
+        let result = a.cross(&b);
+
+        // Assert
+        // exactly 0 for real numbers, but not for rounded coordinates
+        assert_eq!(result.is_zero(), Predicate::Uncertain);
+        assert!(result.width() < 1e-15);
+    }
+
+    #[test]
+    fn test_cross_of_interval3_is_perpendicular() {
+        // Arrange
+        let a = Interval3::from((1.0, 0.0, 0.0));
+        let b = Interval3::from((0.0, 1.0, 0.0));
+
+        // Act
+        let result = a.cross(&b);
+
+        // Assert
+        assert!(result.z.contains(1.0));
+        assert!(result.dot(&a).contains(0.0));
+        assert_eq!(result.norm2().is_within(1.1), Predicate::True);
+    }
+}
@@ -1,38 +1,42 @@
 use std::cmp::Ordering;
 
+pub mod dual;
+pub mod exact;
+pub mod interval;
+
 /// Trait for definition of epsilon for value margin
 pub trait Epsilon {
-    const EPSILON: f32;
+    const EPSILON: f64;
 }
 
 /// Default precision of Epsilon
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct DefaultEpsilon;
 impl Epsilon for DefaultEpsilon {
-    const EPSILON: f32 = 1e-5;
+    const EPSILON: f64 = 1e-5;
 }
 
 /// High precision of Epsilon
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct HighPrecisionEpsitlon;
 impl Epsilon for HighPrecisionEpsitlon {
-    const EPSILON: f32 = 1e-9;
+    const EPSILON: f64 = 1e-9;
 }
 
 /// Low precision of Epsilon
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct LowPrecisionEpsitlon;
 impl Epsilon for LowPrecisionEpsitlon {
-    const EPSILON: f32 = 1e-3;
+    const EPSILON: f64 = 1e-3;
 }
 
 /// helper with epsilon
-pub fn approx_eq<E: Epsilon>(a: f32, b: f32) -> bool {
+pub fn approx_eq<E: Epsilon>(a: f64, b: f64) -> bool {
     (a - b).abs() < E::EPSILON
 }
 
 /// helper with epsilon to get total cmp
-pub fn approx_total_cmp<E: Epsilon>(a: f32, b: f32) -> Ordering {
+pub fn approx_total_cmp<E: Epsilon>(a: f64, b: f64) -> Ordering {
     if approx_eq::<E>(a, b) {
         Ordering::Equal
     } else {
@@ -41,7 +45,7 @@ pub fn approx_total_cmp<E: Epsilon>(a: f32, b: f32) -> Ordering {
 }
 
 /// helper with epsilon
-pub fn approx_zero<E: Epsilon>(v: f32) -> bool {
+pub fn approx_zero<E: Epsilon>(v: f64) -> bool {
     v.abs() < E::EPSILON
 }
 
@@ -89,7 +93,7 @@ mod tests {
     #[case(100.0, 100.000001, true)] // large values within epsilon
     #[case(-50.0, -50.000001, true)] // negative values within epsilon
     #[case(0.0, 0.000001, true)] // near zero within epsilon
-    fn test_approx_eq_with_default_epsilon(#[case] a: f32, #[case] b: f32, #[case] expected: bool) {
+    fn test_approx_eq_with_default_epsilon(#[case] a: f64, #[case] b: f64, #[case] expected: bool) {
         // Arrange - inputs provided by rstest
 
         // Act
@@ -100,12 +104,12 @@ mod tests {
     }
 
     #[rstest]
-    #[case(1.0, 1.0 + 5e-11, true)] // within high precision epsilon (note: f32 has limited precision)
-    #[case(1.0, 1.0 + 0.0000000001, true)] // still within due to f32 precision limits
+    #[case(1.0, 1.0 + 5e-11, true)] // difference 5e-11 is within the 1e-9 epsilon
+    #[case(1.0, 1.0 + 0.0000000001, true)] // difference 1e-10 is within the 1e-9 epsilon
     #[case(0.0, 5e-11, true)] // near zero within high precision
     fn test_approx_eq_with_high_precision_epsilon(
-        #[case] a: f32,
-        #[case] b: f32,
+        #[case] a: f64,
+        #[case] b: f64,
         #[case] expected: bool,
     ) {
         // Arrange - inputs provided by rstest
@@ -122,8 +126,8 @@ mod tests {
     #[case(1.0, 1.002, false)] // outside low precision epsilon
     #[case(0.0, 0.0005, true)] // near zero within low precision
     fn test_approx_eq_with_low_precision_epsilon(
-        #[case] a: f32,
-        #[case] b: f32,
+        #[case] a: f64,
+        #[case] b: f64,
         #[case] expected: bool,
     ) {
         // Arrange - inputs provided by rstest
@@ -157,7 +161,7 @@ mod tests {
     #[case(-0.00002, false)] // negative outside epsilon
     #[case(1.0, false)] // clearly not zero
     #[case(-1.0, false)] // negative clearly not zero
-    fn test_approx_zero_with_default_epsilon(#[case] value: f32, #[case] expected: bool) {
+    fn test_approx_zero_with_default_epsilon(#[case] value: f64, #[case] expected: bool) {
         // Arrange - input provided by rstest
 
         // Act
@@ -171,7 +175,7 @@ mod tests {
     #[case(0.0, true)]
     #[case(0.0000000001, true)]
     #[case(0.0000001, false)]
-    fn test_approx_zero_with_high_precision_epsilon(#[case] value: f32, #[case] expected: bool) {
+    fn test_approx_zero_with_high_precision_epsilon(#[case] value: f64, #[case] expected: bool) {
         // Arrange - input provided by rstest
 
         // Act
@@ -186,7 +190,7 @@ mod tests {
     #[case(0.0005, true)] // within low precision epsilon
     #[case(0.002, false)] // outside low precision epsilon
     #[case(-0.0005, true)] // negative within low precision epsilon
-    fn test_approx_zero_with_low_precision_epsilon(#[case] value: f32, #[case] expected: bool) {
+    fn test_approx_zero_with_low_precision_epsilon(#[case] value: f64, #[case] expected: bool) {
         // Arrange - input provided by rstest
 
         // Act
@@ -229,7 +233,7 @@ mod tests {
     #[case(2.0, 1.0, Ordering::Greater)]
     #[case(1.0, 1.00002, Ordering::Less)] // outside epsilon
     #[case(1.00002, 1.0, Ordering::Greater)] // outside epsilon
-    fn test_approx_total_cmp(#[case] a: f32, #[case] b: f32, #[case] expected: Ordering) {
+    fn test_approx_total_cmp(#[case] a: f64, #[case] b: f64, #[case] expected: Ordering) {
         // Arrange - inputs provided by rstest
 
         // Act
@@ -0,0 +1,94 @@
+use criterion::{BatchSize, BenchmarkId, Criterion, criterion_group, criterion_main};
+use epsilon::DefaultEpsilon;
+use solver::{
+    DefaultEquationIdGenerator, LinearSolver, SolveOptions, Solver,
+    environment::Environment,
+    equation::parse,
+    matrix::{Matrix, lu::SparseLu, simple::SimpleMatrix, sparse::SparseMatrix},
+};
+
+/// Make a solver of chained points. Each point is distance 1 from the previous one.
+fn chain(points: usize, linear_solver: LinearSolver) -> Solver {
+    let mut solver = Solver::new::<DefaultEpsilon>(Box::new(DefaultEquationIdGenerator::default()));
+    solver.set_options(SolveOptions {
+        linear_solver,
+        ..solver.options()
+    });
+
+    let mut tuples = vec![];
+    for i in 0..points {
+        tuples.push((format!("x{}", i), i as f64));
+        tuples.push((format!("y{}", i), 0.0));
+    }
+    let tuples: Vec<(&str, f64)> = tuples.iter().map(|(k, v)| (k.as_str(), *v)).collect();
+    solver.update_variables(&Environment::from_tuples(&tuples));
+
+    solver.add_equation(parse("x0").unwrap());
+    solver.add_equation(parse("y0").unwrap());
+    for i in 1..points {
+        let dx = format!("(x{} - x{})", i, i - 1);
+        let dy = format!("(y{} - y{})", i, i - 1);
+        solver.add_equation(parse(&format!("{}^2 + {}^2 - 1", dx, dy)).unwrap());
+        solver.add_equation(parse(&format!("{} - 0.6", dy)).unwrap());
+    }
+
+    solver
+}
+
+/// Make a tridiagonal matrix
+fn tridiagonal(n: usize, scale: f64) -> SparseMatrix<f64> {
+    let mut mat = SimpleMatrix::<f64>::new(n, n).unwrap();
+    for i in 0..n {
+        mat.set(i, i, 4.0 * scale).unwrap();
+        if i > 0 {
+            mat.set(i, i - 1, -scale).unwrap();
+            mat.set(i - 1, i, -scale).unwrap();
+        }
+    }
+
+    SparseMatrix::from_matrix(&mat)
+}
+
+fn bench_solve(c: &mut Criterion) {
+    let mut group = c.benchmark_group("solve_chain");
+    group.sample_size(10);
+
+    for points in [25, 100] {
+        for (name, linear_solver) in [
+            ("sparse_lu", LinearSolver::SparseLu),
+            ("dense", LinearSolver::Dense),
+        ] {
+            group.bench_with_input(BenchmarkId::new(name, points), &points, |b, points| {
+                b.iter_batched(
+                    || chain(*points, linear_solver),
+                    |mut solver| solver.solve().unwrap(),
+                    BatchSize::LargeInput,
+                )
+            });
+        }
+    }
+    group.finish();
+}
+
+fn bench_refactorize(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sparse_lu_factorize");
+    let n = 400;
+    let first = tridiagonal(n, 1.0);
+    let second = tridiagonal(n, 2.0);
+
+    group.bench_function("analyze_and_factorize", |b| {
+        b.iter(|| {
+            let mut lu = SparseLu::analyze(&second).unwrap();
+            lu.factorize(&second).unwrap()
+        })
+    });
+    group.bench_function("reuse_analysis", |b| {
+        let mut lu = SparseLu::analyze(&first).unwrap();
+        lu.factorize(&first).unwrap();
+        b.iter(|| lu.factorize(&second).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_solve, bench_refactorize);
+criterion_main!(benches);
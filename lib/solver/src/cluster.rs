@@ -0,0 +1,472 @@
+use std::collections::HashMap;
+
+use crate::{EquationId, equation::Equation, equation::Evaluate};
+
+/// Connected component of the constraint system, or a block of it.
+///
+/// Equations in different components do not share any variable, so each component can be solved
+/// independently. Blocks of a component by [`triangularize`] must be solved in the order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Cluster {
+    /// Indices of equations, in ascending order
+    pub equations: Vec<usize>,
+
+    /// Indices of variables, in ascending order
+    pub variables: Vec<usize>,
+}
+
+/// Decompose equations and variables into connected components.
+///
+/// An equation and a variable are connected when the equation depends on the variable. Variables
+/// that are not related to any equation do not belong to any cluster. Clusters are ordered by
+/// the first equation of them.
+///
+/// # Arguments
+/// * `equations` - equations of the system
+/// * `variables` - variable names of the system. Names not in this are treated as constant.
+pub(crate) fn decompose(
+    equations: &[(EquationId, &Equation)],
+    variables: &[String],
+) -> Vec<Cluster> {
+    let related = related_variables(equations, variables);
+
+    let mut sets = DisjointSet::new(variables.len());
+    for vars in &related {
+        for pair in vars.windows(2) {
+            sets.union(pair[0], pair[1]);
+        }
+    }
+
+    let mut clusters: Vec<Cluster> = vec![];
+    let mut cluster_of_root: HashMap<usize, usize> = HashMap::new();
+    for (i, vars) in related.iter().enumerate() {
+        let Some(first) = vars.first() else {
+            // equation without variables can not be connected to others
+            clusters.push(Cluster {
+                equations: vec![i],
+                variables: vec![],
+            });
+            continue;
+        };
+
+        let root = sets.find(*first);
+        match cluster_of_root.get(&root) {
+            Some(index) => clusters[*index].equations.push(i),
+            None => {
+                cluster_of_root.insert(root, clusters.len());
+                clusters.push(Cluster {
+                    equations: vec![i],
+                    variables: vec![],
+                });
+            }
+        }
+    }
+
+    for (j, _) in variables.iter().enumerate() {
+        if let Some(index) = cluster_of_root.get(&sets.find(j)) {
+            clusters[*index].variables.push(j);
+        }
+    }
+
+    clusters
+}
+
+/// Split the cluster into blocks of the block triangular form, that is the fine part of the
+/// Dulmage–Mendelsohn decomposition.
+///
+/// Each equation is matched to a variable, and equations that depend on variables matched to each
+/// other make a block. Blocks are ordered so that each block depends only on variables of itself
+/// and blocks before it, so blocks can be solved one by one in the order. A cluster that is not
+/// square or does not have a perfect matching is not split, because it is not well-constrained.
+///
+/// # Arguments
+/// * `equations` - equations of the system
+/// * `variables` - variable names of the system
+/// * `cluster` - a cluster of [`decompose`] with the same arguments
+pub(crate) fn triangularize(
+    equations: &[(EquationId, &Equation)],
+    variables: &[String],
+    cluster: &Cluster,
+) -> Vec<Cluster> {
+    if cluster.equations.len() != cluster.variables.len() {
+        return vec![cluster.clone()];
+    }
+
+    let related = related_variables(equations, variables);
+    let local: HashMap<usize, usize> = cluster
+        .variables
+        .iter()
+        .enumerate()
+        .map(|(i, v)| (*v, i))
+        .collect();
+    let adjacency: Vec<Vec<usize>> = cluster
+        .equations
+        .iter()
+        .map(|e| related[*e].iter().map(|v| local[v]).collect())
+        .collect();
+
+    let Some(matched) = perfect_matching(&adjacency) else {
+        return vec![cluster.clone()];
+    };
+    // an equation depends on equations matched to its variables
+    let dependencies: Vec<Vec<usize>> = adjacency
+        .iter()
+        .map(|vars| vars.iter().map(|v| matched[*v]).collect())
+        .collect();
+
+    strongly_connected_components(&dependencies)
+        .into_iter()
+        .map(|component| {
+            let mut block = Cluster {
+                equations: component.iter().map(|e| cluster.equations[*e]).collect(),
+                variables: cluster
+                    .variables
+                    .iter()
+                    .enumerate()
+                    .filter(|(v, _)| component.contains(&matched[*v]))
+                    .map(|(_, v)| *v)
+                    .collect(),
+            };
+            block.equations.sort();
+            block
+        })
+        .collect()
+}
+
+/// Get indices of variables that each equation depends on
+fn related_variables(
+    equations: &[(EquationId, &Equation)],
+    variables: &[String],
+) -> Vec<Vec<usize>> {
+    let indices: HashMap<&str, usize> = variables
+        .iter()
+        .enumerate()
+        .map(|(i, v)| (v.as_str(), i))
+        .collect();
+
+    equations
+        .iter()
+        .map(|(_, e)| {
+            e.related_variables()
+                .iter()
+                .filter_map(|v| indices.get(v.as_str()).copied())
+                .collect()
+        })
+        .collect()
+}
+
+/// Match each equation to a variable with augmenting paths.
+///
+/// # Returns
+/// * The equation matched to each variable, or `None` if some equation can not be matched
+fn perfect_matching(adjacency: &[Vec<usize>]) -> Option<Vec<usize>> {
+    fn augment(
+        equation: usize,
+        adjacency: &[Vec<usize>],
+        visited: &mut [bool],
+        matched: &mut [Option<usize>],
+    ) -> bool {
+        for v in &adjacency[equation] {
+            if visited[*v] {
+                continue;
+            }
+            visited[*v] = true;
+
+            if matched[*v].is_none_or(|other| augment(other, adjacency, visited, matched)) {
+                matched[*v] = Some(equation);
+                return true;
+            }
+        }
+        false
+    }
+
+    let mut matched = vec![None; adjacency.len()];
+    for equation in 0..adjacency.len() {
+        let mut visited = vec![false; adjacency.len()];
+        if !augment(equation, adjacency, &mut visited, &mut matched) {
+            return None;
+        }
+    }
+
+    matched.into_iter().collect()
+}
+
+/// Get strongly connected components with Tarjan's algorithm. A component comes after all
+/// components that it depends on.
+fn strongly_connected_components(dependencies: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    struct Tarjan<'a> {
+        dependencies: &'a [Vec<usize>],
+        index: Vec<Option<usize>>,
+        lowlink: Vec<usize>,
+        stack: Vec<usize>,
+        on_stack: Vec<bool>,
+        next: usize,
+        components: Vec<Vec<usize>>,
+    }
+
+    impl Tarjan<'_> {
+        fn visit(&mut self, node: usize) {
+            self.index[node] = Some(self.next);
+            self.lowlink[node] = self.next;
+            self.next += 1;
+            self.stack.push(node);
+            self.on_stack[node] = true;
+
+            for dependency in &self.dependencies[node] {
+                match self.index[*dependency] {
+                    None => {
+                        self.visit(*dependency);
+                        self.lowlink[node] = self.lowlink[node].min(self.lowlink[*dependency]);
+                    }
+                    Some(index) if self.on_stack[*dependency] => {
+                        self.lowlink[node] = self.lowlink[node].min(index);
+                    }
+                    Some(_) => (),
+                }
+            }
+
+            if Some(self.lowlink[node]) == self.index[node] {
+                let mut component = vec![];
+                while let Some(member) = self.stack.pop() {
+                    self.on_stack[member] = false;
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                self.components.push(component);
+            }
+        }
+    }
+
+    let size = dependencies.len();
+    let mut tarjan = Tarjan {
+        dependencies,
+        index: vec![None; size],
+        lowlink: vec![0; size],
+        stack: vec![],
+        on_stack: vec![false; size],
+        next: 0,
+        components: vec![],
+    };
+    for node in 0..size {
+        if tarjan.index[node].is_none() {
+            tarjan.visit(node);
+        }
+    }
+
+    tarjan.components
+}
+
+/// Simple union-find with path compression
+struct DisjointSet {
+    parents: Vec<usize>,
+}
+
+impl DisjointSet {
+    fn new(size: usize) -> Self {
+        DisjointSet {
+            parents: (0..size).collect(),
+        }
+    }
+
+    fn find(&mut self, index: usize) -> usize {
+        let parent = self.parents[index];
+        if parent == index {
+            return index;
+        }
+
+        let root = self.find(parent);
+        self.parents[index] = root;
+        root
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let a = self.find(a);
+        let b = self.find(b);
+        if a != b {
+            self.parents[b.max(a)] = a.min(b);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::equation::parse;
+    use pretty_assertions::assert_eq;
+
+    fn names(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_decompose_unrelated_equations_into_clusters() {
+        // Arrange
+        let e1 = parse("x - 1").unwrap();
+        let e2 = parse("y + z").unwrap();
+        let e3 = parse("z - 2").unwrap();
+        let equations = vec![
+            (EquationId::from(1), &e1),
+            (EquationId::from(2), &e2),
+            (EquationId::from(3), &e3),
+        ];
+
+        // Act
+        let clusters = decompose(&equations, &names(&["x", "y", "z"]));
+
+        // Assert
+        assert_eq!(
+            clusters,
+            vec![
+                Cluster {
+                    equations: vec![0],
+                    variables: vec![0]
+                },
+                Cluster {
+                    equations: vec![1, 2],
+                    variables: vec![1, 2]
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_decompose_connects_clusters_through_shared_variable() {
+        // Arrange
+        let e1 = parse("a - b").unwrap();
+        let e2 = parse("c - d").unwrap();
+        let e3 = parse("b * c").unwrap();
+        let equations = vec![
+            (EquationId::from(1), &e1),
+            (EquationId::from(2), &e2),
+            (EquationId::from(3), &e3),
+        ];
+
+        // Act
+        let clusters = decompose(&equations, &names(&["a", "b", "c", "d"]));
+
+        // Assert
+        assert_eq!(
+            clusters,
+            vec![Cluster {
+                equations: vec![0, 1, 2],
+                variables: vec![0, 1, 2, 3]
+            }]
+        );
+    }
+
+    #[test]
+    fn test_decompose_ignores_dimensions_and_free_variables() {
+        // Arrange
+        let e1 = parse("x - d").unwrap();
+        let e2 = parse("y - d").unwrap();
+        let e3 = parse("d").unwrap();
+        let equations = vec![
+            (EquationId::from(1), &e1),
+            (EquationId::from(2), &e2),
+            (EquationId::from(3), &e3),
+        ];
+
+        // Act
+        let clusters = decompose(&equations, &names(&["w", "x", "y"]));
+
+        // Assert
+        assert_eq!(
+            clusters,
+            vec![
+                Cluster {
+                    equations: vec![0],
+                    variables: vec![1]
+                },
+                Cluster {
+                    equations: vec![1],
+                    variables: vec![2]
+                },
+                Cluster {
+                    equations: vec![2],
+                    variables: vec![]
+                },
+            ]
+        );
+    }
+
+    fn clusters_of(inputs: &[&str], variables: &[&str]) -> Vec<Cluster> {
+        let parsed: Vec<Equation> = inputs.iter().map(|e| parse(e).unwrap()).collect();
+        let equations: Vec<(EquationId, &Equation)> = parsed
+            .iter()
+            .enumerate()
+            .map(|(i, e)| (EquationId::from(i as u64), e))
+            .collect();
+        let variables = names(variables);
+
+        decompose(&equations, &variables)
+            .iter()
+            .flat_map(|c| triangularize(&equations, &variables, c))
+            .collect()
+    }
+
+    #[test]
+    fn test_triangularize_keeps_cluster_that_is_not_square() {
+        // Arrange
+        let inputs = ["z - x * y", "x + y - 3", "y - z + 4", "x - y - 1"];
+
+        // Act
+        let blocks = clusters_of(&inputs, &["x", "y", "z"]);
+
+        // Assert
+        assert_eq!(
+            blocks,
+            vec![Cluster {
+                equations: vec![0, 1, 2, 3],
+                variables: vec![0, 1, 2]
+            }]
+        );
+    }
+
+    #[test]
+    fn test_triangularize_orders_blocks_by_dependencies() {
+        // Arrange
+        let inputs = ["w - y * z", "y + z - 3", "x - 2", "y - z - x + 1"];
+
+        // Act
+        let blocks = clusters_of(&inputs, &["w", "x", "y", "z"]);
+
+        // Assert
+        assert_eq!(
+            blocks,
+            vec![
+                Cluster {
+                    equations: vec![2],
+                    variables: vec![1]
+                },
+                Cluster {
+                    equations: vec![1, 3],
+                    variables: vec![2, 3]
+                },
+                Cluster {
+                    equations: vec![0],
+                    variables: vec![0]
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_triangularize_keeps_cluster_without_perfect_matching() {
+        // Arrange
+        let inputs = ["x - 1", "x + y + z", "x - 2"];
+
+        // Act
+        let blocks = clusters_of(&inputs, &["x", "y", "z"]);
+
+        // Assert
+        assert_eq!(
+            blocks,
+            vec![Cluster {
+                equations: vec![0, 1, 2],
+                variables: vec![0, 1, 2]
+            }]
+        );
+    }
+}
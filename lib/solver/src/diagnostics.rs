@@ -0,0 +1,302 @@
+use std::collections::HashMap;
+
+use crate::{
+    EquationId,
+    equation::{Equation, EquationError, Evaluate},
+    matrix::Matrix,
+};
+
+/// status to indicate if the dimension specification is correct
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DimensionSpecificationStatus {
+    /// There are no equations and variables
+    Empty,
+    /// All variables are determined by equations, and all equations are independent
+    WellConstrained,
+    /// Some variables are not determined yet
+    UnderConstrained,
+    /// Some equations are dependent on others, but they do not conflict
+    Redundant,
+    /// Some equations are dependent on others and contradict them
+    Conflicting,
+    /// Some equations can not be evaluated with current variables and dimensions
+    Invalid,
+}
+
+/// Diagnostics of the constraint system.
+///
+/// The numerical analysis is a linearisation of equations at the current variables, so
+/// non-linear equations can be reported differently at other positions.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ConstraintDiagnostics {
+    /// Number of variables
+    pub variable_count: usize,
+
+    /// Number of equations
+    pub equation_count: usize,
+
+    /// Remaining degrees of freedom. This is number of variables minus numerical rank.
+    pub dof: usize,
+
+    /// Numerical rank of the jacobian
+    pub rank: usize,
+
+    /// Structural rank, the size of maximum matching between equations and variables
+    pub structural_rank: usize,
+
+    /// Names of variables that are not determined by equations, ordered by name
+    pub free_variables: Vec<String>,
+
+    /// Equations that depend on others, and consistent with them
+    pub redundant_equations: Vec<EquationId>,
+
+    /// Equations that depend on others, but contradict them. For non-linear equations, this is
+    /// reliable only when current variables are a solution of other equations.
+    pub conflicting_equations: Vec<EquationId>,
+
+    /// Equations that can not be evaluated, such as ones with unknown variables. They are not
+    /// analyzed numerically.
+    pub invalid_equations: Vec<EquationId>,
+}
+
+impl ConstraintDiagnostics {
+    /// Get the summarized status of the diagnostics.
+    ///
+    /// Invalid equations are prior to conflicts, conflicts are prior to redundancy, and redundancy
+    /// is prior to free variables.
+    pub fn status(&self) -> DimensionSpecificationStatus {
+        if self.variable_count == 0 && self.equation_count == 0 {
+            DimensionSpecificationStatus::Empty
+        } else if !self.invalid_equations.is_empty() {
+            DimensionSpecificationStatus::Invalid
+        } else if !self.conflicting_equations.is_empty() {
+            DimensionSpecificationStatus::Conflicting
+        } else if !self.redundant_equations.is_empty() {
+            DimensionSpecificationStatus::Redundant
+        } else if self.dof > 0 {
+            DimensionSpecificationStatus::UnderConstrained
+        } else {
+            DimensionSpecificationStatus::WellConstrained
+        }
+    }
+
+    /// Return `true` if the variable is not determined by equations
+    pub fn is_free(&self, variable: &str) -> bool {
+        self.free_variables.iter().any(|v| v == variable)
+    }
+}
+
+/// Analyze equations with structural matching and numerical rank.
+///
+/// # Arguments
+/// * `equations` - equations ordered as same as rows of `jacobian`
+/// * `variables` - variable names ordered as same as columns of `jacobian`
+/// * `jacobian` - evaluated jacobian. `None` when there are no equations or variables.
+/// * `residuals` - evaluated equations at current variables. Equations that failed to evaluate are
+///   reported as invalid
+/// * `tolerance` - threshold to treat a value as zero
+///
+/// Dependent equations are split into redundant and conflicting ones by residuals at current
+/// variables. This is exact for linear equations, but for non-linear equations it is valid only at
+/// a solution of independent equations, such as after solving. Elsewhere, a redundant non-linear
+/// equation can be reported as conflicting.
+pub(crate) fn analyze(
+    equations: &[(EquationId, &Equation)],
+    variables: &[String],
+    jacobian: Option<&impl Matrix<f64>>,
+    residuals: &[Result<f64, EquationError>],
+    tolerance: f64,
+) -> ConstraintDiagnostics {
+    let structural_rank = structural_rank(equations, variables);
+
+    // orthonormal basis of independent rows, with residual transformed as same as rows.
+    let mut basis: Vec<(Vec<f64>, f64)> = vec![];
+    let mut redundant_equations = vec![];
+    let mut conflicting_equations = vec![];
+    let mut invalid_equations = vec![];
+
+    for (i, (id, _)) in equations.iter().enumerate() {
+        let initial = match residuals.get(i) {
+            Some(Ok(residual)) => *residual,
+            Some(Err(_)) => {
+                invalid_equations.push(*id);
+                continue;
+            }
+            None => 0.0,
+        };
+        let mut row: Vec<f64> = match jacobian {
+            Some(jacobian) => (0..variables.len())
+                .map(|j| jacobian.get(i, j).ok().flatten().copied().unwrap_or(0.0))
+                .collect(),
+            None => vec![0.0; variables.len()],
+        };
+        let mut residual = initial;
+        let scale = norm(&row).max(1.0);
+
+        for (q, g) in &basis {
+            let a = dot(&row, q);
+            row.iter_mut().zip(q).for_each(|(r, q)| *r -= a * q);
+            residual -= a * g;
+        }
+
+        let remainder = norm(&row);
+        if remainder > tolerance * scale {
+            row.iter_mut().for_each(|r| *r /= remainder);
+            basis.push((row, residual / remainder));
+        } else if residual.abs() > tolerance * (1.0 + initial.abs()) {
+            conflicting_equations.push(*id);
+        } else {
+            redundant_equations.push(*id);
+        }
+    }
+
+    // variable is determined when the unit vector of it is in the row space.
+    let mut free_variables: Vec<String> = variables
+        .iter()
+        .enumerate()
+        .filter(|(j, _)| {
+            let projected: f64 = basis.iter().map(|(q, _)| q[*j] * q[*j]).sum();
+            1.0 - projected > tolerance.sqrt()
+        })
+        .map(|(_, v)| v.clone())
+        .collect();
+    free_variables.sort();
+
+    ConstraintDiagnostics {
+        variable_count: variables.len(),
+        equation_count: equations.len(),
+        // floating point errors can keep more basis than variables
+        dof: variables.len().saturating_sub(basis.len()),
+        rank: basis.len(),
+        structural_rank,
+        free_variables,
+        redundant_equations,
+        conflicting_equations,
+        invalid_equations,
+    }
+}
+
+/// Get the size of maximum bipartite matching between equations and variables.
+fn structural_rank(equations: &[(EquationId, &Equation)], variables: &[String]) -> usize {
+    let indices: HashMap<&str, usize> = variables
+        .iter()
+        .enumerate()
+        .map(|(i, v)| (v.as_str(), i))
+        .collect();
+    let adjacent: Vec<Vec<usize>> = equations
+        .iter()
+        .map(|(_, e)| {
+            e.related_variables()
+                .iter()
+                .filter_map(|v| indices.get(v.as_str()).copied())
+                .collect()
+        })
+        .collect();
+
+    // augmenting path search
+    fn augment(
+        equation: usize,
+        adjacent: &[Vec<usize>],
+        visited: &mut [bool],
+        matched: &mut [Option<usize>],
+    ) -> bool {
+        for &variable in &adjacent[equation] {
+            if visited[variable] {
+                continue;
+            }
+            visited[variable] = true;
+
+            let free = match matched[variable] {
+                None => true,
+                Some(other) => augment(other, adjacent, visited, matched),
+            };
+
+            if free {
+                matched[variable] = Some(equation);
+                return true;
+            }
+        }
+        false
+    }
+
+    let mut matched: Vec<Option<usize>> = vec![None; variables.len()];
+    let mut rank = 0;
+    for equation in 0..adjacent.len() {
+        let mut visited = vec![false; variables.len()];
+        if augment(equation, &adjacent, &mut visited, &mut matched) {
+            rank += 1;
+        }
+    }
+
+    rank
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+fn norm(a: &[f64]) -> f64 {
+    dot(a, a).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_status_of_default_diagnostics_is_empty() {
+        // Arrange
+        let diagnostics = ConstraintDiagnostics::default();
+
+        // Act
+        let status = diagnostics.status();
+
+        // Assert
+        assert_eq!(status, DimensionSpecificationStatus::Empty);
+    }
+
+    #[test]
+    fn test_status_prefers_conflicts_over_free_variables() {
+        // Arrange
+        let diagnostics = ConstraintDiagnostics {
+            variable_count: 2,
+            equation_count: 2,
+            dof: 1,
+            rank: 1,
+            structural_rank: 1,
+            free_variables: vec!["y".to_string()],
+            redundant_equations: vec![],
+            conflicting_equations: vec![EquationId::from(2)],
+            invalid_equations: vec![],
+        };
+
+        // Act
+        let status = diagnostics.status();
+
+        // Assert
+        assert_eq!(status, DimensionSpecificationStatus::Conflicting);
+    }
+
+    #[test]
+    fn test_status_prefers_invalid_equations_over_conflicts() {
+        // Arrange
+        let diagnostics = ConstraintDiagnostics {
+            variable_count: 1,
+            equation_count: 3,
+            dof: 0,
+            rank: 1,
+            structural_rank: 1,
+            free_variables: vec![],
+            redundant_equations: vec![],
+            conflicting_equations: vec![EquationId::from(2)],
+            invalid_equations: vec![EquationId::from(3)],
+        };
+
+        // Act
+        let status = diagnostics.status();
+
+        // Assert
+        assert_eq!(status, DimensionSpecificationStatus::Invalid);
+    }
+}
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use crate::variable::Variable;
+use crate::{unit::Dimension, variable::Variable};
 
 /// The enviromnet that has all variables in a scope
 ///
@@ -48,7 +48,7 @@ impl Environment {
     ///
     /// # Returns
     /// * `Environment` - A new environment containing the provided variables
-    pub fn from_tuples(variables: &[(&str, f32)]) -> Self {
+    pub fn from_tuples(variables: &[(&str, f64)]) -> Self {
         let mut vars_map = HashMap::new();
         for (name, v) in variables {
             vars_map.insert(name.to_string(), Variable::new(name, *v));
@@ -90,6 +90,14 @@ impl Environment {
         self.variables.values().cloned().collect()
     }
 
+    /// Get dimensions of variables that have dimension
+    pub fn dimensions(&self) -> HashMap<String, Dimension> {
+        self.variables
+            .values()
+            .filter_map(|v| v.dimension().map(|d| (v.name.to_string(), d)))
+            .collect()
+    }
+
     /// Merge the two environment to new one
     ///
     /// If self and `other` have same variable, overwrite it with `other`'s.
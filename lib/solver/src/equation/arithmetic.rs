@@ -55,7 +55,7 @@ pub struct ArithmeticEquation {
 }
 
 impl Evaluate for ArithmeticEquation {
-    fn evaluate(&self, env: &Environment) -> Result<f32, EquationError> {
+    fn evaluate(&self, env: &Environment) -> Result<f64, EquationError> {
         let values: Result<Vec<_>, _> = self.operands.iter().map(|e| e.evaluate(env)).collect();
         let values = values?;
 
@@ -84,6 +84,60 @@ impl Evaluate for ArithmeticEquation {
         }
         variables.into_iter().collect()
     }
+
+    fn derivative(&self, variable: &str) -> Equation {
+        match self.operator {
+            Operator::Add => self
+                .operands
+                .iter()
+                .map(|o| o.derivative(variable))
+                .reduce(add)
+                .unwrap_or_else(|| 0.0.into()),
+            Operator::Subtract => self
+                .operands
+                .iter()
+                .map(|o| o.derivative(variable))
+                .reduce(subtract)
+                .unwrap_or_else(|| 0.0.into()),
+            // product rule for n operands: sum of (u_i' * product of others)
+            Operator::Multiply => (0..self.operands.len())
+                .map(|i| {
+                    self.operands
+                        .iter()
+                        .enumerate()
+                        .map(|(j, o)| {
+                            if i == j {
+                                o.derivative(variable)
+                            } else {
+                                o.clone()
+                            }
+                        })
+                        .reduce(multiply)
+                        .unwrap_or_else(|| 0.0.into())
+                })
+                .reduce(add)
+                .unwrap_or_else(|| 0.0.into()),
+            // quotient rule for left-assosiated division: (u / v)' = (u'v - uv') / v^2
+            Operator::Divide => {
+                let (last, rest) = self.operands.split_last().expect("Must have operands");
+                let numerator = if rest.len() == 1 {
+                    rest[0].clone()
+                } else {
+                    ArithmeticEquation::new(Operator::Divide, rest)
+                        .expect("Must have operands")
+                        .into()
+                };
+
+                let du = numerator.derivative(variable);
+                let dv = last.derivative(variable);
+
+                divide(
+                    subtract(multiply(du, last.clone()), multiply(numerator.clone(), dv)),
+                    multiply(last.clone(), last.clone()),
+                )
+            }
+        }
+    }
 }
 
 impl std::fmt::Display for ArithmeticEquation {
@@ -96,10 +150,25 @@ impl std::fmt::Display for ArithmeticEquation {
         };
 
         for (i, e) in self.operands.iter().enumerate() {
-            if i == self.operands.len() - 1 {
-                write!(f, "{}", e)?;
+            // keep the structure with parenthesis, because operators are left-associative and
+            // a chain of the same operator is parsed as one equation.
+            let paren = match e {
+                Equation::Arithmetic(inner) => match inner.operator.cmp(&self.operator) {
+                    Ordering::Less => true,
+                    Ordering::Equal => i > 0 || inner.operator == self.operator,
+                    Ordering::Greater => false,
+                },
+                _ => false,
+            };
+
+            if paren {
+                write!(f, "({})", e)?;
             } else {
-                write!(f, "{}{}", e, op)?;
+                write!(f, "{}", e)?;
+            }
+
+            if i != self.operands.len() - 1 {
+                write!(f, "{}", op)?;
             }
         }
         Ok(())
@@ -126,6 +195,67 @@ impl ArithmeticEquation {
             operands: Vec::from(operands),
         })
     }
+
+    /// Get the operator
+    pub(crate) fn operator(&self) -> Operator {
+        self.operator
+    }
+
+    /// Get the operands
+    pub(crate) fn operands(&self) -> &[Equation] {
+        &self.operands
+    }
+}
+
+/// Make `lhs + rhs` with folding constants and removing zero.
+pub(crate) fn add(lhs: Equation, rhs: Equation) -> Equation {
+    match (lhs.as_constant(), rhs.as_constant()) {
+        (Some(l), Some(r)) => (l + r).into(),
+        (Some(0.0), _) => rhs,
+        (_, Some(0.0)) => lhs,
+        _ => binary(Operator::Add, lhs, rhs),
+    }
+}
+
+/// Make `lhs - rhs` with folding constants and removing zero.
+pub(crate) fn subtract(lhs: Equation, rhs: Equation) -> Equation {
+    match (lhs.as_constant(), rhs.as_constant()) {
+        (Some(l), Some(r)) => (l - r).into(),
+        (Some(0.0), _) => multiply((-1.0).into(), rhs),
+        (_, Some(0.0)) => lhs,
+        _ => binary(Operator::Subtract, lhs, rhs),
+    }
+}
+
+/// Make `lhs * rhs` with folding constants, removing one and vanishing by zero.
+pub(crate) fn multiply(lhs: Equation, rhs: Equation) -> Equation {
+    match (lhs.as_constant(), rhs.as_constant()) {
+        (Some(l), Some(r)) => (l * r).into(),
+        (Some(0.0), _) => 0.0.into(),
+        (_, Some(0.0)) => 0.0.into(),
+        (Some(1.0), _) => rhs,
+        (_, Some(1.0)) => lhs,
+        _ => binary(Operator::Multiply, lhs, rhs),
+    }
+}
+
+/// Make `lhs / rhs` with folding constants, removing one and vanishing zero numerator.
+pub(crate) fn divide(lhs: Equation, rhs: Equation) -> Equation {
+    match (lhs.as_constant(), rhs.as_constant()) {
+        (Some(l), Some(r)) if r != 0.0 => (l / r).into(),
+        (Some(0.0), _) => 0.0.into(),
+        (_, Some(1.0)) => lhs,
+        _ => binary(Operator::Divide, lhs, rhs),
+    }
+}
+
+/// Make `lhs operator rhs` as written, without folding.
+pub(crate) fn binary(operator: Operator, lhs: Equation, rhs: Equation) -> Equation {
+    ArithmeticEquation {
+        operator,
+        operands: vec![lhs, rhs],
+    }
+    .into()
 }
 
 #[cfg(test)]
@@ -144,9 +274,9 @@ mod tests {
     #[case(Operator::Divide, 20.0, 4.0, 5.0)]
     fn test_evaluate_with_constants(
         #[case] operator: Operator,
-        #[case] first_val: f32,
-        #[case] second_val: f32,
-        #[case] expected: f32,
+        #[case] first_val: f64,
+        #[case] second_val: f64,
+        #[case] expected: f64,
     ) -> Result<()> {
         // arrange
         let first = first_val.into();
@@ -171,10 +301,10 @@ mod tests {
     fn test_evaluate_with_variables(
         #[case] operator: Operator,
         #[case] var1_name: &str,
-        #[case] var1_val: f32,
+        #[case] var1_val: f64,
         #[case] var2_name: &str,
-        #[case] var2_val: f32,
-        #[case] expected: f32,
+        #[case] var2_val: f64,
+        #[case] expected: f64,
     ) -> Result<()> {
         // arrange
         let first = MonomialEquation::new(1.0, var1_name, 1).into();
@@ -251,9 +381,9 @@ mod tests {
     #[case(Operator::Divide, 99.0, 1.0, 99.0)]
     fn test_evaluate_with_identity_elements(
         #[case] operator: Operator,
-        #[case] first_val: f32,
-        #[case] second_val: f32,
-        #[case] expected: f32,
+        #[case] first_val: f64,
+        #[case] second_val: f64,
+        #[case] expected: f64,
     ) -> Result<()> {
         // arrange
         let first = first_val.into();
@@ -403,7 +533,7 @@ mod tests {
         #[test]
         fn test_operators_can_be_sorted_by_precedence() {
             // arrange
-            let mut operators = vec![
+            let mut operators = [
                 Operator::Add,
                 Operator::Divide,
                 Operator::Subtract,
@@ -716,4 +846,84 @@ mod tests {
             Ok(())
         }
     }
+
+    mod derivative_tests {
+        use super::*;
+        use crate::equation::parse;
+        use approx::assert_relative_eq;
+        use pretty_assertions::assert_eq;
+        use rstest::rstest;
+
+        #[rstest]
+        #[case("x + 3", 1.0)]
+        #[case("x^2 + y", 4.0)]
+        #[case("5 - x^2", -4.0)]
+        #[case("x * y", 3.0)]
+        #[case("x * x * y", 12.0)]
+        #[case("x / y", 1.0 / 3.0)]
+        #[case("y / x", -0.75)]
+        #[case("x / y / 2", 1.0 / 6.0)]
+        #[case("(x + 1) * (x - 1)", 4.0)]
+        fn test_derivative_evaluates_to_expected_value(
+            #[case] input: &str,
+            #[case] expected: f64,
+        ) -> Result<()> {
+            // arrange
+            let equation = parse(input).map_err(|e| eyre!("{}", e))?;
+            let env = Environment::from_tuples(&[("x", 2.0), ("y", 3.0)]);
+
+            // act
+            let result = equation
+                .derivative("x")
+                .evaluate(&env)
+                .map_err(|e| eyre!("{:?}", e))?;
+
+            // assert
+            assert_relative_eq!(result, expected, epsilon = 1e-6);
+            Ok(())
+        }
+
+        #[test]
+        fn test_derivative_of_unrelated_variable_is_simplified_to_zero() -> Result<()> {
+            // arrange
+            let equation = parse("x^2 * y + 3 * x").map_err(|e| eyre!("{}", e))?;
+
+            // act
+            let result = equation.derivative("z");
+
+            // assert
+            assert_eq!(result, 0.0.into());
+            Ok(())
+        }
+
+        #[test]
+        fn test_derivative_of_linear_sum_is_simplified_to_constant() -> Result<()> {
+            // arrange
+            let equation = parse("2x + y - 3").map_err(|e| eyre!("{}", e))?;
+
+            // act
+            let result = equation.derivative("x");
+
+            // assert
+            assert_eq!(result, 2.0.into());
+            Ok(())
+        }
+
+        #[rstest]
+        #[case(add(1.0.into(), 2.0.into()), 3.0.into())]
+        #[case(add(0.0.into(), MonomialEquation::new(1.0, "x", 1).into()), MonomialEquation::new(1.0, "x", 1).into())]
+        #[case(subtract(MonomialEquation::new(1.0, "x", 1).into(), 0.0.into()), MonomialEquation::new(1.0, "x", 1).into())]
+        #[case(multiply(0.0.into(), MonomialEquation::new(1.0, "x", 1).into()), 0.0.into())]
+        #[case(multiply(1.0.into(), MonomialEquation::new(1.0, "x", 1).into()), MonomialEquation::new(1.0, "x", 1).into())]
+        #[case(divide(MonomialEquation::new(1.0, "x", 1).into(), 1.0.into()), MonomialEquation::new(1.0, "x", 1).into())]
+        #[case(divide(6.0.into(), 3.0.into()), 2.0.into())]
+        fn test_simplifying_builders(#[case] actual: Equation, #[case] expected: Equation) {
+            // arrange
+
+            // act
+
+            // assert
+            assert_eq!(actual, expected);
+        }
+    }
 }
@@ -1,16 +1,22 @@
 use crate::{
     environment::Environment,
-    equation::{EquationError, Evaluate},
+    equation::{Equation, EquationError, Evaluate},
+    unit::Unit,
     variable::Variable,
 };
 
 /// Represents a constant equation that always evaluates to a fixed value.
+///
+/// A constant can have a unit. The value is kept as written, and evaluated in the base unit.
 #[derive(Debug, Clone, PartialEq)]
-pub struct ConstantEquation(f32);
+pub struct ConstantEquation {
+    value: f64,
+    unit: Option<Unit>,
+}
 
 impl Evaluate for ConstantEquation {
-    fn evaluate(&self, _env: &Environment) -> Result<f32, EquationError> {
-        Ok(self.0)
+    fn evaluate(&self, _env: &Environment) -> Result<f64, EquationError> {
+        Ok(self.value())
     }
 
     fn is_variable_related(&self, _variable: &Variable) -> bool {
@@ -20,17 +26,66 @@ impl Evaluate for ConstantEquation {
     fn related_variables(&self) -> Vec<String> {
         vec![]
     }
+
+    fn derivative(&self, _variable: &str) -> Equation {
+        0.0.into()
+    }
+}
+
+impl ConstantEquation {
+    /// Create a new constant with the unit
+    pub(crate) fn with_unit(value: f64, unit: Unit) -> Self {
+        Self {
+            value,
+            unit: Some(unit),
+        }
+    }
+
+    /// Get the value of this constant in the base unit
+    pub(crate) fn value(&self) -> f64 {
+        match self.unit {
+            Some(unit) => unit.to_base(self.value),
+            None => self.value,
+        }
+    }
+
+    /// Get the unit of this constant
+    pub(crate) fn unit(&self) -> Option<Unit> {
+        self.unit
+    }
+
+    /// Get the negated constant in the same unit
+    pub(crate) fn negate(&self) -> Self {
+        Self {
+            value: -self.value,
+            unit: self.unit,
+        }
+    }
+}
+
+/// Write the number in the shortest form that reads back to the same value. Very large and small
+/// numbers are written with exponent, such as `1e300`.
+pub(crate) fn number_text(value: f64) -> String {
+    let magnitude = value.abs();
+    if magnitude != 0.0 && magnitude.is_finite() && !(1e-5..1e16).contains(&magnitude) {
+        format!("{:e}", value)
+    } else {
+        value.to_string()
+    }
 }
 
 impl std::fmt::Display for ConstantEquation {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.0)
+        match self.unit {
+            Some(unit) => write!(f, "{}[{}]", number_text(self.value), unit),
+            None => write!(f, "{}", number_text(self.value)),
+        }
     }
 }
 
-impl From<f32> for ConstantEquation {
-    fn from(value: f32) -> Self {
-        ConstantEquation(value)
+impl From<f64> for ConstantEquation {
+    fn from(value: f64) -> Self {
+        ConstantEquation { value, unit: None }
     }
 }
 
@@ -133,4 +188,30 @@ mod tests {
         // assert
         assert_eq!(result, Vec::<String>::new());
     }
+
+    #[test]
+    fn test_evaluate_returns_value_in_base_unit() {
+        // arrange
+        let equation = ConstantEquation::with_unit(2.0, Unit::Centimeter);
+        let env = Environment::empty();
+
+        // act
+        let result = equation.evaluate(&env);
+
+        // assert
+        assert_eq!(result.unwrap(), 20.0);
+        assert_eq!(equation.to_string(), "2[cm]");
+    }
+
+    #[test]
+    fn test_derivative_returns_zero() {
+        // arrange
+        let equation: ConstantEquation = 42.0.into();
+
+        // act
+        let result = equation.derivative("x");
+
+        // assert
+        assert_eq!(result, 0.0.into());
+    }
 }
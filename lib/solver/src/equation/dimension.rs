@@ -0,0 +1,321 @@
+use std::collections::HashMap;
+
+use color_eyre::eyre::Result;
+
+use crate::{
+    environment::Environment,
+    equation::{
+        Equation, EquationError, Evaluate,
+        arithmetic::{ArithmeticEquation, Operator, binary},
+        constant::ConstantEquation,
+        function::Function,
+    },
+    unit::{Dimension, DimensionError, Unit},
+};
+
+impl Equation {
+    /// Check dimensions of the equation, and get the dimension of the result.
+    ///
+    /// Numbers without unit and variables not in `variables` have no specific dimension, so they can
+    /// be added to any dimension. Numbers without unit in multiplications and divisions are
+    /// dimensionless. `None` is returned when the dimension of the result is not specific.
+    ///
+    /// # Arguments
+    /// * `variables` - Dimensions of variables
+    ///
+    /// # Returns
+    /// * The dimension of the result, or an error when the equation combines incompatible dimensions
+    pub fn dimension(
+        &self,
+        variables: &HashMap<String, Dimension>,
+    ) -> Result<Option<Dimension>, DimensionError> {
+        match self {
+            Equation::Constant(c) => Ok(c.unit().map(|u| u.dimension())),
+            Equation::Monomial(m) => Ok(variables.get(m.variable()).map(|d| d.powi(m.exponent()))),
+            Equation::Arithmetic(a) => {
+                // numbers without unit are scales in products, but values of the operand's
+                // dimension in sums.
+                let operand_dimension = |operand: &Equation| match (operand, a.operator()) {
+                    (Equation::Constant(c), Operator::Multiply | Operator::Divide)
+                        if c.unit().is_none() =>
+                    {
+                        Ok(Some(Dimension::NONE))
+                    }
+                    _ => operand.dimension(variables),
+                };
+                let mut operands = a.operands().iter();
+                let first = operands
+                    .next()
+                    .map(operand_dimension)
+                    .transpose()?
+                    .flatten();
+
+                operands.try_fold(first, |lhs, operand| {
+                    let rhs = operand_dimension(operand)?;
+
+                    match a.operator() {
+                        Operator::Add | Operator::Subtract => same(lhs, rhs),
+                        Operator::Multiply => Ok(lhs.zip(rhs).map(|(l, r)| l.multiply(&r))),
+                        Operator::Divide => Ok(lhs.zip(rhs).map(|(l, r)| l.divide(&r))),
+                    }
+                })
+            }
+            Equation::Function(f) => {
+                let arguments = f
+                    .arguments()
+                    .iter()
+                    .map(|a| a.dimension(variables))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let invalid = |dimension: Dimension| DimensionError::InvalidArgument {
+                    function: f.function().name(),
+                    dimension,
+                };
+
+                match f.function() {
+                    Function::Sin | Function::Cos | Function::Tan => match arguments[0] {
+                        Some(d) if !d.is_none() && d != Dimension::ANGLE => Err(invalid(d)),
+                        _ => Ok(Some(Dimension::NONE)),
+                    },
+                    Function::Ln => match arguments[0] {
+                        Some(d) if !d.is_none() => Err(invalid(d)),
+                        _ => Ok(Some(Dimension::NONE)),
+                    },
+                    Function::Atan2 => {
+                        same(arguments[0], arguments[1]).map(|_| Some(Dimension::ANGLE))
+                    }
+                    Function::Sqrt => match arguments[0] {
+                        Some(d) if d.length % 2 != 0 || d.angle % 2 != 0 => Err(invalid(d)),
+                        d => Ok(d.map(|d| Dimension {
+                            length: d.length / 2,
+                            angle: d.angle / 2,
+                        })),
+                    },
+                    Function::Abs => Ok(arguments[0]),
+                    Function::Min | Function::Max => same(arguments[0], arguments[1]),
+                    Function::Sign => Ok(Some(Dimension::NONE)),
+                }
+            }
+            Equation::Power(p) => {
+                let base = p.base().dimension(variables)?;
+                match p.exponent().dimension(variables)? {
+                    Some(d) if !d.is_none() => return Err(DimensionError::InvalidExponent(d)),
+                    _ => (),
+                }
+
+                match (base, p.exponent().as_constant()) {
+                    (Some(d), _) if d.is_none() => Ok(Some(d)),
+                    (Some(d), Some(n)) if n.fract() == 0.0 => Ok(Some(d.powi(n as i32))),
+                    (Some(d), _) => Err(DimensionError::FractionalPower(d)),
+                    (None, _) => Ok(None),
+                }
+            }
+        }
+    }
+}
+
+impl Equation {
+    /// Evaluate the equation after checking dimensions with dimensions of variables in `env`.
+    ///
+    /// # Returns
+    /// * The value in the base unit and the dimension of the result, or an error when the equation
+    ///   can not be evaluated or combines incompatible dimensions
+    pub fn evaluate_with_dimension(
+        &self,
+        env: &Environment,
+    ) -> Result<(f64, Option<Dimension>), EquationError> {
+        let dimension = self.dimension(&env.dimensions())?;
+
+        Ok((self.evaluate(env)?, dimension))
+    }
+
+    /// Read numbers without unit in the equation as values in `unit`.
+    ///
+    /// Numbers in sums and dimensionless terms, such as `2 * 3`, get `unit`. Terms that have a
+    /// dimension, or whose dimension is unknown, are kept as written.
+    ///
+    /// # Arguments
+    /// * `unit` - Unit of numbers without unit
+    /// * `variables` - Dimensions of variables
+    pub fn with_default_unit(
+        &self,
+        unit: Unit,
+        variables: &HashMap<String, Dimension>,
+    ) -> Result<Equation> {
+        match self {
+            Equation::Constant(c) if c.unit().is_none() => {
+                Ok(ConstantEquation::with_unit(c.value(), unit).into())
+            }
+            Equation::Arithmetic(a)
+                if matches!(a.operator(), Operator::Add | Operator::Subtract) =>
+            {
+                let operands = a
+                    .operands()
+                    .iter()
+                    .map(|o| o.with_default_unit(unit, variables))
+                    .collect::<Result<Vec<_>>>()?;
+
+                Ok(ArithmeticEquation::new(a.operator(), &operands)?.into())
+            }
+            _ => match self.dimension(variables)? {
+                Some(d) if d.is_none() => Ok(binary(
+                    Operator::Multiply,
+                    self.clone(),
+                    ConstantEquation::with_unit(1.0, unit).into(),
+                )),
+                _ => Ok(self.clone()),
+            },
+        }
+    }
+}
+
+/// Dimension of values that must be same, such as operands of addition
+fn same(
+    lhs: Option<Dimension>,
+    rhs: Option<Dimension>,
+) -> Result<Option<Dimension>, DimensionError> {
+    match (lhs, rhs) {
+        (Some(l), Some(r)) if l != r => Err(DimensionError::Mismatch { lhs: l, rhs: r }),
+        _ => Ok(lhs.or(rhs)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{equation::parse, variable::Variable};
+    use approx::assert_relative_eq;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    fn variables() -> HashMap<String, Dimension> {
+        HashMap::from([
+            ("l".to_string(), Dimension::LENGTH),
+            ("a".to_string(), Dimension::ANGLE),
+        ])
+    }
+
+    #[rstest]
+    #[case("10[mm] + 2[in]", Some(Dimension::LENGTH))]
+    #[case("10 + 2", None)]
+    #[case("10 + 2[cm]", Some(Dimension::LENGTH))]
+    #[case("l * 2[m]", Some(Dimension::LENGTH.powi(2)))]
+    #[case("l^2 / 1[cm]", Some(Dimension::LENGTH))]
+    #[case("(2[m])^2", Some(Dimension::LENGTH.powi(2)))]
+    #[case("sqrt(l^2 + 3[mm] * 4[mm])", Some(Dimension::LENGTH))]
+    #[case("l * cos(a - 90[deg])", Some(Dimension::LENGTH))]
+    #[case("atan2(l, 2[mm]) + a", Some(Dimension::ANGLE))]
+    #[case("l * 2", Some(Dimension::LENGTH))]
+    #[case("2 * 3 / 4", Some(Dimension::NONE))]
+    #[case("x * 2", None)]
+    #[case("x * 2[mm]", None)]
+    #[case("l / l", Some(Dimension::NONE))]
+    fn test_dimension(#[case] input: &str, #[case] expected: Option<Dimension>) {
+        // arrange
+        let equation = parse(input).unwrap();
+
+        // act
+        let result = equation.dimension(&variables());
+
+        // assert
+        assert_eq!(result, Ok(expected));
+    }
+
+    #[rstest]
+    #[case("10[mm] + 10[deg]")]
+    #[case("l - a")]
+    #[case("sin(l)")]
+    #[case("ln(2[mm])")]
+    #[case("min(l, a)")]
+    #[case("sqrt(l)")]
+    #[case("2^l")]
+    #[case("(l)^0.5")]
+    fn test_dimension_fails_with_incompatible_dimensions(#[case] input: &str) {
+        // arrange
+        let equation = parse(input).unwrap();
+
+        // act
+        let result = equation.dimension(&variables());
+
+        // assert
+        assert!(result.is_err(), "{:?}", result);
+    }
+
+    #[rstest]
+    #[case("l + 10[mm]", Some(Dimension::LENGTH))]
+    #[case("l * a", Some(Dimension::LENGTH.multiply(&Dimension::ANGLE)))]
+    #[case("x * 2", None)]
+    fn test_evaluate_with_dimension(#[case] input: &str, #[case] expected: Option<Dimension>) {
+        // arrange
+        let equation = parse(input).unwrap();
+        let env = Environment::from_variables(vec![
+            Variable::new("l", 2.0).with_dimension(Dimension::LENGTH),
+            Variable::new("a", 3.0).with_dimension(Dimension::ANGLE),
+            Variable::new("x", 4.0),
+        ]);
+
+        // act
+        let result = equation.evaluate_with_dimension(&env);
+
+        // assert
+        assert_eq!(result.map(|(_, d)| d), Ok(expected));
+    }
+
+    #[test]
+    fn test_evaluate_with_dimension_rejects_variable_of_other_dimension() {
+        // arrange
+        let equation = parse("l + 10[deg]").unwrap();
+        let env = Environment::from_variables(vec![
+            Variable::new("l", 2.0).with_dimension(Dimension::LENGTH),
+        ]);
+
+        // act
+        let result = equation.evaluate_with_dimension(&env);
+
+        // assert
+        assert_eq!(
+            result,
+            Err(EquationError::Dimension(DimensionError::Mismatch {
+                lhs: Dimension::LENGTH,
+                rhs: Dimension::ANGLE
+            }))
+        );
+    }
+
+    #[rstest]
+    #[case("10", 254.0)]
+    #[case("10 + 1[mm]", 255.0)]
+    #[case("2 * 5", 254.0)]
+    #[case("10 - l", 252.0)]
+    #[case("l * 5", 10.0)]
+    fn test_with_default_unit_reads_numbers_in_unit(#[case] input: &str, #[case] expected: f64) {
+        // arrange
+        let equation = parse(input).unwrap();
+        let env = Environment::from_variables(vec![
+            Variable::new("l", 2.0).with_dimension(Dimension::LENGTH),
+        ]);
+
+        // act
+        let result = equation
+            .with_default_unit(Unit::Inch, &env.dimensions())
+            .unwrap();
+
+        // assert
+        assert_relative_eq!(result.evaluate(&env).unwrap(), expected, epsilon = 1e-9);
+        assert_eq!(
+            result.dimension(&env.dimensions()),
+            Ok(Some(Dimension::LENGTH))
+        );
+    }
+
+    #[test]
+    fn test_with_default_unit_keeps_unknown_dimension() {
+        // arrange
+        let equation = parse("x * 2").unwrap();
+
+        // act
+        let result = equation.with_default_unit(Unit::Inch, &HashMap::new());
+
+        // assert
+        assert_eq!(result.unwrap(), equation);
+    }
+}
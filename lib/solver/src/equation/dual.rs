@@ -0,0 +1,126 @@
+use epsilon::dual::Dual;
+
+use crate::{
+    environment::Environment,
+    equation::{Equation, EquationError, arithmetic::Operator, function::Function},
+};
+
+impl Equation {
+    /// Evaluate the equation with dual numbers, to get the value and the derivative with respect
+    /// to `parameter` at once.
+    ///
+    /// `parameter` is seeded as the variable and other variables as constants. Unlike
+    /// [`crate::equation::Evaluate::derivative`], this does not make any symbolic equation, so the
+    /// result can be fed into geometry calculations over [`Dual`].
+    ///
+    /// # Returns
+    /// * Dual number of the result. Error when some variables are not in `env`
+    pub fn evaluate_dual(&self, env: &Environment, parameter: &str) -> Result<Dual, EquationError> {
+        match self {
+            Equation::Constant(eq) => Ok(Dual::constant(eq.value())),
+            Equation::Monomial(eq) => {
+                let variable = env.get(eq.variable()).ok_or_else(|| {
+                    EquationError::NoVariableInEnvironment(vec![eq.variable().to_string()])
+                })?;
+                let value = if eq.variable() == parameter {
+                    Dual::variable(*variable.value)
+                } else {
+                    Dual::constant(*variable.value)
+                };
+
+                Ok(value.powi(eq.exponent()) * eq.factor())
+            }
+            Equation::Arithmetic(eq) => {
+                let values = eq
+                    .operands()
+                    .iter()
+                    .map(|o| o.evaluate_dual(env, parameter))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                values
+                    .into_iter()
+                    .reduce(|lhs, rhs| match eq.operator() {
+                        Operator::Add => lhs + rhs,
+                        Operator::Subtract => lhs - rhs,
+                        Operator::Multiply => lhs * rhs,
+                        Operator::Divide => lhs / rhs,
+                    })
+                    .ok_or(EquationError::NoVariableInEnvironment(vec![]))
+            }
+            Equation::Function(eq) => {
+                let args = eq
+                    .arguments()
+                    .iter()
+                    .map(|a| a.evaluate_dual(env, parameter))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                Ok(match eq.function() {
+                    Function::Sin => args[0].sin(),
+                    Function::Cos => args[0].cos(),
+                    Function::Tan => args[0].tan(),
+                    Function::Atan2 => args[0].atan2(args[1]),
+                    Function::Sqrt => args[0].sqrt(),
+                    Function::Abs => args[0].abs(),
+                    Function::Min => args[0].min(args[1]),
+                    Function::Max => args[0].max(args[1]),
+                    Function::Ln => args[0].ln(),
+                    Function::Sign => args[0].sign(),
+                })
+            }
+            Equation::Power(eq) => {
+                let base = eq.base().evaluate_dual(env, parameter)?;
+                let exponent = eq.exponent().evaluate_dual(env, parameter)?;
+
+                Ok(base.powf(exponent))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::environment::Environment;
+    use crate::equation::{Evaluate, parse};
+    use approx::assert_relative_eq;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("3", "x")]
+    #[case("2x^3 - y", "x")]
+    #[case("2x^3 - y", "y")]
+    #[case("x / y / 2", "y")]
+    #[case("sin(x) * cos(y) + atan2(y, x)", "x")]
+    #[case("min(x, y) - max(x, sqrt(y))", "y")]
+    #[case("x ^ y ^ 0.5", "y")]
+    #[case("(x + 1)^2 - ln(y) + sign(x - y)", "x")]
+    #[case("10[mm] + x * y", "x")]
+    #[case("x + 1", "z")]
+    fn test_evaluate_dual_matches_symbolic_derivative(
+        #[case] input: &str,
+        #[case] parameter: &str,
+    ) {
+        // arrange
+        let equation = parse(input).unwrap();
+        let env = Environment::from_tuples(&[("x", 1.5), ("y", 2.5)]);
+
+        // act
+        let result = equation.evaluate_dual(&env, parameter).unwrap();
+
+        // assert
+        let derivative = equation.derivative(parameter).evaluate(&env).unwrap();
+        assert_relative_eq!(result.value(), equation.evaluate(&env).unwrap());
+        assert_relative_eq!(result.derivative(), derivative, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_evaluate_dual_fails_without_variable() {
+        // arrange
+        let equation = parse("x + 1").unwrap();
+
+        // act
+        let result = equation.evaluate_dual(&Environment::empty(), "x");
+
+        // assert
+        assert!(result.is_err());
+    }
+}
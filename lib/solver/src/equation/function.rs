@@ -0,0 +1,360 @@
+use std::collections::HashSet;
+
+use color_eyre::eyre::{Result, eyre};
+
+use crate::{
+    environment::Environment,
+    equation::{
+        Equation, EquationError, Evaluate,
+        arithmetic::{add, divide, multiply, subtract},
+        power::power,
+    },
+    variable::Variable,
+};
+
+/// Functions that can be applied in equations
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Function {
+    Sin,
+    Cos,
+    Tan,
+    Atan2,
+    Sqrt,
+    Abs,
+    Min,
+    Max,
+    /// Natural logarithm
+    Ln,
+    /// Sign of the value, `0` for zero
+    Sign,
+}
+
+impl Function {
+    /// All functions
+    const ALL: [Function; 10] = [
+        Function::Sin,
+        Function::Cos,
+        Function::Tan,
+        Function::Atan2,
+        Function::Sqrt,
+        Function::Abs,
+        Function::Min,
+        Function::Max,
+        Function::Ln,
+        Function::Sign,
+    ];
+
+    /// Get the name of function in expression
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            Function::Sin => "sin",
+            Function::Cos => "cos",
+            Function::Tan => "tan",
+            Function::Atan2 => "atan2",
+            Function::Sqrt => "sqrt",
+            Function::Abs => "abs",
+            Function::Min => "min",
+            Function::Max => "max",
+            Function::Ln => "ln",
+            Function::Sign => "sign",
+        }
+    }
+
+    /// Get the function from the name
+    pub(crate) fn from_name(name: &str) -> Option<Function> {
+        Function::ALL.into_iter().find(|f| f.name() == name)
+    }
+
+    /// Number of arguments of the function
+    pub(crate) fn arity(&self) -> usize {
+        match self {
+            Function::Atan2 | Function::Min | Function::Max => 2,
+            _ => 1,
+        }
+    }
+
+    pub(crate) fn apply(&self, args: &[f64]) -> f64 {
+        match self {
+            Function::Sin => args[0].sin(),
+            Function::Cos => args[0].cos(),
+            Function::Tan => args[0].tan(),
+            Function::Atan2 => args[0].atan2(args[1]),
+            Function::Sqrt => args[0].sqrt(),
+            Function::Abs => args[0].abs(),
+            Function::Min => args[0].min(args[1]),
+            Function::Max => args[0].max(args[1]),
+            Function::Ln => args[0].ln(),
+            Function::Sign => {
+                if args[0] > 0.0 {
+                    1.0
+                } else if args[0] < 0.0 {
+                    -1.0
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+}
+
+/// Implementation of function application, such as `sin(x)` or `atan2(y, x)`
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionEquation {
+    function: Function,
+    arguments: Vec<Equation>,
+}
+
+impl Evaluate for FunctionEquation {
+    fn evaluate(&self, env: &Environment) -> Result<f64, EquationError> {
+        let values: Result<Vec<_>, _> = self.arguments.iter().map(|e| e.evaluate(env)).collect();
+
+        Ok(self.function.apply(&values?))
+    }
+
+    fn is_variable_related(&self, variable: &Variable) -> bool {
+        self.arguments
+            .iter()
+            .any(|o| o.is_variable_related(variable))
+    }
+
+    fn related_variables(&self) -> Vec<String> {
+        let mut variables: HashSet<String> = HashSet::new();
+        for argument in &self.arguments {
+            for var in argument.related_variables() {
+                variables.insert(var);
+            }
+        }
+        variables.into_iter().collect()
+    }
+
+    fn derivative(&self, variable: &str) -> Equation {
+        let u = self.arguments[0].clone();
+        let du = u.derivative(variable);
+
+        match self.function {
+            // (sin u)' = cos(u) * u'
+            Function::Sin => multiply(apply(Function::Cos, vec![u]), du),
+            // (cos u)' = -sin(u) * u'
+            Function::Cos => multiply((-1.0).into(), multiply(apply(Function::Sin, vec![u]), du)),
+            // (tan u)' = u' / cos(u)^2
+            Function::Tan => divide(du, power(apply(Function::Cos, vec![u]), 2.0.into())),
+            // (sqrt u)' = u' / (2 * sqrt(u))
+            Function::Sqrt => divide(du, multiply(2.0.into(), self.clone().into())),
+            // (abs u)' = u' * sign(u)
+            Function::Abs => multiply(du, apply(Function::Sign, vec![u])),
+            // (ln u)' = u' / u
+            Function::Ln => divide(du, u),
+            Function::Sign => 0.0.into(),
+            // atan2(u, v)' = (v * u' - u * v') / (u^2 + v^2)
+            Function::Atan2 => {
+                let v = self.arguments[1].clone();
+                let dv = v.derivative(variable);
+
+                divide(
+                    subtract(multiply(v.clone(), du), multiply(u.clone(), dv)),
+                    add(power(u, 2.0.into()), power(v, 2.0.into())),
+                )
+            }
+            // min(u, v)' = (u' + v' - (u' - v') * sign(u - v)) / 2
+            // max(u, v)' = (u' + v' + (u' - v') * sign(u - v)) / 2
+            Function::Min | Function::Max => {
+                let v = self.arguments[1].clone();
+                let dv = v.derivative(variable);
+                let switch = multiply(
+                    subtract(du.clone(), dv.clone()),
+                    apply(Function::Sign, vec![subtract(u, v)]),
+                );
+                let sum = add(du, dv);
+
+                let numerator = if self.function == Function::Min {
+                    subtract(sum, switch)
+                } else {
+                    add(sum, switch)
+                };
+                divide(numerator, 2.0.into())
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for FunctionEquation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}(", self.function.name())?;
+
+        for (i, e) in self.arguments.iter().enumerate() {
+            if i == self.arguments.len() - 1 {
+                write!(f, "{}", e)?;
+            } else {
+                write!(f, "{}, ", e)?;
+            }
+        }
+        write!(f, ")")
+    }
+}
+
+impl FunctionEquation {
+    /// Create a new function application.
+    ///
+    /// # Arguments
+    /// * `function` - The function to apply
+    /// * `arguments` - Arguments of the function. The number of them must be the arity of the function.
+    pub(crate) fn new(function: Function, arguments: &[Equation]) -> Result<Self> {
+        if arguments.len() != function.arity() {
+            return Err(eyre!(
+                "{} requires {} arguments",
+                function.name(),
+                function.arity()
+            ));
+        }
+
+        Ok(Self {
+            function,
+            arguments: Vec::from(arguments),
+        })
+    }
+
+    /// Get the applied function
+    pub(crate) fn function(&self) -> Function {
+        self.function
+    }
+
+    /// Get the arguments
+    pub(crate) fn arguments(&self) -> &[Equation] {
+        &self.arguments
+    }
+}
+
+/// Make a function application with folding constant arguments.
+pub(crate) fn apply(function: Function, arguments: Vec<Equation>) -> Equation {
+    let values: Option<Vec<f64>> = arguments.iter().map(|a| a.as_constant()).collect();
+
+    match values {
+        Some(values) => function.apply(&values).into(),
+        None => FunctionEquation::new(function, &arguments)
+            .expect("Should be called with valid arguments")
+            .into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::equation::parse;
+    use approx::assert_relative_eq;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    #[test]
+    fn test_new_fails_with_wrong_number_of_arguments() {
+        // arrange
+        let x = parse("x").unwrap();
+
+        // act
+        let result = FunctionEquation::new(Function::Atan2, &[x]);
+
+        // assert
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_related_variables_of_all_arguments() {
+        // arrange
+        let equation = FunctionEquation::new(
+            Function::Max,
+            &[parse("x").unwrap(), parse("y * x").unwrap()],
+        )
+        .unwrap();
+
+        // act
+        let mut result = equation.related_variables();
+        result.sort();
+
+        // assert
+        assert_eq!(result, vec!["x".to_string(), "y".to_string()]);
+    }
+
+    #[test]
+    fn test_apply_folds_constant_arguments() {
+        // arrange
+        let arguments = vec![0.0.into()];
+
+        // act
+        let result = apply(Function::Cos, arguments);
+
+        // assert
+        assert_eq!(result, 1.0.into());
+    }
+
+    #[test]
+    fn test_from_name_gets_all_functions() {
+        // arrange
+        let names = Function::ALL.map(|f| f.name());
+
+        // act
+        let result = names.map(Function::from_name);
+
+        // assert
+        assert_eq!(result, Function::ALL.map(Some));
+        assert_eq!(Function::from_name("foo"), None);
+    }
+
+    #[rstest]
+    #[case(Function::Sin, &[1.0], 1.0_f64.sin())]
+    #[case(Function::Cos, &[1.0], 1.0_f64.cos())]
+    #[case(Function::Tan, &[1.0], 1.0_f64.tan())]
+    #[case(Function::Atan2, &[1.0, -1.0], 1.0_f64.atan2(-1.0))]
+    #[case(Function::Sqrt, &[4.0], 2.0)]
+    #[case(Function::Abs, &[-3.0], 3.0)]
+    #[case(Function::Min, &[2.0, -1.0], -1.0)]
+    #[case(Function::Max, &[2.0, -1.0], 2.0)]
+    #[case(Function::Ln, &[1.0], 0.0)]
+    #[case(Function::Sign, &[-2.0], -1.0)]
+    #[case(Function::Sign, &[0.0], 0.0)]
+    fn test_evaluate(#[case] function: Function, #[case] args: &[f64], #[case] expected: f64) {
+        // arrange
+        let names = ["x", "y"];
+        let arguments: Vec<Equation> = names[..args.len()]
+            .iter()
+            .map(|v| parse(v).unwrap())
+            .collect();
+        let equation = FunctionEquation::new(function, &arguments).unwrap();
+        let tuples: Vec<(&str, f64)> = names.iter().copied().zip(args.iter().copied()).collect();
+        let env = Environment::from_tuples(&tuples);
+
+        // act
+        let result = equation.evaluate(&env).unwrap();
+
+        // assert
+        assert_relative_eq!(result, expected);
+    }
+
+    mod derivative_tests {
+        use super::*;
+
+        #[rstest]
+        #[case("sin(x)", 2.0_f64.cos())]
+        #[case("cos(2 * x)", -2.0 * 4.0_f64.sin())]
+        #[case("tan(x)", 1.0 / (2.0_f64.cos() * 2.0_f64.cos()))]
+        #[case("sqrt(x * y)", 3.0 / (2.0 * 6.0_f64.sqrt()))]
+        #[case("abs(y - x^2)", 4.0)]
+        #[case("ln(x)", 0.5)]
+        #[case("sign(x)", 0.0)]
+        // d/dx atan2(y, x) = -y / (x^2 + y^2)
+        #[case("atan2(y, x)", -3.0 / 13.0)]
+        #[case("min(x^2, y)", 0.0)]
+        #[case("min(x^2, 10)", 4.0)]
+        #[case("max(x^2, y)", 4.0)]
+        #[case("max(x, y)", 0.0)]
+        fn test_derivative(#[case] input: &str, #[case] expected: f64) {
+            // arrange
+            let equation = parse(input).unwrap();
+            let env = Environment::from_tuples(&[("x", 2.0), ("y", 3.0)]);
+
+            // act
+            let result = equation.derivative("x").evaluate(&env).unwrap();
+
+            // assert
+            assert_relative_eq!(result, expected, epsilon = 1e-5);
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use epsilon::interval::Interval;
+
+use crate::{
+    environment::Environment,
+    equation::{Equation, EquationError, arithmetic::Operator, function::Function},
+};
+
+impl Equation {
+    /// Evaluate the equation with interval arithmetic.
+    ///
+    /// Each variable is the point interval of its value, so the result contains the exact value of
+    /// the equation for values in `env`, regardless of rounding errors of evaluation. This is for
+    /// predicates that must not be decided by rounding errors.
+    ///
+    /// # Returns
+    /// * Interval of the result. Error when some variables are not in `env`
+    pub fn evaluate_interval(&self, env: &Environment) -> Result<Interval, EquationError> {
+        match self {
+            Equation::Constant(eq) => {
+                let value = eq.value();
+                // conversion to the base unit rounds
+                Ok(match eq.unit() {
+                    Some(_) => Interval::new(value.next_down(), value.next_up()),
+                    None => Interval::point(value),
+                })
+            }
+            Equation::Monomial(eq) => {
+                let variable = env.get(eq.variable()).ok_or_else(|| {
+                    EquationError::NoVariableInEnvironment(vec![eq.variable().to_string()])
+                })?;
+
+                Ok(Interval::point(eq.factor())
+                    * Interval::point(*variable.value).powi(eq.exponent()))
+            }
+            Equation::Arithmetic(eq) => {
+                let values = eq
+                    .operands()
+                    .iter()
+                    .map(|o| o.evaluate_interval(env))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                values
+                    .into_iter()
+                    .reduce(|lhs, rhs| match eq.operator() {
+                        Operator::Add => lhs + rhs,
+                        Operator::Subtract => lhs - rhs,
+                        Operator::Multiply => lhs * rhs,
+                        Operator::Divide => lhs / rhs,
+                    })
+                    .ok_or(EquationError::NoVariableInEnvironment(vec![]))
+            }
+            Equation::Function(eq) => {
+                let args = eq
+                    .arguments()
+                    .iter()
+                    .map(|a| a.evaluate_interval(env))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                Ok(match eq.function() {
+                    Function::Sin => args[0].sin(),
+                    Function::Cos => args[0].cos(),
+                    Function::Tan => args[0].tan(),
+                    Function::Atan2 => args[0].atan2(args[1]),
+                    Function::Sqrt => args[0].sqrt(),
+                    Function::Abs => args[0].abs(),
+                    Function::Min => args[0].min(args[1]),
+                    Function::Max => args[0].max(args[1]),
+                    Function::Ln => args[0].ln(),
+                    Function::Sign => args[0].sign(),
+                })
+            }
+            Equation::Power(eq) => {
+                let base = eq.base().evaluate_interval(env)?;
+                let exponent = eq.exponent().evaluate_interval(env)?;
+
+                Ok(base.powf(exponent))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::environment::Environment;
+    use crate::equation::{Evaluate, parse};
+    use epsilon::interval::Predicate;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("3")]
+    #[case("2x^3 - y")]
+    #[case("x / y / 2")]
+    #[case("sin(x) * cos(y) + atan2(y, x)")]
+    #[case("min(x, y) - max(x, sqrt(y))")]
+    #[case("x ^ y ^ 0.5")]
+    #[case("(x + 1)^2 - ln(y) + sign(x - y)")]
+    #[case("10[mm] + x")]
+    fn test_evaluate_interval_contains_evaluated_value(#[case] input: &str) {
+        // arrange
+        let equation = parse(input).unwrap();
+        let env = Environment::from_tuples(&[("x", 1.5), ("y", 2.5)]);
+
+        // act
+        let interval = equation.evaluate_interval(&env).unwrap();
+
+        // assert
+        let value = equation.evaluate(&env).unwrap();
+        assert!(interval.contains(value), "{interval:?} for {value}");
+        assert!(interval.width() < 1e-12);
+    }
+
+    #[test]
+    fn test_evaluate_interval_is_uncertain_when_rounding_decides_sign() {
+        // arrange
+        // rounding errors of 0.1 * 3 - 0.3 are as large as the value
+        let equation = parse("x * 3 - 0.3").unwrap();
+        let env = Environment::from_tuples(&[("x", 0.1)]);
+
+        // act
+        let interval = equation.evaluate_interval(&env).unwrap();
+
+        // assert
+        assert!(equation.evaluate(&env).unwrap() > 0.0);
+        assert_eq!(interval.is_positive(), Predicate::Uncertain);
+    }
+
+    #[test]
+    fn test_evaluate_interval_fails_without_variable() {
+        // arrange
+        let equation = parse("x + 1").unwrap();
+
+        // act
+        let result = equation.evaluate_interval(&Environment::empty());
+
+        // assert
+        assert!(result.is_err());
+    }
+}
@@ -1,7 +1,15 @@
 pub(crate) mod arithmetic;
 pub(crate) mod constant;
+mod dimension;
+mod dual;
+pub(crate) mod function;
+mod interval;
 pub(crate) mod monomial;
+mod ops;
 mod parser;
+pub(crate) mod power;
+mod simplify;
+pub mod tape;
 
 use std::fmt::Display;
 
@@ -11,22 +19,36 @@ pub use parser::*;
 use crate::{
     environment::Environment,
     equation::{
-        arithmetic::ArithmeticEquation, constant::ConstantEquation, monomial::MonomialEquation,
+        arithmetic::ArithmeticEquation, constant::ConstantEquation, function::FunctionEquation,
+        monomial::MonomialEquation, power::PowerEquation,
     },
+    unit::DimensionError,
     variable::Variable,
 };
 
-impl From<f32> for Equation {
-    fn from(value: f32) -> Self {
+impl From<f64> for Equation {
+    fn from(value: f64) -> Self {
         Equation::Constant(value.into())
     }
 }
 
 /// Error cases for solving equation
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum EquationError {
     /// Can not found variables in the environment
     NoVariableInEnvironment(Vec<String>),
+
+    /// The equation is divided by constant zero
+    DivisionByZero,
+
+    /// The equation combines incompatible dimensions
+    Dimension(DimensionError),
+}
+
+impl From<DimensionError> for EquationError {
+    fn from(value: DimensionError) -> Self {
+        EquationError::Dimension(value)
+    }
 }
 
 /// Equation trait should provide some of the equation behavior of the solver
@@ -39,7 +61,7 @@ pub trait Evaluate {
     ///
     /// # Returns
     /// result of equation with the environment. Error when some errors
-    fn evaluate(&self, env: &Environment) -> Result<f32, EquationError>;
+    fn evaluate(&self, env: &Environment) -> Result<f64, EquationError>;
 
     /// return the equation related or not
     fn is_variable_related(&self, variable: &Variable) -> bool;
@@ -49,6 +71,16 @@ pub trait Evaluate {
     /// # Returns
     /// A vector of variable names that appear in this equation (without duplicates)
     fn related_variables(&self) -> Vec<String>;
+
+    /// Get the partial derivative of the equation with respect to a variable.
+    ///
+    /// # Arguments
+    /// * `variable` - name of the variable to differentiate by
+    ///
+    /// # Returns
+    /// A new equation of the derivative. Trivial terms such as `0 * x` or `x + 0` are
+    /// simplified while building the result.
+    fn derivative(&self, variable: &str) -> Equation;
 }
 
 /// A central Equation, it avoid to Boxing
@@ -58,6 +90,18 @@ pub enum Equation {
     Constant(ConstantEquation),
     Monomial(MonomialEquation),
     Arithmetic(ArithmeticEquation),
+    Function(FunctionEquation),
+    Power(PowerEquation),
+}
+
+impl Equation {
+    /// Get the value of the equation if it is a constant.
+    pub fn as_constant(&self) -> Option<f64> {
+        match self {
+            Equation::Constant(eq) => Some(eq.value()),
+            _ => None,
+        }
+    }
 }
 
 impl Display for Equation {
@@ -66,6 +110,8 @@ impl Display for Equation {
             Equation::Constant(eq) => write!(f, "{}", eq),
             Equation::Monomial(eq) => write!(f, "{}", eq),
             Equation::Arithmetic(eq) => write!(f, "{}", eq),
+            Equation::Function(eq) => write!(f, "{}", eq),
+            Equation::Power(eq) => write!(f, "{}", eq),
         }
     }
 }
@@ -1,6 +1,6 @@
 use crate::{
     environment::Environment,
-    equation::{EquationError, Evaluate},
+    equation::{Equation, EquationError, Evaluate, constant::number_text},
     variable::Variable,
 };
 
@@ -8,7 +8,7 @@ use crate::{
 #[derive(Debug, Clone, PartialEq)]
 pub struct MonomialEquation {
     /// The factor of thisequesion
-    factor: f32,
+    factor: f64,
 
     /// Factorized expression
     variable: String,
@@ -18,12 +18,12 @@ pub struct MonomialEquation {
 }
 
 impl Evaluate for MonomialEquation {
-    fn evaluate(&self, env: &Environment) -> Result<f32, EquationError> {
+    fn evaluate(&self, env: &Environment) -> Result<f64, EquationError> {
         let variable = env
             .get(&self.variable)
             .ok_or_else(|| EquationError::NoVariableInEnvironment(vec![self.variable.clone()]))?;
 
-        Ok(self.factor * variable.value.powf(self.exponent as f32))
+        Ok(self.factor * variable.value.powf(self.exponent as f64))
     }
 
     fn is_variable_related(&self, variable: &Variable) -> bool {
@@ -33,22 +33,71 @@ impl Evaluate for MonomialEquation {
     fn related_variables(&self) -> Vec<String> {
         vec![self.variable.clone()]
     }
+
+    fn derivative(&self, variable: &str) -> Equation {
+        if self.variable != variable || self.exponent == 0 || self.factor == 0.0 {
+            return 0.0.into();
+        }
+
+        let factor = self.factor * self.exponent as f64;
+        if self.exponent == 1 {
+            return factor.into();
+        }
+
+        MonomialEquation::new(factor, &self.variable, self.exponent - 1).into()
+    }
 }
 
 impl std::fmt::Display for MonomialEquation {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}{}^{}", self.factor, self.variable, self.exponent)
+        // `e` just after a number is read as the exponent of the number
+        let separator = if self.variable.starts_with(['e', 'E']) {
+            " "
+        } else {
+            ""
+        };
+        write!(
+            f,
+            "{}{}{}^{}",
+            number_text(self.factor),
+            separator,
+            self.variable,
+            self.exponent
+        )
     }
 }
 
 impl MonomialEquation {
-    pub(crate) fn new(factor: f32, variable: &str, exponent: i32) -> Self {
+    pub(crate) fn new(factor: f64, variable: &str, exponent: i32) -> Self {
         Self {
             factor,
             variable: variable.to_owned(),
             exponent,
         }
     }
+
+    /// Get the factor
+    pub(crate) fn factor(&self) -> f64 {
+        self.factor
+    }
+
+    /// Get the name of the variable
+    pub(crate) fn variable(&self) -> &str {
+        &self.variable
+    }
+
+    /// Get the exponent of the variable
+    pub(crate) fn exponent(&self) -> i32 {
+        self.exponent
+    }
+
+    /// Get the monomial with negated factor
+    pub(crate) fn negate(&self) -> Self {
+        Self {
+            factor: -self.factor,
+            ..self.clone()
+        }
+    }
 }
 
 #[cfg(test)]
@@ -317,4 +366,42 @@ mod tests {
             assert_eq!(result, vec!["x".to_string()]);
         }
     }
+
+    mod derivative_tests {
+        use super::*;
+        use pretty_assertions::assert_eq;
+        use rstest::rstest;
+
+        #[rstest]
+        #[case(MonomialEquation::new(3.0, "x", 2), MonomialEquation::new(6.0, "x", 1).into())]
+        #[case(MonomialEquation::new(2.0, "x", 3), MonomialEquation::new(6.0, "x", 2).into())]
+        #[case(MonomialEquation::new(2.0, "x", -1), MonomialEquation::new(-2.0, "x", -2).into())]
+        #[case(MonomialEquation::new(5.0, "x", 1), 5.0.into())]
+        #[case(MonomialEquation::new(5.0, "x", 0), 0.0.into())]
+        #[case(MonomialEquation::new(0.0, "x", 2), 0.0.into())]
+        fn test_derivative_by_own_variable(
+            #[case] equation: MonomialEquation,
+            #[case] expected: Equation,
+        ) {
+            // arrange
+
+            // act
+            let result = equation.derivative("x");
+
+            // assert
+            assert_eq!(result, expected);
+        }
+
+        #[test]
+        fn test_derivative_by_other_variable_is_zero() {
+            // arrange
+            let equation = MonomialEquation::new(3.0, "x", 2);
+
+            // act
+            let result = equation.derivative("y");
+
+            // assert
+            assert_eq!(result, 0.0.into());
+        }
+    }
 }
@@ -0,0 +1,139 @@
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use crate::equation::{
+    Equation,
+    arithmetic::{add, divide, multiply, subtract},
+    function::{Function, apply},
+    monomial::MonomialEquation,
+    power::power,
+};
+
+/// Operators of equations fold constants while building, as same as [`Equation::simplify`] does.
+macro_rules! binary_operator {
+    ($trait:ident, $method:ident, $build:ident) => {
+        impl $trait<Equation> for Equation {
+            type Output = Equation;
+
+            fn $method(self, rhs: Equation) -> Equation {
+                $build(self, rhs)
+            }
+        }
+
+        impl $trait<&Equation> for Equation {
+            type Output = Equation;
+
+            fn $method(self, rhs: &Equation) -> Equation {
+                $build(self, rhs.clone())
+            }
+        }
+
+        impl $trait<Equation> for &Equation {
+            type Output = Equation;
+
+            fn $method(self, rhs: Equation) -> Equation {
+                $build(self.clone(), rhs)
+            }
+        }
+
+        impl $trait<&Equation> for &Equation {
+            type Output = Equation;
+
+            fn $method(self, rhs: &Equation) -> Equation {
+                $build(self.clone(), rhs.clone())
+            }
+        }
+    };
+}
+
+binary_operator!(Add, add, add);
+binary_operator!(Sub, sub, subtract);
+binary_operator!(Mul, mul, multiply);
+binary_operator!(Div, div, divide);
+
+impl Neg for Equation {
+    type Output = Equation;
+
+    fn neg(self) -> Equation {
+        multiply((-1.0).into(), self)
+    }
+}
+
+impl Neg for &Equation {
+    type Output = Equation;
+
+    fn neg(self) -> Equation {
+        -self.clone()
+    }
+}
+
+/// Builders of equations, to make equations in code without formatting and parsing expressions
+impl Equation {
+    /// Make an equation of the variable
+    pub fn variable(name: &str) -> Equation {
+        MonomialEquation::new(1.0, name, 1).into()
+    }
+
+    /// Make `self ^ exponent`
+    pub fn powi(&self, exponent: i32) -> Equation {
+        power(self.clone(), (exponent as f64).into())
+    }
+
+    /// Make the square root of `self`
+    pub fn sqrt(&self) -> Equation {
+        apply(Function::Sqrt, vec![self.clone()])
+    }
+
+    /// Make the absolute value of `self`
+    pub fn abs(&self) -> Equation {
+        apply(Function::Abs, vec![self.clone()])
+    }
+
+    /// Make the angle of the point `(x, self)` from X-axis, as same as [`f64::atan2`]
+    pub fn atan2(&self, x: &Equation) -> Equation {
+        apply(Function::Atan2, vec![self.clone(), x.clone()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        environment::Environment,
+        equation::{Evaluate, parse},
+        variable::Variable,
+    };
+    use approx::assert_relative_eq;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_operators_build_same_value_as_parsed_equation() {
+        // arrange
+        let (x, y) = (Equation::variable("x"), Equation::variable("y"));
+        let env =
+            Environment::from_variables(vec![Variable::new("x", 3.0), Variable::new("y", -4.0)]);
+        let expected = parse("sqrt(x^2 + y^2) - abs(y) / 2 * -x + atan2(y, x)").unwrap();
+
+        // act
+        let equation =
+            (x.powi(2) + y.powi(2)).sqrt() - y.abs() / Equation::from(2.0) * -&x + y.atan2(&x);
+
+        // assert
+        assert_relative_eq!(
+            equation.evaluate(&env).unwrap(),
+            expected.evaluate(&env).unwrap()
+        );
+        assert!(equation.is_equivalent(&expected));
+    }
+
+    #[test]
+    fn test_operators_fold_constants() {
+        // arrange
+        let x = Equation::variable("x");
+
+        // act
+        let equation = Equation::from(2.0) * Equation::from(3.0) + &x * Equation::from(1.0);
+
+        // assert
+        assert_eq!(equation, parse("6 + x").unwrap());
+    }
+}
@@ -6,17 +6,23 @@ use nom::{
     branch::alt,
     bytes::complete::take_while1,
     character::complete::{char, multispace0, one_of},
-    combinator::{map, opt, recognize},
-    error::ParseError,
-    multi::many0,
+    combinator::{map, not, opt, recognize},
+    error::{ErrorKind, ParseError},
+    multi::{many0, separated_list1},
     number::complete::recognize_float,
-    sequence::delimited,
+    sequence::{delimited, preceded, terminated},
 };
 
-use crate::equation::{
-    Equation,
-    arithmetic::{ArithmeticEquation, Operator},
-    monomial::MonomialEquation,
+use crate::{
+    equation::{
+        Equation,
+        arithmetic::{ArithmeticEquation, Operator},
+        constant::ConstantEquation,
+        function::{Function, FunctionEquation},
+        monomial::MonomialEquation,
+        power::PowerEquation,
+    },
+    unit::Unit,
 };
 
 #[derive(Debug, Clone)]
@@ -24,14 +30,19 @@ enum Syntax {
     // direct representation for constant/monomial
     Constant(Equation),
     Monomial(Equation),
+    // already constructed equation, such as function, power and negation
+    Expression(Equation),
     // operator should be construct with a equation
     Op(Operator),
     Paren(Vec<Syntax>),
 }
 
-fn number(input: &str) -> IResult<&str, f32> {
+/// Name of the circular constant
+const PI: &str = "pi";
+
+fn number(input: &str) -> IResult<&str, f64> {
     let (input, f) = recognize_float(input)?;
-    let v: f32 = f.parse().expect("should be parsable");
+    let v: f64 = f.parse().expect("should be parsable");
 
     Ok((input, v))
 }
@@ -85,18 +96,88 @@ fn signed_digit(input: &str) -> IResult<&str, i32> {
     Ok((input, value))
 }
 
-/// Parse a monomial equation
+/// Parse a monomial equation.
+///
+/// The coefficient is never a part of the base of a power, so `2x^2.5` is `2 * x^2.5` as same as
+/// `2x^2` is `2 * x^2`.
 fn monomial(input: &str) -> IResult<&str, Syntax> {
     let coeff = opt(number);
-    let exponent = map(opt((char('^'), signed_digit)), |v| v.map(|v| v.1));
+    // exponent followed by `.` or `^` is not an integer, so leave it to general power. `^` is
+    // right-associative regardless of spaces.
+    let exponent = map(
+        opt(terminated((char('^'), signed_digit), not(ws(one_of(".^"))))),
+        |v| v.map(|v| v.1),
+    );
 
     let (input, (coeff, var, exp)) = (coeff, ws(variable), exponent).parse(input)?;
 
+    let Some(exp) = exp else {
+        let (input, general) = opt(preceded(ws(char('^')), unary)).parse(input)?;
+        let Some(general) = general else {
+            return Ok((input, monomial_syntax(coeff.unwrap_or(1.0), &var, 1)));
+        };
+
+        let base = to_equation(&monomial_syntax(1.0, &var, 1));
+        let power: Equation = PowerEquation::new(base, to_equation(&general)).into();
+        let term = match coeff {
+            Some(coeff) => ArithmeticEquation::new(Operator::Multiply, &[coeff.into(), power])
+                .expect("Should be convertable")
+                .into(),
+            None => power,
+        };
+        return Ok((input, Syntax::Expression(term)));
+    };
+
+    Ok((input, monomial_syntax(coeff.unwrap_or(1.0), &var, exp)))
+}
+
+/// Make a syntax of a monomial, or a constant for `pi`
+fn monomial_syntax(coeff: f64, var: &str, exp: i32) -> Syntax {
+    if var == PI {
+        return Syntax::Constant((coeff * std::f64::consts::PI.powi(exp)).into());
+    }
+
+    Syntax::Monomial(MonomialEquation::new(coeff, var, exp).into())
+}
+
+/// Parse a function application, such as `sin(x)`
+fn function(input: &str) -> IResult<&str, Syntax> {
+    let (rest, name) = ws(variable).parse(input)?;
+    let Some(function) = Function::from_name(&name) else {
+        return Err(nom::Err::Error(nom::error::Error::new(
+            input,
+            ErrorKind::Tag,
+        )));
+    };
+
+    let lparen = ws(char('('));
+    let rparen = ws(char(')'));
+    let (rest, arguments) =
+        delimited(lparen, separated_list1(ws(char(',')), expression), rparen).parse(rest)?;
+
+    // wrong number of arguments can not be parsed by other parsers
+    let equation = FunctionEquation::new(function, &arguments)
+        .map_err(|_| nom::Err::Failure(nom::error::Error::new(input, ErrorKind::Verify)))?;
+
+    Ok((rest, Syntax::Expression(equation.into())))
+}
+
+/// Parse a number with unit in brackets, such as `10[mm]` or `90 [deg]`. The unit is bracketed
+/// because a number followed by a name is a monomial, such as `2m` as `2 * m`.
+fn quantity(input: &str) -> IResult<&str, Syntax> {
+    let unit = delimited(ws(char('[')), ws(variable), ws(char(']')));
+    let (rest, (value, symbol)) = (ws(number), unit).parse(input)?;
+    let Some(unit) = Unit::from_symbol(&symbol) else {
+        // brackets have no other meaning, so an unknown unit is not a monomial or a constant
+        return Err(nom::Err::Failure(nom::error::Error::new(
+            input,
+            ErrorKind::Verify,
+        )));
+    };
+
     Ok((
-        input,
-        Syntax::Monomial(
-            MonomialEquation::new(coeff.unwrap_or(1.0), &var, exp.unwrap_or(1_i32)).into(),
-        ),
+        rest,
+        Syntax::Constant(ConstantEquation::with_unit(value, unit).into()),
     ))
 }
 
@@ -121,9 +202,61 @@ fn paren_syntax(input: &str) -> IResult<&str, Syntax> {
     Ok((input, Syntax::Paren(ret)))
 }
 
+/// Parse a term without operators
+fn atom(input: &str) -> IResult<&str, Syntax> {
+    alt((function, paren_syntax, quantity, monomial, constant)).parse(input)
+}
+
+/// Parse a power. Power is right-associative, and prior to unary minus of the base.
+fn power(input: &str) -> IResult<&str, Syntax> {
+    let (input, (base, exponent)) = (atom, opt(preceded(ws(char('^')), unary))).parse(input)?;
+
+    let Some(exponent) = exponent else {
+        return Ok((input, base));
+    };
+
+    Ok((
+        input,
+        Syntax::Expression(PowerEquation::new(to_equation(&base), to_equation(&exponent)).into()),
+    ))
+}
+
+/// Parse a term with unary minus
+fn unary(input: &str) -> IResult<&str, Syntax> {
+    let negation = map(preceded(ws(char('-')), unary), |v| {
+        Syntax::Expression(negate(to_equation(&v)))
+    });
+
+    alt((negation, power)).parse(input)
+}
+
+/// Negate the equation. Constant and monomial are negated directly.
+fn negate(equation: Equation) -> Equation {
+    match equation {
+        Equation::Constant(v) => v.negate().into(),
+        Equation::Monomial(v) => v.negate().into(),
+        _ => ArithmeticEquation::new(Operator::Multiply, &[(-1.0).into(), equation])
+            .expect("Should be convertable")
+            .into(),
+    }
+}
+
 /// Parse equation from string.
 fn equation(input: &str) -> IResult<&str, Syntax> {
-    alt((paren_syntax, monomial, constant)).parse(input)
+    unary(input)
+}
+
+/// Parse whole expression that has operators, and construct an equation
+fn expression(input: &str) -> IResult<&str, Equation> {
+    let (input, (syntax, syntaxes)) = (equation, many0(equation_with_op)).parse(input)?;
+
+    let syntaxes: Vec<Syntax> = vec![vec![syntax], syntaxes.into_iter().flatten().collect()]
+        .into_iter()
+        .flatten()
+        .collect();
+    let eq = construct_equation(&syntaxes).expect("Should not be empty");
+
+    Ok((input, eq))
 }
 
 /// Parse equation from string.
@@ -133,22 +266,24 @@ fn equation_with_op(input: &str) -> IResult<&str, Vec<Syntax>> {
     Ok((input, vec![Syntax::Op(op), syntax]))
 }
 
+/// Convert a syntax of a term to an equation
+fn to_equation(v: &Syntax) -> Equation {
+    match v {
+        Syntax::Constant(v) => v.clone(),
+        Syntax::Monomial(v) => v.clone(),
+        Syntax::Expression(v) => v.clone(),
+        Syntax::Paren(v) => construct_equation(v).unwrap(),
+        Syntax::Op(_) => unreachable!("This case is parse error"),
+    }
+}
+
 /// Construct an equation with parsed syntaxs.
 fn construct_equation(syntax: &[Syntax]) -> Result<Equation> {
     let Some((first, rest)) = syntax.split_first() else {
         return Err(color_eyre::eyre::eyre!("Must not empty"));
     };
 
-    fn to_eq(v: &Syntax) -> Equation {
-        match v {
-            Syntax::Constant(v) => v.clone(),
-            Syntax::Monomial(v) => v.clone(),
-            Syntax::Paren(v) => construct_equation(v).unwrap(),
-            Syntax::Op(_) => unreachable!("This case is parse error"),
-        }
-    }
-
-    let first: Equation = to_eq(first);
+    let first: Equation = to_equation(first);
 
     // short cut to stop infinite recursion
     if rest.is_empty() {
@@ -157,7 +292,7 @@ fn construct_equation(syntax: &[Syntax]) -> Result<Equation> {
 
     fn make_tree(syntax: &[Syntax]) -> Equation {
         if syntax.len() == 1 {
-            return to_eq(&syntax[0]);
+            return to_equation(&syntax[0]);
         }
         let mut ordered_ops = syntax
             .iter()
@@ -165,6 +300,7 @@ fn construct_equation(syntax: &[Syntax]) -> Result<Equation> {
             .filter_map(|(i, v)| match v {
                 Syntax::Constant(_) => None,
                 Syntax::Monomial(_) => None,
+                Syntax::Expression(_) => None,
                 Syntax::Op(operator) => Some((i, operator)),
                 Syntax::Paren(_) => None,
             })
@@ -173,14 +309,27 @@ fn construct_equation(syntax: &[Syntax]) -> Result<Equation> {
         // ordered by less-operator, and greater(right most) index.
         ordered_ops.sort_by(|(idx1, v1), (idx2, v2)| v1.cmp(v2).then(idx1.cmp(idx2).reverse()));
 
-        let (first, rest) = syntax.split_at(ordered_ops[0].0);
+        // a chain of the same operator, such as `a + b + c`, makes one equation with all operands.
+        // The chain stops at other operator of the same precedence to keep left-associativity.
+        let operator = *ordered_ops[0].1;
+        let mut splits: Vec<usize> = ordered_ops
+            .iter()
+            .take_while(|(_, v)| **v == operator)
+            .map(|(i, _)| *i)
+            .collect();
+        splits.reverse();
+
+        let mut operands = vec![];
+        let mut start = 0;
+        for split in splits {
+            operands.push(make_tree(&syntax[start..split]));
+            start = split + 1;
+        }
+        operands.push(make_tree(&syntax[start..]));
 
-        ArithmeticEquation::new(
-            *ordered_ops[0].1,
-            &[make_tree(first), make_tree(&rest[1..])],
-        )
-        .expect("Should be convertable")
-        .into()
+        ArithmeticEquation::new(operator, &operands)
+            .expect("Should be convertable")
+            .into()
     }
 
     Ok(make_tree(syntax))
@@ -188,13 +337,18 @@ fn construct_equation(syntax: &[Syntax]) -> Result<Equation> {
 
 /// Parse an equation from input string
 ///
+/// Expression can contain numbers, variables, `+ - * /`, parenthesis, unary minus, `^` as power,
+/// `pi`, and functions `sin`, `cos`, `tan`, `atan2`, `sqrt`, `abs`, `min`, `max`, `ln` and `sign`.
+/// A number followed by a unit in brackets (`[mm]`, `[cm]`, `[m]`, `[in]`, `[deg]` or `[rad]`) is a
+/// constant with the unit, such as `10[mm]`. Without brackets, unit symbols are variables.
+///
 /// # Arguments
 /// * `input` - A string slice that holds the equation
 ///
 /// # Returns
 /// * `Result<Equation, Box<dyn Error>>` - Parsed Equation or an error
 pub fn parse(input: &str) -> Result<Equation, Box<dyn Error + '_>> {
-    let (rest, (syntax, syntaxes)) = (equation, many0(equation_with_op))
+    let (rest, eq) = expression
         .parse(input)
         .finish()
         .map_err(|e| format!("Parse error: {:?}", e))?;
@@ -203,12 +357,6 @@ pub fn parse(input: &str) -> Result<Equation, Box<dyn Error + '_>> {
         return Err(format!("Unparsed input remaining: {}", rest).into());
     }
 
-    let syntaxes: Vec<Syntax> = vec![vec![syntax], syntaxes.into_iter().flatten().collect()]
-        .into_iter()
-        .flatten()
-        .collect();
-    let eq = construct_equation(&syntaxes)?;
-
     Ok(eq)
 }
 
@@ -1,8 +1,13 @@
 use approx::assert_relative_eq;
 use color_eyre::eyre::{Result, eyre};
+use pretty_assertions::assert_eq;
+use rstest::rstest;
 
 use super::parse;
-use crate::{environment::Environment, equation::Evaluate};
+use crate::{
+    environment::Environment,
+    equation::{Equation, Evaluate},
+};
 
 #[test]
 fn test_parse_constant() -> Result<()> {
@@ -525,3 +530,312 @@ fn test_parse_natural_computation_order() -> Result<()> {
 
     Ok(())
 }
+
+#[rstest]
+#[case("sin(x)", 1.0_f64.sin())]
+#[case("cos(x * 2)", 2.0_f64.cos())]
+#[case("tan(x)", 1.0_f64.tan())]
+#[case("atan2(y, x)", 2.0_f64.atan2(1.0))]
+#[case("sqrt(y * 8)", 4.0)]
+#[case("abs(x - y)", 1.0)]
+#[case("min(x, y)", 1.0)]
+#[case("max( x , y )", 2.0)]
+#[case("ln(y)", 2.0_f64.ln())]
+#[case("sign(x - y)", -1.0)]
+#[case("2 * sin(x) + 1", 2.0 * 1.0_f64.sin() + 1.0)]
+#[case("sqrt(abs(x - y * 5))", 3.0)]
+fn test_parse_function(#[case] input: &str, #[case] expected: f64) -> Result<()> {
+    // Arrange
+    let env = Environment::from_tuples(&[("x", 1.0), ("y", 2.0)]);
+
+    // Act
+    let eq = parse(input).map_err(|e| eyre!("{}", e))?;
+    let result = eq.evaluate(&env).map_err(|e| eyre!("{:?}", e))?;
+
+    // Assert
+    assert_relative_eq!(result, expected);
+
+    Ok(())
+}
+
+#[rstest]
+#[case("sin(x, y)")]
+#[case("atan2(x)")]
+#[case("min()")]
+fn test_parse_function_with_wrong_arguments(#[case] input: &str) {
+    // Arrange
+
+    // Act
+    let result = parse(input);
+
+    // Assert
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_parse_function_name_without_paren_is_variable() -> Result<()> {
+    // Arrange
+    let input = "sin + 1";
+    let env = Environment::from_tuples(&[("sin", 2.0)]);
+
+    // Act
+    let eq = parse(input).map_err(|e| eyre!("{}", e))?;
+    let result = eq.evaluate(&env).map_err(|e| eyre!("{:?}", e))?;
+
+    // Assert
+    assert_relative_eq!(result, 3.0);
+
+    Ok(())
+}
+
+#[rstest]
+#[case("pi", std::f64::consts::PI)]
+#[case("2pi", 2.0 * std::f64::consts::PI)]
+#[case("pi / 2", std::f64::consts::FRAC_PI_2)]
+#[case("pix", 5.0)]
+fn test_parse_pi(#[case] input: &str, #[case] expected: f64) -> Result<()> {
+    // Arrange
+    let env = Environment::from_tuples(&[("pix", 5.0)]);
+
+    // Act
+    let eq = parse(input).map_err(|e| eyre!("{}", e))?;
+    let result = eq.evaluate(&env).map_err(|e| eyre!("{:?}", e))?;
+
+    // Assert
+    assert_relative_eq!(result, expected);
+
+    Ok(())
+}
+
+#[rstest]
+#[case("x^0.5", 2.0)]
+#[case("x ^ 2", 16.0)]
+#[case("2^x", 16.0)]
+#[case("(x + 4)^2", 64.0)]
+#[case("x^-0.5", 0.5)]
+#[case("2^3^2", 512.0)]
+#[case("3 * x^1.5 / 2", 12.0)]
+#[case("2^(x / 2)", 4.0)]
+#[case("sin(x)^2 + cos(x)^2", 1.0)]
+fn test_parse_power(#[case] input: &str, #[case] expected: f64) -> Result<()> {
+    // Arrange
+    let env = Environment::from_tuples(&[("x", 4.0)]);
+
+    // Act
+    let eq = parse(input).map_err(|e| eyre!("{}", e))?;
+    let result = eq.evaluate(&env).map_err(|e| eyre!("{:?}", e))?;
+
+    // Assert
+    assert_relative_eq!(result, expected, epsilon = 1e-5);
+
+    Ok(())
+}
+
+#[rstest]
+#[case("2x^2.5", "2 * x^2.5")]
+#[case("2x^2.5", "2 * (x^2.5)")]
+#[case("2x^2", "2 * x^2")]
+#[case("x^2 ^3", "x^(2^3)")]
+#[case("x^2^3", "x^(2^3)")]
+#[case("x ^ 2 ^ 3", "x^(2^3)")]
+#[case("2pi^2.5", "2 * pi^2.5")]
+fn test_parse_power_does_not_depend_on_coefficient_or_spaces(
+    #[case] input: &str,
+    #[case] same: &str,
+) -> Result<()> {
+    // Arrange
+    let env = Environment::from_tuples(&[("x", 1.5)]);
+
+    // Act
+    let eq = parse(input).map_err(|e| eyre!("{}", e))?;
+    let result = eq.evaluate(&env).map_err(|e| eyre!("{:?}", e))?;
+    let expected = parse(same)
+        .map_err(|e| eyre!("{}", e))?
+        .evaluate(&env)
+        .map_err(|e| eyre!("{:?}", e))?;
+
+    // Assert
+    assert_relative_eq!(result, expected, epsilon = 1e-9);
+
+    Ok(())
+}
+
+#[rstest]
+#[case("-x", -3.0)]
+#[case("-(x + 1)", -4.0)]
+#[case("- x * 2", -6.0)]
+#[case("x - -2", 5.0)]
+#[case("2 * -x", -6.0)]
+#[case("-x^2", -9.0)]
+#[case("-2^2", -4.0)]
+#[case("--x", 3.0)]
+#[case("-sin(x)", -(3.0_f64.sin()))]
+fn test_parse_unary_minus(#[case] input: &str, #[case] expected: f64) -> Result<()> {
+    // Arrange
+    let env = Environment::from_tuples(&[("x", 3.0)]);
+
+    // Act
+    let eq = parse(input).map_err(|e| eyre!("{}", e))?;
+    let result = eq.evaluate(&env).map_err(|e| eyre!("{:?}", e))?;
+
+    // Assert
+    assert_relative_eq!(result, expected, epsilon = 1e-6);
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_negated_monomial_as_monomial() -> Result<()> {
+    // Arrange
+    let input = "-3x^2";
+    let env = Environment::from_tuples(&[("x", 2.0)]);
+
+    // Act
+    let eq = parse(input).map_err(|e| eyre!("{}", e))?;
+    let result = eq.evaluate(&env).map_err(|e| eyre!("{:?}", e))?;
+
+    // Assert
+    assert!(matches!(eq, Equation::Monomial(_)));
+    assert_relative_eq!(result, -12.0);
+
+    Ok(())
+}
+
+#[rstest]
+#[case("10[mm]", 10.0)]
+#[case("2[cm]", 20.0)]
+#[case("1.5[m]", 1500.0)]
+#[case("2[in] + x", 55.8)]
+#[case("180[deg]", std::f64::consts::PI)]
+#[case("-0.5[rad]", -0.5)]
+#[case("(3[cm])^2", 900.0)]
+#[case("2[m] * x", 10000.0)]
+#[case("2mx", 10.0)]
+fn test_parse_unit(#[case] input: &str, #[case] expected: f64) -> Result<()> {
+    // Arrange
+    let env = Environment::from_tuples(&[("x", 5.0), ("mx", 5.0)]);
+
+    // Act
+    let eq = parse(input).map_err(|e| eyre!("{}", e))?;
+    let result = eq.evaluate(&env).map_err(|e| eyre!("{:?}", e))?;
+
+    // Assert
+    assert_relative_eq!(result, expected, epsilon = 1e-3);
+
+    Ok(())
+}
+
+#[rstest]
+#[case("2m", 6.0)]
+#[case("2 m", 6.0)]
+#[case("3m^2", 27.0)]
+#[case("2 in", 8.0)]
+#[case("2m + 1[m]", 1006.0)]
+fn test_parse_number_before_unit_symbol_is_monomial(
+    #[case] input: &str,
+    #[case] expected: f64,
+) -> Result<()> {
+    // Arrange
+    // equations over variables named as units keep their meaning, units need brackets
+    let env = Environment::from_tuples(&[("m", 3.0), ("in", 4.0)]);
+
+    // Act
+    let eq = parse(input).map_err(|e| eyre!("{}", e))?;
+    let result = eq.evaluate(&env).map_err(|e| eyre!("{:?}", e))?;
+
+    // Assert
+    assert_relative_eq!(result, expected, epsilon = 1e-9);
+
+    Ok(())
+}
+
+#[rstest]
+#[case("10[ft]")]
+#[case("10[x]")]
+#[case("10[mm")]
+#[case("[mm]")]
+fn test_parse_invalid_unit(#[case] input: &str) {
+    // Act
+    let result = parse(input);
+
+    // Assert
+    assert!(result.is_err(), "{}", input);
+}
+
+#[test]
+fn test_parse_unit_symbol_without_number_is_variable() -> Result<()> {
+    // Arrange
+    let env = Environment::from_tuples(&[("m", 3.0)]);
+
+    // Act
+    let eq = parse("m * 2").map_err(|e| eyre!("{}", e))?;
+    let result = eq.evaluate(&env).map_err(|e| eyre!("{:?}", e))?;
+
+    // Assert
+    assert_relative_eq!(result, 6.0);
+
+    Ok(())
+}
+
+#[rstest]
+#[case("42.5")]
+#[case("-5")]
+#[case("x")]
+#[case("-3x^2")]
+#[case("x + y - z")]
+#[case("x - (y - z)")]
+#[case("x - (y + z)")]
+#[case("x / (y / z)")]
+#[case("x / y * z")]
+#[case("(x + 1) * (y - 2)")]
+#[case("x * -2")]
+#[case("x - -2")]
+#[case("sin(x) * cos(y)")]
+#[case("atan2(y - 1, x + 1)")]
+#[case("min(x, max(y, z))")]
+#[case("sqrt(x^2 + y^2)")]
+#[case("x^2.5")]
+#[case("(x + 1)^(y - 1)")]
+#[case("2^x^y")]
+#[case("(-2)^x")]
+#[case("-(x + y)")]
+#[case("-sin(x)^2")]
+#[case("pi * x / 180")]
+#[case("x + 10[mm] - 2[in]")]
+#[case("-90[deg]")]
+#[case("(2[m])^2")]
+#[case("2m - 3[m]")]
+#[case("2x^2.5")]
+#[case("x^2 ^3")]
+fn test_round_trip_through_display(#[case] input: &str) -> Result<()> {
+    // Arrange
+    let eq = parse(input).map_err(|e| eyre!("{}", e))?;
+
+    // Act
+    let text = eq.to_string();
+    let result = parse(&text).map_err(|e| eyre!("{}: {}", text, e))?;
+
+    // Assert
+    assert_eq!(result, eq, "{}", text);
+
+    Ok(())
+}
+
+#[rstest]
+#[case("sqrt(x^2 + y^2)")]
+#[case("atan2(y, x) * x^y")]
+#[case("min(x, y) / abs(z)")]
+fn test_round_trip_of_derivative(#[case] input: &str) -> Result<()> {
+    // Arrange
+    let eq = parse(input).map_err(|e| eyre!("{}", e))?;
+    let derivative = eq.derivative("x");
+
+    // Act
+    let text = derivative.to_string();
+    let result = parse(&text).map_err(|e| eyre!("{}: {}", text, e))?;
+
+    // Assert
+    assert_eq!(result.to_string(), text);
+
+    Ok(())
+}
@@ -0,0 +1,215 @@
+use std::collections::HashSet;
+
+use crate::{
+    environment::Environment,
+    equation::{
+        Equation, EquationError, Evaluate,
+        arithmetic::{add, divide, multiply, subtract},
+        function::{Function, apply},
+    },
+    variable::Variable,
+};
+
+/// Implementation of power equation, that has any equation as base and exponent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PowerEquation {
+    /// The base of power
+    base: Box<Equation>,
+
+    /// The exponent of power
+    exponent: Box<Equation>,
+}
+
+impl Evaluate for PowerEquation {
+    fn evaluate(&self, env: &Environment) -> Result<f64, EquationError> {
+        let base = self.base.evaluate(env)?;
+        let exponent = self.exponent.evaluate(env)?;
+
+        Ok(base.powf(exponent))
+    }
+
+    fn is_variable_related(&self, variable: &Variable) -> bool {
+        self.base.is_variable_related(variable) || self.exponent.is_variable_related(variable)
+    }
+
+    fn related_variables(&self) -> Vec<String> {
+        let mut variables: HashSet<String> = HashSet::new();
+        for var in self.base.related_variables() {
+            variables.insert(var);
+        }
+        for var in self.exponent.related_variables() {
+            variables.insert(var);
+        }
+        variables.into_iter().collect()
+    }
+
+    fn derivative(&self, variable: &str) -> Equation {
+        let base = (*self.base).clone();
+        let exponent = (*self.exponent).clone();
+        let db = base.derivative(variable);
+        let de = exponent.derivative(variable);
+
+        match (db.as_constant(), de.as_constant()) {
+            (Some(0.0), Some(0.0)) => 0.0.into(),
+            // power rule: (u^c)' = c * u^(c - 1) * u'
+            (_, Some(0.0)) => multiply(
+                multiply(
+                    exponent.clone(),
+                    power(base, subtract(exponent, 1.0.into())),
+                ),
+                db,
+            ),
+            // exponential rule: (c^v)' = c^v * ln(c) * v'
+            (Some(0.0), _) => multiply(
+                multiply(self.clone().into(), apply(Function::Ln, vec![base])),
+                de,
+            ),
+            // general rule: (u^v)' = u^v * (v' * ln(u) + v * u' / u)
+            _ => multiply(
+                self.clone().into(),
+                add(
+                    multiply(de, apply(Function::Ln, vec![base.clone()])),
+                    divide(multiply(exponent, db), base),
+                ),
+            ),
+        }
+    }
+}
+
+impl std::fmt::Display for PowerEquation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn operand(e: &Equation) -> String {
+            match e {
+                Equation::Constant(c) if c.value().is_sign_positive() => format!("{}", e),
+                Equation::Function(_) => format!("{}", e),
+                _ => format!("({})", e),
+            }
+        }
+
+        write!(f, "{}^{}", operand(&self.base), operand(&self.exponent))
+    }
+}
+
+impl PowerEquation {
+    /// Create a new power equation, `base ^ exponent`.
+    pub(crate) fn new(base: Equation, exponent: Equation) -> Self {
+        Self {
+            base: Box::new(base),
+            exponent: Box::new(exponent),
+        }
+    }
+
+    /// Get the base
+    pub(crate) fn base(&self) -> &Equation {
+        &self.base
+    }
+
+    /// Get the exponent
+    pub(crate) fn exponent(&self) -> &Equation {
+        &self.exponent
+    }
+}
+
+/// Make `base ^ exponent` with folding constants and removing trivial exponents.
+pub(crate) fn power(base: Equation, exponent: Equation) -> Equation {
+    match (base.as_constant(), exponent.as_constant()) {
+        (Some(b), Some(e)) => b.powf(e).into(),
+        (_, Some(0.0)) => 1.0.into(),
+        (_, Some(1.0)) => base,
+        _ => PowerEquation::new(base, exponent).into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::equation::parse;
+    use approx::assert_relative_eq;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    #[test]
+    fn test_evaluate_power_of_variables() {
+        // arrange
+        let equation = PowerEquation::new(parse("x").unwrap(), parse("y").unwrap());
+        let env = Environment::from_tuples(&[("x", 2.0), ("y", 3.0)]);
+
+        // act
+        let result = equation.evaluate(&env);
+
+        // assert
+        assert_eq!(result.unwrap(), 8.0);
+    }
+
+    #[test]
+    fn test_evaluate_fails_without_variable() {
+        // arrange
+        let equation = PowerEquation::new(parse("x").unwrap(), 2.5.into());
+        let env = Environment::empty();
+
+        // act
+        let result = equation.evaluate(&env);
+
+        // assert
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_related_variables_contains_base_and_exponent() {
+        // arrange
+        let equation = PowerEquation::new(parse("x + y").unwrap(), parse("x * z").unwrap());
+
+        // act
+        let mut result = equation.related_variables();
+        result.sort();
+
+        // assert
+        assert_eq!(
+            result,
+            vec!["x".to_string(), "y".to_string(), "z".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_power_folds_trivial_exponent() {
+        // arrange
+        let base = parse("x + 1").unwrap();
+
+        // act
+        let zero = power(base.clone(), 0.0.into());
+        let one = power(base.clone(), 1.0.into());
+        let constant = power(2.0.into(), 3.0.into());
+
+        // assert
+        assert_eq!(zero, 1.0.into());
+        assert_eq!(one, base);
+        assert_eq!(constant, 8.0.into());
+    }
+
+    mod derivative_tests {
+        use super::*;
+
+        #[rstest]
+        // (x^2.5)' = 2.5 * x^1.5
+        #[case("(x)^2.5", 2.5 * 2.0_f64.powf(1.5))]
+        // (2^x)' = 2^x * ln(2)
+        #[case("2^x", 4.0 * 2.0_f64.ln())]
+        // (x^x)' = x^x * (ln(x) + 1)
+        #[case("(x)^x", 4.0 * (2.0_f64.ln() + 1.0))]
+        // (y^x)' where y is not related, y^x * ln(y)
+        #[case("y^x", 9.0 * 3.0_f64.ln())]
+        // exponent does not depend on x
+        #[case("(y)^2.5", 0.0)]
+        fn test_derivative(#[case] input: &str, #[case] expected: f64) {
+            // arrange
+            let equation = parse(input).unwrap();
+            let env = Environment::from_tuples(&[("x", 2.0), ("y", 3.0)]);
+
+            // act
+            let result = equation.derivative("x").evaluate(&env).unwrap();
+
+            // assert
+            assert_relative_eq!(result, expected, epsilon = 1e-4);
+        }
+    }
+}
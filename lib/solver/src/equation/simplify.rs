@@ -0,0 +1,612 @@
+use std::cmp::Ordering;
+
+use crate::equation::{
+    Equation, EquationError,
+    arithmetic::{ArithmeticEquation, Operator},
+    function::apply,
+    monomial::MonomialEquation,
+    power::power,
+};
+
+impl Equation {
+    /// Get the simplified equation in canonical form.
+    ///
+    /// Simplification folds constants, removes identities such as `x * 1` or `x + 0`, flattens nested
+    /// additions and multiplications, collects like terms and factors, and sorts operands in
+    /// canonical order. Equations that differ only in these points are simplified to the same structure.
+    ///
+    /// # Returns
+    /// The simplified equation, or [`EquationError::DivisionByZero`] when the equation is divided by
+    /// constant zero
+    pub fn simplify(&self) -> Result<Equation, EquationError> {
+        let simplified = match self {
+            // constants with unit are normalised into the base unit
+            Equation::Constant(c) => c.value().into(),
+            Equation::Monomial(m) => simplify_monomial(m),
+            Equation::Function(f) => apply(
+                f.function(),
+                f.arguments()
+                    .iter()
+                    .map(|a| a.simplify())
+                    .collect::<Result<_, _>>()?,
+            ),
+            Equation::Power(p) => simplify_power(p.base().simplify()?, p.exponent().simplify()?)?,
+            Equation::Arithmetic(a) => match a.operator() {
+                Operator::Add | Operator::Subtract => {
+                    let mut sum = Sum::default();
+                    sum.collect(self, 1.0)?;
+                    sum.build()
+                }
+                Operator::Multiply | Operator::Divide => {
+                    let mut product = Product::default();
+                    product.collect(self, false)?;
+                    product.build()
+                }
+            },
+        };
+
+        Ok(simplified)
+    }
+
+    /// Return `true` if both equations have the same canonical form.
+    ///
+    /// `PartialEq` compares the structure as is, so `x + 1` and `1 + x` are not equal but equivalent.
+    /// Equations that can not be simplified are not equivalent to any equation.
+    pub fn is_equivalent(&self, other: &Equation) -> bool {
+        matches!((self.simplify(), other.simplify()), (Ok(a), Ok(b)) if a == b)
+    }
+
+    /// Get the canonical form of the constraint `self = 0`.
+    ///
+    /// In addition to [`Equation::simplify`], terms are scaled so that the coefficient of the first
+    /// term is `1`. So constraints that differ only in sign or scale, such as `f = 0`, `-f = 0` and
+    /// `2f = 0`, have the same canonical form.
+    pub fn canonical_constraint(&self) -> Result<Equation, EquationError> {
+        let simplified = self.simplify()?;
+        let terms = match &simplified {
+            Equation::Arithmetic(a) if a.operator() == Operator::Add => a.operands().to_vec(),
+            _ => vec![simplified.clone()],
+        };
+
+        // order terms without coefficients, so the first term does not depend on the scale
+        let mut terms: Vec<(f64, Option<Equation>)> =
+            terms.into_iter().map(split_coefficient).collect();
+        terms.sort_by(|(_, a), (_, b)| match (a, b) {
+            (Some(a), Some(b)) => compare(a, b),
+            (a, b) => b.is_some().cmp(&a.is_some()),
+        });
+
+        let Some(scale) = terms.first().map(|(c, _)| *c).filter(|c| *c != 0.0) else {
+            return Ok(simplified);
+        };
+        let terms = terms
+            .into_iter()
+            .map(|(c, term)| match term {
+                Some(term) => with_coefficient(c / scale, term),
+                None => (c / scale).into(),
+            })
+            .collect();
+
+        Ok(nary(Operator::Add, terms))
+    }
+}
+
+fn simplify_monomial(m: &MonomialEquation) -> Equation {
+    if m.factor() == 0.0 {
+        return 0.0.into();
+    }
+    if m.exponent() == 0 {
+        return m.factor().into();
+    }
+
+    m.clone().into()
+}
+
+fn simplify_power(base: Equation, exponent: Equation) -> Result<Equation, EquationError> {
+    let simplified = match (&base, base.as_constant(), exponent.as_constant()) {
+        // (c * x^e)^n = c^n * x^(e * n)
+        (Equation::Monomial(m), _, Some(n)) if n.fract() == 0.0 => {
+            simplify_monomial(&MonomialEquation::new(
+                m.factor().powi(n as i32),
+                m.variable(),
+                m.exponent() * n as i32,
+            ))
+        }
+        (_, Some(0.0), Some(n)) if n < 0.0 => return Err(EquationError::DivisionByZero),
+        (_, Some(1.0), _) => 1.0.into(),
+        _ => power(base, exponent),
+    };
+
+    Ok(simplified)
+}
+
+/// Terms of flattened additions and subtractions
+#[derive(Default)]
+struct Sum {
+    constant: f64,
+
+    /// Terms without coefficient, and coefficient of them
+    terms: Vec<(Equation, f64)>,
+}
+
+impl Sum {
+    /// Collect terms of nested additions and subtractions with the sign of them
+    fn collect(&mut self, equation: &Equation, sign: f64) -> Result<(), EquationError> {
+        match equation {
+            Equation::Arithmetic(a)
+                if matches!(a.operator(), Operator::Add | Operator::Subtract) =>
+            {
+                for (i, operand) in a.operands().iter().enumerate() {
+                    let sign = if a.operator() == Operator::Subtract && i > 0 {
+                        -sign
+                    } else {
+                        sign
+                    };
+                    self.collect(operand, sign)?;
+                }
+            }
+            _ => match equation.simplify()? {
+                // product with single factor can be a sum
+                Equation::Arithmetic(a) if a.operator() == Operator::Add => {
+                    for operand in a.operands() {
+                        self.add(operand.clone(), sign);
+                    }
+                }
+                simplified => self.add(simplified, sign),
+            },
+        }
+
+        Ok(())
+    }
+
+    /// Add a simplified term
+    fn add(&mut self, term: Equation, sign: f64) {
+        let (coefficient, term) = split_coefficient(term);
+
+        let Some(term) = term else {
+            self.constant += sign * coefficient;
+            return;
+        };
+
+        match self.terms.iter_mut().find(|(t, _)| *t == term) {
+            Some((_, c)) => *c += sign * coefficient,
+            None => self.terms.push((term, sign * coefficient)),
+        }
+    }
+
+    fn build(self) -> Equation {
+        let mut terms: Vec<Equation> = self
+            .terms
+            .into_iter()
+            .filter(|(_, c)| *c != 0.0)
+            .map(|(t, c)| with_coefficient(c, t))
+            .collect();
+        terms.sort_by(compare);
+
+        if self.constant != 0.0 || terms.is_empty() {
+            terms.push(self.constant.into());
+        }
+
+        nary(Operator::Add, terms)
+    }
+}
+
+/// Factors of flattened multiplications and divisions
+struct Product {
+    coefficient: f64,
+
+    /// Variables of monomials and exponent of them
+    variables: Vec<(String, i32)>,
+
+    /// Other factors and exponent of them
+    factors: Vec<(Equation, i32)>,
+}
+
+impl Default for Product {
+    fn default() -> Self {
+        Self {
+            coefficient: 1.0,
+            variables: vec![],
+            factors: vec![],
+        }
+    }
+}
+
+impl Product {
+    /// Collect factors of nested multiplications and divisions. `invert` is true for divisors.
+    fn collect(&mut self, equation: &Equation, invert: bool) -> Result<(), EquationError> {
+        match equation {
+            Equation::Arithmetic(a)
+                if matches!(a.operator(), Operator::Multiply | Operator::Divide) =>
+            {
+                for (i, operand) in a.operands().iter().enumerate() {
+                    let invert = if a.operator() == Operator::Divide && i > 0 {
+                        !invert
+                    } else {
+                        invert
+                    };
+                    self.collect(operand, invert)?;
+                }
+            }
+            _ => {
+                let sign = if invert { -1 } else { 1 };
+
+                match equation.simplify()? {
+                    // simplified sum can be a product, such as `2 * x * y`
+                    Equation::Arithmetic(a)
+                        if matches!(a.operator(), Operator::Multiply | Operator::Divide) =>
+                    {
+                        self.collect(&a.into(), invert)?
+                    }
+                    Equation::Constant(c) if invert && c.value() == 0.0 => {
+                        return Err(EquationError::DivisionByZero);
+                    }
+                    Equation::Constant(c) if invert => self.coefficient /= c.value(),
+                    Equation::Constant(c) => self.coefficient *= c.value(),
+                    Equation::Monomial(m) => {
+                        if invert {
+                            self.coefficient /= m.factor();
+                        } else {
+                            self.coefficient *= m.factor();
+                        }
+                        self.multiply_variable(m.variable(), sign * m.exponent());
+                    }
+                    Equation::Power(p)
+                        if p.exponent().as_constant().is_some_and(|n| n.fract() == 0.0) =>
+                    {
+                        let n = p.exponent().as_constant().unwrap_or(1.0) as i32;
+                        self.multiply_factor(p.base().clone(), sign * n);
+                    }
+                    simplified => self.multiply_factor(simplified, sign),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn multiply_variable(&mut self, variable: &str, exponent: i32) {
+        match self.variables.iter_mut().find(|(v, _)| v == variable) {
+            Some((_, e)) => *e += exponent,
+            None => self.variables.push((variable.to_string(), exponent)),
+        }
+    }
+
+    fn multiply_factor(&mut self, factor: Equation, exponent: i32) {
+        match self.factors.iter_mut().find(|(f, _)| *f == factor) {
+            Some((_, e)) => *e += exponent,
+            None => self.factors.push((factor, exponent)),
+        }
+    }
+
+    fn build(self) -> Equation {
+        if self.coefficient == 0.0 {
+            return 0.0.into();
+        }
+
+        let mut numerators: Vec<Equation> = self
+            .variables
+            .into_iter()
+            .filter(|(_, e)| *e != 0)
+            .map(|(v, e)| MonomialEquation::new(1.0, &v, e).into())
+            .collect();
+        let mut denominators = vec![];
+
+        for (factor, exponent) in self.factors {
+            match exponent.cmp(&0) {
+                Ordering::Greater => numerators.push(power(factor, (exponent as f64).into())),
+                Ordering::Less => denominators.push(power(factor, (-exponent as f64).into())),
+                Ordering::Equal => (),
+            }
+        }
+        numerators.sort_by(compare);
+        denominators.sort_by(compare);
+
+        let numerator = product(self.coefficient, numerators);
+        if denominators.is_empty() {
+            numerator
+        } else {
+            nary(
+                Operator::Divide,
+                vec![numerator, product(1.0, denominators)],
+            )
+        }
+    }
+}
+
+/// Make a product of the coefficient and sorted factors
+fn product(coefficient: f64, mut factors: Vec<Equation>) -> Equation {
+    match factors.as_slice() {
+        [] => coefficient.into(),
+        [Equation::Monomial(m)] => {
+            MonomialEquation::new(coefficient * m.factor(), m.variable(), m.exponent()).into()
+        }
+        [_] if coefficient == 1.0 => factors.remove(0),
+        _ if coefficient == 1.0 => nary(Operator::Multiply, factors),
+        _ => {
+            factors.insert(0, coefficient.into());
+            nary(Operator::Multiply, factors)
+        }
+    }
+}
+
+/// Split a simplified term into the coefficient and the term without coefficient.
+/// The term is `None` for a constant.
+fn split_coefficient(term: Equation) -> (f64, Option<Equation>) {
+    match &term {
+        Equation::Constant(c) => (c.value(), None),
+        Equation::Monomial(m) => (
+            m.factor(),
+            Some(MonomialEquation::new(1.0, m.variable(), m.exponent()).into()),
+        ),
+        Equation::Arithmetic(a) if a.operator() == Operator::Multiply => {
+            match a.operands().split_first() {
+                Some((first, rest)) if first.as_constant().is_some() => (
+                    first.as_constant().unwrap_or(1.0),
+                    Some(nary(Operator::Multiply, rest.to_vec())),
+                ),
+                _ => (1.0, Some(term)),
+            }
+        }
+        Equation::Arithmetic(a) if a.operator() == Operator::Divide => {
+            let (coefficient, numerator) = split_coefficient(a.operands()[0].clone());
+            let mut operands = a.operands().to_vec();
+            operands[0] = numerator.unwrap_or_else(|| 1.0.into());
+
+            (coefficient, Some(nary(Operator::Divide, operands)))
+        }
+        _ => (1.0, Some(term)),
+    }
+}
+
+/// Multiply the coefficient to a term split by [`split_coefficient`]
+fn with_coefficient(coefficient: f64, term: Equation) -> Equation {
+    if coefficient == 1.0 {
+        return term;
+    }
+
+    match &term {
+        Equation::Monomial(m) => {
+            MonomialEquation::new(coefficient * m.factor(), m.variable(), m.exponent()).into()
+        }
+        Equation::Arithmetic(a) if a.operator() == Operator::Multiply => {
+            let mut operands = vec![coefficient.into()];
+            operands.extend_from_slice(a.operands());
+            nary(Operator::Multiply, operands)
+        }
+        Equation::Arithmetic(a) if a.operator() == Operator::Divide => {
+            let mut operands = a.operands().to_vec();
+            operands[0] = with_coefficient(coefficient, operands[0].clone());
+            nary(Operator::Divide, operands)
+        }
+        _ => nary(Operator::Multiply, vec![coefficient.into(), term]),
+    }
+}
+
+/// Make an arithmetic equation, or the operand itself if only one operand
+fn nary(operator: Operator, mut operands: Vec<Equation>) -> Equation {
+    if operands.len() == 1 {
+        return operands.remove(0);
+    }
+
+    ArithmeticEquation::new(operator, &operands)
+        .expect("Must have operands")
+        .into()
+}
+
+/// Canonical order of equations. Monomials come first, and constants come last.
+fn compare(a: &Equation, b: &Equation) -> Ordering {
+    fn rank(e: &Equation) -> u8 {
+        match e {
+            Equation::Monomial(_) => 0,
+            Equation::Power(_) => 1,
+            Equation::Function(_) => 2,
+            Equation::Arithmetic(_) => 3,
+            Equation::Constant(_) => 4,
+        }
+    }
+
+    fn operator_rank(o: Operator) -> u8 {
+        match o {
+            Operator::Add => 0,
+            Operator::Subtract => 1,
+            Operator::Multiply => 2,
+            Operator::Divide => 3,
+        }
+    }
+
+    fn compare_all(a: &[Equation], b: &[Equation]) -> Ordering {
+        a.iter()
+            .zip(b)
+            .map(|(a, b)| compare(a, b))
+            .find(|o| *o != Ordering::Equal)
+            .unwrap_or_else(|| a.len().cmp(&b.len()))
+    }
+
+    rank(a).cmp(&rank(b)).then_with(|| match (a, b) {
+        (Equation::Monomial(a), Equation::Monomial(b)) => a
+            .variable()
+            .cmp(b.variable())
+            .then(b.exponent().cmp(&a.exponent()))
+            .then(a.factor().total_cmp(&b.factor())),
+        (Equation::Power(a), Equation::Power(b)) => {
+            compare(a.base(), b.base()).then_with(|| compare(a.exponent(), b.exponent()))
+        }
+        (Equation::Function(a), Equation::Function(b)) => a
+            .function()
+            .name()
+            .cmp(b.function().name())
+            .then_with(|| compare_all(a.arguments(), b.arguments())),
+        (Equation::Arithmetic(a), Equation::Arithmetic(b)) => operator_rank(a.operator())
+            .cmp(&operator_rank(b.operator()))
+            .then_with(|| compare_all(a.operands(), b.operands())),
+        (Equation::Constant(a), Equation::Constant(b)) => a.value().total_cmp(&b.value()),
+        _ => Ordering::Equal,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        environment::Environment,
+        equation::{Evaluate, parse},
+    };
+    use approx::assert_relative_eq;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("x * 1 + 0", "x")]
+    #[case("1 + 2 * 3", "7")]
+    #[case("0 * sin(x) + y", "y")]
+    #[case("x + x", "2x")]
+    #[case("2 * x - x", "x")]
+    #[case("x - x", "0")]
+    #[case("x * x", "x^2")]
+    #[case("x^2 / x", "x")]
+    #[case("x / 2", "0.5x")]
+    #[case("(x^2)^3", "x^6")]
+    #[case("1^x", "1")]
+    #[case("sin(0) + x", "x")]
+    #[case("x * y - y * x", "0")]
+    #[case("sin(x) + sin(x)", "2 * sin(x)")]
+    #[case("sin(x) * sin(x)", "sin(x)^2")]
+    #[case("3 + x + 2", "x + 5")]
+    #[case("1[cm] + 5[mm]", "15")]
+    fn test_simplify(#[case] input: &str, #[case] expected: &str) {
+        // arrange
+        let equation = parse(input).unwrap();
+
+        // act
+        let result = equation.simplify().unwrap();
+
+        // assert
+        assert_eq!(result, parse(expected).unwrap());
+    }
+
+    #[test]
+    fn test_simplify_flattens_nested_operators() {
+        // arrange
+        let equation = parse("((x + y) + z) - (1 - w)").unwrap();
+
+        // act
+        let result = equation.simplify().unwrap();
+
+        // assert
+        match result {
+            Equation::Arithmetic(a) => {
+                assert_eq!(a.operator(), Operator::Add);
+                assert_eq!(a.operands().len(), 5);
+            }
+            _ => panic!("should be arithmetic: {}", result),
+        }
+    }
+
+    #[rstest]
+    #[case("x + y", "y + x")]
+    #[case("(x + y) + z", "z + (y + x)")]
+    #[case("x * y * 2", "2 * (y * x)")]
+    #[case("x - y", "-y + x")]
+    #[case("x / y / z", "x / (z * y)")]
+    #[case("2 * (x + y) * z", "z * (y + x) * 2")]
+    #[case("sqrt(x * y + 0)", "sqrt(y * x)")]
+    #[case("x + 1[cm]", "x + 10[mm]")]
+    fn test_equivalent(#[case] lhs: &str, #[case] rhs: &str) {
+        // arrange
+        let lhs = parse(lhs).unwrap();
+        let rhs = parse(rhs).unwrap();
+
+        // act
+        let result = lhs.is_equivalent(&rhs);
+
+        // assert
+        assert!(result, "{:?} and {:?}", lhs.simplify(), rhs.simplify());
+    }
+
+    #[rstest]
+    #[case("x / 0")]
+    #[case("x / (2 - 2)")]
+    #[case("1 + 0^-1")]
+    fn test_simplify_fails_with_division_by_zero(#[case] input: &str) {
+        // arrange
+        let equation = parse(input).unwrap();
+
+        // act
+        let result = equation.simplify();
+
+        // assert
+        assert_eq!(result, Err(EquationError::DivisionByZero));
+    }
+
+    #[rstest]
+    #[case("x - 2 * y + 1", "2 * y - x - 1")]
+    #[case("x - 2 * y + 1", "3 * x - 6 * y + 3")]
+    #[case("sin(x) * 2 - y", "y / 4 - sin(x) / 2")]
+    #[case("x^2 + y^2 - 4", "-0.5 * (x * x) - y^2 / 2 + 2")]
+    fn test_canonical_constraint_ignores_sign_and_scale(#[case] lhs: &str, #[case] rhs: &str) {
+        // arrange
+        let lhs = parse(lhs).unwrap();
+        let rhs = parse(rhs).unwrap();
+
+        // act
+        let result = (lhs.canonical_constraint(), rhs.canonical_constraint());
+
+        // assert
+        assert_eq!(result.0, result.1);
+    }
+
+    #[test]
+    fn test_canonical_constraint_keeps_different_constraints() {
+        // arrange
+        let lhs = parse("x - 2 * y").unwrap();
+        let rhs = parse("x + 2 * y").unwrap();
+
+        // act
+        let result = (lhs.canonical_constraint(), rhs.canonical_constraint());
+
+        // assert
+        assert_ne!(result.0, result.1);
+    }
+
+    #[rstest]
+    #[case("x + y", "x - y")]
+    #[case("x * y", "x / y")]
+    #[case("sin(x)", "cos(x)")]
+    #[case("x^2", "x^3")]
+    fn test_not_equivalent(#[case] lhs: &str, #[case] rhs: &str) {
+        // arrange
+        let lhs = parse(lhs).unwrap();
+        let rhs = parse(rhs).unwrap();
+
+        // act
+        let result = lhs.is_equivalent(&rhs);
+
+        // assert
+        assert!(!result);
+    }
+
+    #[rstest]
+    #[case("x * 1 + 0")]
+    #[case("(x + y) * (x + y) - 2 * x * y")]
+    #[case("x / (y * 2) + 3 * x / y")]
+    #[case("sqrt((x - 1)^2 + (y - 2)^2) - 3")]
+    #[case("atan2(y - 1, x) * 2 - min(x, y) / x")]
+    #[case("-(x - y) * (2 - x)^3")]
+    fn test_simplify_keeps_value_and_is_stable(#[case] input: &str) {
+        // arrange
+        let equation = parse(input).unwrap();
+        let env = Environment::from_tuples(&[("x", 1.5), ("y", 2.5)]);
+
+        // act
+        let result = equation.simplify().unwrap();
+
+        // assert
+        assert_relative_eq!(
+            result.evaluate(&env).unwrap(),
+            equation.evaluate(&env).unwrap(),
+            epsilon = 1e-5
+        );
+        assert_eq!(result.simplify(), Ok(result));
+    }
+}
@@ -0,0 +1,395 @@
+use std::collections::HashMap;
+
+use crate::{
+    environment::Environment,
+    equation::{Equation, EquationError, arithmetic::Operator, function::Function},
+};
+
+/// Indices of variables that compiled equations read.
+///
+/// Each variable has one slot regardless of how many times it appears in equations, so values are
+/// loaded once per evaluation.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Slots {
+    names: Vec<String>,
+    indices: HashMap<String, usize>,
+}
+
+impl Slots {
+    /// Get the slot of the variable, and assign new one when the variable does not have it yet
+    fn assign(&mut self, name: &str) -> usize {
+        if let Some(slot) = self.indices.get(name) {
+            return *slot;
+        }
+
+        let slot = self.names.len();
+        self.names.push(name.to_string());
+        self.indices.insert(name.to_string(), slot);
+        slot
+    }
+
+    /// Get the slot of the variable
+    pub fn get(&self, name: &str) -> Option<usize> {
+        self.indices.get(name).copied()
+    }
+
+    /// Names of variables, ordered by slot
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Number of slots
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Load values of all slots into `values`.
+    ///
+    /// # Arguments
+    /// * `environments` - Environments to read. When some of them have the same variable, the last one
+    ///   is used, same as [`Environment::merge`].
+    /// * `values` - Buffer of values. It is resized to the number of slots.
+    ///
+    /// # Returns
+    /// * Error with names of all variables that no environment has
+    pub fn load(
+        &self,
+        environments: &[&Environment],
+        values: &mut Vec<f64>,
+    ) -> Result<(), EquationError> {
+        values.clear();
+        let mut missing = vec![];
+        for name in &self.names {
+            match environments.iter().rev().find_map(|env| env.get(name)) {
+                Some(variable) => values.push(*variable.value),
+                None => {
+                    missing.push(name.clone());
+                    values.push(f64::NAN);
+                }
+            }
+        }
+
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(EquationError::NoVariableInEnvironment(missing))
+        }
+    }
+}
+
+/// An instruction of [`Tape`]. Each instruction pushes one value to the stack.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Instruction {
+    Constant(f64),
+    /// `factor * slot^exponent`
+    Monomial {
+        factor: f64,
+        slot: usize,
+        exponent: i32,
+    },
+    /// Pop two values and push the result of them
+    Arithmetic(Operator),
+    /// Pop the base and the exponent, and push the power
+    Power,
+    /// Pop arguments of the function, and push the result
+    Function(Function),
+}
+
+/// An equation compiled to a flat instruction list in postfix order.
+///
+/// Variables are read from a slice of values indexed by [`Slots`], so evaluating a tape does not
+/// look up names or walk the tree of the equation. The result is the same as
+/// [`crate::equation::Evaluate::evaluate`], including the order of operations.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tape {
+    instructions: Vec<Instruction>,
+}
+
+impl Tape {
+    /// Compile the equation. Variables of the equation are assigned to `slots`.
+    pub fn compile(equation: &Equation, slots: &mut Slots) -> Self {
+        let mut instructions = vec![];
+        emit(equation, slots, &mut instructions);
+
+        Tape { instructions }
+    }
+
+    /// Evaluate the tape.
+    ///
+    /// # Arguments
+    /// * `values` - Values of variables ordered by slot, loaded by [`Slots::load`]
+    /// * `stack` - Buffer for intermediate values. It is reused between evaluations to avoid
+    ///   allocation.
+    pub fn run(&self, values: &[f64], stack: &mut Vec<f64>) -> f64 {
+        stack.clear();
+
+        for instruction in &self.instructions {
+            let value = match *instruction {
+                Instruction::Constant(value) => value,
+                Instruction::Monomial {
+                    factor,
+                    slot,
+                    exponent,
+                } => factor * values[slot].powf(exponent as f64),
+                Instruction::Arithmetic(operator) => {
+                    let rhs = stack.pop().unwrap_or(f64::NAN);
+                    let lhs = stack.pop().unwrap_or(f64::NAN);
+                    match operator {
+                        Operator::Add => lhs + rhs,
+                        Operator::Subtract => lhs - rhs,
+                        Operator::Multiply => lhs * rhs,
+                        Operator::Divide => lhs / rhs,
+                    }
+                }
+                Instruction::Power => {
+                    let exponent = stack.pop().unwrap_or(f64::NAN);
+                    let base = stack.pop().unwrap_or(f64::NAN);
+                    base.powf(exponent)
+                }
+                Instruction::Function(function) => {
+                    let start = stack.len().saturating_sub(function.arity());
+                    let value = function.apply(&stack[start..]);
+                    stack.truncate(start);
+                    value
+                }
+            };
+            stack.push(value);
+        }
+
+        stack.pop().unwrap_or(f64::NAN)
+    }
+}
+
+/// Append instructions of `equation` in postfix order
+fn emit(equation: &Equation, slots: &mut Slots, instructions: &mut Vec<Instruction>) {
+    match equation {
+        Equation::Constant(eq) => instructions.push(Instruction::Constant(eq.value())),
+        Equation::Monomial(eq) => instructions.push(Instruction::Monomial {
+            factor: eq.factor(),
+            slot: slots.assign(eq.variable()),
+            exponent: eq.exponent(),
+        }),
+        Equation::Arithmetic(eq) => {
+            // same as the left fold of evaluation
+            for (i, operand) in eq.operands().iter().enumerate() {
+                emit(operand, slots, instructions);
+                if i > 0 {
+                    instructions.push(Instruction::Arithmetic(eq.operator()));
+                }
+            }
+        }
+        Equation::Function(eq) => {
+            for argument in eq.arguments() {
+                emit(argument, slots, instructions);
+            }
+            instructions.push(Instruction::Function(eq.function()));
+        }
+        Equation::Power(eq) => {
+            emit(eq.base(), slots, instructions);
+            emit(eq.exponent(), slots, instructions);
+            instructions.push(Instruction::Power);
+        }
+    }
+}
+
+/// Buffers for evaluating tapes, reused between evaluations
+#[derive(Debug, Clone, Default)]
+pub struct Scratch {
+    values: Vec<f64>,
+    stack: Vec<f64>,
+}
+
+/// Equations compiled to tapes that share slots.
+///
+/// This is for evaluating all residuals or derivatives of a system at once. Values of variables are
+/// loaded once for all tapes, and no allocation happens after buffers of [`Scratch`] grow enough.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TapeSet {
+    slots: Slots,
+    tapes: Vec<Tape>,
+}
+
+impl TapeSet {
+    /// Compile all equations with shared slots
+    pub fn compile<'a>(equations: impl IntoIterator<Item = &'a Equation>) -> Self {
+        let mut slots = Slots::default();
+        let tapes = equations
+            .into_iter()
+            .map(|e| Tape::compile(e, &mut slots))
+            .collect();
+
+        TapeSet { slots, tapes }
+    }
+
+    /// Slots of variables that tapes read
+    pub fn slots(&self) -> &Slots {
+        &self.slots
+    }
+
+    /// Number of tapes
+    pub fn len(&self) -> usize {
+        self.tapes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tapes.is_empty()
+    }
+
+    /// Load values of variables into `scratch`. Tapes are evaluated with them by [`Self::run`].
+    pub fn load(
+        &self,
+        environments: &[&Environment],
+        scratch: &mut Scratch,
+    ) -> Result<(), EquationError> {
+        self.slots.load(environments, &mut scratch.values)
+    }
+
+    /// Evaluate the tape at `index` with values loaded by [`Self::load`]
+    pub fn run(&self, index: usize, scratch: &mut Scratch) -> f64 {
+        self.tapes[index].run(&scratch.values, &mut scratch.stack)
+    }
+
+    /// Evaluate all tapes into `out`, ordered as compiled equations.
+    ///
+    /// # Arguments
+    /// * `environments` - Environments to read, same as [`Slots::load`]
+    /// * `scratch` - Buffers for evaluation
+    /// * `out` - Results. The length must be the number of tapes.
+    pub fn evaluate(
+        &self,
+        environments: &[&Environment],
+        scratch: &mut Scratch,
+        out: &mut [f64],
+    ) -> Result<(), EquationError> {
+        self.load(environments, scratch)?;
+
+        for (tape, value) in self.tapes.iter().zip(out.iter_mut()) {
+            *value = tape.run(&scratch.values, &mut scratch.stack);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::equation::{Evaluate, parse};
+    use crate::variable::Variable;
+    use approx::assert_relative_eq;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("3")]
+    #[case("2x^3")]
+    #[case("x + y - 2 * x")]
+    #[case("x / y / 2")]
+    #[case("sin(x) * cos(y) + atan2(y, x)")]
+    #[case("min(x, y) - max(x, sqrt(y))")]
+    #[case("x ^ y ^ 0.5")]
+    #[case("(x + 1)^2 - ln(y) + sign(x - y)")]
+    #[case("10[mm] + x")]
+    fn test_run_is_same_as_evaluate(#[case] input: &str) {
+        // arrange
+        let equation = parse(input).unwrap();
+        let env = Environment::from_tuples(&[("x", 1.5), ("y", 2.5)]);
+        let mut slots = Slots::default();
+        let tape = Tape::compile(&equation, &mut slots);
+        let mut values = vec![];
+        slots.load(&[&env], &mut values).unwrap();
+
+        // act
+        let result = tape.run(&values, &mut vec![]);
+
+        // assert
+        assert_eq!(result, equation.evaluate(&env).unwrap());
+    }
+
+    #[test]
+    fn test_compile_assigns_one_slot_per_variable() {
+        // arrange
+        let equations = [parse("x * y + x").unwrap(), parse("y^2 - z").unwrap()];
+
+        // act
+        let tapes = TapeSet::compile(&equations);
+
+        // assert
+        assert_eq!(tapes.len(), 2);
+        assert_eq!(tapes.slots().names(), &["x", "y", "z"]);
+        assert_eq!(tapes.slots().get("z"), Some(2));
+    }
+
+    #[test]
+    fn test_load_prefers_later_environment() {
+        // arrange
+        let tapes = TapeSet::compile(&[parse("x + d").unwrap()]);
+        let variables = Environment::from_tuples(&[("x", 1.0), ("d", 2.0)]);
+        let dimensions = Environment::from_tuples(&[("d", 5.0)]);
+        let mut out = [0.0];
+
+        // act
+        tapes
+            .evaluate(
+                &[&variables, &dimensions],
+                &mut Scratch::default(),
+                &mut out,
+            )
+            .unwrap();
+
+        // assert
+        assert_relative_eq!(out[0], 6.0);
+    }
+
+    #[test]
+    fn test_load_fails_with_names_of_missing_variables() {
+        // arrange
+        let tapes = TapeSet::compile(&[parse("x + y + z").unwrap()]);
+        let env = Environment::from_variables(vec![Variable::new("y", 1.0)]);
+        let mut out = [0.0];
+
+        // act
+        let result = tapes.evaluate(&[&env], &mut Scratch::default(), &mut out);
+
+        // assert
+        assert_eq!(
+            result,
+            Err(EquationError::NoVariableInEnvironment(vec![
+                "x".to_string(),
+                "z".to_string()
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_evaluate_reuses_scratch_between_environments() {
+        // arrange
+        let tapes = TapeSet::compile(&[parse("x^2").unwrap(), parse("x * y").unwrap()]);
+        let mut scratch = Scratch::default();
+        let mut out = [0.0; 2];
+
+        // act
+        tapes
+            .evaluate(
+                &[&Environment::from_tuples(&[("x", 2.0), ("y", 3.0)])],
+                &mut scratch,
+                &mut out,
+            )
+            .unwrap();
+        let first = out;
+        tapes
+            .evaluate(
+                &[&Environment::from_tuples(&[("x", -1.0), ("y", 4.0)])],
+                &mut scratch,
+                &mut out,
+            )
+            .unwrap();
+
+        // assert
+        assert_eq!(first, [4.0, 6.0]);
+        assert_eq!(out, [1.0, -4.0]);
+    }
+}
@@ -1,47 +1,73 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, path::Path};
 
 use color_eyre::eyre::{Result, eyre};
-use epsilon::Epsilon;
+use epsilon::{DefaultEpsilon, Epsilon};
 
+pub use crate::diagnostics::{ConstraintDiagnostics, DimensionSpecificationStatus};
+pub use crate::report::{
+    ClusterReport, Relaxation, SolveError, SolveReport, Termination, Violation,
+};
+pub use crate::serialize::{FORMAT_VERSION, FormatError, TextFormat};
+pub use crate::unit::{Dimension, DimensionError, Unit};
 use crate::{
     environment::Environment,
-    equation::{Equation, Evaluate},
+    equation::{
+        Equation, EquationError, Evaluate,
+        arithmetic::{ArithmeticEquation, Operator},
+        tape::{Scratch, TapeSet},
+    },
     matrix::{
-        Matrix, MatrixExtract,
-        op::{Solve, solve},
+        Matrix,
+        lu::SparseLu,
+        op::{Solve, damped_least_squares, solve, solve_sparse},
         simple::SimpleMatrix,
-        size::Size,
         sparse::SparseMatrix,
     },
+    trace::{Replay, ReplayError, Trace, TraceStep},
     variable::Variable,
     vector::Vector,
 };
 
+mod cluster;
+pub mod diagnostics;
 pub mod environment;
 pub mod equation;
 pub mod matrix;
+pub mod parameter;
+pub mod report;
+pub mod serialize;
+pub mod trace;
+pub mod unit;
 pub mod variable;
 pub mod vector;
 
-const DEFAULT_EPSILON: f32 = 1e-5;
+/// Internal Jacobian matrix. Each element is the analytic partial derivative of an equation,
+/// so it only needs evaluation on each iteration.
+struct Jacobian {
+    /// Partial derivatives of equations
+    derivatives: SparseMatrix<Equation>,
 
-/// Internal Jacobian matrix. It is a matrix of constraint matrix.
-struct Jacobian(SparseMatrix<(Equation, String)>, f32);
+    /// Index of the compiled tape of each derivative, in the same pattern as `derivatives`
+    indices: SparseMatrix<usize>,
+
+    /// Compiled derivatives that share slots of variables
+    tapes: TapeSet,
+}
 
 impl Jacobian {
-    /// Create Jacobian from equations and variables
+    /// Create Jacobian from equations and variables. Rows are ordered as `equations`, and columns are
+    /// ordered by the name of variables.
     fn from_equations(
         equations: &[Equation],
         variables: &[Variable],
-        accuracy: f32,
     ) -> Result<Self, color_eyre::eyre::Error> {
-        if equations.len() != variables.len() {
+        if equations.is_empty() || variables.is_empty() {
             return Err(eyre!("Can not create valid jacobian"));
         }
         let mut variables = Vec::from(variables);
         variables.sort_by_key(|v| (*v.name).clone());
 
-        let mut matrix = SimpleMatrix::new(equations.len(), equations.len())?;
+        let mut matrix = SimpleMatrix::new(equations.len(), variables.len())?;
 
         for (i, equation) in equations.iter().enumerate() {
             for (j, variable) in variables.iter().enumerate() {
@@ -50,25 +76,136 @@ impl Jacobian {
                     continue;
                 }
 
-                matrix.set(i, j, (equation.clone(), (*variable.name).clone()))?;
+                let derivative = equation
+                    .derivative(&variable.name)
+                    .simplify()
+                    .map_err(|e| eyre!("Can not simplify derivative: {:?}", e))?;
+                // keep empty when the derivative vanishes
+                if derivative.as_constant() == Some(0.0) {
+                    continue;
+                }
+
+                matrix.set(i, j, derivative)?;
             }
         }
 
-        Ok(Jacobian(SparseMatrix::from_matrix(&matrix), accuracy))
+        Ok(Jacobian::compile(SparseMatrix::from_matrix(&matrix)))
+    }
+
+    /// Compile derivatives to tapes
+    fn compile(derivatives: SparseMatrix<Equation>) -> Self {
+        let mut ordered = vec![];
+        let indices = derivatives.map(|e| {
+            ordered.push(e.clone());
+            ordered.len() - 1
+        });
+
+        Jacobian {
+            tapes: TapeSet::compile(&ordered),
+            derivatives,
+            indices,
+        }
     }
 
-    /// Resolve forwarded
-    fn forward(&self, env: Environment) -> impl Matrix<f32> {
-        self.0.extract(move |(e, name)| {
-            let mut new_env = env.clone();
-            if let Some(v) = new_env.get_mut(name) {
-                v.set_value(*v.value + self.1);
+    /// Get the sub jacobian that consists of `rows` and `columns`
+    fn select(&self, rows: &[usize], columns: &[usize]) -> Result<Self, color_eyre::eyre::Error> {
+        let mut matrix = SimpleMatrix::new(rows.len(), columns.len())?;
+
+        for (i, row) in rows.iter().enumerate() {
+            for (j, column) in columns.iter().enumerate() {
+                if let Some(derivative) = self.derivatives.get(*row, *column)? {
+                    matrix.set(i, j, derivative.clone())?;
+                }
             }
+        }
 
-            let origin = e.evaluate(&env).unwrap_or(0.0);
-            let forwarded = e.evaluate(&new_env).unwrap_or(0.0);
-            (forwarded - origin) / self.1
-        })
+        Ok(Jacobian::compile(SparseMatrix::from_matrix(&matrix)))
+    }
+
+    /// Evaluate the jacobian with variables of `environments`, same as [`TapeSet::load`]
+    fn evaluate(
+        &self,
+        environments: &[&Environment],
+        scratch: &mut Scratch,
+    ) -> Result<SparseMatrix<f64>, EquationError> {
+        self.tapes.load(environments, scratch)?;
+
+        Ok(self.indices.map(|i| self.tapes.run(*i, scratch)))
+    }
+}
+
+/// Independent part of the system, with the jacobian of it.
+struct ClusterSystem {
+    /// Equations in the cluster, ordered by id
+    equations: Vec<EquationId>,
+
+    /// Variables in the cluster, ordered by name
+    variables: Vec<String>,
+
+    /// Equations of the cluster compiled to evaluate residuals
+    residuals: TapeSet,
+
+    /// Jacobian of the cluster. `None` when the cluster does not have any variable.
+    jacobian: Option<Jacobian>,
+
+    /// Sparse LU analysis of the jacobian, reused while the cluster is unchanged
+    lu: Option<SparseLu>,
+
+    /// Blocks of the block triangular form in the order to solve. Empty when the cluster can not
+    /// be split.
+    blocks: Vec<ClusterSystem>,
+
+    /// Jacobian without columns of pinned variables, reused while dragging the same variables
+    pinned: Option<PinnedJacobian>,
+}
+
+/// Sub jacobian of a cluster for unpinned variables
+struct PinnedJacobian {
+    /// Pinned variables in the cluster, ordered by name
+    pinned: Vec<String>,
+
+    /// Unpinned variables in the cluster, ordered by name
+    variables: Vec<String>,
+
+    /// Jacobian of the unpinned variables. `None` when all variables are pinned.
+    jacobian: Option<Jacobian>,
+}
+
+/// A bound or an inequality in the active set of [`Solver::solve`]
+#[derive(Debug, Clone, PartialEq)]
+enum ActiveConstraint {
+    /// The variable is fixed at the bound
+    Bound {
+        variable: String,
+        bound: f64,
+        lower: bool,
+    },
+    /// The inequality is solved as an equation
+    Inequality(EquationId),
+}
+
+impl ActiveConstraint {
+    /// Get the constraint to activate for the violation. Bounds of variables not in `names` can not
+    /// be kept by moving them.
+    fn of(violation: &Violation, names: &[String]) -> Option<Self> {
+        match violation {
+            Violation::LowerBound {
+                variable, bound, ..
+            } if names.contains(variable) => Some(ActiveConstraint::Bound {
+                variable: variable.clone(),
+                bound: *bound,
+                lower: true,
+            }),
+            Violation::UpperBound {
+                variable, bound, ..
+            } if names.contains(variable) => Some(ActiveConstraint::Bound {
+                variable: variable.clone(),
+                bound: *bound,
+                lower: false,
+            }),
+            Violation::Inequality { equation, .. } => Some(ActiveConstraint::Inequality(*equation)),
+            _ => None,
+        }
     }
 }
 
@@ -89,21 +226,116 @@ impl From<EquationId> for u64 {
     }
 }
 
-/// status to indicate if the dimension specification is correct
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum DimensionSpecificationStatus {
-    /// Incorrect dimension specification
-    Incorrect,
-    /// Correct dimension specification
-    Correct,
+/// Strategy to solve equations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SolveMode {
+    /// Newton-Raphson method. This is fast, but only for well-constrained systems.
+    #[default]
+    Newton,
+    /// Damped least squares (Levenberg-Marquardt) method. This minimizes the norm of equations,
+    /// so it can solve under- or over-constrained systems.
+    LeastSquares,
+}
+
+/// Linear solver used in each step of Newton-Raphson method
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LinearSolver {
+    /// Sparse LU factorisation with fill-reducing ordering. The analysis of the jacobian is
+    /// reused across iterations and solves while equations are unchanged.
+    #[default]
+    SparseLu,
+    /// Gaussian elimination of the dense matrix
+    Dense,
+}
+
+/// Options to control iterations of [`Solver::solve`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SolveOptions {
+    /// Maximum iterations for each cluster
+    pub max_iterations: usize,
+
+    /// Iterations stop when the norm of residuals is smaller than this
+    pub residual_tolerance: f64,
+
+    /// Iterations stop when the norm of the step is smaller than this
+    pub step_tolerance: f64,
+
+    /// Linear solver of Newton-Raphson method
+    pub linear_solver: LinearSolver,
+}
+
+impl SolveOptions {
+    /// Get default options with tolerances of the epsilon
+    pub fn new<E: Epsilon>() -> Self {
+        SolveOptions {
+            max_iterations: DEFAULT_MAX_ITERATIONS,
+            residual_tolerance: E::EPSILON,
+            step_tolerance: E::EPSILON,
+            linear_solver: LinearSolver::default(),
+        }
+    }
+}
+
+/// Priority and weight of a soft equation, that is a goal such as "keep near the original
+/// position" and yields to equations.
+///
+/// Soft equations are satisfied in order of priority, lower value first, as long as they do not
+/// move equations and soft equations of higher priorities. Soft equations of the same priority are
+/// balanced by weights.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SoftConstraint {
+    priority: u32,
+    weight: f64,
+}
+
+impl SoftConstraint {
+    /// Get a new soft constraint.
+    ///
+    /// # Errors
+    /// Returns error when `weight` is not a positive finite number.
+    pub fn new(priority: u32, weight: f64) -> Result<Self> {
+        if !(weight.is_finite() && weight > 0.0) {
+            return Err(eyre!("Weight must be positive, but {}", weight));
+        }
+
+        Ok(SoftConstraint { priority, weight })
+    }
+
+    pub fn priority(&self) -> u32 {
+        self.priority
+    }
+
+    pub fn weight(&self) -> f64 {
+        self.weight
+    }
 }
 
+/// Default maximum iterations for each cluster
+const DEFAULT_MAX_ITERATIONS: usize = 100;
+
+/// Newton-Raphson method is treated as diverged when the norm of residuals grows over
+/// this times of the initial one.
+const DIVERGENCE_FACTOR: f64 = 1e6;
+
+/// Initial damping factor of least squares mode
+const INITIAL_DAMPING: f64 = 1e-3;
+
+/// Damping factor to give up least squares, because the step can not decrease the cost anymore
+const MAX_DAMPING: f64 = 1e10;
+
+/// Weight of equations and locked soft equations while solving soft equations
+const HARD_WEIGHT: f64 = 1e6;
+
 /// Solver struct.
 pub struct Solver {
-    status: DimensionSpecificationStatus,
+    /// Diagnostics of current equations and variables.
+    diagnostics: ConstraintDiagnostics,
 
     /// Current jacobian. It is updated when equation or variable updated.
-    jacobian: Jacobian,
+    jacobian: Option<Jacobian>,
+
+    /// Independent clusters of the system. It is updated with the jacobian.
+    clusters: Vec<ClusterSystem>,
 
     /// Current variables
     variables: Environment,
@@ -115,10 +347,32 @@ pub struct Solver {
     /// member of equation mod, because equation does not have identity of it.
     equations: HashMap<EquationId, Equation>,
 
+    /// current inequalities with equation id. Each of them is kept as `equation >= 0`, and does
+    /// not affect the diagnostics.
+    inequalities: HashMap<EquationId, Equation>,
+
+    /// current soft equations with equation id. They do not affect the diagnostics.
+    soft: HashMap<EquationId, (Equation, SoftConstraint)>,
+
     generator: Box<dyn EquationIdGenerator>,
 
     /// The resolution of solving
-    epsilon: f32,
+    epsilon: f64,
+
+    /// Strategy to solve equations
+    mode: SolveMode,
+
+    /// Options of iterations
+    options: SolveOptions,
+
+    /// Record traces of [`Solver::solve`] when `true`
+    tracing: bool,
+
+    /// The trace being recorded by current solve
+    recording: Option<Trace>,
+
+    /// The trace of the last solve
+    trace: Option<Trace>,
 }
 
 /// Trait for specialized generating equation id
@@ -169,36 +423,141 @@ impl Solver {
     /// * A new solver with empty variables, dimensions, and equations
     ///
     /// # Initial State
-    /// * Status is set to `Incorrect` until variables match equation count
-    /// * Jacobian is initialized with minimal 1x1 sparse matrix
+    /// * Status is set to `Empty` until variables or equations are added
+    /// * Jacobian is not available until both of variables and equations exist
     pub fn new<E: Epsilon>(generator: Box<dyn EquationIdGenerator>) -> Self {
         Solver {
-            status: DimensionSpecificationStatus::Incorrect,
-            jacobian: Jacobian(
-                SparseMatrix::empty(Size::new(1, 1)).expect("should be suceess"),
-                DEFAULT_EPSILON,
-            ),
+            diagnostics: ConstraintDiagnostics::default(),
+            jacobian: None,
+            clusters: vec![],
             variables: Environment::empty(),
             dimensions: Environment::empty(),
             equations: HashMap::new(),
+            inequalities: HashMap::new(),
+            soft: HashMap::new(),
             generator: generator.clone(),
             epsilon: E::EPSILON,
+            mode: SolveMode::default(),
+            options: SolveOptions::new::<E>(),
+            tracing: false,
+            recording: None,
+            trace: None,
         }
     }
 
+    /// Make a solver with equations, variables and settings of the trace. Ids of equations are
+    /// kept, so the solver solves the system in the same order as the recorded one.
+    pub fn from_trace(trace: &Trace) -> Self {
+        let current = trace
+            .equations
+            .iter()
+            .chain(&trace.inequalities)
+            .map(|(id, _)| *id)
+            .chain(trace.soft.iter().map(|(id, _, _)| *id))
+            .map(u64::from)
+            .max()
+            .unwrap_or(0);
+        let mut solver =
+            Solver::new::<DefaultEpsilon>(Box::new(DefaultEquationIdGenerator { current }));
+        solver.epsilon = trace.epsilon;
+        solver.mode = trace.mode;
+        solver.options = trace.options;
+        solver.equations = trace.equations.iter().cloned().collect();
+        solver.inequalities = trace.inequalities.iter().cloned().collect();
+        solver.soft = trace
+            .soft
+            .iter()
+            .map(|(id, equation, soft)| (*id, (equation.clone(), *soft)))
+            .collect();
+        solver.variables = trace.variables.clone();
+        solver.dimensions = trace.dimensions.clone();
+        solver.recaluculate_status();
+
+        solver
+    }
+
+    /// Read the trace file written by [`Trace::write`], and solve the recorded system again.
+    ///
+    /// The replayed solver records a trace too, so it can be compared with the recorded one.
+    ///
+    /// # Returns
+    /// * Error when the file can not be read as a trace. Errors of solving are in [`Replay`].
+    pub fn replay(path: impl AsRef<Path>) -> Result<Replay, ReplayError> {
+        let recorded = Trace::read(path)?;
+        let mut solver = Solver::from_trace(&recorded);
+        solver.set_tracing(true);
+        let result = solver.solve();
+
+        Ok(Replay {
+            recorded,
+            solver,
+            result,
+        })
+    }
+
+    /// Enable or disable recording traces of [`Solver::solve`]. Tracing is disabled by default.
+    pub fn set_tracing(&mut self, tracing: bool) {
+        self.tracing = tracing;
+    }
+
+    /// Get the trace of the last solve while tracing is enabled
+    pub fn trace(&self) -> Option<&Trace> {
+        self.trace.as_ref()
+    }
+
+    /// Get the current solve mode
+    pub fn mode(&self) -> SolveMode {
+        self.mode
+    }
+
+    /// Set the solve mode used by [`Solver::solve`]
+    pub fn set_mode(&mut self, mode: SolveMode) {
+        self.mode = mode;
+    }
+
+    /// Get the current options of iterations
+    pub fn options(&self) -> SolveOptions {
+        self.options
+    }
+
+    /// Set the options of iterations used by [`Solver::solve`]
+    pub fn set_options(&mut self, options: SolveOptions) {
+        self.options = options;
+    }
+
+    /// Get current variables
+    pub fn variables(&self) -> &Environment {
+        &self.variables
+    }
+
     /// Get the status
     pub fn status(&self) -> DimensionSpecificationStatus {
-        self.status
+        self.diagnostics.status()
+    }
+
+    /// Get the diagnostics of current equations and variables.
+    ///
+    /// Diagnostics is updated when equations, variables or dimensions are updated.
+    pub fn diagnostics(&self) -> &ConstraintDiagnostics {
+        &self.diagnostics
     }
 
     /// Updates the solver's variable environment and recalculates dimension specification status.
     ///
+    /// The jacobian is rebuilt only when the set of variables changes. When only values change,
+    /// the cached jacobian is reused and only the diagnostics is updated.
+    ///
     /// # Parameters
     /// * `env` - New environment containing variable definitions
     pub fn update_variables(&mut self, env: &Environment) {
+        let names = self.variable_names();
         self.variables = env.clone();
 
-        self.recaluculate_status()
+        if names == self.variable_names() {
+            self.refresh_diagnostics()
+        } else {
+            self.recaluculate_status()
+        }
     }
 
     /// Updates the solver's dimension environment.
@@ -209,39 +568,96 @@ impl Solver {
     /// # Parameters
     /// * `env` - New environment containing dimension definitions
     pub fn update_dimensions(&mut self, env: &Environment) {
-        self.dimensions = env.clone()
+        self.dimensions = env.clone();
+
+        // dimensions do not change the structure of the jacobian
+        self.refresh_diagnostics()
     }
 
-    /// Recalculates the dimension specification status.
-    ///
-    /// Compares the number of variables against the number of equations to determine
-    /// if the system is properly specified for solving.
-    ///
-    /// # Status Rules
-    /// * `Correct` - Variable count equals equation count (system is solvable)
-    /// * `Incorrect` - Variable count differs from equation count (under/over-determined)
+    /// Get names of current variables, ordered by name
+    fn variable_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .variables
+            .variables()
+            .iter()
+            .map(|v| (*v.name).clone())
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Get current equations ordered by id
+    fn ordered_equations(&self) -> Vec<(EquationId, &Equation)> {
+        let mut equations: Vec<_> = self.equations.iter().map(|(k, v)| (*k, v)).collect();
+        equations.sort_by_key(|(k, _)| *k);
+        equations
+    }
+
+    /// Recalculates the jacobian, clusters and the diagnostics of the system.
     fn recaluculate_status(&mut self) {
-        let variable_count = self.variables.variables().len();
-        let equation_count = self.equations.values().len();
+        let equations = self.ordered_equations();
 
-        if variable_count == equation_count && variable_count > 0 {
-            self.status = DimensionSpecificationStatus::Correct
-        } else {
-            self.status = DimensionSpecificationStatus::Incorrect
-        }
+        let mut variables = self.variables.variables();
+        variables.sort_by_key(|v| (*v.name).clone());
+        let names: Vec<String> = variables.iter().map(|v| (*v.name).clone()).collect();
+
+        let ordered: Vec<Equation> = equations.iter().map(|(_, v)| (*v).clone()).collect();
+        let jacobian = Jacobian::from_equations(&ordered, &variables).ok();
+        let system = |c: &cluster::Cluster| ClusterSystem {
+            equations: c.equations.iter().map(|i| equations[*i].0).collect(),
+            variables: c.variables.iter().map(|j| names[*j].clone()).collect(),
+            residuals: TapeSet::compile(c.equations.iter().map(|i| equations[*i].1)),
+            jacobian: jacobian
+                .as_ref()
+                .and_then(|j| j.select(&c.equations, &c.variables).ok()),
+            lu: None,
+            blocks: vec![],
+            pinned: None,
+        };
+        self.clusters = cluster::decompose(&equations, &names)
+            .iter()
+            .map(|c| {
+                let blocks = cluster::triangularize(&equations, &names, c);
+                ClusterSystem {
+                    blocks: match blocks.len() {
+                        1 => vec![],
+                        _ => blocks.iter().map(system).collect(),
+                    },
+                    ..system(c)
+                }
+            })
+            .collect();
+        self.jacobian = jacobian;
 
-        match self.status {
-            DimensionSpecificationStatus::Incorrect => (),
-            DimensionSpecificationStatus::Correct => {
-                let mut equations: Vec<_> = self.equations.iter().collect();
-                equations.sort_by_key(|(k, _)| *k);
-                let equations: Vec<_> = equations.iter().map(|(_, v)| *v).cloned().collect();
+        self.refresh_diagnostics();
+    }
 
-                self.jacobian =
-                    Jacobian::from_equations(&equations, &self.variables.variables(), self.epsilon)
-                        .expect("Must be valid")
-            }
-        }
+    /// Recalculates the diagnostics of the system with the cached jacobian.
+    ///
+    /// The diagnostics compares structural and numerical rank of the jacobian at current variables,
+    /// then detects free variables, redundant equations and conflicting equations. Equations that can
+    /// not be evaluated are reported as invalid instead of being analyzed.
+    fn refresh_diagnostics(&mut self) {
+        let equations = self.ordered_equations();
+        let names = self.variable_names();
+
+        let env = self.variables.merge(&self.dimensions);
+        let residuals: Vec<_> = equations.iter().map(|(_, e)| e.evaluate(&env)).collect();
+        let jacobian = self.jacobian.as_ref().and_then(|j| {
+            j.evaluate(
+                &[&self.variables, &self.dimensions],
+                &mut Scratch::default(),
+            )
+            .ok()
+        });
+
+        self.diagnostics = diagnostics::analyze(
+            &equations,
+            &names,
+            jacobian.as_ref(),
+            &residuals,
+            self.epsilon,
+        );
     }
 
     /// Adds an equation to the solver and returns its unique identifier.
@@ -267,7 +683,81 @@ impl Solver {
         new_id
     }
 
-    /// Removes an equation from the solver by its identifier.
+    /// Adds equations to the solver at once and returns their unique identifiers in the same order.
+    ///
+    /// This is same as [`Solver::add_equation`] for each equation, but the jacobian, clusters and
+    /// the diagnostics are recalculated only once. Use this to load many equations.
+    ///
+    /// # Parameters
+    /// * `equations` - Equations to add to the system
+    ///
+    /// # Returns
+    /// * `Vec<EquationId>` - Unique identifiers for the added equations
+    pub fn add_equations(
+        &mut self,
+        equations: impl IntoIterator<Item = Equation>,
+    ) -> Vec<EquationId> {
+        let ids = equations
+            .into_iter()
+            .map(|equation| {
+                let new_id = self.generator.generate();
+                self.equations.insert(new_id, equation);
+                new_id
+            })
+            .collect();
+
+        self.recaluculate_status();
+
+        ids
+    }
+
+    /// Adds an inequality `equation >= 0` to the solver and returns its unique identifier.
+    ///
+    /// Inequalities are not counted in the diagnostics. After solving equations, violated
+    /// inequalities are made active, that is treated as `equation = 0`, and solved again with
+    /// least squares method. The inequality can be removed by [`Solver::remove_equation`].
+    ///
+    /// # Parameters
+    /// * `equation` - Equation that must be greater than or equal to zero
+    ///
+    /// # Returns
+    /// * `EquationId` - Unique identifier for the added inequality
+    pub fn add_inequality(&mut self, equation: Equation) -> EquationId {
+        let new_id = self.generator.generate();
+
+        self.inequalities.insert(new_id, equation);
+
+        new_id
+    }
+
+    /// Adds a soft equation `equation = 0` to the solver and returns its unique identifier.
+    ///
+    /// Soft equations are not counted in the diagnostics. After solving equations, soft equations
+    /// are solved with weighted least squares method for each priority, so variables that
+    /// equations do not decide move toward them. [`SolveReport::relaxed`] reports soft equations
+    /// that could not be satisfied. In [`SolveMode::Newton`], equations must be well constrained
+    /// as without soft equations, so use [`SolveMode::LeastSquares`] for under-constrained systems.
+    /// The soft equation can be removed by [`Solver::remove_equation`].
+    ///
+    /// # Parameters
+    /// * `equation` - Equation that should be zero
+    /// * `constraint` - Priority and weight of the equation
+    ///
+    /// # Returns
+    /// * `EquationId` - Unique identifier for the added soft equation
+    pub fn add_soft_equation(
+        &mut self,
+        equation: Equation,
+        constraint: SoftConstraint,
+    ) -> EquationId {
+        let new_id = self.generator.generate();
+
+        self.soft.insert(new_id, (equation, constraint));
+
+        new_id
+    }
+
+    /// Removes an equation, an inequality or a soft equation from the solver by its identifier.
     ///
     /// # Parameters
     /// * `id` - Unique identifier of the equation to remove
@@ -276,6 +766,12 @@ impl Solver {
     /// * `Some(Equation)` - The removed equation if it existed
     /// * `None` - If no equation with the given ID was found
     pub fn remove_equation(&mut self, id: EquationId) -> Option<Equation> {
+        if let Some(v) = self.inequalities.remove(&id) {
+            return Some(v);
+        }
+        if let Some((v, _)) = self.soft.remove(&id) {
+            return Some(v);
+        }
         let v = self.equations.remove(&id);
 
         self.recaluculate_status();
@@ -284,230 +780,864 @@ impl Solver {
     }
 
     /// Solve current equations and get variables.
-    pub fn solve(&mut self) -> Result<Environment> {
-        if self.status != DimensionSpecificationStatus::Correct {
-            return Err(eyre!("Can not solve incorrect solver"));
+    ///
+    /// Equations are decomposed into clusters that do not share any variable, and each cluster is
+    /// solved independently. So a cluster that can not converge does not affect others. In
+    /// [`SolveMode::Newton`], a cluster is solved block by block of its block triangular form, so
+    /// each iteration only solves the equations coupled with each other.
+    ///
+    /// [`SolveMode::Newton`] requires well-constrained equations. [`SolveMode::LeastSquares`] accepts
+    /// any equations, and returns variables that minimize the norm of equations.
+    ///
+    /// When the jacobian became singular or iterations diverged, variables are restored to the
+    /// values before solving. When some equations can not be evaluated, such as ones with unknown
+    /// variables, the error of the evaluation is returned.
+    pub fn solve(&mut self) -> Result<SolveReport, SolveError> {
+        self.recording = self.tracing.then(|| self.snapshot());
+        let result = self.solve_clusters();
+        if let Some(trace) = self.recording.take() {
+            self.trace = Some(trace);
         }
 
-        // make direct solve
-        // x_1 = x_0 - J_0^-1 * f_0 -> J_0 * x_delta = - f_0
-        let mut ordered = self.variables.variables();
-        ordered.sort_by_key(|f| (*f.name).clone());
-        let mut equation_order = self.equations.keys().collect::<Vec<_>>();
-        equation_order.sort();
-        let equations: Vec<_> = equation_order.iter().map(|k| &self.equations[k]).collect();
+        result
+    }
 
-        // initial value
-        let mut x0 = Vector::from(&ordered.iter().map(|f| f.into()).collect::<Vec<_>>())?;
+    /// Solve all clusters, and enforce inequalities
+    fn solve_clusters(&mut self) -> Result<SolveReport, SolveError> {
+        // no mode can solve equations that can not be evaluated, so report the reason of them
+        if let Some(id) = self.diagnostics.invalid_equations.first() {
+            self.equations[id].evaluate(&self.variables.merge(&self.dimensions))?;
+        }
 
-        // Do newton-rhapson method
-        loop {
-            // calculate rhs. this result is simple vector that is column-transposed
-            let extractor = self.variables.merge(&self.dimensions);
-            let b = {
-                let f0 = equations
-                    .iter()
-                    .map(|e| e.evaluate(&extractor).unwrap_or(0.0))
-                    .collect::<Vec<_>>();
+        match self.mode {
+            SolveMode::Newton if self.status() != DimensionSpecificationStatus::WellConstrained => {
+                return Err(SolveError::InvalidStatus(self.status()));
+            }
+            SolveMode::LeastSquares if self.jacobian.is_none() && self.soft.is_empty() => {
+                return Err(SolveError::NoJacobian);
+            }
+            _ => (),
+        }
 
-                Vector::from(&f0)? * -1.0
-            };
+        let initial = self.variables.clone();
+        let mut clusters = std::mem::take(&mut self.clusters);
+        let reports = clusters
+            .iter_mut()
+            .map(|cluster| self.solve_cluster(cluster))
+            .collect::<Result<Vec<_>, _>>();
+        self.clusters = clusters;
+
+        let names = self.variable_names();
+        let reports = reports.and_then(|clusters| Ok((clusters, self.solve_soft(&names)?)));
+        match reports
+            .and_then(|(clusters, moved)| self.enforce_inequalities(&names, clusters, moved))
+        {
+            Ok(report) => Ok(report),
+            Err(e) => {
+                self.variables = initial;
+                Err(e)
+            }
+        }
+    }
 
-            let j0 = self.jacobian.forward(extractor);
+    /// Solve a cluster with current mode
+    fn solve_cluster(&mut self, cluster: &mut ClusterSystem) -> Result<ClusterReport, SolveError> {
+        let equations: Vec<Equation> = cluster
+            .equations
+            .iter()
+            .map(|id| self.equations[id].clone())
+            .collect();
+
+        // least squares minimizes residuals of the whole cluster, so only newton uses blocks
+        if self.mode == SolveMode::Newton && !cluster.blocks.is_empty() {
+            let mut blocks = std::mem::take(&mut cluster.blocks);
+            let reports = blocks
+                .iter_mut()
+                .map(|block| self.solve_cluster(block))
+                .collect::<Result<Vec<_>, _>>();
+            cluster.blocks = blocks;
+            let reports = reports?;
+
+            let termination = reports
+                .iter()
+                .map(|r| r.termination)
+                .find(|t| *t != Termination::ResidualTolerance)
+                .unwrap_or(Termination::ResidualTolerance);
+            let iterations = reports.iter().map(|r| r.iterations).sum();
+            return self.report_cluster(cluster, &equations, termination, iterations);
+        }
 
-            // direct solve x1
-            let Solve::Solved(x_delta) = solve(&j0, &b)? else {
-                break;
-            };
+        let (termination, iterations) = match (cluster.jacobian.as_ref(), self.mode) {
+            (None, _) => self.check_satisfied(&equations)?,
+            (Some(jacobian), SolveMode::Newton) => {
+                let mut lu = cluster.lu.take();
+                let result = self.solve_newton(cluster, jacobian, &mut lu);
+                cluster.lu = lu;
+                result?
+            }
+            (Some(jacobian), SolveMode::LeastSquares) => {
+                self.solve_least_squares(&cluster.variables, &cluster.residuals, jacobian)?
+            }
+        };
 
-            let x1 = (x0.clone() + x_delta.clone())?;
+        self.report_cluster(cluster, &equations, termination, iterations)
+    }
 
-            // check epsilon between norm
-            if (x1.norm() - x0.norm()).abs() < self.epsilon {
-                break;
-            }
+    /// Move pinned variables to targets, and solve other variables again from current values.
+    ///
+    /// This is for interactive operations such as dragging a point of sketch. Pinned variables are
+    /// treated as constants, and other variables are solved with least squares method regardless of
+    /// the mode. Each step is the minimum norm one, so unpinned variables stay close to the previous
+    /// solution and do not flip to another solution. For linear equations, the result is the closest
+    /// solution to the previous values. For non-linear equations, this is not guaranteed, because no
+    /// term of the movement is minimized over whole iterations.
+    ///
+    /// The jacobian is not rebuilt, and the jacobian without pinned columns is cached while the same
+    /// variables are pinned. The diagnostics is not updated by this.
+    ///
+    /// # Arguments
+    /// * `targets` - Names and new values of pinned variables
+    ///
+    /// # Returns
+    /// * Report of solving. When a cluster can not satisfy equations with the targets, the cluster
+    ///   keeps the closest values. When solving failed, variables are restored.
+    pub fn drag(&mut self, targets: &[(&str, f64)]) -> Result<SolveReport, SolveError> {
+        if let Some((name, _)) = targets
+            .iter()
+            .find(|(n, _)| self.variables.get(n).is_none())
+        {
+            return Err(SolveError::UnknownVariable(name.to_string()));
+        }
 
-            // update variable for next loop
-            for i in 0..(ordered.len()) {
-                if let Some(v) = self.variables.get_mut(&ordered[i].name) {
-                    v.set_value(x1[i]);
-                }
+        let initial = self.variables.clone();
+        for (name, value) in targets {
+            if let Some(variable) = self.variables.get_mut(name) {
+                variable.set_value(*value);
             }
-            x0 = x1.clone();
         }
 
-        Ok(self.variables.clone())
+        let mut pinned: Vec<String> = targets.iter().map(|(name, _)| name.to_string()).collect();
+        pinned.sort();
+        pinned.dedup();
+
+        let mut clusters = std::mem::take(&mut self.clusters);
+        let reports = clusters
+            .iter_mut()
+            .map(|cluster| self.drag_cluster(cluster, &pinned))
+            .collect::<Result<Vec<_>, _>>();
+        self.clusters = clusters;
+
+        let mut unpinned = self.variable_names();
+        unpinned.retain(|v| !pinned.contains(v));
+        match reports.and_then(|clusters| self.enforce_inequalities(&unpinned, clusters, false)) {
+            Ok(report) => Ok(report),
+            Err(e) => {
+                self.variables = initial;
+                Err(e)
+            }
+        }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    mod status {
-        use crate::environment::Environment;
-        use crate::equation::Equation;
-        use crate::variable::Variable;
-        use crate::{DefaultEquationIdGenerator, DimensionSpecificationStatus, Solver};
-        use epsilon::DefaultEpsilon;
-        use pretty_assertions::assert_eq;
+    /// Solve unpinned variables of a cluster with least squares method
+    fn drag_cluster(
+        &mut self,
+        cluster: &mut ClusterSystem,
+        pinned: &[String],
+    ) -> Result<ClusterReport, SolveError> {
+        let equations: Vec<Equation> = cluster
+            .equations
+            .iter()
+            .map(|id| self.equations[id].clone())
+            .collect();
+
+        let in_cluster: Vec<String> = cluster
+            .variables
+            .iter()
+            .filter(|v| pinned.contains(v))
+            .cloned()
+            .collect();
+        let cached = match cluster.pinned.take() {
+            Some(cached) if cached.pinned == in_cluster => cached,
+            _ => {
+                let columns: Vec<usize> = (0..cluster.variables.len())
+                    .filter(|i| !in_cluster.contains(&cluster.variables[*i]))
+                    .collect();
+                let rows: Vec<usize> = (0..cluster.equations.len()).collect();
+
+                PinnedJacobian {
+                    variables: columns
+                        .iter()
+                        .map(|i| cluster.variables[*i].clone())
+                        .collect(),
+                    jacobian: match cluster.jacobian.as_ref() {
+                        Some(jacobian) if !columns.is_empty() => {
+                            Some(jacobian.select(&rows, &columns)?)
+                        }
+                        _ => None,
+                    },
+                    pinned: in_cluster,
+                }
+            }
+        };
 
-        #[test]
-        fn test_new_solver_has_incorrect_status() {
-            // Arrange
-            let generator = Box::new(DefaultEquationIdGenerator::default());
+        let result = match cached.jacobian.as_ref() {
+            None => self.check_satisfied(&equations),
+            Some(jacobian) => {
+                self.solve_least_squares(&cached.variables, &cluster.residuals, jacobian)
+            }
+        };
+        cluster.pinned = Some(cached);
+        let (termination, iterations) = result?;
 
-            // Act
-            let solver = Solver::new::<DefaultEpsilon>(generator);
+        self.report_cluster(cluster, &equations, termination, iterations)
+    }
 
-            // Assert
-            assert_eq!(solver.status(), DimensionSpecificationStatus::Incorrect);
+    /// Keep bounds of variables and inequalities with active set method, and make the report.
+    ///
+    /// Violated bounds and inequalities enter the active set. Variables on active bounds are fixed at
+    /// the bounds, so bounds are never violated by moved variables. Active inequalities are treated
+    /// as equations, and all equations are solved with least squares method for the other variables.
+    /// When no constraint is violated, an active constraint whose multiplier has the wrong sign, that
+    /// is the constraint pulls variables to reduce residuals, leaves the active set and equations are
+    /// solved again. Residuals that can not vanish under active constraints are reported with
+    /// [`Termination::Constrained`].
+    ///
+    /// # Arguments
+    /// * `names` - Variables that can be moved to keep constraints
+    /// * `clusters` - Reports of solved clusters. Residuals of them are updated when variables move
+    fn enforce_inequalities(
+        &mut self,
+        names: &[String],
+        mut clusters: Vec<ClusterReport>,
+        mut moved: bool,
+    ) -> Result<SolveReport, SolveError> {
+        let equations: Vec<Equation> = self
+            .ordered_equations()
+            .into_iter()
+            .map(|(_, e)| e.clone())
+            .collect();
+
+        let mut active: Vec<ActiveConstraint> = vec![];
+        // a constraint can re-enter after leaving, so limit iterations to avoid cycling
+        let limit = 2 * (names.len() + self.inequalities.len()) + 1;
+        for _ in 0..limit {
+            let entering: Vec<ActiveConstraint> = self
+                .violations()?
+                .iter()
+                .filter_map(|v| ActiveConstraint::of(v, names))
+                .filter(|a| !active.contains(a))
+                .collect();
+
+            if !entering.is_empty() {
+                active.extend(entering);
+            } else if let Some(i) = self.releasable(names, &equations, &active)? {
+                active.remove(i);
+            } else {
+                break;
+            }
+
+            self.solve_active(names, &equations, &active)?;
+            moved = true;
         }
 
-        #[test]
-        fn test_status_remains_incorrect_with_only_variables() {
-            // Arrange
-            let generator = Box::new(DefaultEquationIdGenerator::default());
-            let mut solver = Solver::new::<DefaultEpsilon>(generator);
-            let env = Environment::from_variables(vec![Variable::new("x", 1.0)]);
+        if moved {
+            let tolerance = self.options.residual_tolerance;
+            for cluster in clusters.iter_mut() {
+                let equations: Vec<Equation> = cluster
+                    .equations
+                    .iter()
+                    .map(|id| self.equations[id].clone())
+                    .collect();
+                let residuals = self.residuals(&equations)?;
+
+                cluster.residuals = residuals.iter().copied().collect();
+                if residuals.norm() < tolerance {
+                    cluster.termination = Termination::ResidualTolerance;
+                } else if cluster.converged() {
+                    cluster.termination = Termination::Constrained;
+                }
+            }
+        }
 
-            // Act
-            solver.update_variables(&env);
+        Ok(SolveReport {
+            variables: self.variables.clone(),
+            clusters,
+            violations: self.violations()?,
+            relaxed: self.relaxations()?,
+        })
+    }
 
-            // Assert
-            assert_eq!(solver.status(), DimensionSpecificationStatus::Incorrect);
+    /// Solve soft equations for each priority, and return `true` if variables moved.
+    ///
+    /// Each priority is solved with weighted least squares method, where equations and soft
+    /// equations of higher priorities are weighted by [`HARD_WEIGHT`] and locked at their current
+    /// residuals. Then locked equations are solved again to remove errors of the weighting. For
+    /// linear equations, this is the same as lexicographic least squares.
+    fn solve_soft(&mut self, names: &[String]) -> Result<bool, SolveError> {
+        if self.soft.is_empty() {
+            return Ok(false);
         }
 
-        #[test]
-        fn test_status_remains_incorrect_with_only_equations() {
-            // Arrange
-            let generator = Box::new(DefaultEquationIdGenerator::default());
-            let mut solver = Solver::new::<DefaultEpsilon>(generator);
-            let equation: Equation = 1.0.into();
+        let mut soft: Vec<_> = self.soft.iter().map(|(k, v)| (*k, v.clone())).collect();
+        soft.sort_by_key(|(k, (_, c))| (c.priority, *k));
+        let mut locked: Vec<Equation> = self
+            .ordered_equations()
+            .into_iter()
+            .map(|(_, e)| e.clone())
+            .collect();
+
+        for level in soft.chunk_by(|(_, (_, a)), (_, (_, b))| a.priority == b.priority) {
+            let mut weighted: Vec<Equation> = locked
+                .iter()
+                .map(|e| scaled(e, HARD_WEIGHT.sqrt()))
+                .collect::<Result<_, _>>()?;
+            for (_, (equation, constraint)) in level {
+                weighted.push(scaled(equation, constraint.weight.sqrt())?);
+            }
 
-            // Act
-            solver.add_equation(equation);
+            let mut variables = self.variables.variables();
+            variables.retain(|v| {
+                names.contains(&v.name) && weighted.iter().any(|e| e.is_variable_related(v))
+            });
+            variables.sort_by_key(|v| (*v.name).clone());
+            if variables.is_empty() {
+                continue;
+            }
+            let free: Vec<String> = variables.iter().map(|v| (*v.name).clone()).collect();
 
-            // Assert
-            assert_eq!(solver.status(), DimensionSpecificationStatus::Incorrect);
+            let jacobian = Jacobian::from_equations(&weighted, &variables)?;
+            self.solve_least_squares(&free, &TapeSet::compile(&weighted), &jacobian)?;
+            if !locked.is_empty() {
+                self.solve_active(&free, &locked, &[])?;
+            }
+
+            let env = self.variables.merge(&self.dimensions);
+            for (_, (equation, _)) in level {
+                let residual = equation.evaluate(&env)?;
+                locked.push(
+                    ArithmeticEquation::new(
+                        Operator::Subtract,
+                        &[equation.clone(), residual.into()],
+                    )?
+                    .into(),
+                );
+            }
         }
 
-        #[test]
-        fn test_status_becomes_correct_when_variable_and_equation_counts_match() {
-            // Arrange
-            let generator = Box::new(DefaultEquationIdGenerator::default());
-            let mut solver = Solver::new::<DefaultEpsilon>(generator);
-            let env = Environment::from_variables(vec![Variable::new("x", 1.0)]);
-            let equation: Equation = 1.0.into();
+        Ok(true)
+    }
 
-            // Act
-            solver.add_equation(equation);
-            solver.update_variables(&env);
+    /// Get soft equations that are not satisfied, ordered by id
+    fn relaxations(&self) -> Result<Vec<Relaxation>, SolveError> {
+        let env = self.variables.merge(&self.dimensions);
+        let mut soft: Vec<_> = self.soft.iter().collect();
+        soft.sort_by_key(|(k, _)| **k);
+
+        let mut relaxed = vec![];
+        for (id, (equation, constraint)) in soft {
+            let residual = equation.evaluate(&env)?;
+            if residual.abs() >= self.options.residual_tolerance {
+                relaxed.push(Relaxation {
+                    equation: *id,
+                    priority: constraint.priority,
+                    residual,
+                });
+            }
+        }
 
-            // Assert
-            assert_eq!(solver.status(), DimensionSpecificationStatus::Correct);
+        Ok(relaxed)
+    }
+
+    /// Fix variables on active bounds, and solve equations and active inequalities for other
+    /// variables in `names`
+    fn solve_active(
+        &mut self,
+        names: &[String],
+        equations: &[Equation],
+        active: &[ActiveConstraint],
+    ) -> Result<(), SolveError> {
+        let mut equations = equations.to_vec();
+        let mut fixed = vec![];
+        for constraint in active {
+            match constraint {
+                ActiveConstraint::Bound {
+                    variable, bound, ..
+                } => {
+                    if let Some(v) = self.variables.get_mut(variable) {
+                        v.set_value(*bound);
+                    }
+                    fixed.push(variable.clone());
+                }
+                ActiveConstraint::Inequality(id) => equations.push(self.inequalities[id].clone()),
+            }
         }
 
-        #[test]
-        fn test_status_becomes_correct_with_multiple_variables_and_equations() {
-            // Arrange
-            let generator = Box::new(DefaultEquationIdGenerator::default());
-            let mut solver = Solver::new::<DefaultEpsilon>(generator);
-            let env = Environment::from_variables(vec![
-                Variable::new("x", 1.0),
-                Variable::new("y", 2.0),
-                Variable::new("z", 3.0),
-            ]);
+        let free: Vec<String> = names
+            .iter()
+            .filter(|n| !fixed.contains(n))
+            .cloned()
+            .collect();
+        if free.is_empty() || equations.is_empty() {
+            return Ok(());
+        }
 
-            // Act
-            solver.add_equation(1.0.into());
-            solver.add_equation(2.0.into());
-            solver.add_equation(3.0.into());
-            solver.update_variables(&env);
+        let mut variables = self.variables.variables();
+        variables.retain(|v| free.contains(&v.name));
+        variables.sort_by_key(|v| (*v.name).clone());
 
-            // Assert
-            assert_eq!(solver.status(), DimensionSpecificationStatus::Correct);
+        let jacobian = Jacobian::from_equations(&equations, &variables)?;
+        self.solve_least_squares(&free, &TapeSet::compile(&equations), &jacobian)?;
+        Ok(())
+    }
+
+    /// Get the index of the active constraint that should leave the active set.
+    ///
+    /// Multipliers are estimated from the gradient of the half squared norm of `equations`. A bound
+    /// or an inequality `g >= 0` needs `gradient = multiplier * grad(g)` with non-negative
+    /// multiplier, and the constraint with the most negative one is returned.
+    fn releasable(
+        &self,
+        names: &[String],
+        equations: &[Equation],
+        active: &[ActiveConstraint],
+    ) -> Result<Option<usize>, SolveError> {
+        if active.is_empty() {
+            return Ok(None);
         }
 
-        #[test]
-        fn test_status_becomes_incorrect_when_adding_more_variables() {
-            // Arrange
-            let generator = Box::new(DefaultEquationIdGenerator::default());
-            let mut solver = Solver::new::<DefaultEpsilon>(generator);
-            let env1 = Environment::from_variables(vec![Variable::new("x", 1.0)]);
-            solver.add_equation(1.0.into());
-            solver.update_variables(&env1);
+        let mut variables = self.variables.variables();
+        variables.retain(|v| names.contains(&v.name));
+        variables.sort_by_key(|v| (*v.name).clone());
 
-            // Act
-            let env2 =
-                Environment::from_variables(vec![Variable::new("x", 1.0), Variable::new("y", 2.0)]);
-            solver.update_variables(&env2);
+        // gradient of 1/2 |f|^2 is J^T f
+        let residuals: Vec<f64> = match equations {
+            [] => vec![],
+            _ => self.residuals(equations)?.iter().copied().collect(),
+        };
+        let gradient = self.gradient(equations, &variables, &residuals)?;
+        let fixed: Vec<&String> = active
+            .iter()
+            .filter_map(|a| match a {
+                ActiveConstraint::Bound { variable, .. } => Some(variable),
+                ActiveConstraint::Inequality(_) => None,
+            })
+            .collect();
+
+        let mut release: Option<(usize, f64)> = None;
+        for (i, constraint) in active.iter().enumerate() {
+            let multiplier = match constraint {
+                ActiveConstraint::Bound {
+                    variable, lower, ..
+                } => {
+                    let Some(j) = names.iter().position(|n| n == variable) else {
+                        continue;
+                    };
+                    if *lower { gradient[j] } else { -gradient[j] }
+                }
+                ActiveConstraint::Inequality(id) => {
+                    // only free variables can move along the inequality
+                    let normal: Vec<f64> = self
+                        .gradient(&[self.inequalities[id].clone()], &variables, &[1.0])?
+                        .into_iter()
+                        .zip(names)
+                        .map(|(g, n)| if fixed.contains(&n) { 0.0 } else { g })
+                        .collect();
+                    let length: f64 = normal.iter().map(|g| g * g).sum();
+                    if length == 0.0 {
+                        continue;
+                    }
+                    normal
+                        .iter()
+                        .zip(&gradient)
+                        .map(|(n, g)| n * g)
+                        .sum::<f64>()
+                        / length
+                }
+            };
 
-            // Assert
-            assert_eq!(solver.status(), DimensionSpecificationStatus::Incorrect);
+            if multiplier < -self.options.residual_tolerance
+                && release.is_none_or(|(_, m)| multiplier < m)
+            {
+                release = Some((i, multiplier));
+            }
+        }
+
+        Ok(release.map(|(i, _)| i))
+    }
+
+    /// Get `J^T weights` of the jacobian of `equations` for `variables` ordered by name
+    fn gradient(
+        &self,
+        equations: &[Equation],
+        variables: &[Variable],
+        weights: &[f64],
+    ) -> Result<Vec<f64>, SolveError> {
+        if equations.is_empty() || variables.is_empty() {
+            return Ok(vec![0.0; variables.len()]);
+        }
+
+        let jacobian = Jacobian::from_equations(equations, variables)?.evaluate(
+            &[&self.variables, &self.dimensions],
+            &mut Scratch::default(),
+        )?;
+
+        (0..variables.len())
+            .map(|j| {
+                (0..equations.len()).try_fold(0.0, |sum, i| {
+                    let derivative = jacobian.get(i, j)?.copied().unwrap_or(0.0);
+                    Ok::<_, SolveError>(sum + derivative * weights[i])
+                })
+            })
+            .collect()
+    }
+
+    /// Get bounds and inequalities that current variables violate, ordered by variable name and
+    /// equation id
+    fn violations(&self) -> Result<Vec<Violation>, SolveError> {
+        let tolerance = self.options.residual_tolerance;
+        let mut variables = self.variables.variables();
+        variables.sort_by_key(|v| (*v.name).clone());
+
+        let mut violations = vec![];
+        for v in variables {
+            let (variable, value) = ((*v.name).clone(), *v.value);
+            match (v.lower(), v.upper()) {
+                (Some(bound), _) if value < bound - tolerance => {
+                    violations.push(Violation::LowerBound {
+                        variable,
+                        bound,
+                        value,
+                    })
+                }
+                (_, Some(bound)) if value > bound + tolerance => {
+                    violations.push(Violation::UpperBound {
+                        variable,
+                        bound,
+                        value,
+                    })
+                }
+                _ => (),
+            }
+        }
+
+        let env = self.variables.merge(&self.dimensions);
+        let mut inequalities: Vec<_> = self.inequalities.iter().collect();
+        inequalities.sort_by_key(|(k, _)| **k);
+        for (id, inequality) in inequalities {
+            let value = inequality.evaluate(&env)?;
+            if value < -tolerance {
+                violations.push(Violation::Inequality {
+                    equation: *id,
+                    value,
+                });
+            }
+        }
+
+        Ok(violations)
+    }
+
+    /// Check the equations are satisfied already, for clusters that have nothing to solve
+    fn check_satisfied(&self, equations: &[Equation]) -> Result<(Termination, usize), SolveError> {
+        if self.residuals(equations)?.norm() < self.options.residual_tolerance {
+            Ok((Termination::ResidualTolerance, 0))
+        } else {
+            Ok((Termination::Stalled, 0))
+        }
+    }
+
+    /// Make the report of the solved cluster with final residuals
+    fn report_cluster(
+        &self,
+        cluster: &ClusterSystem,
+        equations: &[Equation],
+        termination: Termination,
+        iterations: usize,
+    ) -> Result<ClusterReport, SolveError> {
+        let residuals = self.residuals(equations)?;
+        if !residuals.norm().is_finite() {
+            return Err(SolveError::Diverged {
+                equations: cluster.equations.clone(),
+            });
+        }
+
+        Ok(ClusterReport {
+            equations: cluster.equations.clone(),
+            variables: cluster.variables.clone(),
+            termination,
+            iterations,
+            residuals: residuals.iter().copied().collect(),
+        })
+    }
+
+    /// Get current values of variables with the order of `names`
+    fn values(&self, names: &[String]) -> Result<Vector> {
+        let values = names
+            .iter()
+            .map(|name| self.variables.get(name).map(|v| v.into()).unwrap_or(0.0))
+            .collect::<Vec<_>>();
+
+        Vector::from(&values)
+    }
+
+    /// Evaluate all equations with current variables and dimensions
+    fn residuals(&self, equations: &[Equation]) -> Result<Vector, SolveError> {
+        let extractor = self.variables.merge(&self.dimensions);
+        let f = equations
+            .iter()
+            .map(|e| e.evaluate(&extractor))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Vector::from(&f)?)
+    }
+
+    /// Evaluate compiled equations with current variables and dimensions
+    fn evaluate_tapes(&self, tapes: &TapeSet, scratch: &mut Scratch) -> Result<Vector, SolveError> {
+        let mut f = vec![0.0; tapes.len()];
+        tapes.evaluate(&[&self.variables, &self.dimensions], scratch, &mut f)?;
+
+        Ok(Vector::from(&f)?)
+    }
+
+    /// Get the trace with current equations, variables and settings
+    fn snapshot(&self) -> Trace {
+        let ordered = |equations: &HashMap<EquationId, Equation>| {
+            let mut equations: Vec<_> = equations.iter().map(|(k, v)| (*k, v.clone())).collect();
+            equations.sort_by_key(|(k, _)| *k);
+            equations
+        };
+
+        Trace {
+            mode: self.mode,
+            options: self.options,
+            epsilon: self.epsilon,
+            equations: ordered(&self.equations),
+            inequalities: ordered(&self.inequalities),
+            soft: {
+                let mut soft: Vec<_> = self
+                    .soft
+                    .iter()
+                    .map(|(k, (e, c))| (*k, e.clone(), *c))
+                    .collect();
+                soft.sort_by_key(|(k, _, _)| *k);
+                soft
+            },
+            variables: self.variables.clone(),
+            dimensions: self.dimensions.clone(),
+            steps: vec![],
+        }
+    }
+
+    /// Record the step of an iteration while recording a trace
+    fn record_step(
+        &mut self,
+        iteration: usize,
+        names: &[String],
+        residuals: &Vector,
+        step: &Vector,
+    ) {
+        if let Some(trace) = self.recording.as_mut() {
+            trace.steps.push(TraceStep {
+                iteration,
+                variables: names.to_vec(),
+                residuals: residuals.iter().copied().collect(),
+                step: step.iter().copied().collect(),
+            });
+        }
+    }
+
+    /// Write values into variables with the order of `names`
+    fn apply_values(&mut self, names: &[String], values: &Vector) {
+        for (i, name) in names.iter().enumerate() {
+            if let Some(v) = self.variables.get_mut(name) {
+                v.set_value(values[i]);
+            }
+        }
+    }
+
+    /// Solve with Levenberg-Marquardt method, and return the termination and iterations.
+    ///
+    /// Each step solves `(J^T J + lambda I) dx = -J^T f`, or the minimum norm form for under-determined
+    /// systems. Damping decreases when the step reduces the cost, and increases when not.
+    /// Iterations stop with [`Termination::LocalMinimum`] when an accepted step is small but
+    /// residuals are not, and with [`Termination::Stalled`] when no step can reduce the cost.
+    fn solve_least_squares(
+        &mut self,
+        names: &[String],
+        residuals: &TapeSet,
+        jacobian: &Jacobian,
+    ) -> Result<(Termination, usize), SolveError> {
+        let mut scratch = Scratch::default();
+        let mut x0 = self.values(names)?;
+        let mut f0 = self.evaluate_tapes(residuals, &mut scratch)?;
+        let mut damping = INITIAL_DAMPING;
+
+        if f0.norm() < self.options.residual_tolerance {
+            return Ok((Termination::ResidualTolerance, 0));
+        }
+
+        for iteration in 1..=self.options.max_iterations {
+            if damping > MAX_DAMPING {
+                return Ok((Termination::Stalled, iteration - 1));
+            }
+
+            let j0 = jacobian.evaluate(&[&self.variables, &self.dimensions], &mut scratch)?;
+            let Solve::Solved(x_delta) = damped_least_squares(&j0, &(f0.clone() * -1.0), damping)?
+            else {
+                damping *= 10.0;
+                continue;
+            };
+            self.record_step(iteration, names, &f0, &x_delta);
+
+            let x1 = (x0.clone() + x_delta.clone())?;
+            self.apply_values(names, &x1);
+            let f1 = self.evaluate_tapes(residuals, &mut scratch)?;
+
+            if f1.norm() < f0.norm() {
+                // accept the step, and get closer to gauss-newton
+                x0 = x1;
+                f0 = f1;
+                damping /= 10.0;
+
+                if f0.norm() < self.options.residual_tolerance {
+                    return Ok((Termination::ResidualTolerance, iteration));
+                }
+                // small steps of rejected ones only mean large damping, so check only accepted one
+                if x_delta.norm() < self.options.step_tolerance {
+                    return Ok((Termination::LocalMinimum, iteration));
+                }
+            } else {
+                // reject the step, and get closer to gradient descent
+                self.apply_values(names, &x0);
+                damping *= 10.0;
+            }
+        }
+
+        Ok((Termination::MaxIterations, self.options.max_iterations))
+    }
+
+    /// Solve with Newton-Raphson method, and return the termination and iterations.
+    fn solve_newton(
+        &mut self,
+        cluster: &ClusterSystem,
+        jacobian: &Jacobian,
+        lu: &mut Option<SparseLu>,
+    ) -> Result<(Termination, usize), SolveError> {
+        let names = &cluster.variables;
+        let mut scratch = Scratch::default();
+
+        // make direct solve
+        // x_1 = x_0 - J_0^-1 * f_0 -> J_0 * x_delta = - f_0
+
+        // initial value
+        let mut x0 = self.values(names)?;
+        let mut f0 = self.evaluate_tapes(&cluster.residuals, &mut scratch)?;
+        let limit = f0.norm().max(1.0) * DIVERGENCE_FACTOR;
+
+        if f0.norm() < self.options.residual_tolerance {
+            return Ok((Termination::ResidualTolerance, 0));
+        }
+
+        // Do newton-rhapson method
+        for iteration in 1..=self.options.max_iterations {
+            let j0 = jacobian.evaluate(&[&self.variables, &self.dimensions], &mut scratch)?;
+
+            // direct solve x1. rhs is simple vector that is column-transposed
+            let rhs = f0.clone() * -1.0;
+            let step = match self.options.linear_solver {
+                LinearSolver::SparseLu => solve_sparse(lu, &j0, &rhs)?,
+                LinearSolver::Dense => solve(&j0, &rhs)?,
+            };
+            let Solve::Solved(x_delta) = step else {
+                return Err(SolveError::Singular {
+                    equations: cluster.equations.clone(),
+                });
+            };
+            self.record_step(iteration, names, &f0, &x_delta);
+
+            let x1 = (x0 + x_delta.clone())?;
+
+            // update variable for next loop
+            self.apply_values(names, &x1);
+            f0 = self.evaluate_tapes(&cluster.residuals, &mut scratch)?;
+
+            if !f0.norm().is_finite() || f0.norm() > limit {
+                return Err(SolveError::Diverged {
+                    equations: cluster.equations.clone(),
+                });
+            }
+            if f0.norm() < self.options.residual_tolerance {
+                return Ok((Termination::ResidualTolerance, iteration));
+            }
+            if x_delta.norm() < self.options.step_tolerance {
+                return Ok((Termination::StepTolerance, iteration));
+            }
+            x0 = x1;
         }
 
+        Ok((Termination::MaxIterations, self.options.max_iterations))
+    }
+}
+
+/// Multiply the equation by `factor`
+fn scaled(equation: &Equation, factor: f64) -> Result<Equation> {
+    Ok(ArithmeticEquation::new(Operator::Multiply, &[factor.into(), equation.clone()])?.into())
+}
+
+#[cfg(test)]
+mod tests {
+    mod jacobian {
+        use crate::Jacobian;
+        use crate::environment::Environment;
+        use crate::equation::parse;
+        use crate::equation::tape::Scratch;
+        use crate::matrix::Matrix;
+        use crate::variable::Variable;
+        use approx::assert_relative_eq;
+        use pretty_assertions::assert_eq;
+
         #[test]
-        fn test_status_becomes_incorrect_after_removing_equation() {
+        fn test_evaluate_returns_analytic_partial_derivatives() -> color_eyre::eyre::Result<()> {
             // Arrange
-            let generator = Box::new(DefaultEquationIdGenerator::default());
-            let mut solver = Solver::new::<DefaultEpsilon>(generator);
-            let env = Environment::from_variables(vec![Variable::new("x", 1.0)]);
-            let eq_id = solver.add_equation(1.0.into());
-            solver.update_variables(&env);
+            let equations = vec![parse("x^2 * y").unwrap(), parse("x + 3").unwrap()];
+            let variables = vec![Variable::new("y", 3.0), Variable::new("x", 2.0)];
+            let env = Environment::from_variables(variables.clone());
+            let jacobian = Jacobian::from_equations(&equations, &variables)?;
 
             // Act
-            solver.remove_equation(eq_id);
+            let matrix = jacobian.evaluate(&[&env], &mut Scratch::default()).unwrap();
 
             // Assert
-            assert_eq!(solver.status(), DimensionSpecificationStatus::Incorrect);
+            // columns are ordered by variable name: x, y
+            assert_relative_eq!(*matrix.get(0, 0)?.unwrap(), 12.0);
+            assert_relative_eq!(*matrix.get(0, 1)?.unwrap(), 4.0);
+            assert_relative_eq!(*matrix.get(1, 0)?.unwrap(), 1.0);
+            assert_eq!(matrix.get(1, 1)?, None);
+            Ok(())
         }
     }
 
-    mod solve {
+    mod status {
         use crate::environment::Environment;
-
         use crate::equation::{Equation, parse};
         use crate::variable::Variable;
         use crate::{DefaultEquationIdGenerator, DimensionSpecificationStatus, Solver};
-        use approx::assert_relative_eq;
         use epsilon::DefaultEpsilon;
         use pretty_assertions::assert_eq;
 
         #[test]
-        fn test_solve_line_diminsion() -> color_eyre::eyre::Result<()> {
+        fn test_new_solver_status_is_empty() {
             // Arrange
             let generator = Box::new(DefaultEquationIdGenerator::default());
-            let mut solver = Solver::new::<DefaultEpsilon>(generator);
-            let env =
-                Environment::from_tuples(&[("x1", 0.0), ("y1", 0.0), ("x2", 1.0), ("y2", 1.0)]);
-            let dimension = Environment::from_tuples(&[("d", 4.5)]);
-            solver.update_variables(&env);
-            solver.update_dimensions(&dimension);
-            // x1 - 3 = 0
-            solver.add_equation(parse("x1 - 3.0").unwrap());
-            // y1 - 0 = 0
-            solver.add_equation(parse("y1").unwrap());
-            // (x2 - x1)^2 + (y2 - y1)^2 - d^2 = 0
-            // = x2^2 - 2 * x2 * x1 + x1^2 + y2^2 - 2 * y2 * y1 + y1^2 - d^2 = 0
-            solver.add_equation(
-                parse("x2^2 - 2.0 * x2 * x1 + x1^2 + y2^2 - 2.0 * y2 * y1 + y1^2 - d^2").unwrap(),
-            );
-            // y2 - y1 = 0
-            solver.add_equation(parse("y2 - y1").unwrap());
 
             // Act
-            let ret = solver.solve()?;
+            let solver = Solver::new::<DefaultEpsilon>(generator);
 
             // Assert
-            assert_eq!(solver.status(), DimensionSpecificationStatus::Correct);
-            assert_relative_eq!(*ret.get("x1").unwrap().value, 3.0, epsilon = 1e-5);
-            assert_relative_eq!(*ret.get("y1").unwrap().value, 0.0, epsilon = 1e-5);
-            assert_relative_eq!(*ret.get("x2").unwrap().value, 7.5, epsilon = 1e-5);
-            assert_relative_eq!(*ret.get("y2").unwrap().value, 0.0, epsilon = 1e-5);
-            Ok(())
+            assert_eq!(solver.status(), DimensionSpecificationStatus::Empty);
         }
 
         #[test]
-        fn test_status_remains_incorrect_with_only_variables() {
+        fn test_status_is_under_constrained_with_only_variables() {
             // Arrange
             let generator = Box::new(DefaultEquationIdGenerator::default());
             let mut solver = Solver::new::<DefaultEpsilon>(generator);
@@ -517,11 +1647,14 @@ mod tests {
             solver.update_variables(&env);
 
             // Assert
-            assert_eq!(solver.status(), DimensionSpecificationStatus::Incorrect);
+            assert_eq!(
+                solver.status(),
+                DimensionSpecificationStatus::UnderConstrained
+            );
         }
 
         #[test]
-        fn test_status_remains_incorrect_with_only_equations() {
+        fn test_status_is_conflicting_with_only_non_zero_constant_equation() {
             // Arrange
             let generator = Box::new(DefaultEquationIdGenerator::default());
             let mut solver = Solver::new::<DefaultEpsilon>(generator);
@@ -531,27 +1664,30 @@ mod tests {
             solver.add_equation(equation);
 
             // Assert
-            assert_eq!(solver.status(), DimensionSpecificationStatus::Incorrect);
+            assert_eq!(solver.status(), DimensionSpecificationStatus::Conflicting);
         }
 
         #[test]
-        fn test_status_becomes_correct_when_variable_and_equation_counts_match() {
+        fn test_status_becomes_well_constrained_when_equation_determines_variable() {
             // Arrange
             let generator = Box::new(DefaultEquationIdGenerator::default());
             let mut solver = Solver::new::<DefaultEpsilon>(generator);
             let env = Environment::from_variables(vec![Variable::new("x", 1.0)]);
-            let equation: Equation = 1.0.into();
+            let equation: Equation = parse("x - 1").unwrap();
 
             // Act
             solver.add_equation(equation);
             solver.update_variables(&env);
 
             // Assert
-            assert_eq!(solver.status(), DimensionSpecificationStatus::Correct);
+            assert_eq!(
+                solver.status(),
+                DimensionSpecificationStatus::WellConstrained
+            );
         }
 
         #[test]
-        fn test_status_becomes_correct_with_multiple_variables_and_equations() {
+        fn test_status_becomes_well_constrained_with_multiple_variables_and_equations() {
             // Arrange
             let generator = Box::new(DefaultEquationIdGenerator::default());
             let mut solver = Solver::new::<DefaultEpsilon>(generator);
@@ -562,22 +1698,61 @@ mod tests {
             ]);
 
             // Act
-            solver.add_equation(1.0.into());
-            solver.add_equation(2.0.into());
-            solver.add_equation(3.0.into());
+            solver.add_equation(parse("x + y").unwrap());
+            solver.add_equation(parse("y - 2").unwrap());
+            solver.add_equation(parse("z * x - 3").unwrap());
             solver.update_variables(&env);
 
             // Assert
-            assert_eq!(solver.status(), DimensionSpecificationStatus::Correct);
+            assert_eq!(
+                solver.status(),
+                DimensionSpecificationStatus::WellConstrained
+            );
+        }
+
+        #[test]
+        fn test_add_equations_is_same_as_adding_each_equation() {
+            // Arrange
+            let env = Environment::from_variables(vec![
+                Variable::new("x", 1.0),
+                Variable::new("y", 2.0),
+                Variable::new("z", 3.0),
+            ]);
+            let equations = ["x + y", "y - 2", "z * x - 3"].map(|e| parse(e).unwrap());
+            let mut each =
+                Solver::new::<DefaultEpsilon>(Box::new(DefaultEquationIdGenerator::default()));
+            each.update_variables(&env);
+            let each_ids = equations
+                .iter()
+                .map(|e| each.add_equation(e.clone()))
+                .collect::<Vec<_>>();
+            let mut bulk =
+                Solver::new::<DefaultEpsilon>(Box::new(DefaultEquationIdGenerator::default()));
+            bulk.update_variables(&env);
+
+            // Act
+            let ids = bulk.add_equations(equations);
+
+            // Assert
+            assert_eq!(ids, each_ids);
+            assert_eq!(bulk.status(), DimensionSpecificationStatus::WellConstrained);
+            assert_eq!(bulk.diagnostics(), each.diagnostics());
+            let (bulk, each) = (bulk.solve().unwrap(), each.solve().unwrap());
+            for name in ["x", "y", "z"] {
+                assert_eq!(
+                    *bulk.variables.get(name).unwrap().value,
+                    *each.variables.get(name).unwrap().value
+                );
+            }
         }
 
         #[test]
-        fn test_status_becomes_incorrect_when_adding_more_variables() {
+        fn test_status_becomes_under_constrained_when_adding_more_variables() {
             // Arrange
             let generator = Box::new(DefaultEquationIdGenerator::default());
             let mut solver = Solver::new::<DefaultEpsilon>(generator);
             let env1 = Environment::from_variables(vec![Variable::new("x", 1.0)]);
-            solver.add_equation(1.0.into());
+            solver.add_equation(parse("x - 1").unwrap());
             solver.update_variables(&env1);
 
             // Act
@@ -586,23 +1761,1375 @@ mod tests {
             solver.update_variables(&env2);
 
             // Assert
-            assert_eq!(solver.status(), DimensionSpecificationStatus::Incorrect);
+            assert_eq!(
+                solver.status(),
+                DimensionSpecificationStatus::UnderConstrained
+            );
         }
 
         #[test]
-        fn test_status_becomes_incorrect_after_removing_equation() {
+        fn test_status_becomes_under_constrained_after_removing_equation() {
             // Arrange
             let generator = Box::new(DefaultEquationIdGenerator::default());
             let mut solver = Solver::new::<DefaultEpsilon>(generator);
             let env = Environment::from_variables(vec![Variable::new("x", 1.0)]);
-            let eq_id = solver.add_equation(1.0.into());
+            let eq_id = solver.add_equation(parse("x - 1").unwrap());
             solver.update_variables(&env);
 
             // Act
             solver.remove_equation(eq_id);
 
             // Assert
-            assert_eq!(solver.status(), DimensionSpecificationStatus::Incorrect);
+            assert_eq!(
+                solver.status(),
+                DimensionSpecificationStatus::UnderConstrained
+            );
+        }
+    }
+
+    mod diagnostics {
+        use crate::environment::Environment;
+        use crate::equation::parse;
+        use crate::{DefaultEquationIdGenerator, DimensionSpecificationStatus, Solver};
+        use epsilon::DefaultEpsilon;
+        use pretty_assertions::assert_eq;
+
+        fn make_solver(variables: &[(&str, f64)], equations: &[&str]) -> Solver {
+            let generator = Box::new(DefaultEquationIdGenerator::default());
+            let mut solver = Solver::new::<DefaultEpsilon>(generator);
+            solver.update_variables(&Environment::from_tuples(variables));
+            for equation in equations {
+                solver.add_equation(parse(equation).unwrap());
+            }
+            solver
+        }
+
+        #[test]
+        fn test_reports_free_variables_and_dof() {
+            // Arrange
+            let solver = make_solver(&[("x", 0.0), ("y", 0.0), ("z", 0.0)], &["x - 1", "y + z"]);
+
+            // Act
+            let diagnostics = solver.diagnostics();
+
+            // Assert
+            assert_eq!(diagnostics.dof, 1);
+            assert_eq!(diagnostics.rank, 2);
+            assert_eq!(
+                diagnostics.free_variables,
+                vec!["y".to_string(), "z".to_string()]
+            );
+            assert_eq!(
+                solver.status(),
+                DimensionSpecificationStatus::UnderConstrained
+            );
+        }
+
+        #[test]
+        fn test_reports_redundant_equation_when_consistent() {
+            // Arrange
+            let solver = make_solver(&[("x", 0.0), ("y", 0.0)], &["x - 1", "y - 2", "x + y - 3"]);
+
+            // Act
+            let diagnostics = solver.diagnostics();
+
+            // Assert
+            assert_eq!(diagnostics.dof, 0);
+            assert_eq!(diagnostics.redundant_equations, vec![3.into()]);
+            assert_eq!(diagnostics.conflicting_equations, vec![]);
+            assert_eq!(solver.status(), DimensionSpecificationStatus::Redundant);
+        }
+
+        #[test]
+        fn test_reports_conflicting_equation_when_inconsistent() {
+            // Arrange
+            let solver = make_solver(&[("x", 0.0), ("y", 0.0)], &["x - 1", "y - 2", "x + y - 5"]);
+
+            // Act
+            let diagnostics = solver.diagnostics();
+
+            // Assert
+            assert_eq!(diagnostics.redundant_equations, vec![]);
+            assert_eq!(diagnostics.conflicting_equations, vec![3.into()]);
+            assert_eq!(solver.status(), DimensionSpecificationStatus::Conflicting);
+        }
+
+        #[test]
+        fn test_reports_invalid_equation_with_unknown_variable() {
+            // Arrange
+            let solver = make_solver(&[("x", 0.0)], &["x - 1", "x - z"]);
+
+            // Act
+            let diagnostics = solver.diagnostics();
+
+            // Assert
+            assert_eq!(diagnostics.invalid_equations, vec![2.into()]);
+            assert_eq!(diagnostics.redundant_equations, vec![]);
+            assert_eq!(solver.status(), DimensionSpecificationStatus::Invalid);
+        }
+
+        #[test]
+        fn test_square_but_dependent_system_is_not_well_constrained() {
+            // Arrange
+            let solver = make_solver(&[("x", 0.0), ("y", 0.0)], &["x + y - 1", "2x + 2y - 2"]);
+
+            // Act
+            let diagnostics = solver.diagnostics();
+
+            // Assert
+            assert_eq!(diagnostics.structural_rank, 2);
+            assert_eq!(diagnostics.rank, 1);
+            assert_eq!(diagnostics.dof, 1);
+            assert_eq!(diagnostics.redundant_equations, vec![2.into()]);
+            assert_eq!(
+                diagnostics.free_variables,
+                vec!["x".to_string(), "y".to_string()]
+            );
+        }
+
+        #[test]
+        fn test_structural_rank_ignores_dimensions() {
+            // Arrange
+            let mut solver = make_solver(&[("x", 0.0)], &["x - d", "d - 2"]);
+
+            // Act
+            solver.update_dimensions(&Environment::from_tuples(&[("d", 2.0)]));
+
+            // Assert
+            let diagnostics = solver.diagnostics();
+            assert_eq!(diagnostics.structural_rank, 1);
+            assert_eq!(diagnostics.redundant_equations, vec![2.into()]);
+        }
+    }
+
+    mod solve {
+        use crate::environment::Environment;
+
+        use crate::equation::EquationError;
+        use crate::equation::{Equation, parse};
+        use crate::variable::Variable;
+        use crate::{
+            DefaultEquationIdGenerator, DimensionSpecificationStatus, LinearSolver, SolveError,
+            SolveMode, SolveOptions, Solver,
+        };
+        use approx::assert_relative_eq;
+        use epsilon::DefaultEpsilon;
+        use pretty_assertions::assert_eq;
+        use rstest::rstest;
+
+        #[rstest]
+        #[case::newton(SolveMode::Newton)]
+        #[case::least_squares(SolveMode::LeastSquares)]
+        fn test_solve_fails_with_equation_of_unknown_variable(#[case] mode: SolveMode) {
+            // Arrange
+            let generator = Box::new(DefaultEquationIdGenerator::default());
+            let mut solver = Solver::new::<DefaultEpsilon>(generator);
+            solver.set_mode(mode);
+            solver.update_variables(&Environment::from_tuples(&[("x", 0.0)]));
+            solver.add_equation(parse("x - 1").unwrap());
+            solver.add_equation(parse("z - 1").unwrap());
+
+            // Act
+            let ret = solver.solve();
+
+            // Assert
+            assert_eq!(
+                ret.err(),
+                Some(SolveError::Evaluation(
+                    EquationError::NoVariableInEnvironment(vec!["z".to_string()])
+                ))
+            );
+            assert_relative_eq!(*solver.variables().get("x").unwrap().value, 0.0);
+        }
+
+        #[test]
+        fn test_solve_same_result_with_each_linear_solver() -> color_eyre::eyre::Result<()> {
+            // Arrange
+            // chain of points, each point is distance 1 from the previous one with fixed dy
+            let n = 20;
+            let mut tuples = vec![];
+            for i in 0..=n {
+                tuples.push((format!("x{}", i), i as f64));
+                tuples.push((format!("y{}", i), 0.0));
+            }
+            let tuples: Vec<(&str, f64)> = tuples.iter().map(|(k, v)| (k.as_str(), *v)).collect();
+            let mut results = vec![];
+
+            // Act
+            for linear_solver in [LinearSolver::SparseLu, LinearSolver::Dense] {
+                let generator = Box::new(DefaultEquationIdGenerator::default());
+                let mut solver = Solver::new::<DefaultEpsilon>(generator);
+                solver.set_options(SolveOptions {
+                    linear_solver,
+                    ..solver.options()
+                });
+                solver.update_variables(&Environment::from_tuples(&tuples));
+                solver.add_equation(parse("x0").unwrap());
+                solver.add_equation(parse("y0").unwrap());
+                for i in 1..=n {
+                    let (dx, dy) = (
+                        format!("(x{} - x{})", i, i - 1),
+                        format!("(y{} - y{})", i, i - 1),
+                    );
+                    solver.add_equation(parse(&format!("{}^2 + {}^2 - 1", dx, dy)).unwrap());
+                    solver.add_equation(parse(&format!("{} - 0.6", dy)).unwrap());
+                }
+                results.push(solver.solve()?);
+            }
+
+            // Assert
+            for report in results {
+                assert!(report.converged());
+                for i in 0..=n {
+                    let x = *report.variables.get(&format!("x{}", i)).unwrap().value;
+                    let y = *report.variables.get(&format!("y{}", i)).unwrap().value;
+                    assert_relative_eq!(x, 0.8 * i as f64, epsilon = 1e-3);
+                    assert_relative_eq!(y, 0.6 * i as f64, epsilon = 1e-3);
+                }
+            }
+            Ok(())
+        }
+
+        #[test]
+        fn test_solve_line_diminsion() -> color_eyre::eyre::Result<()> {
+            // Arrange
+            let generator = Box::new(DefaultEquationIdGenerator::default());
+            let mut solver = Solver::new::<DefaultEpsilon>(generator);
+            // x1 and y1 are solved before x2, so x2 goes to the root near the initial value
+            let env =
+                Environment::from_tuples(&[("x1", 0.0), ("y1", 0.0), ("x2", 5.0), ("y2", 1.0)]);
+            let dimension = Environment::from_tuples(&[("d", 4.5)]);
+            solver.update_variables(&env);
+            solver.update_dimensions(&dimension);
+            // x1 - 3 = 0
+            solver.add_equation(parse("x1 - 3.0").unwrap());
+            // y1 - 0 = 0
+            solver.add_equation(parse("y1").unwrap());
+            // (x2 - x1)^2 + (y2 - y1)^2 - d^2 = 0
+            // = x2^2 - 2 * x2 * x1 + x1^2 + y2^2 - 2 * y2 * y1 + y1^2 - d^2 = 0
+            solver.add_equation(
+                parse("x2^2 - 2.0 * x2 * x1 + x1^2 + y2^2 - 2.0 * y2 * y1 + y1^2 - d^2").unwrap(),
+            );
+            // y2 - y1 = 0
+            solver.add_equation(parse("y2 - y1").unwrap());
+
+            // Act
+            let ret = solver.solve()?.variables;
+
+            // Assert
+            assert_eq!(
+                solver.status(),
+                DimensionSpecificationStatus::WellConstrained
+            );
+            assert_relative_eq!(*ret.get("x1").unwrap().value, 3.0, epsilon = 1e-5);
+            assert_relative_eq!(*ret.get("y1").unwrap().value, 0.0, epsilon = 1e-5);
+            assert_relative_eq!(*ret.get("x2").unwrap().value, 7.5, epsilon = 1e-5);
+            assert_relative_eq!(*ret.get("y2").unwrap().value, 0.0, epsilon = 1e-5);
+            Ok(())
+        }
+
+        #[test]
+        fn test_solve_distance_and_angle_with_functions() -> color_eyre::eyre::Result<()> {
+            // Arrange
+            let generator = Box::new(DefaultEquationIdGenerator::default());
+            let mut solver = Solver::new::<DefaultEpsilon>(generator);
+            solver.update_variables(&Environment::from_tuples(&[("x", 1.0), ("y", 0.5)]));
+            solver.update_dimensions(&Environment::from_tuples(&[("d", 2.0)]));
+            solver.add_equation(parse("sqrt(x^2 + y^2) - d").unwrap());
+            solver.add_equation(parse("atan2(y, x) - pi / 3").unwrap());
+
+            // Act
+            let ret = solver.solve()?.variables;
+
+            // Assert
+            assert_relative_eq!(*ret.get("x").unwrap().value, 1.0, epsilon = 1e-4);
+            assert_relative_eq!(*ret.get("y").unwrap().value, 3.0_f64.sqrt(), epsilon = 1e-4);
+            Ok(())
+        }
+
+        #[test]
+        fn test_status_is_under_constrained_with_only_variables() {
+            // Arrange
+            let generator = Box::new(DefaultEquationIdGenerator::default());
+            let mut solver = Solver::new::<DefaultEpsilon>(generator);
+            let env = Environment::from_variables(vec![Variable::new("x", 1.0)]);
+
+            // Act
+            solver.update_variables(&env);
+
+            // Assert
+            assert_eq!(
+                solver.status(),
+                DimensionSpecificationStatus::UnderConstrained
+            );
+        }
+
+        #[test]
+        fn test_status_is_conflicting_with_only_non_zero_constant_equation() {
+            // Arrange
+            let generator = Box::new(DefaultEquationIdGenerator::default());
+            let mut solver = Solver::new::<DefaultEpsilon>(generator);
+            let equation: Equation = 1.0.into();
+
+            // Act
+            solver.add_equation(equation);
+
+            // Assert
+            assert_eq!(solver.status(), DimensionSpecificationStatus::Conflicting);
+        }
+
+        #[test]
+        fn test_status_becomes_well_constrained_when_equation_determines_variable() {
+            // Arrange
+            let generator = Box::new(DefaultEquationIdGenerator::default());
+            let mut solver = Solver::new::<DefaultEpsilon>(generator);
+            let env = Environment::from_variables(vec![Variable::new("x", 1.0)]);
+            let equation: Equation = parse("x - 1").unwrap();
+
+            // Act
+            solver.add_equation(equation);
+            solver.update_variables(&env);
+
+            // Assert
+            assert_eq!(
+                solver.status(),
+                DimensionSpecificationStatus::WellConstrained
+            );
+        }
+
+        #[test]
+        fn test_status_becomes_well_constrained_with_multiple_variables_and_equations() {
+            // Arrange
+            let generator = Box::new(DefaultEquationIdGenerator::default());
+            let mut solver = Solver::new::<DefaultEpsilon>(generator);
+            let env = Environment::from_variables(vec![
+                Variable::new("x", 1.0),
+                Variable::new("y", 2.0),
+                Variable::new("z", 3.0),
+            ]);
+
+            // Act
+            solver.add_equation(parse("x + y").unwrap());
+            solver.add_equation(parse("y - 2").unwrap());
+            solver.add_equation(parse("z * x - 3").unwrap());
+            solver.update_variables(&env);
+
+            // Assert
+            assert_eq!(
+                solver.status(),
+                DimensionSpecificationStatus::WellConstrained
+            );
+        }
+
+        #[test]
+        fn test_status_becomes_under_constrained_when_adding_more_variables() {
+            // Arrange
+            let generator = Box::new(DefaultEquationIdGenerator::default());
+            let mut solver = Solver::new::<DefaultEpsilon>(generator);
+            let env1 = Environment::from_variables(vec![Variable::new("x", 1.0)]);
+            solver.add_equation(parse("x - 1").unwrap());
+            solver.update_variables(&env1);
+
+            // Act
+            let env2 =
+                Environment::from_variables(vec![Variable::new("x", 1.0), Variable::new("y", 2.0)]);
+            solver.update_variables(&env2);
+
+            // Assert
+            assert_eq!(
+                solver.status(),
+                DimensionSpecificationStatus::UnderConstrained
+            );
+        }
+
+        #[test]
+        fn test_status_becomes_under_constrained_after_removing_equation() {
+            // Arrange
+            let generator = Box::new(DefaultEquationIdGenerator::default());
+            let mut solver = Solver::new::<DefaultEpsilon>(generator);
+            let env = Environment::from_variables(vec![Variable::new("x", 1.0)]);
+            let eq_id = solver.add_equation(parse("x - 1").unwrap());
+            solver.update_variables(&env);
+
+            // Act
+            solver.remove_equation(eq_id);
+
+            // Assert
+            assert_eq!(
+                solver.status(),
+                DimensionSpecificationStatus::UnderConstrained
+            );
+        }
+    }
+
+    mod least_squares {
+        use crate::environment::Environment;
+
+        use crate::equation::parse;
+        use crate::{DefaultEquationIdGenerator, SolveMode, Solver};
+        use approx::assert_relative_eq;
+        use epsilon::DefaultEpsilon;
+        use pretty_assertions::assert_eq;
+
+        fn solver_with(mode: SolveMode) -> Solver {
+            let generator = Box::new(DefaultEquationIdGenerator::default());
+            let mut solver = Solver::new::<DefaultEpsilon>(generator);
+            solver.set_mode(mode);
+            solver
+        }
+
+        #[test]
+        fn test_default_mode_is_newton() {
+            // Arrange
+            let generator = Box::new(DefaultEquationIdGenerator::default());
+
+            // Act
+            let solver = Solver::new::<DefaultEpsilon>(generator);
+
+            // Assert
+            assert_eq!(solver.mode(), SolveMode::Newton);
+        }
+
+        #[test]
+        fn test_newton_mode_can_not_solve_under_constrained_system() {
+            // Arrange
+            let mut solver = solver_with(SolveMode::Newton);
+            solver.update_variables(&Environment::from_tuples(&[("x", 0.0), ("y", 0.0)]));
+            solver.add_equation(parse("x + y - 2").unwrap());
+
+            // Act
+            let ret = solver.solve();
+
+            // Assert
+            assert!(ret.is_err());
+        }
+
+        #[test]
+        fn test_solve_under_constrained_system_with_minimum_norm_step()
+        -> color_eyre::eyre::Result<()> {
+            // Arrange
+            let mut solver = solver_with(SolveMode::LeastSquares);
+            solver.update_variables(&Environment::from_tuples(&[("x", 0.0), ("y", 0.0)]));
+            solver.add_equation(parse("x + y - 2").unwrap());
+
+            // Act
+            let ret = solver.solve()?.variables;
+
+            // Assert
+            assert_relative_eq!(*ret.get("x").unwrap().value, 1.0, epsilon = 1e-4);
+            assert_relative_eq!(*ret.get("y").unwrap().value, 1.0, epsilon = 1e-4);
+            Ok(())
+        }
+
+        #[test]
+        fn test_solve_under_constrained_system_keeps_free_variable() -> color_eyre::eyre::Result<()>
+        {
+            // Arrange
+            let mut solver = solver_with(SolveMode::LeastSquares);
+            solver.update_variables(&Environment::from_tuples(&[("x", 0.0), ("y", 5.0)]));
+            solver.add_equation(parse("x - 3").unwrap());
+
+            // Act
+            let ret = solver.solve()?.variables;
+
+            // Assert
+            assert_relative_eq!(*ret.get("x").unwrap().value, 3.0, epsilon = 1e-4);
+            assert_relative_eq!(*ret.get("y").unwrap().value, 5.0, epsilon = 1e-6);
+            Ok(())
+        }
+
+        #[test]
+        fn test_solve_conflicting_system_with_least_squares() -> color_eyre::eyre::Result<()> {
+            // Arrange
+            let mut solver = solver_with(SolveMode::LeastSquares);
+            solver.update_variables(&Environment::from_tuples(&[("x", 0.0)]));
+            solver.add_equation(parse("x - 1").unwrap());
+            solver.add_equation(parse("x - 3").unwrap());
+
+            // Act
+            let ret = solver.solve()?.variables;
+
+            // Assert
+            assert_relative_eq!(*ret.get("x").unwrap().value, 2.0, epsilon = 1e-3);
+            Ok(())
+        }
+
+        #[test]
+        fn test_solve_non_linear_system_with_least_squares() -> color_eyre::eyre::Result<()> {
+            // Arrange
+            let mut solver = solver_with(SolveMode::LeastSquares);
+            solver.update_variables(&Environment::from_tuples(&[
+                ("x1", 0.0),
+                ("y1", 0.0),
+                ("x2", 1.0),
+                ("y2", 1.0),
+            ]));
+            solver.update_dimensions(&Environment::from_tuples(&[("d", 4.5)]));
+            solver.add_equation(parse("x1 - 3.0").unwrap());
+            solver.add_equation(parse("y1").unwrap());
+            solver.add_equation(
+                parse("x2^2 - 2.0 * x2 * x1 + x1^2 + y2^2 - 2.0 * y2 * y1 + y1^2 - d^2").unwrap(),
+            );
+            solver.add_equation(parse("y2 - y1").unwrap());
+
+            // Act
+            let ret = solver.solve()?.variables;
+
+            // Assert
+            assert_relative_eq!(*ret.get("x1").unwrap().value, 3.0, epsilon = 1e-4);
+            assert_relative_eq!(*ret.get("y1").unwrap().value, 0.0, epsilon = 1e-4);
+            assert_relative_eq!(*ret.get("x2").unwrap().value, 7.5, epsilon = 1e-4);
+            assert_relative_eq!(*ret.get("y2").unwrap().value, 0.0, epsilon = 1e-4);
+            Ok(())
+        }
+
+        #[test]
+        fn test_least_squares_can_not_solve_without_equations() {
+            // Arrange
+            let mut solver = solver_with(SolveMode::LeastSquares);
+            solver.update_variables(&Environment::from_tuples(&[("x", 0.0)]));
+
+            // Act
+            let ret = solver.solve();
+
+            // Assert
+            assert!(ret.is_err());
+        }
+    }
+
+    mod clusters {
+        use crate::environment::Environment;
+
+        use crate::equation::parse;
+        use crate::{DefaultEquationIdGenerator, EquationId, SolveMode, Solver};
+        use approx::assert_relative_eq;
+        use epsilon::DefaultEpsilon;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn test_solve_reports_each_independent_cluster() -> color_eyre::eyre::Result<()> {
+            // Arrange
+            let generator = Box::new(DefaultEquationIdGenerator::default());
+            let mut solver = Solver::new::<DefaultEpsilon>(generator);
+            solver.update_variables(&Environment::from_tuples(&[
+                ("a", 0.0),
+                ("b", 0.0),
+                ("x", 1.0),
+            ]));
+            let e1 = solver.add_equation(parse("a + b - 3").unwrap());
+            let e2 = solver.add_equation(parse("x^2 - 4").unwrap());
+            let e3 = solver.add_equation(parse("a - b - 1").unwrap());
+
+            // Act
+            let report = solver.solve()?;
+
+            // Assert
+            assert!(report.converged());
+            assert_eq!(report.clusters.len(), 2);
+            assert_eq!(report.clusters[0].equations, vec![e1, e3]);
+            assert_eq!(
+                report.clusters[0].variables,
+                vec!["a".to_string(), "b".to_string()]
+            );
+            assert_eq!(report.clusters[1].equations, vec![e2]);
+            assert_relative_eq!(
+                *report.variables.get("a").unwrap().value,
+                2.0,
+                epsilon = 1e-5
+            );
+            assert_relative_eq!(
+                *report.variables.get("b").unwrap().value,
+                1.0,
+                epsilon = 1e-5
+            );
+            assert_relative_eq!(
+                *report.variables.get("x").unwrap().value,
+                2.0,
+                epsilon = 1e-5
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn test_failed_cluster_does_not_affect_others() -> color_eyre::eyre::Result<()> {
+            // Arrange
+            let generator = Box::new(DefaultEquationIdGenerator::default());
+            let mut solver = Solver::new::<DefaultEpsilon>(generator);
+            solver.set_mode(SolveMode::LeastSquares);
+            solver.update_variables(&Environment::from_tuples(&[("x", 0.0), ("y", 0.0)]));
+            let e1 = solver.add_equation(parse("x - 5").unwrap());
+            // y^2 + 1 = 0 does not have any real solution
+            let e2 = solver.add_equation(parse("y^2 + 1").unwrap());
+
+            // Act
+            let report = solver.solve()?;
+
+            // Assert
+            assert!(!report.converged());
+            let cluster = report.cluster_of(e1).unwrap();
+            assert_eq!(cluster.equations, vec![e1]);
+            assert_eq!(cluster.variables, vec!["x".to_string()]);
+            assert!(cluster.converged());
+            assert!(!report.cluster_of(e2).unwrap().converged());
+            assert_eq!(report.cluster_of(EquationId::from(100)), None);
+            assert_relative_eq!(
+                *report.variables.get("x").unwrap().value,
+                5.0,
+                epsilon = 1e-4
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn test_solve_blocks_of_cluster_in_order() -> color_eyre::eyre::Result<()> {
+            // Arrange
+            let generator = Box::new(DefaultEquationIdGenerator::default());
+            let mut solver = Solver::new::<DefaultEpsilon>(generator);
+            solver.update_variables(&Environment::from_tuples(&[
+                ("x", 1.0),
+                ("y", 1.0),
+                ("z", 1.0),
+            ]));
+            let e1 = solver.add_equation(parse("z - x * y").unwrap());
+            let e2 = solver.add_equation(parse("y^2 - x - 7").unwrap());
+            let e3 = solver.add_equation(parse("x - 2").unwrap());
+
+            // Act
+            let report = solver.solve()?;
+
+            // Assert
+            assert!(report.converged());
+            assert_eq!(report.clusters.len(), 1);
+            assert_eq!(report.clusters[0].equations, vec![e1, e2, e3]);
+            assert_relative_eq!(
+                *report.variables.get("y").unwrap().value,
+                3.0,
+                epsilon = 1e-5
+            );
+            assert_relative_eq!(
+                *report.variables.get("z").unwrap().value,
+                6.0,
+                epsilon = 1e-5
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn test_equations_sharing_variable_are_in_same_cluster() -> color_eyre::eyre::Result<()> {
+            // Arrange
+            let generator = Box::new(DefaultEquationIdGenerator::default());
+            let mut solver = Solver::new::<DefaultEpsilon>(generator);
+            solver.update_variables(&Environment::from_tuples(&[("x", 0.0), ("y", 0.0)]));
+            solver.update_dimensions(&Environment::from_tuples(&[("d", 2.0)]));
+            let e1 = solver.add_equation(parse("x - d").unwrap());
+            let e2 = solver.add_equation(parse("y - x * d").unwrap());
+
+            // Act
+            let report = solver.solve()?;
+
+            // Assert
+            assert_eq!(report.clusters.len(), 1);
+            assert_eq!(report.clusters[0].equations, vec![e1, e2]);
+            assert_relative_eq!(
+                *report.variables.get("y").unwrap().value,
+                4.0,
+                epsilon = 1e-5
+            );
+            Ok(())
+        }
+    }
+
+    mod convergence {
+        use crate::environment::Environment;
+
+        use crate::equation::parse;
+        use crate::{
+            DefaultEquationIdGenerator, DimensionSpecificationStatus, SolveError, SolveMode,
+            SolveOptions, Solver, Termination,
+        };
+        use approx::assert_relative_eq;
+        use epsilon::DefaultEpsilon;
+        use pretty_assertions::assert_eq;
+
+        fn solver() -> Solver {
+            let generator = Box::new(DefaultEquationIdGenerator::default());
+            Solver::new::<DefaultEpsilon>(generator)
+        }
+
+        #[test]
+        fn test_report_has_iterations_and_residuals() -> color_eyre::eyre::Result<()> {
+            // Arrange
+            let mut solver = solver();
+            solver.update_variables(&Environment::from_tuples(&[("x", 1.0)]));
+            let id = solver.add_equation(parse("x^2 - 4").unwrap());
+
+            // Act
+            let report = solver.solve()?;
+
+            // Assert
+            let cluster = report.cluster_of(id).unwrap();
+            assert_eq!(cluster.termination, Termination::ResidualTolerance);
+            assert!(cluster.iterations > 1);
+            assert!(cluster.iterations < solver.options().max_iterations);
+            assert!(report.residual_norm() < solver.options().residual_tolerance);
+            assert!(report.residual(id).unwrap().abs() < solver.options().residual_tolerance);
+            Ok(())
+        }
+
+        #[test]
+        fn test_already_solved_equations_need_no_iteration() -> color_eyre::eyre::Result<()> {
+            // Arrange
+            let mut solver = solver();
+            solver.update_variables(&Environment::from_tuples(&[("x", 2.0)]));
+            solver.add_equation(parse("x - 2").unwrap());
+
+            // Act
+            let report = solver.solve()?;
+
+            // Assert
+            assert_eq!(report.iterations(), 0);
+            assert!(report.converged());
+            Ok(())
+        }
+
+        #[test]
+        fn test_stop_iterations_at_limit() -> color_eyre::eyre::Result<()> {
+            // Arrange
+            let mut solver = solver();
+            solver.set_options(SolveOptions {
+                max_iterations: 2,
+                ..solver.options()
+            });
+            solver.update_variables(&Environment::from_tuples(&[("x", 100.0)]));
+            solver.add_equation(parse("x^2 - 4").unwrap());
+
+            // Act
+            let report = solver.solve()?;
+
+            // Assert
+            assert!(!report.converged());
+            assert_eq!(report.iterations(), 2);
+            assert_eq!(report.clusters[0].termination, Termination::MaxIterations);
+            assert!(report.residual_norm() > 1.0);
+            Ok(())
+        }
+
+        #[test]
+        fn test_singular_jacobian_is_error_and_restores_variables() {
+            // Arrange
+            let mut solver = solver();
+            solver.update_variables(&Environment::from_tuples(&[("x", 2.0)]));
+            // first step moves x to 1, where the derivative 3x^2 - 3 vanishes
+            let id = solver.add_equation(parse("x^3 - 3.0 * x + 7.0").unwrap());
+
+            // Act
+            let ret = solver.solve();
+
+            // Assert
+            assert_eq!(
+                ret.unwrap_err(),
+                SolveError::Singular {
+                    equations: vec![id]
+                }
+            );
+            assert_eq!(*solver.variables().get("x").unwrap().value, 2.0);
+        }
+
+        #[test]
+        fn test_diverging_iterations_is_error() {
+            // Arrange
+            let mut solver = solver();
+            solver.update_variables(&Environment::from_tuples(&[("x", 1.001)]));
+            // the derivative is almost 0 at the initial value, so the first step goes too far
+            let id = solver.add_equation(parse("x^3 - 3.0 * x + 7.0").unwrap());
+
+            // Act
+            let ret = solver.solve();
+
+            // Assert
+            assert_eq!(
+                ret.unwrap_err(),
+                SolveError::Diverged {
+                    equations: vec![id]
+                }
+            );
+            assert_relative_eq!(*solver.variables().get("x").unwrap().value, 1.001);
+        }
+
+        #[test]
+        fn test_newton_mode_is_error_for_under_constrained_system() {
+            // Arrange
+            let mut solver = solver();
+            solver.update_variables(&Environment::from_tuples(&[("x", 0.0), ("y", 0.0)]));
+            solver.add_equation(parse("x + y").unwrap());
+
+            // Act
+            let ret = solver.solve();
+
+            // Assert
+            assert_eq!(
+                ret.unwrap_err(),
+                SolveError::InvalidStatus(DimensionSpecificationStatus::UnderConstrained)
+            );
+        }
+
+        #[test]
+        fn test_least_squares_mode_reports_local_minimum_for_inconsistent_system()
+        -> color_eyre::eyre::Result<()> {
+            // Arrange
+            let mut solver = solver();
+            solver.set_mode(SolveMode::LeastSquares);
+            solver.update_variables(&Environment::from_tuples(&[("x", 0.0)]));
+            solver.add_equation(parse("x - 1").unwrap());
+            solver.add_equation(parse("x - 3").unwrap());
+
+            // Act
+            let report = solver.solve()?;
+
+            // Assert
+            assert_eq!(report.clusters[0].termination, Termination::LocalMinimum);
+            assert!(!report.converged());
+            assert_relative_eq!(report.residual_norm(), 2.0_f64.sqrt(), epsilon = 1e-3);
+            Ok(())
+        }
+    }
+
+    mod drag {
+        use crate::environment::Environment;
+        use crate::equation::parse;
+        use crate::{DefaultEquationIdGenerator, DimensionSpecificationStatus, SolveError, Solver};
+        use approx::assert_relative_eq;
+        use epsilon::DefaultEpsilon;
+        use pretty_assertions::assert_eq;
+
+        fn solver(variables: &[(&str, f64)], equations: &[&str]) -> Solver {
+            let generator = Box::new(DefaultEquationIdGenerator::default());
+            let mut solver = Solver::new::<DefaultEpsilon>(generator);
+            solver.update_variables(&Environment::from_tuples(variables));
+            for equation in equations {
+                solver.add_equation(parse(equation).unwrap());
+            }
+            solver
+        }
+
+        #[test]
+        fn test_drag_keeps_solution_on_the_same_side() -> color_eyre::eyre::Result<()> {
+            // Arrange
+            let mut solver = solver(&[("x", 3.0), ("y", 4.0)], &["x^2 + y^2 - 25"]);
+
+            // Act
+            let report = solver.drag(&[("x", 4.0)])?;
+
+            // Assert
+            assert!(report.converged());
+            assert_relative_eq!(*report.variables.get("x").unwrap().value, 4.0);
+            assert_relative_eq!(
+                *report.variables.get("y").unwrap().value,
+                3.0,
+                epsilon = 1e-4
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn test_drag_moves_unpinned_variables_minimally() -> color_eyre::eyre::Result<()> {
+            // Arrange
+            // a point on the line y = x, and a free variable z
+            let mut solver = solver(
+                &[("x", 1.0), ("y", 1.0), ("z", 2.0), ("w", 2.0)],
+                &["y - x", "w - z"],
+            );
+
+            // Act
+            let report = solver.drag(&[("x", 3.0)])?;
+
+            // Assert
+            assert_relative_eq!(
+                *report.variables.get("y").unwrap().value,
+                3.0,
+                epsilon = 1e-4
+            );
+            assert_relative_eq!(*report.variables.get("z").unwrap().value, 2.0);
+            assert_relative_eq!(*report.variables.get("w").unwrap().value, 2.0);
+            Ok(())
+        }
+
+        #[test]
+        fn test_drag_distributes_change_to_unpinned_variables_with_minimal_step()
+        -> color_eyre::eyre::Result<()> {
+            // Arrange
+            // any y and z with y + z = 1 satisfy the equation after dragging, and the closest one to
+            // the previous solution moves both variables equally.
+            let mut solver = solver(&[("x", 1.0), ("y", 1.0), ("z", 1.0)], &["x + y + z - 3"]);
+
+            // Act
+            let report = solver.drag(&[("x", 2.0)])?;
+
+            // Assert
+            assert!(report.converged());
+            assert_relative_eq!(
+                *report.variables.get("y").unwrap().value,
+                0.5,
+                epsilon = 1e-4
+            );
+            assert_relative_eq!(
+                *report.variables.get("z").unwrap().value,
+                0.5,
+                epsilon = 1e-4
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn test_drag_follows_targets_of_each_frame() -> color_eyre::eyre::Result<()> {
+            // Arrange
+            // distance between two points is fixed, and the first point is dragged
+            let mut solver = solver(
+                &[("x1", 0.0), ("y1", 0.0), ("x2", 2.0), ("y2", 0.0)],
+                &["(x2 - x1)^2 + (y2 - y1)^2 - 4"],
+            );
+
+            // Act
+            let mut reports = vec![];
+            for i in 1..=10 {
+                let target = 0.1 * i as f64;
+                reports.push(solver.drag(&[("x1", target), ("y1", target)])?);
+            }
+
+            // Assert
+            for report in reports {
+                assert!(report.converged());
+            }
+            let x2 = *solver.variables().get("x2").unwrap().value;
+            let y2 = *solver.variables().get("y2").unwrap().value;
+            assert_relative_eq!((x2 - 1.0).powi(2) + (y2 - 1.0).powi(2), 4.0, epsilon = 1e-4);
+            // second point is pushed to the dragged direction, and does not flip to the other side
+            assert!(x2 > 2.0);
+            Ok(())
+        }
+
+        #[test]
+        fn test_drag_keeps_closest_values_when_pinned_to_conflict() -> color_eyre::eyre::Result<()>
+        {
+            // Arrange
+            let mut solver = solver(&[("x", 1.0), ("y", 1.0)], &["x - 1", "y - x"]);
+
+            // Act
+            let report = solver.drag(&[("x", 2.0)])?;
+
+            // Assert
+            assert!(!report.converged());
+            assert_relative_eq!(*report.variables.get("x").unwrap().value, 2.0);
+            assert_relative_eq!(
+                *report.variables.get("y").unwrap().value,
+                2.0,
+                epsilon = 1e-4
+            );
+            assert_relative_eq!(report.residual_norm(), 1.0, epsilon = 1e-4);
+            Ok(())
+        }
+
+        #[test]
+        fn test_drag_fails_with_unknown_variable() {
+            // Arrange
+            let mut solver = solver(&[("x", 1.0), ("y", 1.0)], &["y - x"]);
+
+            // Act
+            let ret = solver.drag(&[("x", 2.0), ("z", 1.0)]);
+
+            // Assert
+            assert_eq!(
+                ret.unwrap_err(),
+                SolveError::UnknownVariable("z".to_string())
+            );
+            assert_relative_eq!(*solver.variables().get("x").unwrap().value, 1.0);
+        }
+
+        #[test]
+        fn test_update_values_of_variables_refreshes_diagnostics() {
+            // Arrange
+            // derivative vanishes at x = 0
+            let mut solver = solver(&[("x", 0.0)], &["x^2 - 4"]);
+            let before = solver.status();
+
+            // Act
+            solver.update_variables(&Environment::from_tuples(&[("x", 1.0)]));
+
+            // Assert
+            assert_ne!(before, DimensionSpecificationStatus::WellConstrained);
+            assert_eq!(
+                solver.status(),
+                DimensionSpecificationStatus::WellConstrained
+            );
+        }
+    }
+
+    mod inequality {
+        use crate::environment::Environment;
+        use crate::equation::parse;
+        use crate::variable::Variable;
+        use crate::{DefaultEquationIdGenerator, SolveError, SolveMode, Solver, Termination};
+        use approx::assert_relative_eq;
+        use epsilon::DefaultEpsilon;
+        use pretty_assertions::assert_eq;
+
+        fn solver(variables: Vec<Variable>, equations: &[&str]) -> Solver {
+            let generator = Box::new(DefaultEquationIdGenerator::default());
+            let mut solver = Solver::new::<DefaultEpsilon>(generator);
+            solver.update_variables(&Environment::from_variables(variables));
+            for equation in equations {
+                solver.add_equation(parse(equation).unwrap());
+            }
+            solver
+        }
+
+        #[test]
+        fn test_solve_keeps_satisfied_solution() -> color_eyre::eyre::Result<()> {
+            // Arrange
+            let mut solver = solver(
+                vec![Variable::new("x", 1.0).with_bounds(Some(0.0), None)],
+                &["x^2 - 4"],
+            );
+
+            // Act
+            let report = solver.solve()?;
+
+            // Assert
+            assert!(report.feasible());
+            assert_relative_eq!(
+                *report.variables.get("x").unwrap().value,
+                2.0,
+                epsilon = 1e-4
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn test_solve_moves_free_variable_into_bounds() -> color_eyre::eyre::Result<()> {
+            // Arrange
+            let mut solver = solver(
+                vec![
+                    Variable::new("x", 0.0),
+                    Variable::new("y", 0.0).with_bounds(None, Some(1.0)),
+                ],
+                &["x + y - 4"],
+            );
+            solver.set_mode(SolveMode::LeastSquares);
+
+            // Act
+            let report = solver.solve()?;
+
+            // Assert
+            assert!(report.feasible());
+            assert!(report.converged());
+            assert_relative_eq!(
+                *report.variables.get("y").unwrap().value,
+                1.0,
+                epsilon = 1e-4
+            );
+            assert_relative_eq!(
+                *report.variables.get("x").unwrap().value,
+                3.0,
+                epsilon = 1e-4
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn test_solve_keeps_inequality() -> color_eyre::eyre::Result<()> {
+            // Arrange
+            // radius is at least 2, and the point is on the line y = x
+            let mut solver = solver(
+                vec![Variable::new("x", 0.5), Variable::new("y", 0.5)],
+                &["y - x"],
+            );
+            solver.set_mode(SolveMode::LeastSquares);
+            solver.add_inequality(parse("x^2 + y^2 - 4").unwrap());
+
+            // Act
+            let report = solver.solve()?;
+
+            // Assert
+            let x = *report.variables.get("x").unwrap().value;
+            let y = *report.variables.get("y").unwrap().value;
+            assert!(report.feasible());
+            assert_relative_eq!(x, y, epsilon = 1e-4);
+            assert_relative_eq!(x, 2.0_f64.sqrt(), epsilon = 1e-4);
+            Ok(())
+        }
+
+        #[test]
+        fn test_solve_keeps_bound_hard_and_reports_residual() -> color_eyre::eyre::Result<()> {
+            // Arrange
+            let mut solver = solver(
+                vec![Variable::new("x", 0.0).with_bounds(Some(0.0), Some(2.0))],
+                &["x - 3"],
+            );
+
+            // Act
+            let report = solver.solve()?;
+
+            // Assert
+            assert!(report.feasible());
+            assert!(!report.converged());
+            assert_eq!(report.clusters[0].termination, Termination::Constrained);
+            assert_relative_eq!(*report.variables.get("x").unwrap().value, 2.0);
+            assert_relative_eq!(report.residual_norm(), 1.0, epsilon = 1e-6);
+            Ok(())
+        }
+
+        #[test]
+        fn test_solve_releases_bound_that_becomes_inactive() -> color_eyre::eyre::Result<()> {
+            // Arrange
+            // the solution (2, 2) violates both bounds. With x on the lower bound, y goes to 1 and
+            // violates the upper bound. Then the bound of x pulls x back, so it is released.
+            let mut solver = solver(
+                vec![
+                    Variable::new("x", 0.0).with_bounds(Some(3.0), None),
+                    Variable::new("y", 0.0).with_bounds(None, Some(0.5)),
+                ],
+                &["x + y - 4"],
+            );
+            solver.set_mode(SolveMode::LeastSquares);
+
+            // Act
+            let report = solver.solve()?;
+
+            // Assert
+            assert!(report.feasible());
+            assert!(report.converged());
+            assert_relative_eq!(
+                *report.variables.get("x").unwrap().value,
+                3.5,
+                epsilon = 1e-4
+            );
+            assert_relative_eq!(*report.variables.get("y").unwrap().value, 0.5);
+            Ok(())
+        }
+
+        #[test]
+        fn test_solve_releases_inequality_that_becomes_inactive() -> color_eyre::eyre::Result<()> {
+            // Arrange
+            // `x >= 3` and the bound of y enter together. With y on the bound, the equation needs
+            // x = 3.5, so the inequality is satisfied without being active.
+            let mut solver = solver(
+                vec![
+                    Variable::new("x", 0.0),
+                    Variable::new("y", 0.0).with_bounds(None, Some(0.5)),
+                ],
+                &["x + y - 4"],
+            );
+            solver.set_mode(SolveMode::LeastSquares);
+            solver.add_inequality(parse("x - 3").unwrap());
+
+            // Act
+            let report = solver.solve()?;
+
+            // Assert
+            assert!(report.feasible());
+            assert!(report.converged());
+            assert_relative_eq!(
+                *report.variables.get("x").unwrap().value,
+                3.5,
+                epsilon = 1e-4
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn test_solve_fails_with_inequality_of_unknown_variable() {
+            // Arrange
+            let mut solver = solver(vec![Variable::new("x", 1.0)], &["x - 1"]);
+            solver.add_inequality(parse("z - 1").unwrap());
+
+            // Act
+            let ret = solver.solve();
+
+            // Assert
+            assert!(matches!(ret, Err(SolveError::Evaluation(_))));
+            assert_relative_eq!(*solver.variables().get("x").unwrap().value, 1.0);
+        }
+
+        #[test]
+        fn test_drag_keeps_bounds_of_unpinned_variables() -> color_eyre::eyre::Result<()> {
+            // Arrange
+            let mut solver = solver(
+                vec![
+                    Variable::new("x", 0.0),
+                    Variable::new("y", 0.0).with_bounds(Some(0.0), None),
+                    Variable::new("z", 0.0),
+                ],
+                &["x + y + z"],
+            );
+
+            // Act
+            let report = solver.drag(&[("x", 2.0)])?;
+
+            // Assert
+            assert!(report.feasible());
+            assert_relative_eq!(*report.variables.get("x").unwrap().value, 2.0);
+            assert_relative_eq!(
+                *report.variables.get("y").unwrap().value,
+                0.0,
+                epsilon = 1e-4
+            );
+            assert_relative_eq!(
+                *report.variables.get("z").unwrap().value,
+                -2.0,
+                epsilon = 1e-4
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn test_remove_inequality() -> color_eyre::eyre::Result<()> {
+            // Arrange
+            let mut solver = solver(vec![Variable::new("x", 1.0)], &["x - 1"]);
+            let id = solver.add_inequality(parse("x - 2").unwrap());
+
+            // Act
+            let removed = solver.remove_equation(id);
+            let report = solver.solve()?;
+
+            // Assert
+            assert!(removed.is_some());
+            assert!(report.feasible());
+            Ok(())
+        }
+    }
+
+    mod soft {
+        use crate::environment::Environment;
+        use crate::equation::parse;
+        use crate::{DefaultEquationIdGenerator, Relaxation, SoftConstraint, SolveMode, Solver};
+        use approx::assert_relative_eq;
+        use epsilon::DefaultEpsilon;
+        use pretty_assertions::assert_eq;
+        use rstest::rstest;
+
+        fn solver(variables: &[(&str, f64)], equations: &[&str]) -> Solver {
+            let generator = Box::new(DefaultEquationIdGenerator::default());
+            let mut solver = Solver::new::<DefaultEpsilon>(generator);
+            solver.update_variables(&Environment::from_tuples(variables));
+            for equation in equations {
+                solver.add_equation(parse(equation).unwrap());
+            }
+            solver
+        }
+
+        fn value(solver: &Solver, name: &str) -> f64 {
+            *solver.variables().get(name).unwrap().value
+        }
+
+        #[test]
+        fn test_soft_equation_decides_free_variable() -> color_eyre::eyre::Result<()> {
+            // Arrange
+            let mut solver = solver(&[("x", 0.0), ("y", 0.0)], &["x + y - 10"]);
+            solver.set_mode(SolveMode::LeastSquares);
+            solver.add_soft_equation(parse("x - 3").unwrap(), SoftConstraint::new(0, 1.0)?);
+
+            // Act
+            let report = solver.solve()?;
+
+            // Assert
+            assert!(report.converged());
+            assert_eq!(report.relaxed, vec![]);
+            assert_relative_eq!(value(&solver, "x"), 3.0, epsilon = 1e-4);
+            assert_relative_eq!(value(&solver, "y"), 7.0, epsilon = 1e-4);
+            Ok(())
+        }
+
+        #[test]
+        fn test_soft_equation_yields_to_equation() -> color_eyre::eyre::Result<()> {
+            // Arrange
+            let mut solver = solver(&[("x", 0.0)], &["x - 1"]);
+            let id =
+                solver.add_soft_equation(parse("x - 5").unwrap(), SoftConstraint::new(0, 1.0)?);
+
+            // Act
+            let report = solver.solve()?;
+
+            // Assert
+            assert!(report.converged());
+            assert_relative_eq!(value(&solver, "x"), 1.0, epsilon = 1e-4);
+            assert_eq!(report.relaxed.len(), 1);
+            assert_eq!(report.relaxed[0].equation, id);
+            assert_relative_eq!(report.relaxed[0].residual, -4.0, epsilon = 1e-4);
+            Ok(())
+        }
+
+        #[test]
+        fn test_higher_priority_wins_over_lower_one() -> color_eyre::eyre::Result<()> {
+            // Arrange
+            let mut solver = solver(&[("x", 0.0)], &[]);
+            solver.set_mode(SolveMode::LeastSquares);
+            let low =
+                solver.add_soft_equation(parse("x - 8").unwrap(), SoftConstraint::new(1, 100.0)?);
+            solver.add_soft_equation(parse("x - 2").unwrap(), SoftConstraint::new(0, 1.0)?);
+
+            // Act
+            let report = solver.solve()?;
+
+            // Assert
+            assert_relative_eq!(value(&solver, "x"), 2.0, epsilon = 1e-6);
+            assert_eq!(
+                report
+                    .relaxed
+                    .iter()
+                    .map(|r| (r.equation, r.priority))
+                    .collect::<Vec<_>>(),
+                vec![(low, 1)]
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn test_same_priority_is_balanced_by_weights() -> color_eyre::eyre::Result<()> {
+            // Arrange
+            let mut solver = solver(&[("x", 0.0), ("y", 1.0)], &["y - 1"]);
+            solver.set_mode(SolveMode::LeastSquares);
+            solver.add_soft_equation(parse("x").unwrap(), SoftConstraint::new(0, 1.0)?);
+            solver.add_soft_equation(parse("x - 4").unwrap(), SoftConstraint::new(0, 3.0)?);
+
+            // Act
+            let report = solver.solve()?;
+
+            // Assert
+            assert_relative_eq!(value(&solver, "x"), 3.0, epsilon = 1e-4);
+            let residuals: Vec<f64> = report.relaxed.iter().map(|r| r.residual).collect();
+            assert_relative_eq!(residuals[0], 3.0, epsilon = 1e-4);
+            assert_relative_eq!(residuals[1], -1.0, epsilon = 1e-4);
+            Ok(())
+        }
+
+        #[test]
+        fn test_remove_soft_equation() -> color_eyre::eyre::Result<()> {
+            // Arrange
+            let mut solver = solver(&[("x", 1.0)], &["x - 1"]);
+            let id =
+                solver.add_soft_equation(parse("x - 5").unwrap(), SoftConstraint::new(0, 1.0)?);
+
+            // Act
+            let removed = solver.remove_equation(id);
+            let report = solver.solve()?;
+
+            // Assert
+            assert_eq!(removed, Some(parse("x - 5").unwrap()));
+            assert_eq!(report.relaxed, Vec::<Relaxation>::new());
+            Ok(())
+        }
+
+        #[rstest]
+        #[case(0.0)]
+        #[case(-1.0)]
+        #[case(f64::NAN)]
+        #[case(f64::INFINITY)]
+        fn test_soft_constraint_needs_positive_weight(#[case] weight: f64) {
+            // Act
+            let result = SoftConstraint::new(0, weight);
+
+            // Assert
+            assert!(result.is_err());
         }
     }
 }
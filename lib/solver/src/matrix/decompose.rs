@@ -0,0 +1,561 @@
+/// Orthogonal decompositions of matrices, and numerical rank and condition number by them.
+use color_eyre::eyre::{Result, eyre};
+
+use crate::matrix::{Matrix, simple::SimpleMatrix};
+
+/// Maximum sweeps of Jacobi rotations for SVD. Jacobi method converges quadratically, so this is
+/// reached only for broken input such as NaN.
+const MAX_SWEEPS: usize = 64;
+
+/// Result of QR decomposition with column pivoting, `A P = Q R`.
+///
+/// For `m x n` matrix `A` and `k = min(m, n)`, `Q` is `m x k` with orthonormal columns, and `R` is
+/// `k x n` upper trapezoidal. Absolute values of diagonal of `R` are non-increasing.
+#[derive(Debug, Clone)]
+pub struct Qr {
+    q: SimpleMatrix<f64>,
+    r: SimpleMatrix<f64>,
+    permutation: Vec<usize>,
+}
+
+impl Qr {
+    /// Get `Q`
+    pub fn q(&self) -> &SimpleMatrix<f64> {
+        &self.q
+    }
+
+    /// Get `R`
+    pub fn r(&self) -> &SimpleMatrix<f64> {
+        &self.r
+    }
+
+    /// Get the permutation of columns. The `j`-th column of `A P` is the `permutation[j]`-th
+    /// column of `A`.
+    pub fn permutation(&self) -> &[usize] {
+        &self.permutation
+    }
+
+    /// Get the numerical rank, that is the number of diagonal elements of `R` that are greater than
+    /// `tolerance` relative to the largest one.
+    pub fn rank(&self, tolerance: f64) -> usize {
+        let diagonal: Vec<f64> = (0..self.r.size().rows())
+            .map(|i| element(&self.r, i, i).abs())
+            .collect();
+        let largest = diagonal.first().copied().unwrap_or(0.0);
+
+        diagonal
+            .iter()
+            .take_while(|d| **d > tolerance * largest && **d > 0.0)
+            .count()
+    }
+}
+
+/// Result of thin singular value decomposition, `A = U S V^T`.
+///
+/// For `m x n` matrix `A` and `k = min(m, n)`, `U` is `m x k` and `V` is `n x k`, both with
+/// orthonormal columns. Singular values are in descending order.
+#[derive(Debug, Clone)]
+pub struct Svd {
+    u: SimpleMatrix<f64>,
+    singular_values: Vec<f64>,
+    v: SimpleMatrix<f64>,
+}
+
+impl Svd {
+    /// Get `U`
+    pub fn u(&self) -> &SimpleMatrix<f64> {
+        &self.u
+    }
+
+    /// Get singular values in descending order
+    pub fn singular_values(&self) -> &[f64] {
+        &self.singular_values
+    }
+
+    /// Get `V`
+    pub fn v(&self) -> &SimpleMatrix<f64> {
+        &self.v
+    }
+
+    /// Get the numerical rank, that is the number of singular values that are greater than
+    /// `tolerance` relative to the largest one.
+    pub fn rank(&self, tolerance: f64) -> usize {
+        let largest = self.singular_values.first().copied().unwrap_or(0.0);
+
+        self.singular_values
+            .iter()
+            .filter(|s| **s > tolerance * largest && **s > 0.0)
+            .count()
+    }
+
+    /// Get the condition number in 2-norm, that is the ratio of the largest singular value to the
+    /// smallest one. This is infinity when the smallest one is exactly zero, and huge for
+    /// numerically rank-deficient matrices.
+    pub fn condition_number(&self) -> f64 {
+        match (self.singular_values.first(), self.singular_values.last()) {
+            (Some(largest), Some(smallest)) if *smallest > 0.0 => largest / smallest,
+            _ => f64::INFINITY,
+        }
+    }
+}
+
+/// Decompose the matrix with Householder QR with column pivoting. Empty elements are zero.
+pub fn qr(mat: &impl Matrix<f64>) -> Result<Qr> {
+    let (m, n) = (mat.size().rows(), mat.size().columns());
+    let k = m.min(n);
+    let mut a = dense(mat)?;
+    let mut permutation: Vec<usize> = (0..n).collect();
+    let mut reflectors: Vec<Vec<f64>> = Vec::with_capacity(k);
+
+    for step in 0..k {
+        // move the column that has the largest remaining norm to the front
+        let norm2 = |j: usize| (step..m).map(|i| a[i][j] * a[i][j]).sum::<f64>();
+        let pivot = (step..n)
+            .max_by(|x, y| norm2(*x).total_cmp(&norm2(*y)))
+            .unwrap_or(step);
+        if pivot != step {
+            a.iter_mut().for_each(|row| row.swap(step, pivot));
+            permutation.swap(step, pivot);
+        }
+
+        let mut v: Vec<f64> = (step..m).map(|i| a[i][step]).collect();
+        let alpha = norm(&v);
+        if alpha == 0.0 {
+            reflectors.push(vec![0.0; v.len()]);
+            continue;
+        }
+
+        // reflect the column to `-sign(a) |a| e1` to avoid cancellation
+        let alpha = if v[0] > 0.0 { -alpha } else { alpha };
+        v[0] -= alpha;
+        let length = norm(&v);
+        v.iter_mut().for_each(|x| *x /= length);
+
+        reflect(&v, &mut a[step..], step..n);
+        reflectors.push(v);
+    }
+
+    // Q is product of reflectors applied to the first k columns of identity
+    let mut q = vec![vec![0.0; k]; m];
+    (0..k).for_each(|i| q[i][i] = 1.0);
+    for (step, v) in reflectors.iter().enumerate().rev() {
+        reflect(v, &mut q[step..], 0..k);
+    }
+
+    let r: Vec<Vec<f64>> = (0..k)
+        .map(|i| (0..n).map(|j| if j < i { 0.0 } else { a[i][j] }).collect())
+        .collect();
+
+    Ok(Qr {
+        q: to_matrix(&q)?,
+        r: to_matrix(&r)?,
+        permutation,
+    })
+}
+
+/// Decompose the matrix with thin singular value decomposition by one-sided Jacobi method. Empty
+/// elements are zero.
+pub fn svd(mat: &impl Matrix<f64>) -> Result<Svd> {
+    let a = dense(mat)?;
+    let (m, n) = (mat.size().rows(), mat.size().columns());
+
+    // one-sided Jacobi orthogonalizes columns, so decompose the transposed one for wide matrices
+    if m < n {
+        let transposed: Vec<Vec<f64>> = (0..n).map(|j| (0..m).map(|i| a[i][j]).collect()).collect();
+        let (u, singular_values, v) = jacobi_svd(transposed);
+        return Ok(Svd {
+            u: to_matrix(&v)?,
+            singular_values,
+            v: to_matrix(&u)?,
+        });
+    }
+
+    let (u, singular_values, v) = jacobi_svd(a);
+    Ok(Svd {
+        u: to_matrix(&u)?,
+        singular_values,
+        v: to_matrix(&v)?,
+    })
+}
+
+/// Get the numerical rank of the matrix. Singular values lesser than `tolerance` relative to the
+/// largest one are treated as zero. When `tolerance` is `None`, `max(m, n) * f64::EPSILON` is used.
+pub fn rank(mat: &impl Matrix<f64>, tolerance: Option<f64>) -> Result<usize> {
+    let tolerance =
+        tolerance.unwrap_or(mat.size().rows().max(mat.size().columns()) as f64 * f64::EPSILON);
+
+    Ok(svd(mat)?.rank(tolerance))
+}
+
+/// Get the condition number of the matrix in 2-norm. See [`Svd::condition_number`].
+pub fn condition_number(mat: &impl Matrix<f64>) -> Result<f64> {
+    Ok(svd(mat)?.condition_number())
+}
+
+/// Apply the Householder reflection `I - 2 v v^T` to `columns` of `rows`
+fn reflect(v: &[f64], rows: &mut [Vec<f64>], columns: std::ops::Range<usize>) {
+    for j in columns {
+        let d: f64 = rows.iter().zip(v).map(|(row, v)| v * row[j]).sum();
+        rows.iter_mut()
+            .zip(v)
+            .for_each(|(row, v)| row[j] -= 2.0 * v * d);
+    }
+}
+
+/// SVD of `m x n` rows with `m >= n`. Returns `U` as `m x n` rows, singular values and `V` as
+/// `n x n` rows.
+fn jacobi_svd(mut a: Vec<Vec<f64>>) -> (Vec<Vec<f64>>, Vec<f64>, Vec<Vec<f64>>) {
+    let m = a.len();
+    let n = a.first().map(|r| r.len()).unwrap_or(0);
+    let mut v = vec![vec![0.0; n]; n];
+    (0..n).for_each(|i| v[i][i] = 1.0);
+
+    for _ in 0..MAX_SWEEPS {
+        let mut rotated = false;
+        for p in 0..n {
+            for q in (p + 1)..n {
+                let alpha: f64 = (0..m).map(|i| a[i][p] * a[i][p]).sum();
+                let beta: f64 = (0..m).map(|i| a[i][q] * a[i][q]).sum();
+                let gamma: f64 = (0..m).map(|i| a[i][p] * a[i][q]).sum();
+                if gamma == 0.0 || gamma.abs() <= f64::EPSILON * (alpha * beta).sqrt() {
+                    continue;
+                }
+                rotated = true;
+
+                // rotation that makes columns p and q orthogonal
+                let zeta = (beta - alpha) / (2.0 * gamma);
+                let t = zeta.signum() / (zeta.abs() + (1.0 + zeta * zeta).sqrt());
+                let c = 1.0 / (1.0 + t * t).sqrt();
+                let s = c * t;
+                for row in a.iter_mut().chain(v.iter_mut()) {
+                    let (x, y) = (row[p], row[q]);
+                    row[p] = c * x - s * y;
+                    row[q] = s * x + c * y;
+                }
+            }
+        }
+        if !rotated {
+            break;
+        }
+    }
+
+    let mut order: Vec<(usize, f64)> = (0..n)
+        .map(|j| (j, (0..m).map(|i| a[i][j] * a[i][j]).sum::<f64>().sqrt()))
+        .collect();
+    order.sort_by(|x, y| y.1.total_cmp(&x.1));
+
+    let singular_values: Vec<f64> = order.iter().map(|(_, s)| *s).collect();
+    let v: Vec<Vec<f64>> = (0..n)
+        .map(|i| order.iter().map(|(j, _)| v[i][*j]).collect())
+        .collect();
+
+    // columns of U for zero singular values are completed to orthonormal ones
+    let mut columns: Vec<Vec<f64>> = vec![];
+    for (j, s) in &order {
+        let column = if *s > 0.0 {
+            (0..m).map(|i| a[i][*j] / s).collect()
+        } else {
+            orthonormal_complement(&columns, m)
+        };
+        columns.push(column);
+    }
+    let u: Vec<Vec<f64>> = (0..m)
+        .map(|i| columns.iter().map(|c| c[i]).collect())
+        .collect();
+
+    (u, singular_values, v)
+}
+
+/// Get a unit vector that is orthogonal to all of `columns`, with Gram-Schmidt of unit vectors
+fn orthonormal_complement(columns: &[Vec<f64>], m: usize) -> Vec<f64> {
+    let mut best = vec![0.0; m];
+    let mut best_norm = 0.0;
+    for axis in 0..m {
+        let mut e = vec![0.0; m];
+        e[axis] = 1.0;
+        // twice for numerical orthogonality
+        for _ in 0..2 {
+            for c in columns {
+                let d: f64 = e.iter().zip(c).map(|(x, y)| x * y).sum();
+                e.iter_mut().zip(c).for_each(|(x, y)| *x -= d * y);
+            }
+        }
+
+        let length = norm(&e);
+        if length > best_norm {
+            best_norm = length;
+            best = e;
+        }
+    }
+
+    best.iter().map(|x| x / best_norm).collect()
+}
+
+fn norm(v: &[f64]) -> f64 {
+    v.iter().map(|x| x * x).sum::<f64>().sqrt()
+}
+
+/// Get the element, or zero when it is empty
+fn element(mat: &impl Matrix<f64>, row: usize, column: usize) -> f64 {
+    mat.get(row, column).ok().flatten().copied().unwrap_or(0.0)
+}
+
+/// Get rows of the matrix with zero for empty elements
+fn dense(mat: &impl Matrix<f64>) -> Result<Vec<Vec<f64>>> {
+    let (m, n) = (mat.size().rows(), mat.size().columns());
+    if m == 0 || n == 0 {
+        return Err(eyre!("Can not decompose 0-sized matrix"));
+    }
+
+    Ok((0..m)
+        .map(|i| (0..n).map(|j| element(mat, i, j)).collect())
+        .collect())
+}
+
+fn to_matrix(rows: &[Vec<f64>]) -> Result<SimpleMatrix<f64>> {
+    let columns = rows.first().map(|r| r.len()).unwrap_or(0);
+    let mut mat = SimpleMatrix::new(rows.len(), columns)?;
+    for (i, row) in rows.iter().enumerate() {
+        for (j, value) in row.iter().enumerate() {
+            mat.set(i, j, *value)?;
+        }
+    }
+
+    Ok(mat)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matrix::sparse::SparseMatrix;
+    use approx::assert_relative_eq;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    fn matrix(rows: &[&[f64]]) -> SimpleMatrix<f64> {
+        let mut mat = SimpleMatrix::new(rows.len(), rows[0].len()).unwrap();
+        for (i, row) in rows.iter().enumerate() {
+            for (j, value) in row.iter().enumerate() {
+                if *value != 0.0 {
+                    mat.set(i, j, *value).unwrap();
+                }
+            }
+        }
+        mat
+    }
+
+    fn tall() -> SimpleMatrix<f64> {
+        matrix(&[&[1.0, 2.0], &[3.0, 4.0], &[5.0, 6.0]])
+    }
+
+    fn wide() -> SimpleMatrix<f64> {
+        matrix(&[&[1.0, 0.0, 2.0], &[0.0, 3.0, 4.0]])
+    }
+
+    fn deficient() -> SimpleMatrix<f64> {
+        // the third row is the sum of others
+        matrix(&[&[1.0, 2.0, 3.0], &[4.0, 5.0, 6.0], &[5.0, 7.0, 9.0]])
+    }
+
+    /// `lhs * rhs^T`, with the diagonal scale between them
+    fn product(lhs: &SimpleMatrix<f64>, scale: &[f64], rhs: &SimpleMatrix<f64>) -> Vec<Vec<f64>> {
+        (0..lhs.size().rows())
+            .map(|i| {
+                (0..rhs.size().rows())
+                    .map(|j| {
+                        (0..scale.len())
+                            .map(|k| element(lhs, i, k) * scale[k] * element(rhs, j, k))
+                            .sum()
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn assert_orthonormal_columns(mat: &SimpleMatrix<f64>) {
+        let gram = product(
+            &transposed(mat),
+            &vec![1.0; mat.size().rows()],
+            &transposed(mat),
+        );
+        for (i, row) in gram.iter().enumerate() {
+            for (j, value) in row.iter().enumerate() {
+                let expected = if i == j { 1.0 } else { 0.0 };
+                assert_relative_eq!(*value, expected, epsilon = 1e-10);
+            }
+        }
+    }
+
+    fn transposed(mat: &SimpleMatrix<f64>) -> SimpleMatrix<f64> {
+        let rows: Vec<Vec<f64>> = (0..mat.size().columns())
+            .map(|j| (0..mat.size().rows()).map(|i| element(mat, i, j)).collect())
+            .collect();
+        to_matrix(&rows).unwrap()
+    }
+
+    fn assert_same(actual: &[Vec<f64>], expected: &SimpleMatrix<f64>) {
+        for (i, row) in actual.iter().enumerate() {
+            for (j, value) in row.iter().enumerate() {
+                assert_relative_eq!(*value, element(expected, i, j), epsilon = 1e-10);
+            }
+        }
+    }
+
+    #[rstest]
+    #[case(tall(), 2)]
+    #[case(wide(), 2)]
+    #[case(deficient(), 2)]
+    fn test_qr_reconstructs_permuted_matrix(#[case] mat: SimpleMatrix<f64>, #[case] rank: usize) {
+        // Arrange
+        let k = mat.size().min_row_or_col();
+
+        // Act
+        let qr = qr(&mat).unwrap();
+
+        // Assert
+        assert_eq!(
+            qr.q().size(),
+            crate::matrix::size::Size::new(mat.size().rows(), k)
+        );
+        assert_eq!(
+            qr.r().size(),
+            crate::matrix::size::Size::new(k, mat.size().columns())
+        );
+        assert_orthonormal_columns(qr.q());
+        let reconstructed = product(qr.q(), &vec![1.0; k], &transposed(qr.r()));
+        for (j, column) in qr.permutation().iter().enumerate() {
+            for (i, row) in reconstructed.iter().enumerate() {
+                assert_relative_eq!(row[j], element(&mat, i, *column), epsilon = 1e-10);
+            }
+        }
+        for i in 0..k {
+            for j in 0..i {
+                assert_eq!(element(qr.r(), i, j), 0.0);
+            }
+        }
+        assert_eq!(qr.rank(1e-10), rank);
+    }
+
+    #[test]
+    fn test_qr_pivots_largest_column_first() {
+        // Arrange
+        let mat = matrix(&[&[1.0, 0.0, 10.0], &[0.0, 1.0, 0.0]]);
+
+        // Act
+        let qr = qr(&mat).unwrap();
+
+        // Assert
+        assert_eq!(qr.permutation()[0], 2);
+        assert_relative_eq!(element(qr.r(), 0, 0).abs(), 10.0, epsilon = 1e-12);
+    }
+
+    #[rstest]
+    #[case(tall(), 2)]
+    #[case(wide(), 2)]
+    #[case(deficient(), 2)]
+    fn test_svd_reconstructs_matrix(#[case] mat: SimpleMatrix<f64>, #[case] rank: usize) {
+        // Arrange
+        let k = mat.size().min_row_or_col();
+
+        // Act
+        let svd = svd(&mat).unwrap();
+
+        // Assert
+        assert_eq!(
+            svd.u().size(),
+            crate::matrix::size::Size::new(mat.size().rows(), k)
+        );
+        assert_eq!(
+            svd.v().size(),
+            crate::matrix::size::Size::new(mat.size().columns(), k)
+        );
+        assert_orthonormal_columns(svd.u());
+        assert_orthonormal_columns(svd.v());
+        assert_same(&product(svd.u(), svd.singular_values(), svd.v()), &mat);
+        assert!(
+            svd.singular_values()
+                .windows(2)
+                .all(|pair| pair[0] >= pair[1])
+        );
+        assert_eq!(svd.rank(1e-10), rank);
+    }
+
+    #[test]
+    fn test_svd_of_diagonal_matrix_has_sorted_absolute_diagonal() {
+        // Arrange
+        let mat = matrix(&[&[2.0, 0.0], &[0.0, -5.0]]);
+
+        // Act
+        let svd = svd(&mat).unwrap();
+
+        // Assert
+        assert_relative_eq!(svd.singular_values()[0], 5.0, epsilon = 1e-12);
+        assert_relative_eq!(svd.singular_values()[1], 2.0, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_decompose_sparse_matrix() {
+        // Arrange
+        let sparse = SparseMatrix::from_matrix(&deficient());
+
+        // Act
+        let qr = qr(&sparse).unwrap();
+        let svd = svd(&sparse).unwrap();
+
+        // Assert
+        assert_eq!(qr.rank(1e-10), 2);
+        assert_eq!(svd.rank(1e-10), 2);
+        assert_same(
+            &product(svd.u(), svd.singular_values(), svd.v()),
+            &deficient(),
+        );
+    }
+
+    #[rstest]
+    #[case(tall(), 2)]
+    #[case(wide(), 2)]
+    #[case(deficient(), 2)]
+    #[case(matrix(&[&[0.0, 0.0], &[0.0, 0.0]]), 0)]
+    #[case(matrix(&[&[1.0, 0.0], &[0.0, 1e-20]]), 1)]
+    fn test_rank_with_default_tolerance(#[case] mat: SimpleMatrix<f64>, #[case] expected: usize) {
+        // Arrange - inputs provided by rstest
+
+        // Act
+        let result = rank(&mat, None).unwrap();
+
+        // Assert
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_rank_with_loose_tolerance_ignores_small_singular_value() {
+        // Arrange
+        let mat = matrix(&[&[1.0, 0.0], &[0.0, 1e-6]]);
+
+        // Act
+        let strict = rank(&mat, None).unwrap();
+        let loose = rank(&mat, Some(1e-3)).unwrap();
+
+        // Assert
+        assert_eq!((strict, loose), (2, 1));
+    }
+
+    #[rstest]
+    #[case(matrix(&[&[1.0, 0.0], &[0.0, 1.0]]), 1.0)]
+    #[case(matrix(&[&[1.0, 0.0], &[0.0, 10.0]]), 10.0)]
+    #[case(matrix(&[&[3.0], &[4.0]]), 1.0)]
+    #[case(deficient(), f64::INFINITY)]
+    fn test_condition_number(#[case] mat: SimpleMatrix<f64>, #[case] expected: f64) {
+        // Arrange - inputs provided by rstest
+
+        // Act
+        let result = condition_number(&mat).unwrap();
+
+        // Assert
+        if expected.is_infinite() {
+            assert!(result > 1e12, "{result}");
+        } else {
+            assert_relative_eq!(result, expected, epsilon = 1e-10);
+        }
+    }
+}
@@ -0,0 +1,475 @@
+use std::collections::BTreeSet;
+
+use color_eyre::eyre::{Result, eyre};
+
+use crate::{matrix::Matrix, matrix::sparse::SparseMatrix, vector::Vector};
+
+/// Pivots smaller than this are treated as zero, same as dense solve
+const SINGULAR_PIVOT: f64 = 1e-10;
+
+/// Pivot row of the previous factorisation is kept while its value is larger than this ratio of
+/// the largest candidate.
+const PIVOT_THRESHOLD: f64 = 0.1;
+
+/// Sparse LU factorisation of square matrices, `P * A * Q = L * U`.
+///
+/// This keeps the symbolic part of the factorisation, that is the fill-reducing column ordering
+/// and pivot rows of the last numeric factorisation. They are reused while the sparsity pattern of
+/// matrices does not change, such as jacobians across Newton iterations.
+#[derive(Debug, Clone)]
+pub struct SparseLu {
+    size: usize,
+
+    /// Non-zero pattern of the analysed matrix, as column indices of each row
+    pattern: Vec<Vec<usize>>,
+
+    /// Column ordering `Q`. `columns[k]` is the column eliminated at step `k`
+    columns: Vec<usize>,
+
+    /// Pivot rows of the last factorisation
+    pivots: Option<Vec<usize>>,
+}
+
+/// Result of numeric factorisation
+#[derive(Debug, Clone)]
+pub enum Factorization {
+    Singular,
+    Factorized(LuFactors),
+}
+
+/// Numeric factors of [`SparseLu`]
+#[derive(Debug, Clone)]
+pub struct LuFactors {
+    /// Column ordering
+    columns: Vec<usize>,
+
+    /// `pivots[k]` is the row eliminated at step `k`
+    pivots: Vec<usize>,
+
+    /// Columns of unit lower triangular matrix, as original row and value
+    lower: Vec<Vec<(usize, f64)>>,
+
+    /// Columns of strictly upper triangular matrix, as step and value
+    upper: Vec<Vec<(usize, f64)>>,
+
+    /// Diagonal of upper triangular matrix
+    diagonal: Vec<f64>,
+}
+
+impl SparseLu {
+    /// Analyse the sparsity pattern of the matrix, and make the fill-reducing ordering.
+    ///
+    /// Columns are ordered by minimum degree of `A^T * A`, so the ordering does not depend on
+    /// row pivots chosen in numeric factorisation.
+    pub fn analyze<M: Clone + std::fmt::Debug>(mat: &SparseMatrix<M>) -> Result<Self> {
+        let size = mat.size();
+        if size.rows() != size.columns() {
+            return Err(eyre!("Can not factorize non-square matrix: {:?}", size));
+        }
+
+        let pattern: Vec<Vec<usize>> = (0..size.rows())
+            .map(|r| mat.row_entries(r).map(|(c, _)| c).collect())
+            .collect();
+
+        Ok(SparseLu {
+            size: size.rows(),
+            columns: minimum_degree(size.columns(), &pattern),
+            pattern,
+            pivots: None,
+        })
+    }
+
+    /// Return `true` if the matrix has the same sparsity pattern as the analysed one
+    pub fn matches<M: Clone + std::fmt::Debug>(&self, mat: &SparseMatrix<M>) -> bool {
+        mat.size().rows() == self.size
+            && mat.size().columns() == self.size
+            && self
+                .pattern
+                .iter()
+                .enumerate()
+                .all(|(r, row)| mat.row_entries(r).map(|(c, _)| c).eq(row.iter().copied()))
+    }
+
+    /// Factorize the matrix numerically with the analysed ordering.
+    ///
+    /// Pivot rows of the previous factorisation are preferred while they are stable enough, so
+    /// refactorisation of matrices that change slightly keeps the same structure.
+    ///
+    /// # Returns
+    /// * Factors, or [`Factorization::Singular`] when the matrix is singular. Error when the pattern
+    ///   of the matrix differs from the analysed one.
+    pub fn factorize(&mut self, mat: &SparseMatrix<f64>) -> Result<Factorization> {
+        if !self.matches(mat) {
+            return Err(eyre!("Sparsity pattern differs from the analysed matrix"));
+        }
+
+        let n = self.size;
+        let mut matrix_columns: Vec<Vec<(usize, f64)>> = vec![vec![]; n];
+        for r in 0..n {
+            for (c, v) in mat.row_entries(r) {
+                matrix_columns[c].push((r, *v));
+            }
+        }
+
+        let mut steps: Vec<Option<usize>> = vec![None; n];
+        let mut pivots = Vec::with_capacity(n);
+        let mut lower: Vec<Vec<(usize, f64)>> = Vec::with_capacity(n);
+        let mut upper: Vec<Vec<(usize, f64)>> = Vec::with_capacity(n);
+        let mut diagonal = Vec::with_capacity(n);
+
+        // work space shared by each step
+        let mut x = vec![0.0_f64; n];
+        let mut touched_at = vec![usize::MAX; n];
+        let mut reached_at = vec![usize::MAX; n];
+
+        for k in 0..n {
+            let column = &matrix_columns[self.columns[k]];
+
+            // steps that update this column. An update from step `j` only affects rows pivoted
+            // after `j`, so ascending order of steps is a topological order.
+            let mut reached = vec![];
+            let mut stack: Vec<usize> = column.iter().filter_map(|(r, _)| steps[*r]).collect();
+            while let Some(j) = stack.pop() {
+                if reached_at[j] == k {
+                    continue;
+                }
+                reached_at[j] = k;
+                reached.push(j);
+                stack.extend(lower[j].iter().filter_map(|(r, _)| steps[*r]));
+            }
+            reached.sort_unstable();
+
+            let mut touched = vec![];
+            for (r, v) in column {
+                x[*r] = *v;
+                touched_at[*r] = k;
+                touched.push(*r);
+            }
+            for j in &reached {
+                for (r, _) in &lower[*j] {
+                    if touched_at[*r] != k {
+                        touched_at[*r] = k;
+                        x[*r] = 0.0;
+                        touched.push(*r);
+                    }
+                }
+            }
+
+            // sparse triangular solve with computed columns of L
+            let mut upper_column = Vec::with_capacity(reached.len());
+            for j in reached {
+                let xj = x[pivots[j]];
+                if xj == 0.0 {
+                    continue;
+                }
+                upper_column.push((j, xj));
+                for (r, l) in &lower[j] {
+                    x[*r] -= l * xj;
+                }
+            }
+
+            let candidates: Vec<usize> = touched
+                .iter()
+                .copied()
+                .filter(|r| steps[*r].is_none())
+                .collect();
+            let Some(pivot) = self.choose_pivot(k, &candidates, &x) else {
+                return Ok(Factorization::Singular);
+            };
+
+            let value = x[pivot];
+            steps[pivot] = Some(k);
+            pivots.push(pivot);
+            diagonal.push(value);
+            upper.push(upper_column);
+            lower.push(
+                candidates
+                    .into_iter()
+                    .filter(|r| *r != pivot && x[*r] != 0.0)
+                    .map(|r| (r, x[r] / value))
+                    .collect(),
+            );
+        }
+
+        self.pivots = Some(pivots.clone());
+
+        Ok(Factorization::Factorized(LuFactors {
+            columns: self.columns.clone(),
+            pivots,
+            lower,
+            upper,
+            diagonal,
+        }))
+    }
+
+    /// Choose the pivot row of the step from candidates. `None` if all candidates are too small.
+    fn choose_pivot(&self, step: usize, candidates: &[usize], x: &[f64]) -> Option<usize> {
+        let largest = candidates
+            .iter()
+            .copied()
+            .max_by(|a, b| x[*a].abs().total_cmp(&x[*b].abs()))?;
+        let max_value = x[largest].abs();
+        if max_value < SINGULAR_PIVOT {
+            return None;
+        }
+
+        let previous = self.pivots.as_ref().map(|p| p[step]);
+        match previous {
+            Some(row)
+                if candidates.contains(&row) && x[row].abs() >= PIVOT_THRESHOLD * max_value =>
+            {
+                Some(row)
+            }
+            _ => Some(largest),
+        }
+    }
+}
+
+impl LuFactors {
+    /// Solve `A * x = factors` with the factors
+    pub fn solve(&self, factors: &Vector) -> Result<Vector> {
+        let n = self.diagonal.len();
+        if factors.len() != n {
+            return Err(eyre!(
+                "Can not solve with different size of vector: {} / {}",
+                factors.len(),
+                n
+            ));
+        }
+
+        let mut y: Vec<f64> = (0..n).map(|i| factors[i]).collect();
+        let mut z = vec![0.0_f64; n];
+
+        // forward substitution with L
+        for k in 0..n {
+            let v = y[self.pivots[k]];
+            z[k] = v;
+            for (r, l) in &self.lower[k] {
+                y[*r] -= l * v;
+            }
+        }
+
+        // backward substitution with U
+        for k in (0..n).rev() {
+            z[k] /= self.diagonal[k];
+            let zk = z[k];
+            for (j, u) in &self.upper[k] {
+                z[*j] -= u * zk;
+            }
+        }
+
+        let mut x = vec![0.0_f64; n];
+        for (k, c) in self.columns.iter().enumerate() {
+            x[*c] = z[k];
+        }
+
+        Vector::from(&x)
+    }
+
+    /// Number of non-zero elements in L and U
+    pub fn non_zeros(&self) -> usize {
+        self.lower.iter().map(|c| c.len()).sum::<usize>()
+            + self.upper.iter().map(|c| c.len()).sum::<usize>()
+            + self.diagonal.len()
+    }
+}
+
+/// Get the minimum degree ordering of columns, with the graph of `A^T * A`.
+///
+/// Dense rows are ignored as COLAMD does, because they connect all of their columns and hide
+/// the structure of other rows.
+fn minimum_degree(columns: usize, pattern: &[Vec<usize>]) -> Vec<usize> {
+    let dense = ((10.0 * (columns as f64).sqrt()) as usize).max(16);
+
+    let mut adjacency: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); columns];
+    for row in pattern.iter().filter(|r| r.len() <= dense) {
+        for a in row {
+            adjacency[*a].extend(row.iter().filter(|b| *b != a));
+        }
+    }
+
+    let mut eliminated = vec![false; columns];
+    let mut order = Vec::with_capacity(columns);
+
+    for _ in 0..columns {
+        let Some(v) = (0..columns)
+            .filter(|c| !eliminated[*c])
+            .min_by_key(|c| adjacency[*c].len())
+        else {
+            break;
+        };
+
+        eliminated[v] = true;
+        order.push(v);
+
+        // eliminating the column makes a clique of its neighbours
+        let neighbours: Vec<usize> = std::mem::take(&mut adjacency[v]).into_iter().collect();
+        for a in &neighbours {
+            adjacency[*a].remove(&v);
+            adjacency[*a].extend(neighbours.iter().filter(|b| *b != a));
+        }
+    }
+
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matrix::{op, simple::SimpleMatrix};
+    use approx::assert_relative_eq;
+    use color_eyre::eyre::Result;
+    use pretty_assertions::assert_eq;
+
+    fn sparse(rows: &[&[f64]]) -> Result<SparseMatrix<f64>> {
+        let mut mat = SimpleMatrix::<f64>::new(rows.len(), rows[0].len())?;
+        for (i, row) in rows.iter().enumerate() {
+            for (j, v) in row.iter().enumerate() {
+                if *v != 0.0 {
+                    mat.set(i, j, *v)?;
+                }
+            }
+        }
+
+        Ok(SparseMatrix::from_matrix(&mat))
+    }
+
+    fn factorize(lu: &mut SparseLu, mat: &SparseMatrix<f64>) -> Result<LuFactors> {
+        match lu.factorize(mat)? {
+            Factorization::Factorized(factors) => Ok(factors),
+            Factorization::Singular => Err(eyre!("should not be singular")),
+        }
+    }
+
+    #[test]
+    fn test_solve_same_as_dense_solve() -> Result<()> {
+        // Arrange
+        let mat = sparse(&[
+            &[0.0, 2.0, 0.0, 1.0],
+            &[3.0, 0.0, 0.0, 0.0],
+            &[1.0, -1.0, 4.0, 0.0],
+            &[0.0, 0.0, 2.0, -5.0],
+        ])?;
+        let b = Vector::from(&[1.0, 2.0, 3.0, 4.0])?;
+        let mut lu = SparseLu::analyze(&mat)?;
+
+        // Act
+        let result = factorize(&mut lu, &mat)?.solve(&b)?;
+
+        // Assert
+        let op::Solve::Solved(expected) = op::solve(&mat, &b)? else {
+            return Err(eyre!("should be solved"));
+        };
+        for i in 0..4 {
+            assert_relative_eq!(result[i], expected[i], epsilon = 1e-5);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_factorize_needs_pivoting() -> Result<()> {
+        // Arrange
+        let mat = sparse(&[&[0.0, 1.0], &[1.0, 0.0]])?;
+        let b = Vector::from(&[2.0, 3.0])?;
+        let mut lu = SparseLu::analyze(&mat)?;
+
+        // Act
+        let result = factorize(&mut lu, &mat)?.solve(&b)?;
+
+        // Assert
+        assert_eq!(result, Vector::from(&[3.0, 2.0])?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_factorize_singular_matrix() -> Result<()> {
+        // Arrange
+        let mat = sparse(&[&[1.0, 2.0, 0.0], &[2.0, 4.0, 0.0], &[0.0, 1.0, 1.0]])?;
+        let mut lu = SparseLu::analyze(&mat)?;
+
+        // Act
+        let result = lu.factorize(&mat)?;
+
+        // Assert
+        assert!(matches!(result, Factorization::Singular));
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_fails_with_non_square_matrix() -> Result<()> {
+        // Arrange
+        let mat = sparse(&[&[1.0, 2.0, 0.0], &[2.0, 4.0, 1.0]])?;
+
+        // Act
+        let result = SparseLu::analyze(&mat);
+
+        // Assert
+        assert!(result.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_refactorize_with_same_pattern_reuses_analysis() -> Result<()> {
+        // Arrange
+        let first = sparse(&[&[4.0, 1.0, 0.0], &[1.0, 4.0, 1.0], &[0.0, 1.0, 4.0]])?;
+        let second = sparse(&[&[5.0, 2.0, 0.0], &[1.0, 3.0, 1.0], &[0.0, 2.0, 6.0]])?;
+        let b = Vector::from(&[1.0, 1.0, 1.0])?;
+        let mut lu = SparseLu::analyze(&first)?;
+        let first_factors = factorize(&mut lu, &first)?;
+
+        // Act
+        let second_factors = factorize(&mut lu, &second)?;
+        let result = second_factors.solve(&b)?;
+
+        // Assert
+        assert_eq!(first_factors.pivots, second_factors.pivots);
+        let op::Solve::Solved(expected) = op::solve(&second, &b)? else {
+            return Err(eyre!("should be solved"));
+        };
+        for i in 0..3 {
+            assert_relative_eq!(result[i], expected[i], epsilon = 1e-5);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_factorize_fails_with_different_pattern() -> Result<()> {
+        // Arrange
+        let first = sparse(&[&[4.0, 1.0], &[0.0, 4.0]])?;
+        let second = sparse(&[&[4.0, 0.0], &[1.0, 4.0]])?;
+        let mut lu = SparseLu::analyze(&first)?;
+
+        // Act
+        let result = lu.factorize(&second);
+
+        // Assert
+        assert!(!lu.matches(&second));
+        assert!(result.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_minimum_degree_avoids_fill_of_arrow_matrix() -> Result<()> {
+        // Arrange
+        // arrow matrix has dense first row and column. Eliminating it first fills whole matrix.
+        let n = 200;
+        let mut rows = vec![vec![0.0_f64; n]; n];
+        rows[0].fill(1.0);
+        for (i, row) in rows.iter_mut().enumerate() {
+            row[0] = 1.0;
+            row[i] = 4.0 + i as f64;
+        }
+        let rows: Vec<&[f64]> = rows.iter().map(|r| r.as_slice()).collect();
+        let mat = sparse(&rows)?;
+        let mut lu = SparseLu::analyze(&mat)?;
+
+        // Act
+        let factors = factorize(&mut lu, &mat)?;
+
+        // Assert
+        // dense column is eliminated at last, with the last remaining column
+        assert!(!lu.columns[..n - 2].contains(&0));
+        assert!(factors.non_zeros() <= 3 * n);
+        Ok(())
+    }
+}
@@ -2,7 +2,9 @@ use color_eyre::eyre::Result;
 
 use size::Size;
 
-pub(crate) mod op;
+pub mod decompose;
+pub mod lu;
+pub mod op;
 pub mod simple;
 pub mod size;
 pub mod sparse;
@@ -53,16 +55,16 @@ where
 }
 
 pub trait MatrixExtract<Element> {
-    /// Extract a matrix of f32 from this matrix
+    /// Extract a matrix of f64 from this matrix
     ///
-    /// This function to use specialized math function for f32, such as determinant calculation.
+    /// This function to use specialized math function for f64, such as determinant calculation.
     ///
     /// # Arguments
-    /// * `extract` - a function to extract `f32` from the element.
+    /// * `extract` - a function to extract `f64` from the element.
     ///
     /// # Returns
-    /// * Return a new matrix of f32
-    fn extract<T>(&self, extract: T) -> impl Matrix<f32>
+    /// * Return a new matrix of f64
+    fn extract<T>(&self, extract: T) -> impl Matrix<f64>
     where
-        T: Fn(&Element) -> f32;
+        T: Fn(&Element) -> f64;
 }
@@ -4,17 +4,22 @@ use std::ops::{Add, Mul};
 use color_eyre::eyre::Result;
 
 use crate::{
-    matrix::{Matrix, simple::SimpleMatrix},
-    vector::Vector,
+    matrix::{
+        Matrix,
+        lu::{Factorization, SparseLu},
+        simple::SimpleMatrix,
+        sparse::SparseMatrix,
+    },
+    vector::{TransposeMethod, Vector},
 };
 
 /// Multiply operation between matrix
-pub fn mul<M: std::fmt::Debug, T: Matrix<M>, U: Matrix<M>>(
+pub fn mul<M, T: Matrix<M>, U: Matrix<M>>(
     lhs: &T,
     rhs: &U,
 ) -> Result<impl Matrix<M> + use<M, T, U>, color_eyre::eyre::Error>
 where
-    M: Add<Output = M> + Mul<Output = M> + Default + Copy,
+    M: Add<Output = M> + Mul<Output = M> + Default + Copy + std::fmt::Debug,
 {
     if lhs.size().columns() != rhs.size().rows() {
         return Err(color_eyre::eyre::eyre!(
@@ -49,7 +54,7 @@ pub(crate) enum Solve {
 }
 
 /// Solve the matrix and return result as vector. If can not solve the matrix (when all values in the column are 0), Err with error.
-pub(crate) fn solve<M: Matrix<f32>>(mat: &M, factors: &Vector) -> Result<Solve> {
+pub(crate) fn solve<M: Matrix<f64>>(mat: &M, factors: &Vector) -> Result<Solve> {
     let mut mat = SimpleMatrix::from_matrix(mat);
     let mut factors = factors.clone();
 
@@ -57,8 +62,9 @@ pub(crate) fn solve<M: Matrix<f32>>(mat: &M, factors: &Vector) -> Result<Solve>
     for k in 0..mat.size().rows() {
         let kv = mat.get(k, k)?.copied();
 
-        // pivotting
-        let mut max_value = kv.unwrap_or(0.0).abs();
+        // pivotting. keep the sign of the pivot, because the row is normalized with it even if
+        // rows are not swapped.
+        let mut max_value = kv.unwrap_or(0.0);
         let mut max_index = k;
 
         for i in (k + 1)..mat.size().rows() {
@@ -136,34 +142,117 @@ pub(crate) fn solve<M: Matrix<f32>>(mat: &M, factors: &Vector) -> Result<Solve>
     Ok(Solve::Solved(factors))
 }
 
+/// Solve the square matrix with sparse LU factorisation.
+///
+/// `lu` keeps the analysis of the matrix. It is made when `None` or the sparsity pattern of `mat`
+/// differs from the analysed one, otherwise reused.
+pub(crate) fn solve_sparse(
+    lu: &mut Option<SparseLu>,
+    mat: &SparseMatrix<f64>,
+    factors: &Vector,
+) -> Result<Solve> {
+    let analysis = match lu.take() {
+        Some(analysis) if analysis.matches(mat) => lu.insert(analysis),
+        _ => lu.insert(SparseLu::analyze(mat)?),
+    };
+
+    match analysis.factorize(mat)? {
+        Factorization::Singular => Ok(Solve::Singular),
+        Factorization::Factorized(decomposed) => Ok(Solve::Solved(decomposed.solve(factors)?)),
+    }
+}
+
+/// Get the transposed matrix
+pub(crate) fn transpose<M: Clone + std::fmt::Debug>(
+    mat: &impl Matrix<M>,
+) -> Result<SimpleMatrix<M>> {
+    let mut ret = SimpleMatrix::new(mat.size().columns(), mat.size().rows())?;
+
+    for i in 0..mat.size().rows() {
+        for j in 0..mat.size().columns() {
+            if let Some(v) = mat.get(i, j)? {
+                ret.set(j, i, v.clone())?;
+            }
+        }
+    }
+
+    Ok(ret)
+}
+
+/// Solve damped least squares problem, that minimizes `|mat * x - factors|^2 + damping * |x|^2`.
+///
+/// When `mat` has more columns than rows, this solves `x = mat^T (mat mat^T + damping I)^-1 factors`,
+/// so the result is the minimum norm solution when `damping` goes to 0.
+pub(crate) fn damped_least_squares<M: Matrix<f64>>(
+    mat: &M,
+    factors: &Vector,
+    damping: f64,
+) -> Result<Solve> {
+    let transposed = transpose(mat)?;
+
+    // make normal matrix with smaller dimension
+    let under_determined = mat.size().rows() < mat.size().columns();
+    let mut normal = if under_determined {
+        SimpleMatrix::from_matrix(&mul(mat, &transposed)?)
+    } else {
+        SimpleMatrix::from_matrix(&mul(&transposed, mat)?)
+    };
+
+    for i in 0..normal.size().rows() {
+        let v = normal.get(i, i)?.copied().unwrap_or(0.0);
+        normal.set(i, i, v + damping)?;
+    }
+
+    if under_determined {
+        let Solve::Solved(y) = solve(&normal, factors)? else {
+            return Ok(Solve::Singular);
+        };
+
+        Ok(Solve::Solved(mul_vector(&transposed, &y)?))
+    } else {
+        solve(&normal, &mul_vector(&transposed, factors)?)
+    }
+}
+
+/// Multiply matrix and column vector
+fn mul_vector(mat: &impl Matrix<f64>, vector: &Vector) -> Result<Vector> {
+    let column = mul(mat, &vector.to_matrix(TransposeMethod::Column))?;
+
+    let values = (0..column.size().rows())
+        .map(|i| Ok(column.get(i, 0)?.copied().unwrap_or(0.0)))
+        .collect::<Result<Vec<_>>>()?;
+
+    Vector::from(&values)
+}
+
 /// New type for LU Splitted matrix to reuse
 pub struct LUSplit {
-    l_matrix: SimpleMatrix<f32>,
-    u_matrix: SimpleMatrix<f32>,
+    l_matrix: SimpleMatrix<f64>,
+    u_matrix: SimpleMatrix<f64>,
 }
 
 impl LUSplit {
     /// Get Left Triangle Matrix
-    pub fn l(&self) -> &SimpleMatrix<f32> {
+    pub fn l(&self) -> &SimpleMatrix<f64> {
         &self.l_matrix
     }
 
     /// Get Upper Triangle Matrix
-    pub fn u(&self) -> &SimpleMatrix<f32> {
+    pub fn u(&self) -> &SimpleMatrix<f64> {
         &self.u_matrix
     }
 }
 
 /// Implemetation for LU split algorithm
-pub fn lu_split(mat: &impl Matrix<f32>) -> Result<LUSplit> {
+pub fn lu_split(mat: &impl Matrix<f64>) -> Result<LUSplit> {
     if mat.size().rows() != mat.size().columns() {
         return Err(color_eyre::eyre::eyre!(
             "can not make the LU split without exponent matrix"
         ));
     }
 
-    let mut l = SimpleMatrix::<f32>::new(mat.size().rows(), mat.size().columns())?;
-    let mut u = SimpleMatrix::<f32>::new(mat.size().rows(), mat.size().columns())?;
+    let mut l = SimpleMatrix::<f64>::new(mat.size().rows(), mat.size().columns())?;
+    let mut u = SimpleMatrix::<f64>::new(mat.size().rows(), mat.size().columns())?;
     let n = mat.size().min_row_or_col();
 
     // initialize L/U matrix
@@ -208,7 +297,7 @@ pub fn lu_split(mat: &impl Matrix<f32>) -> Result<LUSplit> {
 }
 
 /// Get the determinant when it is square matrix and defined
-pub fn determinant(mat: &impl Matrix<f32>) -> Option<f32> {
+pub fn determinant(mat: &impl Matrix<f64>) -> Option<f64> {
     if let Ok(splited) = lu_split(mat) {
         let u = splited.u();
 
@@ -257,17 +346,17 @@ mod tests {
         // Assert
         assert_eq!(result.size().rows(), 2);
         assert_eq!(result.size().columns(), 2);
-        assert_eq!(result.get(0, 0)?.map(|v| *v), Some(58));
-        assert_eq!(result.get(0, 1)?.map(|v| *v), Some(64));
-        assert_eq!(result.get(1, 0)?.map(|v| *v), Some(139));
-        assert_eq!(result.get(1, 1)?.map(|v| *v), Some(154));
+        assert_eq!(result.get(0, 0)?.copied(), Some(58));
+        assert_eq!(result.get(0, 1)?.copied(), Some(64));
+        assert_eq!(result.get(1, 0)?.copied(), Some(139));
+        assert_eq!(result.get(1, 1)?.copied(), Some(154));
         Ok(())
     }
 
     #[test]
-    fn test_mul_with_f32_matrices() -> Result<()> {
+    fn test_mul_with_f64_matrices() -> Result<()> {
         // Arrange
-        let mut lhs = SimpleMatrix::<f32>::new(2, 3)?;
+        let mut lhs = SimpleMatrix::<f64>::new(2, 3)?;
         lhs.set(0, 0, 1.5)?;
         lhs.set(0, 1, 2.5)?;
         lhs.set(0, 2, 3.5)?;
@@ -275,7 +364,7 @@ mod tests {
         lhs.set(1, 1, 5.5)?;
         lhs.set(1, 2, 6.5)?;
 
-        let mut rhs = SimpleMatrix::<f32>::new(3, 2)?;
+        let mut rhs = SimpleMatrix::<f64>::new(3, 2)?;
         rhs.set(0, 0, 1.0)?;
         rhs.set(0, 1, 2.0)?;
         rhs.set(1, 0, 3.0)?;
@@ -289,10 +378,10 @@ mod tests {
         // Assert
         assert_eq!(result.size().rows(), 2);
         assert_eq!(result.size().columns(), 2);
-        assert_eq!(result.get(0, 0)?.map(|v| *v), Some(26.5));
-        assert_eq!(result.get(0, 1)?.map(|v| *v), Some(34.0));
-        assert_eq!(result.get(1, 0)?.map(|v| *v), Some(53.5));
-        assert_eq!(result.get(1, 1)?.map(|v| *v), Some(70.0));
+        assert_eq!(result.get(0, 0)?.copied(), Some(26.5));
+        assert_eq!(result.get(0, 1)?.copied(), Some(34.0));
+        assert_eq!(result.get(1, 0)?.copied(), Some(53.5));
+        assert_eq!(result.get(1, 1)?.copied(), Some(70.0));
         Ok(())
     }
 
@@ -316,7 +405,7 @@ mod tests {
         // Matrix: | 1  2 |
         //         | 3  4 |
         // det = 1*4 - 2*3 = -2
-        let mut matrix = SimpleMatrix::<f32>::new(2, 2)?;
+        let mut matrix = SimpleMatrix::<f64>::new(2, 2)?;
         matrix.set(0, 0, 1.0)?;
         matrix.set(0, 1, 2.0)?;
         matrix.set(1, 0, 3.0)?;
@@ -340,7 +429,7 @@ mod tests {
         //     = 1*(45-48) - 2*(36-42) + 3*(32-35)
         //     = 1*(-3) - 2*(-6) + 3*(-3)
         //     = -3 + 12 - 9 = 0
-        let mut matrix = SimpleMatrix::<f32>::new(3, 3)?;
+        let mut matrix = SimpleMatrix::<f64>::new(3, 3)?;
         matrix.set(0, 0, 1.0)?;
         matrix.set(0, 1, 2.0)?;
         matrix.set(0, 2, 3.0)?;
@@ -363,7 +452,7 @@ mod tests {
     fn test_determinant_identity_matrix() -> Result<()> {
         // Arrange
         // Identity matrix has determinant = 1
-        let mut matrix = SimpleMatrix::<f32>::new(3, 3)?;
+        let mut matrix = SimpleMatrix::<f64>::new(3, 3)?;
         matrix.set(0, 0, 1.0)?;
         matrix.set(1, 1, 1.0)?;
         matrix.set(2, 2, 1.0)?;
@@ -387,7 +476,7 @@ mod tests {
         #[case] description: &str,
     ) -> Result<()> {
         // Arrange
-        let matrix = SimpleMatrix::<f32>::new(rows, cols)?;
+        let matrix = SimpleMatrix::<f64>::new(rows, cols)?;
 
         // Act
         let det = determinant(&matrix);
@@ -414,7 +503,7 @@ mod tests {
         // det = 2*(3*2 - 0*0) - 0*(0*2 - 0*1) + 1*(0*0 - 3*1)
         //     = 2*6 - 0 + 1*(-3)
         //     = 12 - 3 = 9
-        let mut source = SimpleMatrix::<f32>::new(3, 3)?;
+        let mut source = SimpleMatrix::<f64>::new(3, 3)?;
         source.set(0, 0, 2.0)?;
         source.set(0, 2, 1.0)?;
         source.set(1, 1, 3.0)?;
@@ -435,7 +524,7 @@ mod tests {
         // Arrange
         // Matrix A (2x2):  | 3.0  4.0 |
         //                  | 5.0  6.0 |
-        let mut matrix = SimpleMatrix::<f32>::new(2, 2)?;
+        let mut matrix = SimpleMatrix::<f64>::new(2, 2)?;
         matrix.set(0, 0, 3.0)?;
         matrix.set(0, 1, 4.0)?;
         matrix.set(1, 0, 5.0)?;
@@ -443,7 +532,7 @@ mod tests {
 
         // Identity matrix (2x2): | 1.0  0.0 |
         //                         | 0.0  1.0 |
-        let mut identity = SimpleMatrix::<f32>::new(2, 2)?;
+        let mut identity = SimpleMatrix::<f64>::new(2, 2)?;
         identity.set(0, 0, 1.0)?;
         identity.set(1, 1, 1.0)?;
 
@@ -452,10 +541,10 @@ mod tests {
 
         // Assert
         // Result should equal original matrix
-        assert_eq!(result.get(0, 0)?.map(|v| *v), Some(3.0));
-        assert_eq!(result.get(0, 1)?.map(|v| *v), Some(4.0));
-        assert_eq!(result.get(1, 0)?.map(|v| *v), Some(5.0));
-        assert_eq!(result.get(1, 1)?.map(|v| *v), Some(6.0));
+        assert_eq!(result.get(0, 0)?.copied(), Some(3.0));
+        assert_eq!(result.get(0, 1)?.copied(), Some(4.0));
+        assert_eq!(result.get(1, 0)?.copied(), Some(5.0));
+        assert_eq!(result.get(1, 1)?.copied(), Some(6.0));
         Ok(())
     }
 
@@ -465,7 +554,7 @@ mod tests {
         // Matrix A (2x3) with some None values:
         //   | 2.0  None  3.0 |
         //   | None 5.0   None|
-        let mut lhs = SimpleMatrix::<f32>::new(2, 3)?;
+        let mut lhs = SimpleMatrix::<f64>::new(2, 3)?;
         lhs.set(0, 0, 2.0)?;
         lhs.set(0, 2, 3.0)?;
         lhs.set(1, 1, 5.0)?;
@@ -474,7 +563,7 @@ mod tests {
         //   | 1.0  None |
         //   | 4.0  2.0  |
         //   | None 3.0  |
-        let mut rhs = SimpleMatrix::<f32>::new(3, 2)?;
+        let mut rhs = SimpleMatrix::<f64>::new(3, 2)?;
         rhs.set(0, 0, 1.0)?;
         rhs.set(1, 0, 4.0)?;
         rhs.set(1, 1, 2.0)?;
@@ -488,20 +577,20 @@ mod tests {
         //   | 2.0*1.0 + 0 + 0           0 + 0 + 3.0*3.0      | = | 2.0  9.0  |
         //   | 0 + 5.0*4.0 + 0           0 + 5.0*2.0 + 0      |   | 20.0 10.0 |
         assert_eq!(result.size(), Size::new(2, 2));
-        assert_eq!(result.get(0, 0)?.map(|v| *v), Some(2.0));
-        assert_eq!(result.get(0, 1)?.map(|v| *v), Some(9.0));
-        assert_eq!(result.get(1, 0)?.map(|v| *v), Some(20.0));
-        assert_eq!(result.get(1, 1)?.map(|v| *v), Some(10.0));
+        assert_eq!(result.get(0, 0)?.copied(), Some(2.0));
+        assert_eq!(result.get(0, 1)?.copied(), Some(9.0));
+        assert_eq!(result.get(1, 0)?.copied(), Some(20.0));
+        assert_eq!(result.get(1, 1)?.copied(), Some(10.0));
         Ok(())
     }
 
     #[test]
     fn test_mul_with_single_element_matrices() -> Result<()> {
         // Arrange
-        let mut lhs = SimpleMatrix::<f32>::new(1, 1)?;
+        let mut lhs = SimpleMatrix::<f64>::new(1, 1)?;
         lhs.set(0, 0, 5.0)?;
 
-        let mut rhs = SimpleMatrix::<f32>::new(1, 1)?;
+        let mut rhs = SimpleMatrix::<f64>::new(1, 1)?;
         rhs.set(0, 0, 3.0)?;
 
         // Act
@@ -509,7 +598,7 @@ mod tests {
 
         // Assert
         assert_eq!(result.size(), Size::new(1, 1));
-        assert_eq!(result.get(0, 0)?.map(|v| *v), Some(15.0));
+        assert_eq!(result.get(0, 0)?.copied(), Some(15.0));
         Ok(())
     }
 
@@ -520,7 +609,7 @@ mod tests {
         //   x + 2y = 5
         //   3x + 4y = 11
         // Expected solution: x = 1, y = 2
-        let mut matrix = SimpleMatrix::<f32>::new(2, 2)?;
+        let mut matrix = SimpleMatrix::<f64>::new(2, 2)?;
         matrix.set(0, 0, 1.0)?;
         matrix.set(0, 1, 2.0)?;
         matrix.set(1, 0, 3.0)?;
@@ -540,6 +629,58 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_solve_with_negative_pivot_without_row_swap() -> Result<()> {
+        // Arrange
+        // System of equations:
+        //   -2x + y = 0
+        //    x + 3y = 7
+        // Expected solution: x = 1, y = 2
+        let mut matrix = SimpleMatrix::<f64>::new(2, 2)?;
+        matrix.set(0, 0, -2.0)?;
+        matrix.set(0, 1, 1.0)?;
+        matrix.set(1, 0, 1.0)?;
+        matrix.set(1, 1, 3.0)?;
+
+        let factors = Vector::from(&[0.0, 7.0])?;
+
+        // Act
+        let Solve::Solved(result) = solve(&matrix, &factors)? else {
+            return Err(eyre!("should be solved"));
+        };
+
+        // Assert
+        assert_relative_eq!(result[0], 1.0);
+        assert_relative_eq!(result[1], 2.0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve_with_negative_pivot() -> Result<()> {
+        // Arrange
+        // System of equations:
+        //   x + y = 3
+        //   x - y = 1
+        // Expected solution: x = 2, y = 1
+        let mut matrix = SimpleMatrix::<f64>::new(2, 2)?;
+        matrix.set(0, 0, 1.0)?;
+        matrix.set(0, 1, 1.0)?;
+        matrix.set(1, 0, 1.0)?;
+        matrix.set(1, 1, -1.0)?;
+
+        let factors = Vector::from(&[3.0, 1.0])?;
+
+        // Act
+        let Solve::Solved(result) = solve(&matrix, &factors)? else {
+            return Err(eyre!("should be solved"));
+        };
+
+        // Assert
+        assert_eq!(result[0], 2.0);
+        assert_eq!(result[1], 1.0);
+        Ok(())
+    }
+
     #[test]
     fn test_solve_3x3_system() -> Result<()> {
         // Arrange
@@ -548,7 +689,7 @@ mod tests {
         //   x + 2y + z = 5
         //   x + y + 2z = 6
         // Expected solution: x = 1, y = 1, z = 2
-        let mut matrix = SimpleMatrix::<f32>::new(3, 3)?;
+        let mut matrix = SimpleMatrix::<f64>::new(3, 3)?;
         matrix.set(0, 0, 2.0)?;
         matrix.set(0, 1, 1.0)?;
         matrix.set(0, 2, 1.0)?;
@@ -582,7 +723,7 @@ mod tests {
         //   2x + 4y + 6z = 2
         //   3x + 6y + 9z = 3
         // The second and third rows are multiples of the first row
-        let mut matrix = SimpleMatrix::<f32>::new(3, 3)?;
+        let mut matrix = SimpleMatrix::<f64>::new(3, 3)?;
         matrix.set(0, 0, 1.0)?;
         matrix.set(0, 1, 2.0)?;
         matrix.set(0, 2, 3.0)?;
@@ -611,7 +752,7 @@ mod tests {
         //   0x + 1y + 0z = 5
         //   0x + 0y + 1z = 7
         // Expected solution: x = 3, y = 5, z = 7
-        let mut matrix = SimpleMatrix::<f32>::new(3, 3)?;
+        let mut matrix = SimpleMatrix::<f64>::new(3, 3)?;
         matrix.set(0, 0, 1.0)?;
         matrix.set(1, 1, 1.0)?;
         matrix.set(2, 2, 1.0)?;
@@ -630,4 +771,78 @@ mod tests {
         assert_relative_eq!(result[2], 7.0, epsilon = 1e-5);
         Ok(())
     }
+
+    #[test]
+    fn test_transpose_swaps_rows_and_columns() -> Result<()> {
+        // Arrange
+        let mut matrix = SimpleMatrix::<f64>::new(2, 3)?;
+        matrix.set(0, 1, 2.0)?;
+        matrix.set(1, 2, 5.0)?;
+
+        // Act
+        let result = transpose(&matrix)?;
+
+        // Assert
+        assert_eq!(result.size(), Size::new(3, 2));
+        assert_eq!(result.get(1, 0)?.copied(), Some(2.0));
+        assert_eq!(result.get(2, 1)?.copied(), Some(5.0));
+        assert_eq!(result.get(0, 0)?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_damped_least_squares_gives_minimum_norm_for_wide_matrix() -> Result<()> {
+        // Arrange
+        // x + y = 2 has infinite solutions, minimum norm solution is x = y = 1
+        let mut matrix = SimpleMatrix::<f64>::new(1, 2)?;
+        matrix.set(0, 0, 1.0)?;
+        matrix.set(0, 1, 1.0)?;
+        let factors = Vector::from(&[2.0])?;
+
+        // Act
+        let Solve::Solved(result) = damped_least_squares(&matrix, &factors, 1e-6)? else {
+            return Err(eyre!("should be solved"));
+        };
+
+        // Assert
+        assert_relative_eq!(result[0], 1.0, epsilon = 1e-4);
+        assert_relative_eq!(result[1], 1.0, epsilon = 1e-4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_damped_least_squares_gives_least_squares_for_tall_matrix() -> Result<()> {
+        // Arrange
+        // x = 1 and x = 3 conflict, least squares solution is x = 2
+        let mut matrix = SimpleMatrix::<f64>::new(2, 1)?;
+        matrix.set(0, 0, 1.0)?;
+        matrix.set(1, 0, 1.0)?;
+        let factors = Vector::from(&[1.0, 3.0])?;
+
+        // Act
+        let Solve::Solved(result) = damped_least_squares(&matrix, &factors, 1e-6)? else {
+            return Err(eyre!("should be solved"));
+        };
+
+        // Assert
+        assert_relative_eq!(result[0], 2.0, epsilon = 1e-4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_damped_least_squares_shrinks_step_with_large_damping() -> Result<()> {
+        // Arrange
+        let mut matrix = SimpleMatrix::<f64>::new(1, 1)?;
+        matrix.set(0, 0, 1.0)?;
+        let factors = Vector::from(&[2.0])?;
+
+        // Act
+        let Solve::Solved(result) = damped_least_squares(&matrix, &factors, 1.0)? else {
+            return Err(eyre!("should be solved"));
+        };
+
+        // Assert
+        assert_relative_eq!(result[0], 1.0, epsilon = 1e-6);
+        Ok(())
+    }
 }
@@ -127,11 +127,11 @@ impl<M: Clone + std::fmt::Debug> Matrix<M> for SimpleMatrix<M> {
 }
 
 impl<M: Clone + std::fmt::Debug> MatrixExtract<M> for SimpleMatrix<M> {
-    fn extract<T>(&self, extract: T) -> impl Matrix<f32>
+    fn extract<T>(&self, extract: T) -> impl Matrix<f64>
     where
-        T: Fn(&M) -> f32,
+        T: Fn(&M) -> f64,
     {
-        let mut new_matrix = SimpleMatrix::<f32>::new(self.size.rows(), self.size.columns())
+        let mut new_matrix = SimpleMatrix::<f64>::new(self.size.rows(), self.size.columns())
             .expect("Must be valid matrix in this");
         for r in 0..self.size.rows() {
             for c in 0..self.size.columns() {
@@ -228,12 +228,12 @@ mod tests {
 
         // Assert
         assert_eq!(old_value, Some(10));
-        assert_eq!(matrix.get(1, 1)?.map(|v| *v), Some(20));
+        assert_eq!(matrix.get(1, 1)?.copied(), Some(20));
         Ok(())
     }
 
     #[test]
-    fn test_extract_creates_f32_matrix_from_complex_type() -> Result<(), color_eyre::eyre::Error> {
+    fn test_extract_creates_f64_matrix_from_complex_type() -> Result<(), color_eyre::eyre::Error> {
         // Arrange
         let mut matrix = SimpleMatrix::<i32>::new(2, 2)?;
         matrix.set(0, 0, 10)?;
@@ -241,13 +241,13 @@ mod tests {
         matrix.set(1, 0, 30)?;
 
         // Act
-        let extracted = matrix.extract(|&val| val as f32 * 2.0);
+        let extracted = matrix.extract(|&val| val as f64 * 2.0);
 
         // Assert
         assert_eq!(extracted.size(), Size::new(2, 2));
-        assert_eq!(extracted.get(0, 0)?.map(|v| *v), Some(20.0));
-        assert_eq!(extracted.get(0, 1)?.map(|v| *v), Some(40.0));
-        assert_eq!(extracted.get(1, 0)?.map(|v| *v), Some(60.0));
+        assert_eq!(extracted.get(0, 0)?.copied(), Some(20.0));
+        assert_eq!(extracted.get(0, 1)?.copied(), Some(40.0));
+        assert_eq!(extracted.get(1, 0)?.copied(), Some(60.0));
         assert_eq!(extracted.get(1, 1)?, None);
         Ok(())
     }
@@ -320,14 +320,14 @@ mod tests {
         matrix.set(1, 1, 10)?;
 
         // Act
-        let extracted = matrix.extract(|&val| val as f32);
+        let extracted = matrix.extract(|&val| val as f64);
 
         // Assert
-        assert_eq!(extracted.get(0, 0)?.map(|v| *v), Some(5.0));
+        assert_eq!(extracted.get(0, 0)?.copied(), Some(5.0));
         assert_eq!(extracted.get(0, 1)?, None);
         assert_eq!(extracted.get(0, 2)?, None);
         assert_eq!(extracted.get(1, 0)?, None);
-        assert_eq!(extracted.get(1, 1)?.map(|v| *v), Some(10.0));
+        assert_eq!(extracted.get(1, 1)?.copied(), Some(10.0));
         assert_eq!(extracted.get(1, 2)?, None);
         Ok(())
     }
@@ -402,12 +402,12 @@ mod tests {
 
         // Assert
         assert_eq!(copied.size(), Size::new(3, 2));
-        assert_eq!(copied.get(0, 0)?.map(|v| *v), Some(10));
-        assert_eq!(copied.get(0, 1)?.map(|v| *v), Some(20));
-        assert_eq!(copied.get(1, 0)?.map(|v| *v), Some(30));
-        assert_eq!(copied.get(1, 1)?.map(|v| *v), Some(40));
-        assert_eq!(copied.get(2, 0)?.map(|v| *v), Some(50));
-        assert_eq!(copied.get(2, 1)?.map(|v| *v), Some(60));
+        assert_eq!(copied.get(0, 0)?.copied(), Some(10));
+        assert_eq!(copied.get(0, 1)?.copied(), Some(20));
+        assert_eq!(copied.get(1, 0)?.copied(), Some(30));
+        assert_eq!(copied.get(1, 1)?.copied(), Some(40));
+        assert_eq!(copied.get(2, 0)?.copied(), Some(50));
+        assert_eq!(copied.get(2, 1)?.copied(), Some(60));
         Ok(())
     }
 
@@ -424,12 +424,12 @@ mod tests {
 
         // Assert
         assert_eq!(copied.size(), Size::new(2, 3));
-        assert_eq!(copied.get(0, 0)?.map(|v| *v), Some(5));
+        assert_eq!(copied.get(0, 0)?.copied(), Some(5));
         assert_eq!(copied.get(0, 1)?, None);
         assert_eq!(copied.get(0, 2)?, None);
         assert_eq!(copied.get(1, 0)?, None);
         assert_eq!(copied.get(1, 1)?, None);
-        assert_eq!(copied.get(1, 2)?.map(|v| *v), Some(15));
+        assert_eq!(copied.get(1, 2)?.copied(), Some(15));
         Ok(())
     }
 
@@ -446,12 +446,12 @@ mod tests {
 
         // Assert
         assert_eq!(
-            source.get(0, 0)?.map(|v| *v),
+            source.get(0, 0)?.copied(),
             Some(100),
             "Source should be unchanged"
         );
         assert_eq!(
-            copied.get(0, 0)?.map(|v| *v),
+            copied.get(0, 0)?.copied(),
             Some(999),
             "Copy should be modified"
         );
@@ -21,7 +21,7 @@ pub struct SparseMatrix<M: std::fmt::Debug> {
 impl<M: Clone + std::fmt::Debug> SparseMatrix<M> {
     /// Create a empty Sparse matrix
     pub fn empty(size: Size) -> Result<Self, color_eyre::eyre::Error> {
-        if size.columns() <= 0 || size.rows() <= 0 {
+        if size.columns() == 0 || size.rows() == 0 {
             return Err(color_eyre::eyre::eyre!("can not create 0-sized matrix"));
         }
 
@@ -67,6 +67,28 @@ impl<M: Clone + std::fmt::Debug> SparseMatrix<M> {
     }
 }
 
+impl<M: Clone + std::fmt::Debug> SparseMatrix<M> {
+    /// Get non-zero elements of the row, as column index and value ordered by column
+    pub fn row_entries(&self, row: usize) -> impl Iterator<Item = (usize, &M)> {
+        let range = self.row_ptr[row]..self.row_ptr[row + 1];
+
+        self.col_indices[range.clone()]
+            .iter()
+            .copied()
+            .zip(self.values[range].iter())
+    }
+
+    /// Make a new matrix that has the same pattern, with values converted by `f`
+    pub fn map<T: std::fmt::Debug, F: FnMut(&M) -> T>(&self, f: F) -> SparseMatrix<T> {
+        SparseMatrix::<T> {
+            size: self.size,
+            values: self.values.iter().map(f).collect(),
+            col_indices: self.col_indices.clone(),
+            row_ptr: self.row_ptr.clone(),
+        }
+    }
+}
+
 impl<M: Clone + std::fmt::Debug> Matrix<M> for SparseMatrix<M> {
     fn size(&self) -> super::size::Size {
         self.size
@@ -134,16 +156,11 @@ impl<M: Clone + std::fmt::Debug> Matrix<M> for SparseMatrix<M> {
 }
 
 impl<M: Clone + std::fmt::Debug> MatrixExtract<M> for SparseMatrix<M> {
-    fn extract<T>(&self, extract: T) -> impl Matrix<f32>
+    fn extract<T>(&self, extract: T) -> impl Matrix<f64>
     where
-        T: Fn(&M) -> f32,
+        T: Fn(&M) -> f64,
     {
-        SparseMatrix::<f32> {
-            size: self.size,
-            values: self.values.iter().map(extract).collect(),
-            col_indices: self.col_indices.clone(),
-            row_ptr: self.row_ptr.clone(),
-        }
+        self.map(extract)
     }
 }
 
@@ -184,10 +201,10 @@ mod tests {
         let sparse = SparseMatrix::from_matrix(&source);
 
         // Assert
-        assert_eq!(sparse.get(0, 0)?.map(|v| *v), Some(1));
-        assert_eq!(sparse.get(0, 2)?.map(|v| *v), Some(3));
-        assert_eq!(sparse.get(1, 1)?.map(|v| *v), Some(5));
-        assert_eq!(sparse.get(2, 0)?.map(|v| *v), Some(7));
+        assert_eq!(sparse.get(0, 0)?.copied(), Some(1));
+        assert_eq!(sparse.get(0, 2)?.copied(), Some(3));
+        assert_eq!(sparse.get(1, 1)?.copied(), Some(5));
+        assert_eq!(sparse.get(2, 0)?.copied(), Some(7));
         assert_eq!(sparse.get(0, 1)?, None);
         assert_eq!(sparse.get(1, 0)?, None);
         assert_eq!(sparse.get(2, 2)?, None);
@@ -224,10 +241,10 @@ mod tests {
         let sparse = SparseMatrix::from_matrix(&source);
 
         // Assert
-        assert_eq!(sparse.get(0, 0)?.map(|v| *v), Some(1));
-        assert_eq!(sparse.get(0, 1)?.map(|v| *v), Some(2));
-        assert_eq!(sparse.get(1, 0)?.map(|v| *v), Some(3));
-        assert_eq!(sparse.get(1, 1)?.map(|v| *v), Some(4));
+        assert_eq!(sparse.get(0, 0)?.copied(), Some(1));
+        assert_eq!(sparse.get(0, 1)?.copied(), Some(2));
+        assert_eq!(sparse.get(1, 0)?.copied(), Some(3));
+        assert_eq!(sparse.get(1, 1)?.copied(), Some(4));
         Ok(())
     }
 
@@ -292,11 +309,11 @@ mod tests {
         let sparse = SparseMatrix::from_matrix(&source);
 
         // Act
-        let extracted = sparse.extract(|&v| v as f32 * 2.0);
+        let extracted = sparse.extract(|&v| v as f64 * 2.0);
 
         // Assert
-        assert_eq!(extracted.get(0, 0)?.map(|v| *v), Some(20.0));
-        assert_eq!(extracted.get(1, 1)?.map(|v| *v), Some(40.0));
+        assert_eq!(extracted.get(0, 0)?.copied(), Some(20.0));
+        assert_eq!(extracted.get(1, 1)?.copied(), Some(40.0));
         assert_eq!(extracted.get(0, 1)?, None);
         Ok(())
     }
@@ -311,12 +328,12 @@ mod tests {
         let sparse = SparseMatrix::from_matrix(&source);
 
         // Act
-        let extracted = sparse.extract(|&v| v as f32);
+        let extracted = sparse.extract(|&v| v as f64);
 
         // Assert
         assert_eq!(extracted.size(), Size::new(3, 4));
-        assert_eq!(extracted.get(0, 1)?.map(|v| *v), Some(5.0));
-        assert_eq!(extracted.get(2, 3)?.map(|v| *v), Some(15.0));
+        assert_eq!(extracted.get(0, 1)?.copied(), Some(5.0));
+        assert_eq!(extracted.get(2, 3)?.copied(), Some(15.0));
         assert_eq!(extracted.get(1, 1)?, None);
         Ok(())
     }
@@ -418,11 +435,11 @@ mod tests {
         Ok(())
     }
 
-    /// Test that sparse matrix works with f32 values
+    /// Test that sparse matrix works with f64 values
     #[test]
-    fn test_sparse_matrix_with_f32_values() -> Result<()> {
+    fn test_sparse_matrix_with_f64_values() -> Result<()> {
         // Arrange
-        let mut source = SimpleMatrix::<f32>::new(2, 2)?;
+        let mut source = SimpleMatrix::<f64>::new(2, 2)?;
         source.set(0, 0, 1.5)?;
         source.set(1, 1, 2.7)?;
 
@@ -430,8 +447,8 @@ mod tests {
         let sparse = SparseMatrix::from_matrix(&source);
 
         // Assert
-        assert_eq!(sparse.get(0, 0)?.map(|v| *v), Some(1.5));
-        assert_eq!(sparse.get(1, 1)?.map(|v| *v), Some(2.7));
+        assert_eq!(sparse.get(0, 0)?.copied(), Some(1.5));
+        assert_eq!(sparse.get(1, 1)?.copied(), Some(2.7));
         assert_eq!(sparse.get(0, 1)?, None);
         Ok(())
     }
@@ -448,7 +465,7 @@ mod tests {
 
         // Assert
         assert_eq!(sparse.size(), Size::new(1, 1));
-        assert_eq!(sparse.get(0, 0)?.map(|v| *v), Some(42));
+        assert_eq!(sparse.get(0, 0)?.copied(), Some(42));
         Ok(())
     }
 
@@ -466,9 +483,9 @@ mod tests {
 
         // Assert
         dbg!(&sparse);
-        assert_eq!(sparse.get(0, 0)?.map(|v| *v), Some(1));
-        assert_eq!(sparse.get(0, 2)?.map(|v| *v), Some(3));
-        assert_eq!(sparse.get(0, 4)?.map(|v| *v), Some(5));
+        assert_eq!(sparse.get(0, 0)?.copied(), Some(1));
+        assert_eq!(sparse.get(0, 2)?.copied(), Some(3));
+        assert_eq!(sparse.get(0, 4)?.copied(), Some(5));
         assert_eq!(sparse.get(0, 1)?, None);
         assert_eq!(sparse.get(0, 3)?, None);
         Ok(())
@@ -487,9 +504,9 @@ mod tests {
         let sparse = SparseMatrix::from_matrix(&source);
 
         // Assert
-        assert_eq!(sparse.get(0, 0)?.map(|v| *v), Some(1));
-        assert_eq!(sparse.get(2, 0)?.map(|v| *v), Some(3));
-        assert_eq!(sparse.get(4, 0)?.map(|v| *v), Some(5));
+        assert_eq!(sparse.get(0, 0)?.copied(), Some(1));
+        assert_eq!(sparse.get(2, 0)?.copied(), Some(3));
+        assert_eq!(sparse.get(4, 0)?.copied(), Some(5));
         assert_eq!(sparse.get(1, 0)?, None);
         assert_eq!(sparse.get(3, 0)?, None);
         Ok(())
@@ -0,0 +1,382 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    environment::Environment,
+    equation::{Equation, EquationError, Evaluate},
+    variable::Variable,
+};
+
+/// Errors of [`Parameters`]
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum ParameterError {
+    /// Driven variables depend on themselves. Each variable depends on the next one, and the last
+    /// one depends on the first one.
+    #[error("Driven variables make a cycle: {0:?}")]
+    Cycle(Vec<String>),
+
+    #[error("Variable {0} is driven by an equation")]
+    Driven(String),
+
+    #[error("Unknown variable: {0}")]
+    UnknownVariable(String),
+
+    #[error("Failed to evaluate {variable}: {error:?}")]
+    Evaluation {
+        variable: String,
+        error: EquationError,
+    },
+}
+
+/// Variables that some of them are driven by equations over other variables, such as
+/// `width = 2 * height`.
+///
+/// Values of driven variables are not solved, but evaluated from their equations in topological
+/// order. Setting the value of a free variable updates driven variables that depend on it. Values
+/// can be given to [`crate::Solver::update_dimensions`] with [`Parameters::environment`].
+#[derive(Debug, Clone)]
+pub struct Parameters {
+    /// Values of all variables, including driven ones
+    env: Environment,
+
+    /// Equations of driven variables
+    drivers: HashMap<String, Equation>,
+
+    /// Driven variables in topological order. A variable comes after all variables it depends on.
+    order: Vec<String>,
+}
+
+impl Parameters {
+    /// Get new parameters that all variables in `env` are free
+    pub fn new(env: Environment) -> Self {
+        Parameters {
+            env,
+            drivers: HashMap::new(),
+            order: vec![],
+        }
+    }
+
+    /// Get values of all variables
+    pub fn environment(&self) -> &Environment {
+        &self.env
+    }
+
+    /// Get the equation of the driven variable
+    pub fn driver(&self, name: &str) -> Option<&Equation> {
+        self.drivers.get(name)
+    }
+
+    /// Return `true` if the variable is driven
+    pub fn is_driven(&self, name: &str) -> bool {
+        self.drivers.contains_key(name)
+    }
+
+    /// Get driven variables in order of evaluation
+    pub fn order(&self) -> &[String] {
+        &self.order
+    }
+
+    /// Add or update a free variable. Driven variables that depend on it are evaluated again.
+    ///
+    /// # Returns
+    /// * Error when the variable is driven, or evaluation failed. Values are not changed on error.
+    pub fn set_value(&mut self, name: &str, value: f64) -> Result<(), ParameterError> {
+        if self.is_driven(name) {
+            return Err(ParameterError::Driven(name.to_string()));
+        }
+
+        let mut env = self.env.clone();
+        match env.get_mut(name) {
+            Some(variable) => variable.set_value(value),
+            None => env.add_variable(Variable::new(name, value)),
+        }
+        self.env = self.propagate(env, &[name.to_string()])?;
+        Ok(())
+    }
+
+    /// Drive the variable by the equation. The variable is added when it does not exist.
+    ///
+    /// # Returns
+    /// * Error when the equation makes a cycle, or evaluation failed. Parameters are not changed
+    ///   on error.
+    pub fn drive(&mut self, name: &str, equation: Equation) -> Result<(), ParameterError> {
+        let mut drivers = self.drivers.clone();
+        drivers.insert(name.to_string(), equation);
+        let order = topological_order(&drivers)?;
+
+        let mut env = self.env.clone();
+        if env.get(name).is_none() {
+            env.add_variable(Variable::new(name, 0.0));
+        }
+        let previous = std::mem::replace(&mut self.drivers, drivers);
+        match self.propagate_with(env, &[name.to_string()], &order, true) {
+            Ok(env) => {
+                self.env = env;
+                self.order = order;
+                Ok(())
+            }
+            Err(e) => {
+                self.drivers = previous;
+                Err(e)
+            }
+        }
+    }
+
+    /// Release the driven variable. It becomes a free variable with the current value.
+    ///
+    /// # Returns
+    /// * The equation that drove the variable, or `None` if it is not driven
+    pub fn release(&mut self, name: &str) -> Option<Equation> {
+        let equation = self.drivers.remove(name)?;
+        self.order.retain(|v| v != name);
+
+        Some(equation)
+    }
+
+    /// Evaluate driven variables that depend on `changed` with current order
+    fn propagate(
+        &self,
+        env: Environment,
+        changed: &[String],
+    ) -> Result<Environment, ParameterError> {
+        self.propagate_with(env, changed, &self.order, false)
+    }
+
+    /// Evaluate driven variables in `order` that depend on `changed` directly or indirectly.
+    /// `changed` themselves are evaluated too if `include` is `true`.
+    fn propagate_with(
+        &self,
+        mut env: Environment,
+        changed: &[String],
+        order: &[String],
+        include: bool,
+    ) -> Result<Environment, ParameterError> {
+        let mut dirty: HashSet<String> = changed.iter().cloned().collect();
+
+        for name in order {
+            let equation = &self.drivers[name];
+            let depends = equation
+                .related_variables()
+                .iter()
+                .any(|v| dirty.contains(v));
+            let requested = include && changed.contains(name);
+            if !depends && !requested {
+                continue;
+            }
+
+            for v in equation.related_variables() {
+                if env.get(&v).is_none() {
+                    return Err(ParameterError::UnknownVariable(v));
+                }
+            }
+            let value = equation
+                .evaluate(&env)
+                .map_err(|error| ParameterError::Evaluation {
+                    variable: name.clone(),
+                    error,
+                })?;
+            if let Some(variable) = env.get_mut(name) {
+                variable.set_value(value);
+            }
+            dirty.insert(name.clone());
+        }
+
+        Ok(env)
+    }
+}
+
+/// Get driven variables in topological order, ordered by name among independent ones.
+///
+/// # Returns
+/// * Error with the first cycle found
+fn topological_order(drivers: &HashMap<String, Equation>) -> Result<Vec<String>, ParameterError> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum Mark {
+        Visiting,
+        Done,
+    }
+
+    fn visit(
+        name: &str,
+        drivers: &HashMap<String, Equation>,
+        marks: &mut HashMap<String, Mark>,
+        path: &mut Vec<String>,
+        order: &mut Vec<String>,
+    ) -> Result<(), ParameterError> {
+        match marks.get(name) {
+            Some(Mark::Done) => return Ok(()),
+            Some(Mark::Visiting) => {
+                let start = path.iter().position(|v| v == name).unwrap_or(0);
+                return Err(ParameterError::Cycle(path[start..].to_vec()));
+            }
+            None => (),
+        }
+
+        marks.insert(name.to_string(), Mark::Visiting);
+        path.push(name.to_string());
+
+        let mut dependencies = drivers[name].related_variables();
+        dependencies.sort();
+        for dependency in dependencies.iter().filter(|v| drivers.contains_key(*v)) {
+            visit(dependency, drivers, marks, path, order)?;
+        }
+
+        path.pop();
+        marks.insert(name.to_string(), Mark::Done);
+        order.push(name.to_string());
+        Ok(())
+    }
+
+    let mut names: Vec<&String> = drivers.keys().collect();
+    names.sort();
+
+    let mut marks = HashMap::new();
+    let mut order = vec![];
+    for name in names {
+        visit(name, drivers, &mut marks, &mut vec![], &mut order)?;
+    }
+
+    Ok(order)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::equation::parse;
+    use approx::assert_relative_eq;
+    use pretty_assertions::assert_eq;
+
+    fn value(parameters: &Parameters, name: &str) -> f64 {
+        *parameters.environment().get(name).unwrap().value
+    }
+
+    #[test]
+    fn test_drive_evaluates_variable() {
+        // arrange
+        let mut parameters = Parameters::new(Environment::from_tuples(&[("height", 3.0)]));
+
+        // act
+        parameters
+            .drive("width", parse("2 * height").unwrap())
+            .unwrap();
+
+        // assert
+        assert_relative_eq!(value(&parameters, "width"), 6.0);
+        assert!(parameters.is_driven("width"));
+        assert!(!parameters.is_driven("height"));
+    }
+
+    #[test]
+    fn test_set_value_propagates_to_downstream_in_order() {
+        // arrange
+        let mut parameters =
+            Parameters::new(Environment::from_tuples(&[("a", 1.0), ("other", 5.0)]));
+        parameters.drive("b", parse("a * 10").unwrap()).unwrap();
+        parameters.drive("c", parse("b + 1").unwrap()).unwrap();
+        parameters.drive("d", parse("other * 2").unwrap()).unwrap();
+
+        // act
+        parameters.set_value("a", 2.0).unwrap();
+
+        // assert
+        assert_eq!(parameters.order(), &["b", "c", "d"]);
+        assert_relative_eq!(value(&parameters, "b"), 20.0);
+        assert_relative_eq!(value(&parameters, "c"), 21.0);
+        assert_relative_eq!(value(&parameters, "d"), 10.0);
+    }
+
+    #[test]
+    fn test_order_puts_dependencies_first_regardless_of_names() {
+        // arrange
+        let mut parameters = Parameters::new(Environment::from_tuples(&[("z", 1.0)]));
+
+        // act
+        parameters.drive("y", parse("z + 1").unwrap()).unwrap();
+        parameters.drive("a", parse("y + 1").unwrap()).unwrap();
+
+        // assert
+        assert_eq!(parameters.order(), &["y", "a"]);
+        assert_relative_eq!(value(&parameters, "a"), 3.0);
+    }
+
+    #[test]
+    fn test_drive_reports_cycle_and_keeps_parameters() {
+        // arrange
+        let mut parameters = Parameters::new(Environment::from_tuples(&[("x", 1.0)]));
+        parameters.drive("a", parse("x + 1").unwrap()).unwrap();
+        parameters.drive("b", parse("a * 2").unwrap()).unwrap();
+        parameters.drive("c", parse("b - 1").unwrap()).unwrap();
+
+        // act
+        let result = parameters.drive("a", parse("c + 1").unwrap());
+
+        // assert
+        assert_eq!(
+            result,
+            Err(ParameterError::Cycle(vec![
+                "a".to_string(),
+                "c".to_string(),
+                "b".to_string()
+            ]))
+        );
+        assert_eq!(parameters.driver("a"), Some(&parse("x + 1").unwrap()));
+        assert_relative_eq!(value(&parameters, "c"), 3.0);
+    }
+
+    #[test]
+    fn test_drive_reports_self_reference_as_cycle() {
+        // arrange
+        let mut parameters = Parameters::new(Environment::from_tuples(&[("x", 1.0)]));
+
+        // act
+        let result = parameters.drive("x", parse("x + 1").unwrap());
+
+        // assert
+        assert_eq!(result, Err(ParameterError::Cycle(vec!["x".to_string()])));
+    }
+
+    #[test]
+    fn test_set_value_of_driven_variable_fails() {
+        // arrange
+        let mut parameters = Parameters::new(Environment::from_tuples(&[("x", 1.0)]));
+        parameters.drive("y", parse("x + 1").unwrap()).unwrap();
+
+        // act
+        let result = parameters.set_value("y", 10.0);
+
+        // assert
+        assert_eq!(result, Err(ParameterError::Driven("y".to_string())));
+        assert_relative_eq!(value(&parameters, "y"), 2.0);
+    }
+
+    #[test]
+    fn test_release_keeps_value_and_stops_propagation() {
+        // arrange
+        let mut parameters = Parameters::new(Environment::from_tuples(&[("x", 1.0)]));
+        parameters.drive("y", parse("x + 1").unwrap()).unwrap();
+
+        // act
+        let released = parameters.release("y");
+        parameters.set_value("x", 5.0).unwrap();
+
+        // assert
+        assert_eq!(released, Some(parse("x + 1").unwrap()));
+        assert_relative_eq!(value(&parameters, "y"), 2.0);
+        assert_eq!(parameters.order(), &[] as &[String]);
+    }
+
+    #[test]
+    fn test_drive_with_unknown_variable_fails() {
+        // arrange
+        let mut parameters = Parameters::new(Environment::empty());
+
+        // act
+        let result = parameters.drive("y", parse("x + 1").unwrap());
+
+        // assert
+        assert_eq!(
+            result,
+            Err(ParameterError::UnknownVariable("x".to_string()))
+        );
+        assert!(!parameters.is_driven("y"));
+    }
+}
@@ -0,0 +1,258 @@
+use crate::{
+    DimensionSpecificationStatus, EquationId, environment::Environment, equation::EquationError,
+};
+
+/// Result of [`crate::Solver::solve`]
+#[derive(Debug, Clone)]
+pub struct SolveReport {
+    /// All variables after solving. Variables in clusters that did not converge keep the last
+    /// values of iterations.
+    pub variables: Environment,
+
+    /// Reports of each independent cluster, ordered by the first equation of them
+    pub clusters: Vec<ClusterReport>,
+
+    /// Bounds and inequalities that are still violated after solving
+    pub violations: Vec<Violation>,
+
+    /// Soft equations that had to be relaxed, ordered by id
+    pub relaxed: Vec<Relaxation>,
+}
+
+impl SolveReport {
+    /// Return `true` if all clusters converged
+    pub fn converged(&self) -> bool {
+        self.clusters.iter().all(|c| c.converged())
+    }
+
+    /// Get the report of the cluster that contains the equation
+    pub fn cluster_of(&self, equation: EquationId) -> Option<&ClusterReport> {
+        self.clusters
+            .iter()
+            .find(|c| c.equations.contains(&equation))
+    }
+
+    /// Total number of iterations over all clusters
+    pub fn iterations(&self) -> usize {
+        self.clusters.iter().map(|c| c.iterations).sum()
+    }
+
+    /// Euclidean norm of residuals of all equations
+    pub fn residual_norm(&self) -> f64 {
+        self.clusters
+            .iter()
+            .flat_map(|c| c.residuals.iter())
+            .map(|r| r * r)
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    /// Get the final residual of the equation
+    pub fn residual(&self, equation: EquationId) -> Option<f64> {
+        self.clusters.iter().find_map(|c| c.residual(equation))
+    }
+
+    /// Return `true` if all bounds and inequalities are satisfied
+    pub fn feasible(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+/// A soft equation that is not satisfied after solving
+#[derive(Debug, Clone, PartialEq)]
+pub struct Relaxation {
+    pub equation: EquationId,
+
+    /// Priority of the soft equation
+    pub priority: u32,
+
+    /// Value of the soft equation, that is how much it was relaxed
+    pub residual: f64,
+}
+
+/// A bound or an inequality that is not satisfied
+#[derive(Debug, Clone, PartialEq)]
+pub enum Violation {
+    /// The value of the variable is less than the lower bound
+    LowerBound {
+        variable: String,
+        bound: f64,
+        value: f64,
+    },
+    /// The value of the variable is greater than the upper bound
+    UpperBound {
+        variable: String,
+        bound: f64,
+        value: f64,
+    },
+    /// The inequality is negative
+    Inequality { equation: EquationId, value: f64 },
+}
+
+/// Reason why iterations of a cluster stopped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Termination {
+    /// Norm of residuals became smaller than the tolerance
+    ResidualTolerance,
+    /// Norm of the step became smaller than the tolerance before residuals became small enough
+    StepTolerance,
+    /// Least squares mode reached a local minimum, that is an accepted step became smaller than
+    /// the tolerance, but residuals are not within the tolerance
+    LocalMinimum,
+    /// Iterations reached the limit before convergence
+    MaxIterations,
+    /// Least squares mode can not decrease residuals anymore
+    Stalled,
+    /// Equations converged, but bounds or inequalities moved variables and residuals can not vanish
+    /// under them
+    Constrained,
+}
+
+/// Result of solving a cluster, that is a set of equations and variables independent of others
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClusterReport {
+    /// Equations in the cluster, ordered by id
+    pub equations: Vec<EquationId>,
+
+    /// Names of variables in the cluster, ordered by name
+    pub variables: Vec<String>,
+
+    /// Reason why iterations stopped
+    pub termination: Termination,
+
+    /// Number of iterations used
+    pub iterations: usize,
+
+    /// Final residuals, ordered as same as `equations`
+    pub residuals: Vec<f64>,
+}
+
+impl ClusterReport {
+    /// Return `true` if solving the cluster converged, that is residuals are within the tolerance
+    pub fn converged(&self) -> bool {
+        self.termination == Termination::ResidualTolerance
+    }
+
+    /// Euclidean norm of final residuals
+    pub fn residual_norm(&self) -> f64 {
+        self.residuals.iter().map(|r| r * r).sum::<f64>().sqrt()
+    }
+
+    /// Get the final residual of the equation
+    pub fn residual(&self, equation: EquationId) -> Option<f64> {
+        self.equations
+            .iter()
+            .position(|e| *e == equation)
+            .map(|i| self.residuals[i])
+    }
+}
+
+/// Errors of [`crate::Solver::solve`]
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum SolveError {
+    #[error("Can not solve equations in status {0:?}")]
+    InvalidStatus(DimensionSpecificationStatus),
+
+    #[error("Can not solve without jacobian")]
+    NoJacobian,
+
+    #[error("Jacobian became singular while solving equations {equations:?}")]
+    Singular { equations: Vec<EquationId> },
+
+    #[error("Solving equations {equations:?} diverged")]
+    Diverged { equations: Vec<EquationId> },
+
+    #[error("Unknown variable: {0}")]
+    UnknownVariable(String),
+
+    #[error("Failed to calculate: {0}")]
+    Calculation(String),
+
+    #[error("Failed to evaluate: {0:?}")]
+    Evaluation(EquationError),
+}
+
+impl From<EquationError> for SolveError {
+    fn from(value: EquationError) -> Self {
+        SolveError::Evaluation(value)
+    }
+}
+
+impl From<color_eyre::eyre::Error> for SolveError {
+    fn from(value: color_eyre::eyre::Error) -> Self {
+        SolveError::Calculation(value.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn cluster(equations: &[u64], residuals: &[f64], termination: Termination) -> ClusterReport {
+        ClusterReport {
+            equations: equations.iter().map(|e| EquationId::from(*e)).collect(),
+            variables: vec![],
+            termination,
+            iterations: 2,
+            residuals: residuals.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_report_aggregates_clusters() {
+        // Arrange
+        let report = SolveReport {
+            variables: Environment::empty(),
+            violations: vec![],
+            relaxed: vec![],
+            clusters: vec![
+                cluster(&[1, 3], &[3.0, 0.0], Termination::ResidualTolerance),
+                cluster(&[2], &[4.0], Termination::ResidualTolerance),
+            ],
+        };
+
+        // Act & Assert
+        assert!(report.converged());
+        assert_eq!(report.iterations(), 4);
+        assert_eq!(report.residual_norm(), 5.0);
+        assert_eq!(report.residual(EquationId::from(2)), Some(4.0));
+        assert_eq!(report.residual(EquationId::from(4)), None);
+    }
+
+    #[test]
+    fn test_report_is_not_converged_when_a_cluster_reached_max_iterations() {
+        // Arrange
+        let report = SolveReport {
+            variables: Environment::empty(),
+            violations: vec![],
+            relaxed: vec![],
+            clusters: vec![
+                cluster(&[1], &[0.0], Termination::ResidualTolerance),
+                cluster(&[2], &[1.0], Termination::MaxIterations),
+            ],
+        };
+
+        // Act
+        let converged = report.converged();
+
+        // Assert
+        assert!(!converged);
+    }
+
+    #[test]
+    fn test_report_is_not_converged_at_step_tolerance_or_local_minimum() {
+        // Arrange
+        let report = |termination| SolveReport {
+            variables: Environment::empty(),
+            violations: vec![],
+            relaxed: vec![],
+            clusters: vec![cluster(&[1], &[1.0], termination)],
+        };
+
+        // Act & Assert
+        assert!(!report(Termination::StepTolerance).converged());
+        assert!(!report(Termination::LocalMinimum).converged());
+        assert!(!report(Termination::Stalled).converged());
+    }
+}
@@ -0,0 +1,881 @@
+use epsilon::{DefaultEpsilon, Epsilon};
+
+use crate::{
+    EquationId, LinearSolver, SoftConstraint, SolveMode, SolveOptions,
+    environment::Environment,
+    equation::{Equation, constant::number_text, parse},
+    trace::{Trace, TraceStep},
+    unit::Dimension,
+    variable::Variable,
+};
+
+/// Version of the text format written by [`TextFormat::to_text`]
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Keyword of the header line, such as `solver 1`
+const HEADER: &str = "solver";
+
+/// Keyword of equation records
+const EQUATION: &str = "equation";
+
+/// Keyword of variable records
+const VARIABLE: &str = "variable";
+
+/// Placeholder of bounds that are not set
+const UNBOUNDED: &str = "_";
+
+/// Keyword of dimension records of traces, written same as variables
+const DIMENSION: &str = "dimension";
+
+/// Keyword of equation records with id of traces
+const EQUALITY: &str = "equality";
+
+/// Keyword of inequality records with id of traces
+const INEQUALITY: &str = "inequality";
+
+/// Keyword of soft equation records with id, priority and weight of traces
+const SOFT: &str = "soft";
+
+/// Keyword of the solve mode record of traces
+const MODE: &str = "mode";
+
+/// Keyword of option records of traces
+const OPTION: &str = "option";
+
+/// Keyword of the epsilon record of traces
+const EPSILON: &str = "epsilon";
+
+/// Keyword of iteration records of traces
+const STEP: &str = "step";
+
+/// Separator of fields in iteration records
+const STEP_SEPARATOR: char = '|';
+
+/// Errors of reading the text format
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum FormatError {
+    #[error("Missing header line")]
+    MissingHeader,
+
+    #[error("Unsupported format version {0}")]
+    UnsupportedVersion(u32),
+
+    #[error("Can not write non-finite number in {0}")]
+    NonFinite(String),
+
+    #[error("Invalid record at line {line}: {message}")]
+    InvalidRecord { line: usize, message: String },
+
+    #[error("Expected {expected} record(s) of {kind}, but found {actual}")]
+    RecordCount {
+        kind: &'static str,
+        expected: usize,
+        actual: usize,
+    },
+}
+
+/// Versioned text format of the data model of the solver.
+///
+/// The text starts with the header `solver <version>`, and each following line is a record.
+/// Empty lines and lines starting with `#` are ignored.
+///
+/// ```text
+/// solver 1
+/// variable x 1.5 0 _
+/// equation x^2 + 10[mm]
+/// ```
+///
+/// An equation record is written with `Display` of [`Equation`] and read with [`parse`], and a
+/// variable record is its name, value, lower and upper bound. `_` is written for missing bounds.
+/// A variable with dimension has exponents of length and angle at the end, such as `1,0`.
+/// Numbers are written in the shortest form that reads back to the same value, with exponent for
+/// very large and small ones, so writing and reading are lossless. Values of variables can be
+/// `inf` or `NaN`, but equations with non-finite numbers are rejected because they can not be
+/// read back. A variable named `pi` can not be read back from monomials, because it is read as
+/// the constant.
+pub trait TextFormat: Sized {
+    /// Write the value in the text format of [`FORMAT_VERSION`]. Returns an error if the value has
+    /// an equation with non-finite numbers.
+    fn to_text(&self) -> Result<String, FormatError>;
+
+    /// Read the value from the text format. Text of newer versions is rejected.
+    fn from_text(input: &str) -> Result<Self, FormatError>;
+}
+
+impl TextFormat for Equation {
+    fn to_text(&self) -> Result<String, FormatError> {
+        Ok(document(&[equation_record(self)?]))
+    }
+
+    fn from_text(input: &str) -> Result<Self, FormatError> {
+        let records = records(input)?;
+        let mut equations = read_equations(&records)?;
+        expect_only(&records, EQUATION, 1)?;
+
+        Ok(equations.remove(0))
+    }
+}
+
+impl TextFormat for Variable {
+    fn to_text(&self) -> Result<String, FormatError> {
+        Ok(document(&[variable_record(self)]))
+    }
+
+    fn from_text(input: &str) -> Result<Self, FormatError> {
+        let records = records(input)?;
+        let mut variables = read_variables(&records)?;
+        expect_only(&records, VARIABLE, 1)?;
+
+        Ok(variables.remove(0))
+    }
+}
+
+impl TextFormat for Environment {
+    /// Variables are written in the order of names
+    fn to_text(&self) -> Result<String, FormatError> {
+        let mut variables = self.variables();
+        variables.sort_by_key(|v| (*v.name).clone());
+
+        Ok(document(
+            &variables.iter().map(variable_record).collect::<Vec<_>>(),
+        ))
+    }
+
+    fn from_text(input: &str) -> Result<Self, FormatError> {
+        let records = records(input)?;
+        let variables = read_variables(&records)?;
+        expect_only(&records, VARIABLE, variables.len())?;
+
+        Ok(Environment::from_variables(variables))
+    }
+}
+
+/// Trace is written with records of settings, equations, initial values and iterations.
+///
+/// ```text
+/// solver 1
+/// mode newton
+/// option max_iterations 100
+/// option residual_tolerance 0.00001
+/// option step_tolerance 0.00001
+/// option linear_solver sparse_lu
+/// epsilon 0.00001
+/// equality 1 1x^2-2
+/// soft 2 0 1 1x^1-1
+/// variable x 1 _ _
+/// step 1 | x | -1 | 0.5
+/// ```
+///
+/// Equations are `equality`, `inequality` and `soft` records with id, and soft ones have priority
+/// and weight before the equation. Initial values are `variable` and `dimension` records. A step
+/// record is the iteration, names of variables, residuals and the step, separated by `|`.
+/// Settings that are not written are read as defaults.
+impl TextFormat for Trace {
+    fn to_text(&self) -> Result<String, FormatError> {
+        let mut records = vec![
+            format!("{} {}", MODE, mode_name(self.mode)),
+            format!("{} max_iterations {}", OPTION, self.options.max_iterations),
+            format!(
+                "{} residual_tolerance {}",
+                OPTION,
+                number_text(self.options.residual_tolerance)
+            ),
+            format!(
+                "{} step_tolerance {}",
+                OPTION,
+                number_text(self.options.step_tolerance)
+            ),
+            format!(
+                "{} linear_solver {}",
+                OPTION,
+                linear_solver_name(self.options.linear_solver)
+            ),
+            format!("{} {}", EPSILON, number_text(self.epsilon)),
+        ];
+        for (kind, equations) in [
+            (EQUALITY, &self.equations),
+            (INEQUALITY, &self.inequalities),
+        ] {
+            for (id, equation) in equations {
+                check_finite(equation)?;
+                records.push(format!("{} {} {}", kind, u64::from(*id), equation));
+            }
+        }
+        for (id, equation, soft) in &self.soft {
+            check_finite(equation)?;
+            records.push(format!(
+                "{} {} {} {} {}",
+                SOFT,
+                u64::from(*id),
+                soft.priority(),
+                number_text(soft.weight()),
+                equation
+            ));
+        }
+        for (kind, env) in [(VARIABLE, &self.variables), (DIMENSION, &self.dimensions)] {
+            let mut variables = env.variables();
+            variables.sort_by_key(|v| (*v.name).clone());
+            records.extend(variables.iter().map(|v| variable_record_of(v, kind)));
+        }
+        for step in &self.steps {
+            let numbers = |values: &[f64]| {
+                values
+                    .iter()
+                    .map(|v| number_text(*v))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            };
+            records.push(format!(
+                "{0} {1} {2} {3} {2} {4} {2} {5}",
+                STEP,
+                step.iteration,
+                STEP_SEPARATOR,
+                step.variables.join(" "),
+                numbers(&step.residuals),
+                numbers(&step.step)
+            ));
+        }
+
+        Ok(document(&records))
+    }
+
+    fn from_text(input: &str) -> Result<Self, FormatError> {
+        let records = records(input)?;
+        if let Some(other) = records.iter().find(|r| r.kind == EQUATION) {
+            return Err(invalid(other.line, "equation needs id in trace"));
+        }
+
+        let mut trace = Trace {
+            mode: SolveMode::default(),
+            options: SolveOptions::new::<DefaultEpsilon>(),
+            epsilon: DefaultEpsilon::EPSILON,
+            equations: vec![],
+            inequalities: vec![],
+            soft: vec![],
+            variables: Environment::from_variables(read_variables_of(&records, VARIABLE)?),
+            dimensions: Environment::from_variables(read_variables_of(&records, DIMENSION)?),
+            steps: vec![],
+        };
+
+        for r in &records {
+            let number = |v: &str| v.parse::<f64>().map_err(|e| invalid(r.line, e));
+            match r.kind {
+                MODE => {
+                    trace.mode = match r.body {
+                        "newton" => SolveMode::Newton,
+                        "least_squares" => SolveMode::LeastSquares,
+                        _ => return Err(invalid(r.line, format!("unknown mode {}", r.body))),
+                    }
+                }
+                OPTION => {
+                    let (name, value) = r.body.split_once(' ').unwrap_or((r.body, ""));
+                    let value = value.trim();
+                    match name {
+                        "max_iterations" => {
+                            trace.options.max_iterations =
+                                value.parse().map_err(|e| invalid(r.line, e))?
+                        }
+                        "residual_tolerance" => trace.options.residual_tolerance = number(value)?,
+                        "step_tolerance" => trace.options.step_tolerance = number(value)?,
+                        "linear_solver" => {
+                            trace.options.linear_solver = match value {
+                                "sparse_lu" => LinearSolver::SparseLu,
+                                "dense" => LinearSolver::Dense,
+                                _ => {
+                                    return Err(invalid(
+                                        r.line,
+                                        format!("unknown linear solver {}", value),
+                                    ));
+                                }
+                            }
+                        }
+                        _ => return Err(invalid(r.line, format!("unknown option {}", name))),
+                    }
+                }
+                EPSILON => trace.epsilon = number(r.body)?,
+                EQUALITY | INEQUALITY => {
+                    let (id, equation) = r.body.split_once(' ').unwrap_or((r.body, ""));
+                    let id: u64 = id.parse().map_err(|e| invalid(r.line, e))?;
+                    let equation = parse(equation.trim()).map_err(|e| invalid(r.line, e))?;
+                    let equations = match r.kind {
+                        EQUALITY => &mut trace.equations,
+                        _ => &mut trace.inequalities,
+                    };
+                    equations.push((EquationId::from(id), equation));
+                }
+                SOFT => {
+                    let [id, priority, weight, equation] =
+                        r.body.splitn(4, ' ').collect::<Vec<_>>()[..]
+                    else {
+                        return Err(invalid(
+                            r.line,
+                            "soft needs id, priority, weight and equation",
+                        ));
+                    };
+
+                    trace.soft.push((
+                        EquationId::from(id.parse::<u64>().map_err(|e| invalid(r.line, e))?),
+                        parse(equation.trim()).map_err(|e| invalid(r.line, e))?,
+                        SoftConstraint::new(
+                            priority.parse().map_err(|e| invalid(r.line, e))?,
+                            number(weight)?,
+                        )
+                        .map_err(|e| invalid(r.line, e))?,
+                    ));
+                }
+                STEP => trace.steps.push(read_step(r)?),
+                _ => (),
+            }
+        }
+        trace.equations.sort_by_key(|(id, _)| *id);
+        trace.inequalities.sort_by_key(|(id, _)| *id);
+        trace.soft.sort_by_key(|(id, _, _)| *id);
+
+        Ok(trace)
+    }
+}
+
+fn mode_name(mode: SolveMode) -> &'static str {
+    match mode {
+        SolveMode::Newton => "newton",
+        SolveMode::LeastSquares => "least_squares",
+    }
+}
+
+fn linear_solver_name(solver: LinearSolver) -> &'static str {
+    match solver {
+        LinearSolver::SparseLu => "sparse_lu",
+        LinearSolver::Dense => "dense",
+    }
+}
+
+fn read_step(record: &Record) -> Result<TraceStep, FormatError> {
+    let [iteration, variables, residuals, step] =
+        record.body.split(STEP_SEPARATOR).collect::<Vec<_>>()[..]
+    else {
+        return Err(invalid(
+            record.line,
+            "step needs iteration, variables, residuals and step",
+        ));
+    };
+    let numbers = |v: &str| {
+        v.split_whitespace()
+            .map(|v| v.parse::<f64>().map_err(|e| invalid(record.line, e)))
+            .collect::<Result<Vec<_>, _>>()
+    };
+
+    Ok(TraceStep {
+        iteration: iteration
+            .trim()
+            .parse()
+            .map_err(|e| invalid(record.line, e))?,
+        variables: variables.split_whitespace().map(String::from).collect(),
+        residuals: numbers(residuals)?,
+        step: numbers(step)?,
+    })
+}
+
+/// A record line, as line number, keyword and the rest
+struct Record<'a> {
+    line: usize,
+    kind: &'a str,
+    body: &'a str,
+}
+
+/// Make a text with the header and records
+fn document(records: &[String]) -> String {
+    let mut text = format!("{} {}\n", HEADER, FORMAT_VERSION);
+    for record in records {
+        text.push_str(record);
+        text.push('\n');
+    }
+    text
+}
+
+fn equation_record(equation: &Equation) -> Result<String, FormatError> {
+    check_finite(equation)?;
+
+    Ok(format!("{} {}", EQUATION, equation))
+}
+
+/// Check numbers in the equation are finite, because non-finite numbers are read as variables
+fn check_finite(equation: &Equation) -> Result<(), FormatError> {
+    fn is_finite(equation: &Equation) -> bool {
+        match equation {
+            Equation::Constant(c) => c.value().is_finite(),
+            Equation::Monomial(m) => m.factor().is_finite(),
+            Equation::Arithmetic(a) => a.operands().iter().all(is_finite),
+            Equation::Function(f) => f.arguments().iter().all(is_finite),
+            Equation::Power(p) => is_finite(p.base()) && is_finite(p.exponent()),
+        }
+    }
+
+    match is_finite(equation) {
+        true => Ok(()),
+        false => Err(FormatError::NonFinite(equation.to_string())),
+    }
+}
+
+fn variable_record(variable: &Variable) -> String {
+    variable_record_of(variable, VARIABLE)
+}
+
+/// Write the variable as a record of `kind`
+fn variable_record_of(variable: &Variable, kind: &str) -> String {
+    let bound = |v: Option<f64>| v.map_or(UNBOUNDED.to_string(), number_text);
+
+    let record = format!(
+        "{} {} {} {} {}",
+        kind,
+        *variable.name,
+        number_text(*variable.value),
+        bound(variable.lower()),
+        bound(variable.upper())
+    );
+
+    match variable.dimension() {
+        Some(d) => format!("{} {},{}", record, d.length, d.angle),
+        None => record,
+    }
+}
+
+/// Split the text into records after checking the header
+fn records(input: &str) -> Result<Vec<Record<'_>>, FormatError> {
+    let mut lines = input
+        .lines()
+        .enumerate()
+        .map(|(i, l)| (i + 1, l.trim()))
+        .filter(|(_, l)| !l.is_empty() && !l.starts_with('#'));
+
+    let Some((line, header)) = lines.next() else {
+        return Err(FormatError::MissingHeader);
+    };
+    let version = match header.split_once(' ') {
+        Some((HEADER, version)) => version
+            .trim()
+            .parse::<u32>()
+            .map_err(|e| invalid(line, e))?,
+        _ => return Err(FormatError::MissingHeader),
+    };
+    if version > FORMAT_VERSION {
+        return Err(FormatError::UnsupportedVersion(version));
+    }
+
+    lines
+        .map(|(line, l)| {
+            let (kind, body) = l.split_once(' ').unwrap_or((l, ""));
+            match kind {
+                EQUATION | VARIABLE | DIMENSION | EQUALITY | INEQUALITY | SOFT | MODE | OPTION
+                | EPSILON | STEP => Ok(Record {
+                    line,
+                    kind,
+                    body: body.trim(),
+                }),
+                _ => Err(invalid(line, format!("unknown record {}", kind))),
+            }
+        })
+        .collect()
+}
+
+fn read_equations(records: &[Record]) -> Result<Vec<Equation>, FormatError> {
+    records
+        .iter()
+        .filter(|r| r.kind == EQUATION)
+        .map(|r| parse(r.body).map_err(|e| invalid(r.line, e)))
+        .collect()
+}
+
+fn read_variables(records: &[Record]) -> Result<Vec<Variable>, FormatError> {
+    read_variables_of(records, VARIABLE)
+}
+
+/// Read records of `kind` that are written as variables
+fn read_variables_of(records: &[Record], kind: &str) -> Result<Vec<Variable>, FormatError> {
+    records
+        .iter()
+        .filter(|r| r.kind == kind)
+        .map(|r| {
+            let fields = r.body.split_whitespace().collect::<Vec<_>>();
+            let ([name, value, lower, upper], dimension) = match fields[..] {
+                [name, value, lower, upper] => ([name, value, lower, upper], None),
+                [name, value, lower, upper, dimension] => {
+                    ([name, value, lower, upper], Some(dimension))
+                }
+                _ => return Err(invalid(r.line, "variable needs name, value and bounds")),
+            };
+            let number = |v: &str| v.parse::<f64>().map_err(|e| invalid(r.line, e));
+            let bound = |v: &str| match v {
+                UNBOUNDED => Ok(None),
+                _ => number(v).map(Some),
+            };
+            let variable =
+                Variable::new(name, number(value)?).with_bounds(bound(lower)?, bound(upper)?);
+
+            match dimension {
+                Some(d) => Ok(variable.with_dimension(read_dimension(d, r.line)?)),
+                None => Ok(variable),
+            }
+        })
+        .collect()
+}
+
+/// Read the dimension written as exponents of length and angle, such as `1,0`
+fn read_dimension(input: &str, line: usize) -> Result<Dimension, FormatError> {
+    let Some((length, angle)) = input.split_once(',') else {
+        return Err(invalid(
+            line,
+            "dimension needs exponents of length and angle",
+        ));
+    };
+    let exponent = |v: &str| v.parse::<i32>().map_err(|e| invalid(line, e));
+
+    Ok(Dimension {
+        length: exponent(length)?,
+        angle: exponent(angle)?,
+    })
+}
+
+/// Check records are only of `kind`, and the number of them
+fn expect_only(records: &[Record], kind: &'static str, expected: usize) -> Result<(), FormatError> {
+    if let Some(other) = records.iter().find(|r| r.kind != kind) {
+        return Err(invalid(
+            other.line,
+            format!("{} is not allowed here", other.kind),
+        ));
+    }
+
+    match records.len() {
+        actual if actual == expected => Ok(()),
+        actual => Err(FormatError::RecordCount {
+            kind,
+            expected,
+            actual,
+        }),
+    }
+}
+
+fn invalid(line: usize, message: impl ToString) -> FormatError {
+    FormatError::InvalidRecord {
+        line,
+        message: message.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        equation::{
+            arithmetic::{ArithmeticEquation, Operator},
+            constant::ConstantEquation,
+            function::{Function, FunctionEquation},
+            monomial::MonomialEquation,
+            power::PowerEquation,
+        },
+        unit::Unit,
+    };
+    use pretty_assertions::assert_eq;
+    use rand::{Rng, SeedableRng, rngs::StdRng, seq::IndexedRandom};
+    use rstest::rstest;
+
+    /// Names that are hard to write after numbers
+    const NAMES: [&str; 6] = ["x", "y_2", "e", "Exp", "sin", "pix"];
+
+    const FUNCTIONS: [Function; 10] = [
+        Function::Sin,
+        Function::Cos,
+        Function::Tan,
+        Function::Atan2,
+        Function::Sqrt,
+        Function::Abs,
+        Function::Min,
+        Function::Max,
+        Function::Ln,
+        Function::Sign,
+    ];
+
+    const OPERATORS: [Operator; 4] = [
+        Operator::Add,
+        Operator::Subtract,
+        Operator::Multiply,
+        Operator::Divide,
+    ];
+
+    fn random_number(rng: &mut StdRng) -> f64 {
+        match rng.random_range(0..4) {
+            0 => *[
+                0.0,
+                -0.0,
+                0.1,
+                1e-9,
+                1e21,
+                -2.5,
+                1e300,
+                -1e-300,
+                5e-324,
+                f64::MAX,
+            ]
+            .choose(rng)
+            .unwrap(),
+            1 => rng.random_range(-10..10) as f64,
+            _ => rng.random_range(-1e3..1e3),
+        }
+    }
+
+    fn random_equation(rng: &mut StdRng, depth: usize) -> Equation {
+        if depth == 0 || rng.random_bool(0.3) {
+            return match rng.random_range(0..3) {
+                0 => random_number(rng).into(),
+                1 => {
+                    ConstantEquation::with_unit(random_number(rng), *Unit::ALL.choose(rng).unwrap())
+                        .into()
+                }
+                _ => MonomialEquation::new(
+                    random_number(rng),
+                    NAMES.choose(rng).unwrap(),
+                    rng.random_range(-3..=3),
+                )
+                .into(),
+            };
+        }
+
+        match rng.random_range(0..3) {
+            0 => {
+                let operands: Vec<Equation> = (0..rng.random_range(2..=4))
+                    .map(|_| random_equation(rng, depth - 1))
+                    .collect();
+                ArithmeticEquation::new(*OPERATORS.choose(rng).unwrap(), &operands)
+                    .unwrap()
+                    .into()
+            }
+            1 => {
+                let function = *FUNCTIONS.choose(rng).unwrap();
+                let arguments: Vec<Equation> = (0..function.arity())
+                    .map(|_| random_equation(rng, depth - 1))
+                    .collect();
+                FunctionEquation::new(function, &arguments).unwrap().into()
+            }
+            _ => PowerEquation::new(
+                random_equation(rng, depth - 1),
+                random_equation(rng, depth - 1),
+            )
+            .into(),
+        }
+    }
+
+    #[test]
+    fn test_random_equations_round_trip_through_display() {
+        // arrange
+        let mut rng = StdRng::seed_from_u64(13);
+
+        for _ in 0..2000 {
+            let equation = random_equation(&mut rng, 4);
+
+            // act
+            let text = equation.to_string();
+            let result = parse(&text);
+
+            // assert
+            assert_eq!(result.ok(), Some(equation), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_random_environments_round_trip() {
+        // arrange
+        let mut rng = StdRng::seed_from_u64(13);
+
+        for _ in 0..200 {
+            let variables: Vec<Variable> = (0..rng.random_range(0..5))
+                .map(|i| {
+                    let bound = |rng: &mut StdRng| rng.random_bool(0.5).then(|| random_number(rng));
+                    let value = match rng.random_range(0..8) {
+                        0 => f64::INFINITY,
+                        1 => f64::NEG_INFINITY,
+                        _ => random_number(&mut rng),
+                    };
+                    let variable = Variable::new(&format!("v{}", i), value)
+                        .with_bounds(bound(&mut rng), bound(&mut rng));
+                    match rng.random_bool(0.5) {
+                        true => variable.with_dimension(Dimension {
+                            length: rng.random_range(-2..3),
+                            angle: rng.random_range(-2..3),
+                        }),
+                        false => variable,
+                    }
+                })
+                .collect();
+            let env = Environment::from_variables(variables);
+
+            // act
+            let result = Environment::from_text(&env.to_text().unwrap()).unwrap();
+
+            // assert
+            let mut expected = env.variables();
+            let mut actual = result.variables();
+            expected.sort_by_key(|v| (*v.name).clone());
+            actual.sort_by_key(|v| (*v.name).clone());
+            type Fields = (String, f64, Option<f64>, Option<f64>, Option<Dimension>);
+            let fields = |vs: &[Variable]| -> Vec<Fields> {
+                vs.iter()
+                    .map(|v| {
+                        let name = (*v.name).clone();
+                        (name, *v.value, v.lower(), v.upper(), v.dimension())
+                    })
+                    .collect()
+            };
+            assert_eq!(fields(&actual), fields(&expected));
+        }
+    }
+
+    #[test]
+    fn test_equation_round_trip() {
+        // arrange
+        let equation = parse("x^2 + (y + 1) + 10[mm]").unwrap();
+
+        // act
+        let text = equation.to_text().unwrap();
+        let result = Equation::from_text(&text);
+
+        // assert
+        assert_eq!(text, "solver 1\nequation 1x^2+(1y^1+1)+10[mm]\n");
+        assert_eq!(result, Ok(equation));
+    }
+
+    #[test]
+    fn test_variable_round_trip() {
+        // arrange
+        let variable = Variable::new("x", 0.1).with_bounds(None, Some(2.0));
+
+        // act
+        let text = variable.to_text().unwrap();
+        let result = Variable::from_text(&text).unwrap();
+
+        // assert
+        assert_eq!(text, "solver 1\nvariable x 0.1 _ 2\n");
+        assert_eq!(*result.value, 0.1);
+        assert_eq!(result.lower(), None);
+        assert_eq!(result.upper(), Some(2.0));
+    }
+
+    #[test]
+    fn test_variable_with_dimension_round_trip() {
+        // arrange
+        let variable = Variable::new("a", 0.5).with_dimension(Dimension::ANGLE);
+
+        // act
+        let text = variable.to_text().unwrap();
+        let result = Variable::from_text(&text).unwrap();
+
+        // assert
+        assert_eq!(text, "solver 1\nvariable a 0.5 _ _ 0,1\n");
+        assert_eq!(result.dimension(), Some(Dimension::ANGLE));
+    }
+
+    #[test]
+    fn test_read_ignores_comments_and_empty_lines() {
+        // arrange
+        let text = "# copied constraints\n\nsolver 1\n\n# x\nvariable x 1 _ _\nvariable y -2 0 _\n";
+
+        // act
+        let result = Environment::from_text(text).unwrap();
+
+        // assert
+        assert_eq!(*result.get("x").unwrap().value, 1.0);
+        assert_eq!(result.get("y").unwrap().lower(), Some(0.0));
+    }
+
+    #[rstest]
+    #[case(f64::INFINITY.into())]
+    #[case(MonomialEquation::new(f64::NEG_INFINITY, "x", 1).into())]
+    #[case(FunctionEquation::new(Function::Sin, &[f64::NAN.into()]).unwrap().into())]
+    fn test_write_rejects_non_finite_numbers_in_equation(#[case] equation: Equation) {
+        // arrange
+
+        // act
+        let result = equation.to_text();
+
+        // assert
+        assert!(
+            matches!(result, Err(FormatError::NonFinite(_))),
+            "{:?}",
+            result
+        );
+    }
+
+    #[test]
+    fn test_large_and_small_numbers_are_written_with_exponent() {
+        // arrange
+        let variable = Variable::new("x", 1e300).with_bounds(Some(-2.5e-300), None);
+
+        // act
+        let text = variable.to_text().unwrap();
+
+        // assert
+        assert_eq!(text, "solver 1\nvariable x 1e300 -2.5e-300 _\n");
+        assert_eq!(*Variable::from_text(&text).unwrap().value, 1e300);
+    }
+
+    #[test]
+    fn test_read_rejects_newer_version() {
+        // arrange
+        let text = "solver 2\nequation x\n";
+
+        // act
+        let result = Equation::from_text(text);
+
+        // assert
+        assert_eq!(result, Err(FormatError::UnsupportedVersion(2)));
+    }
+
+    #[test]
+    fn test_read_rejects_text_without_header() {
+        // arrange
+        let text = "equation x\n";
+
+        // act
+        let result = Equation::from_text(text);
+
+        // assert
+        assert_eq!(result, Err(FormatError::MissingHeader));
+    }
+
+    #[test]
+    fn test_read_reports_line_of_invalid_record() {
+        // arrange
+        let text = "solver 1\nvariable x 1 _ _\nvariable y one _ _\n";
+
+        // act
+        let result = Environment::from_text(text);
+
+        // assert
+        assert!(matches!(
+            result,
+            Err(FormatError::InvalidRecord { line: 3, .. })
+        ));
+    }
+
+    #[test]
+    fn test_read_rejects_unexpected_records() {
+        // arrange
+        let text = "solver 1\nequation x\nequation y\n";
+
+        // act
+        let twice = Equation::from_text(text);
+        let mixed = Environment::from_text(text);
+
+        // assert
+        assert_eq!(
+            twice,
+            Err(FormatError::RecordCount {
+                kind: EQUATION,
+                expected: 1,
+                actual: 2
+            })
+        );
+        assert!(matches!(
+            mixed,
+            Err(FormatError::InvalidRecord { line: 2, .. })
+        ));
+    }
+}
@@ -0,0 +1,262 @@
+use std::path::Path;
+
+use crate::{
+    EquationId, SoftConstraint, SolveError, SolveMode, SolveOptions, SolveReport, Solver,
+    environment::Environment, equation::Equation, serialize::FormatError, serialize::TextFormat,
+};
+
+/// Errors of [`Solver::replay`]
+#[derive(Debug, thiserror::Error)]
+pub enum ReplayError {
+    #[error("Failed to access the trace file: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid trace: {0}")]
+    Format(#[from] FormatError),
+}
+
+/// An iteration recorded in [`Trace`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceStep {
+    /// Iteration number in the cluster, starting from 1
+    pub iteration: usize,
+
+    /// Variables moved by the step
+    pub variables: Vec<String>,
+
+    /// Residuals of equations before the step
+    pub residuals: Vec<f64>,
+
+    /// Step of `variables`, in the same order
+    pub step: Vec<f64>,
+}
+
+/// Record of a run of [`Solver::solve`].
+///
+/// The trace contains everything to solve the same system again, that is equations with their
+/// ids, initial variables and dimensions, the mode and options. Iterations of Newton-Raphson and
+/// least squares methods are recorded in order. This is written as a text with [`TextFormat`] to
+/// attach to bug reports, and [`Solver::replay`] reproduces the run from it.
+#[derive(Debug, Clone)]
+pub struct Trace {
+    pub mode: SolveMode,
+    pub options: SolveOptions,
+    pub epsilon: f64,
+
+    /// Equations ordered by id
+    pub equations: Vec<(EquationId, Equation)>,
+
+    /// Inequalities ordered by id
+    pub inequalities: Vec<(EquationId, Equation)>,
+
+    /// Soft equations ordered by id
+    pub soft: Vec<(EquationId, Equation, SoftConstraint)>,
+
+    /// Variables before solving
+    pub variables: Environment,
+
+    pub dimensions: Environment,
+
+    /// Recorded iterations
+    pub steps: Vec<TraceStep>,
+}
+
+impl Trace {
+    /// Write the trace to the file in the text format
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), ReplayError> {
+        std::fs::write(path, self.to_text()?)?;
+
+        Ok(())
+    }
+
+    /// Read the trace from the file in the text format
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, ReplayError> {
+        let text = std::fs::read_to_string(path)?;
+
+        Ok(Trace::from_text(&text)?)
+    }
+}
+
+/// Result of [`Solver::replay`]
+pub struct Replay {
+    /// The trace read from the file
+    pub recorded: Trace,
+
+    /// The solver after solving again. It has the trace of the replayed run.
+    pub solver: Solver,
+
+    /// Result of solving again
+    pub result: Result<SolveReport, SolveError>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DefaultEquationIdGenerator, equation::parse};
+    use epsilon::DefaultEpsilon;
+    use pretty_assertions::assert_eq;
+
+    fn make_solver(variables: &[(&str, f64)], equations: &[&str]) -> Solver {
+        let mut solver =
+            Solver::new::<DefaultEpsilon>(Box::new(DefaultEquationIdGenerator::default()));
+        solver.update_variables(&Environment::from_tuples(variables));
+        for equation in equations {
+            solver.add_equation(parse(equation).unwrap());
+        }
+        solver
+    }
+
+    fn temp_file(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("solver-{}-{}.trace", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_solve_records_iterations_while_tracing() {
+        // arrange
+        let mut solver = make_solver(&[("x", 1.0)], &["x^2 - 2"]);
+        solver.set_tracing(true);
+
+        // act
+        solver.solve().unwrap();
+
+        // assert
+        let trace = solver.trace().unwrap();
+        assert_eq!(trace.equations, vec![(1.into(), parse("x^2 - 2").unwrap())]);
+        assert_eq!(*trace.variables.get("x").unwrap().value, 1.0);
+        assert_eq!(
+            trace.steps[0],
+            TraceStep {
+                iteration: 1,
+                variables: vec!["x".to_string()],
+                residuals: vec![-1.0],
+                step: vec![0.5],
+            }
+        );
+        assert!(trace.steps.len() > 1);
+    }
+
+    #[test]
+    fn test_solve_does_not_record_without_tracing() {
+        // arrange
+        let mut solver = make_solver(&[("x", 1.0)], &["x^2 - 2"]);
+
+        // act
+        solver.solve().unwrap();
+
+        // assert
+        assert!(solver.trace().is_none());
+    }
+
+    #[test]
+    fn test_trace_round_trip_through_text() {
+        // arrange
+        let mut solver = make_solver(&[("x", 0.1), ("y", 3.0)], &["x * y - 1", "x + y - 3.5"]);
+        solver.update_dimensions(&Environment::from_tuples(&[("d", 2.0)]));
+        solver.add_inequality(parse("d - x").unwrap());
+        solver.add_soft_equation(
+            parse("x - 0.2").unwrap(),
+            SoftConstraint::new(2, 0.5).unwrap(),
+        );
+        solver.set_mode(SolveMode::LeastSquares);
+        solver.set_tracing(true);
+        solver.solve().unwrap();
+        let trace = solver.trace().unwrap();
+
+        // act
+        let result = Trace::from_text(&trace.to_text().unwrap()).unwrap();
+
+        // assert
+        assert_eq!(result.mode, trace.mode);
+        assert_eq!(result.options, trace.options);
+        assert_eq!(result.epsilon, trace.epsilon);
+        assert_eq!(result.equations, trace.equations);
+        assert_eq!(result.inequalities, trace.inequalities);
+        assert_eq!(result.soft, trace.soft);
+        assert_eq!(result.variables.to_text(), trace.variables.to_text());
+        assert_eq!(result.dimensions.to_text(), trace.dimensions.to_text());
+        assert_eq!(result.steps, trace.steps);
+    }
+
+    #[test]
+    fn test_replay_reproduces_recorded_run() {
+        // arrange
+        let mut solver = make_solver(
+            &[("x", 1.0), ("y", 0.5), ("z", 4.0)],
+            &["x^2 + y^2 - 4", "x - y", "z - 1"],
+        );
+        solver.set_tracing(true);
+        let expected = solver.solve().unwrap();
+        let path = temp_file("replay");
+        solver.trace().unwrap().write(&path).unwrap();
+
+        // act
+        let replay = Solver::replay(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        // assert
+        let report = replay.result.unwrap();
+        assert_eq!(report.clusters, expected.clusters);
+        assert_eq!(
+            replay.solver.trace().unwrap().steps,
+            solver.trace().unwrap().steps
+        );
+        assert_eq!(replay.recorded.steps, solver.trace().unwrap().steps);
+        assert_eq!(
+            *replay.solver.variables().get("x").unwrap().value,
+            *solver.variables().get("x").unwrap().value
+        );
+    }
+
+    #[test]
+    fn test_replay_keeps_ids_and_bounds() {
+        // arrange
+        let text = "solver 1\nmode least_squares\nequality 7 x - 3\nvariable x 0 _ 2\n";
+        let path = temp_file("bounds");
+        std::fs::write(&path, text).unwrap();
+
+        // act
+        let replay = Solver::replay(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        // assert
+        let report = replay.result.unwrap();
+        assert_eq!(report.clusters[0].equations, vec![7.into()]);
+        assert_eq!(*replay.solver.variables().get("x").unwrap().value, 2.0);
+        assert_eq!(
+            replay.solver.variables().get("x").unwrap().upper(),
+            Some(2.0)
+        );
+    }
+
+    #[test]
+    fn test_replay_reports_invalid_trace() {
+        // arrange
+        let path = temp_file("invalid");
+        std::fs::write(&path, "solver 1\nequation x - 3\n").unwrap();
+
+        // act
+        let result = Solver::replay(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        // assert
+        assert!(matches!(
+            result,
+            Err(ReplayError::Format(FormatError::InvalidRecord {
+                line: 2,
+                ..
+            }))
+        ));
+    }
+
+    #[test]
+    fn test_replay_reports_missing_file() {
+        // arrange
+        let path = temp_file("missing");
+
+        // act
+        let result = Solver::replay(&path);
+
+        // assert
+        assert!(matches!(result, Err(ReplayError::Io(_))));
+    }
+}
@@ -0,0 +1,225 @@
+use std::fmt::Display;
+
+/// Units that can be written in equations. Values are normalised into base units, millimeter for
+/// length and radian for angle.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum Unit {
+    #[default]
+    Millimeter,
+    Centimeter,
+    Meter,
+    Inch,
+    Degree,
+    Radian,
+}
+
+impl Unit {
+    /// All units
+    pub const ALL: [Unit; 6] = [
+        Unit::Millimeter,
+        Unit::Centimeter,
+        Unit::Meter,
+        Unit::Inch,
+        Unit::Degree,
+        Unit::Radian,
+    ];
+
+    /// Get the symbol of unit in expression
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Unit::Millimeter => "mm",
+            Unit::Centimeter => "cm",
+            Unit::Meter => "m",
+            Unit::Inch => "in",
+            Unit::Degree => "deg",
+            Unit::Radian => "rad",
+        }
+    }
+
+    /// Get the unit from the symbol
+    pub fn from_symbol(symbol: &str) -> Option<Unit> {
+        Unit::ALL.into_iter().find(|u| u.symbol() == symbol)
+    }
+
+    /// Get the dimension that the unit measures
+    pub fn dimension(&self) -> Dimension {
+        match self {
+            Unit::Millimeter | Unit::Centimeter | Unit::Meter | Unit::Inch => Dimension::LENGTH,
+            Unit::Degree | Unit::Radian => Dimension::ANGLE,
+        }
+    }
+
+    /// Scale of the unit in the base unit of the dimension
+    fn scale(&self) -> f64 {
+        match self {
+            Unit::Millimeter => 1.0,
+            Unit::Centimeter => 10.0,
+            Unit::Meter => 1000.0,
+            Unit::Inch => 25.4,
+            Unit::Degree => std::f64::consts::PI / 180.0,
+            Unit::Radian => 1.0,
+        }
+    }
+
+    /// Convert the value in this unit into the base unit
+    pub fn to_base(&self, value: f64) -> f64 {
+        value * self.scale()
+    }
+
+    /// Convert the value in the base unit into this unit
+    pub fn from_base(&self, value: f64) -> f64 {
+        value / self.scale()
+    }
+}
+
+impl Display for Unit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.symbol())
+    }
+}
+
+/// Physical dimension of a value, as exponents of length and angle
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct Dimension {
+    pub length: i32,
+    pub angle: i32,
+}
+
+impl Dimension {
+    /// Dimensionless, such as ratio or count
+    pub const NONE: Dimension = Dimension {
+        length: 0,
+        angle: 0,
+    };
+
+    pub const LENGTH: Dimension = Dimension {
+        length: 1,
+        angle: 0,
+    };
+
+    pub const ANGLE: Dimension = Dimension {
+        length: 0,
+        angle: 1,
+    };
+
+    /// Dimension of product of values
+    pub fn multiply(&self, other: &Dimension) -> Dimension {
+        Dimension {
+            length: self.length + other.length,
+            angle: self.angle + other.angle,
+        }
+    }
+
+    /// Dimension of quotient of values
+    pub fn divide(&self, other: &Dimension) -> Dimension {
+        self.multiply(&other.powi(-1))
+    }
+
+    /// Dimension of the value powered by integer
+    pub fn powi(&self, exponent: i32) -> Dimension {
+        Dimension {
+            length: self.length * exponent,
+            angle: self.angle * exponent,
+        }
+    }
+
+    /// Return `true` if the dimension is dimensionless
+    pub fn is_none(&self) -> bool {
+        *self == Dimension::NONE
+    }
+}
+
+impl Display for Dimension {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_none() {
+            return write!(f, "dimensionless");
+        }
+
+        let parts: Vec<String> = [("length", self.length), ("angle", self.angle)]
+            .into_iter()
+            .filter(|(_, e)| *e != 0)
+            .map(|(name, e)| {
+                if e == 1 {
+                    name.to_string()
+                } else {
+                    format!("{}^{}", name, e)
+                }
+            })
+            .collect();
+        write!(f, "{}", parts.join(" * "))
+    }
+}
+
+/// Errors of dimension check of equations
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum DimensionError {
+    #[error("Can not combine {lhs} with {rhs}")]
+    Mismatch { lhs: Dimension, rhs: Dimension },
+
+    #[error("{function} can not take {dimension}")]
+    InvalidArgument {
+        function: &'static str,
+        dimension: Dimension,
+    },
+
+    #[error("Exponent must be dimensionless, but {0}")]
+    InvalidExponent(Dimension),
+
+    #[error("Can not take non-integer power of {0}")]
+    FractionalPower(Dimension),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(Unit::Millimeter, 2.0, 2.0)]
+    #[case(Unit::Centimeter, 2.0, 20.0)]
+    #[case(Unit::Meter, 2.0, 2000.0)]
+    #[case(Unit::Inch, 2.0, 50.8)]
+    #[case(Unit::Degree, 180.0, std::f64::consts::PI)]
+    #[case(Unit::Radian, 2.0, 2.0)]
+    fn test_convert_to_base_unit(#[case] unit: Unit, #[case] value: f64, #[case] expected: f64) {
+        // arrange
+
+        // act
+        let base = unit.to_base(value);
+        let back = unit.from_base(base);
+
+        // assert
+        assert_relative_eq!(base, expected);
+        assert_relative_eq!(back, value, epsilon = 1e-4);
+    }
+
+    #[test]
+    fn test_from_symbol_gets_all_units() {
+        // arrange
+        let symbols = Unit::ALL.map(|u| u.symbol());
+
+        // act
+        let result = symbols.map(Unit::from_symbol);
+
+        // assert
+        assert_eq!(result, Unit::ALL.map(Some));
+        assert_eq!(Unit::from_symbol("ft"), None);
+    }
+
+    #[test]
+    fn test_dimension_arithmetic() {
+        // arrange
+        let area = Dimension::LENGTH.powi(2);
+
+        // act
+        let length = area.divide(&Dimension::LENGTH);
+        let per_angle = Dimension::LENGTH.divide(&Dimension::ANGLE);
+
+        // assert
+        assert_eq!(length, Dimension::LENGTH);
+        assert_eq!(per_angle.to_string(), "length * angle^-1");
+        assert!(Dimension::LENGTH.divide(&Dimension::LENGTH).is_none());
+    }
+}
@@ -1,12 +1,20 @@
 use immutable::Im;
 
+use crate::unit::Dimension;
+
 /// A simple variable representation
 #[derive(Debug, Clone)]
 pub struct Variable {
     /// Name of the variable
     pub name: Im<String>,
     /// Current value of the variable
-    pub value: Im<f32>,
+    pub value: Im<f64>,
+    /// Lower bound of the value, if any
+    lower: Option<f64>,
+    /// Upper bound of the value, if any
+    upper: Option<f64>,
+    /// Dimension of the value, if known
+    dimension: Option<Dimension>,
 }
 
 impl PartialEq for Variable {
@@ -16,13 +24,13 @@ impl PartialEq for Variable {
     }
 }
 
-impl From<Variable> for f32 {
+impl From<Variable> for f64 {
     fn from(value: Variable) -> Self {
         *value.value
     }
 }
 
-impl From<&Variable> for f32 {
+impl From<&Variable> for f64 {
     fn from(value: &Variable) -> Self {
         *value.value
     }
@@ -30,12 +38,15 @@ impl From<&Variable> for f32 {
 
 impl Variable {
     /// Make a new variable with name
-    pub fn new(name: &str, value: f32) -> Self {
+    pub fn new(name: &str, value: f64) -> Self {
         assert!(!name.trim().is_empty(), "Variable name cannot be empty");
 
         Variable {
             name: name.trim().to_string().into(),
             value: value.into(),
+            lower: None,
+            upper: None,
+            dimension: None,
         }
     }
 
@@ -43,12 +54,49 @@ impl Variable {
     pub fn with_name(&self, name: &str) -> Self {
         Variable {
             name: name.to_string().into(),
-            value: self.value.clone(),
+            ..self.clone()
+        }
+    }
+
+    /// Make a new variable with bounds of the value. Solver keeps the value in the bounds.
+    ///
+    /// # Arguments
+    /// * `lower` - Lower bound, or `None` if not bounded
+    /// * `upper` - Upper bound, or `None` if not bounded
+    pub fn with_bounds(&self, lower: Option<f64>, upper: Option<f64>) -> Self {
+        Variable {
+            lower,
+            upper,
+            ..self.clone()
+        }
+    }
+
+    /// Make a new variable with dimension of the value. Equations with the variable are checked
+    /// with the dimension when evaluated with dimension.
+    pub fn with_dimension(&self, dimension: Dimension) -> Self {
+        Variable {
+            dimension: Some(dimension),
+            ..self.clone()
         }
     }
 
+    /// Get the lower bound of the value
+    pub fn lower(&self) -> Option<f64> {
+        self.lower
+    }
+
+    /// Get the upper bound of the value
+    pub fn upper(&self) -> Option<f64> {
+        self.upper
+    }
+
+    /// Get the dimension of the value
+    pub fn dimension(&self) -> Option<Dimension> {
+        self.dimension
+    }
+
     /// Update the value.
-    pub fn set_value(&mut self, value: f32) {
+    pub fn set_value(&mut self, value: f64) {
         self.value = value.into()
     }
 }
@@ -91,6 +139,36 @@ mod tests {
         assert_eq!(*v.name, "x");
     }
 
+    #[test]
+    fn test_with_bounds_keeps_name_and_value() {
+        // arrange
+        let v = Variable::new("x", 5.);
+
+        // act
+        let bounded = v.with_bounds(Some(0.), None);
+
+        // assert
+        assert_eq!(*bounded.name, "x");
+        assert_eq!(*bounded.value, 5.);
+        assert_eq!(bounded.lower(), Some(0.));
+        assert_eq!(bounded.upper(), None);
+        assert_eq!(v.lower(), None);
+    }
+
+    #[test]
+    fn test_with_dimension_keeps_bounds() {
+        // arrange
+        let v = Variable::new("x", 5.).with_bounds(Some(0.), None);
+
+        // act
+        let length = v.with_dimension(Dimension::LENGTH);
+
+        // assert
+        assert_eq!(length.dimension(), Some(Dimension::LENGTH));
+        assert_eq!(length.lower(), Some(0.));
+        assert_eq!(v.dimension(), None);
+    }
+
     #[test]
     fn test_equality_same_name_same_value() {
         let var1 = Variable::new("x", 10.0);
@@ -1,5 +1,5 @@
 use std::{
-    f32,
+    f64,
     ops::{Add, Div, Index, IndexMut, Mul, Sub},
 };
 
@@ -11,7 +11,7 @@ use crate::matrix::{Matrix, simple::SimpleMatrix};
 
 /// A simple vector type
 #[derive(Debug, Clone, PartialEq)]
-pub struct Vector(Vec<f32>);
+pub struct Vector(Vec<f64>);
 
 /// Method to convert a vector to a [FloatingMatrix], column or row direction
 #[derive(Debug, PartialEq, Clone, Copy)]
@@ -28,7 +28,7 @@ impl Vector {
     ///
     /// # Returns
     /// * new vector. Return `Err` when `vec` is 0-sized slice
-    pub fn from(vec: &[f32]) -> Result<Self, color_eyre::eyre::Error> {
+    pub fn from(vec: &[f64]) -> Result<Self, color_eyre::eyre::Error> {
         if vec.is_empty() {
             return Err(color_eyre::eyre::eyre!("Can not define 0-dimension vector"));
         }
@@ -67,7 +67,7 @@ impl Vector {
     ///
     /// # Return
     /// * New `SimpleMatrix`
-    pub fn to_matrix(&self, method: TransposeMethod) -> SimpleMatrix<f32> {
+    pub fn to_matrix(&self, method: TransposeMethod) -> SimpleMatrix<f64> {
         let rows = match method {
             TransposeMethod::Column => self.len(),
             TransposeMethod::Row => 1,
@@ -79,32 +79,33 @@ impl Vector {
 
         let mut mat = SimpleMatrix::new(rows, columns).expect("Must succeeded");
 
-        let mut update: Box<dyn FnMut(usize, &mut SimpleMatrix<f32>)> = match method {
-            TransposeMethod::Column => Box::new(move |idx, mat| -> () {
-                mat.set(idx, 0, self[idx]).expect("should success to set");
-            }),
-            TransposeMethod::Row => Box::new(move |idx, mat| -> () {
-                mat.set(0, idx, self[idx]).expect("should success to set");
-            }),
-        };
-
         for idx in 0..self.len() {
-            update(idx, &mut mat);
+            let (row, column) = match method {
+                TransposeMethod::Column => (idx, 0),
+                TransposeMethod::Row => (0, idx),
+            };
+            mat.set(row, column, self[idx])
+                .expect("should success to set");
         }
 
         mat
     }
 
+    /// Iterate values of the vector
+    pub fn iter(&self) -> std::slice::Iter<'_, f64> {
+        self.0.iter()
+    }
+
     /// Compute norm of the vector
-    pub fn norm(&self) -> f32 {
-        let f = self.0.iter().map(|f| f * f).sum::<f32>();
+    pub fn norm(&self) -> f64 {
+        let f = self.0.iter().map(|f| f * f).sum::<f64>();
 
-        f32::sqrt(f)
+        f64::sqrt(f)
     }
 }
 
 impl Index<usize> for Vector {
-    type Output = f32;
+    type Output = f64;
 
     fn index(&self, index: usize) -> &Self::Output {
         self.0.index(index)
@@ -117,18 +118,18 @@ impl IndexMut<usize> for Vector {
     }
 }
 
-impl Mul<f32> for Vector {
+impl Mul<f64> for Vector {
     type Output = Self;
 
-    fn mul(self, rhs: f32) -> Self::Output {
+    fn mul(self, rhs: f64) -> Self::Output {
         Vector(self.0.iter().map(|f| f * rhs).collect())
     }
 }
 
-impl Div<f32> for Vector {
+impl Div<f64> for Vector {
     type Output = Self;
 
-    fn div(self, rhs: f32) -> Self::Output {
+    fn div(self, rhs: f64) -> Self::Output {
         Vector(self.0.iter().map(|f| f / rhs).collect())
     }
 }
@@ -186,7 +187,7 @@ mod tests {
     #[case(&[5.0], 1)]
     #[case(&[1.0, 2.0, 3.0, 4.0, 5.0], 5)]
     fn test_new_creates_vector_with_correct_values(
-        #[case] values: &[f32],
+        #[case] values: &[f64],
         #[case] expected_len: usize,
     ) -> Result<(), color_eyre::eyre::Error> {
         // Arrange & Act
@@ -242,11 +243,11 @@ mod tests {
     #[case(&[1.0, 2.0, 3.0, 4.0, 5.0], TransposeMethod::Column, 5, 1, vec![(0, 0, 1.0), (1, 0, 2.0), (2, 0, 3.0), (3, 0, 4.0), (4, 0, 5.0)])]
     #[case(&[1.0, 2.0, 3.0, 4.0, 5.0], TransposeMethod::Row, 1, 5, vec![(0, 0, 1.0), (0, 1, 2.0), (0, 2, 3.0), (0, 3, 4.0), (0, 4, 5.0)])]
     fn test_to_matrix_converts_with_correct_dimensions_and_values(
-        #[case] values: &[f32],
+        #[case] values: &[f64],
         #[case] method: TransposeMethod,
         #[case] expected_rows: usize,
         #[case] expected_columns: usize,
-        #[case] expected_values: Vec<(usize, usize, f32)>,
+        #[case] expected_values: Vec<(usize, usize, f64)>,
     ) -> Result<(), color_eyre::eyre::Error> {
         // Arrange
         let vector = Vector::from(values)?;
@@ -258,7 +259,7 @@ mod tests {
         assert_eq!(matrix.size().rows(), expected_rows);
         assert_eq!(matrix.size().columns(), expected_columns);
         for (row, col, expected_value) in expected_values {
-            assert_eq!(matrix.get(row, col)?.map(|v| *v), Some(expected_value));
+            assert_eq!(matrix.get(row, col)?.copied(), Some(expected_value));
         }
         Ok(())
     }
@@ -269,9 +270,9 @@ mod tests {
     #[case(&[1.0, 2.0, 3.0], 0.0, &[0.0, 0.0, 0.0])]
     #[case(&[5.0], 3.0, &[15.0])]
     fn test_mul_scalar_multiplies_all_elements(
-        #[case] values: &[f32],
-        #[case] scalar: f32,
-        #[case] expected: &[f32],
+        #[case] values: &[f64],
+        #[case] scalar: f64,
+        #[case] expected: &[f64],
     ) -> Result<(), color_eyre::eyre::Error> {
         // Arrange
         let vector = Vector::from(values)?;
@@ -292,9 +293,9 @@ mod tests {
     #[case(&[1.0, 2.0, 3.0], 0.5, &[2.0, 4.0, 6.0])]
     #[case(&[10.0], 5.0, &[2.0])]
     fn test_div_scalar_divides_all_elements(
-        #[case] values: &[f32],
-        #[case] scalar: f32,
-        #[case] expected: &[f32],
+        #[case] values: &[f64],
+        #[case] scalar: f64,
+        #[case] expected: &[f64],
     ) -> Result<(), color_eyre::eyre::Error> {
         // Arrange
         let vector = Vector::from(values)?;
@@ -315,9 +316,9 @@ mod tests {
     #[case(&[1.0, 2.0, 3.0], &[0.0, 0.0, 0.0], &[1.0, 2.0, 3.0])]
     #[case(&[5.0], &[10.0], &[15.0])]
     fn test_add_vectors_adds_elements(
-        #[case] values1: &[f32],
-        #[case] values2: &[f32],
-        #[case] expected: &[f32],
+        #[case] values1: &[f64],
+        #[case] values2: &[f64],
+        #[case] expected: &[f64],
     ) -> Result<(), color_eyre::eyre::Error> {
         // Arrange
         let vector1 = Vector::from(values1)?;
@@ -353,9 +354,9 @@ mod tests {
     #[case(&[1.0, 2.0, 3.0], &[0.0, 0.0, 0.0], &[1.0, 2.0, 3.0])]
     #[case(&[10.0], &[5.0], &[5.0])]
     fn test_sub_vectors_subtracts_elements(
-        #[case] values1: &[f32],
-        #[case] values2: &[f32],
-        #[case] expected: &[f32],
+        #[case] values1: &[f64],
+        #[case] values2: &[f64],
+        #[case] expected: &[f64],
     ) -> Result<(), color_eyre::eyre::Error> {
         // Arrange
         let vector1 = Vector::from(values1)?;
@@ -392,9 +393,9 @@ mod tests {
     #[case(&[1.0, 2.0, 3.0], 2, 3.0)]
     #[case(&[42.0], 0, 42.0)]
     fn test_index_returns_correct_element(
-        #[case] values: &[f32],
+        #[case] values: &[f64],
         #[case] index: usize,
-        #[case] expected: f32,
+        #[case] expected: f64,
     ) -> Result<(), color_eyre::eyre::Error> {
         // Arrange
         let vector = Vector::from(values)?;
@@ -413,9 +414,9 @@ mod tests {
     #[case(&[1.0, 2.0, 3.0], 2, 30.0)]
     #[case(&[42.0], 0, 100.0)]
     fn test_index_mut_sets_correct_element(
-        #[case] values: &[f32],
+        #[case] values: &[f64],
         #[case] index: usize,
-        #[case] new_value: f32,
+        #[case] new_value: f64,
     ) -> Result<(), color_eyre::eyre::Error> {
         // Arrange
         let mut vector = Vector::from(values)?;
@@ -429,12 +430,12 @@ mod tests {
     }
 
     #[rstest]
-    #[case(&[1.0, 2.0, 3.0], f32::sqrt(14.0))]
+    #[case(&[1.0, 2.0, 3.0], f64::sqrt(14.0))]
     #[case(&[42.0], 42.0)]
     #[case(&[-42.0], 42.0)]
     fn test_compute_norm(
-        #[case] values: &[f32],
-        #[case] norm: f32,
+        #[case] values: &[f64],
+        #[case] norm: f64,
     ) -> Result<(), color_eyre::eyre::Error> {
         // Arrange
         let vector = Vector::from(values)?;
@@ -134,5 +134,14 @@ impl<T: Clone> Deref for Correlation<T> {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SketchGeometryOperation {
     LineSegment,
+    /// Rectangle from two corners
     Rectangle,
+    /// Rectangle from the center and a corner
+    CenterRectangle,
+    /// Regular polygon from the center and a vertex
+    Polygon {
+        sides: usize,
+    },
+    /// Slot from the start and the end of its center line, and a point on its side
+    Slot,
 }
@@ -1,6 +1,6 @@
 // Mouse handler for sketch commands.
 use bevy::{prelude::*, window::PrimaryWindow};
-use cad_base::sketch::{Geometry, LineSegment, Point2, SketchPerspective};
+use cad_base::sketch::{Geometry, LineSegment, Point2, Sketch, SketchPerspective, SketchShape};
 use ui_event::SketchGeometryOperation;
 
 use crate::bevy_app::{
@@ -107,13 +107,67 @@ pub fn on_geometory_operation_completed(
                 panic!("line segment operation should contain exactly two points");
             };
 
-            let start = Point2::new(start.x, start.y);
-            let end = Point2::new(end.x, end.y);
-            active_sketch.add_geometry(|scope| {
+            let start = Point2::new(start.x.into(), start.y.into());
+            let end = Point2::new(end.x.into(), end.y.into());
+            if let Err(e) = active_sketch.add_geometry(|scope| {
                 Geometry::LineSegment(LineSegment::from_points(&start, &end, scope))
-            });
+            }) {
+                tracing::warn!("Failed to add line segment: {:?}", e);
+            }
+        }
+        SketchGeometryOperation::Rectangle => {
+            let [first, second] = event.points.as_slice() else {
+                panic!("rectangle operation should contain exactly two points");
+            };
+
+            let first = Point2::new(first.x.into(), first.y.into());
+            let second = Point2::new(second.x.into(), second.y.into());
+            add_shape(active_sketch, &SketchShape::Rectangle(first, second));
+        }
+        SketchGeometryOperation::CenterRectangle => {
+            let [center, corner] = event.points.as_slice() else {
+                panic!("center rectangle operation should contain exactly two points");
+            };
+
+            add_shape(
+                active_sketch,
+                &SketchShape::CenterRectangle {
+                    center: Point2::new(center.x.into(), center.y.into()),
+                    corner: Point2::new(corner.x.into(), corner.y.into()),
+                },
+            );
+        }
+        SketchGeometryOperation::Polygon { sides } => {
+            let [center, vertex] = event.points.as_slice() else {
+                panic!("polygon operation should contain exactly two points");
+            };
+
+            add_shape(
+                active_sketch,
+                &SketchShape::Polygon {
+                    center: Point2::new(center.x.into(), center.y.into()),
+                    vertex: Point2::new(vertex.x.into(), vertex.y.into()),
+                    sides,
+                },
+            );
+        }
+        SketchGeometryOperation::Slot => {
+            let [start, end, side] = event.points.as_slice() else {
+                panic!("slot operation should contain exactly three points");
+            };
+
+            // the radius is the distance from the side point to the center line
+            let direction = (*end - *start).truncate();
+            let radius = direction.perp_dot((*side - *start).truncate()).abs() / direction.length();
+            add_shape(
+                active_sketch,
+                &SketchShape::Slot {
+                    start: Point2::new(start.x.into(), start.y.into()),
+                    end: Point2::new(end.x.into(), end.y.into()),
+                    radius: radius.into(),
+                },
+            );
         }
-        SketchGeometryOperation::Rectangle => todo!("rectangle completion is not implemented yet"),
     }
 
     cursor.0 = None;
@@ -121,6 +175,14 @@ pub fn on_geometory_operation_completed(
     t.commit();
 }
 
+/// Add the shape to the sketch. Shapes that can not be added, such as one without area, are
+/// ignored with a warning.
+fn add_shape(sketch: &mut Sketch, shape: &SketchShape) {
+    if let Err(e) = sketch.add_shape(shape) {
+        tracing::warn!("Failed to add {}: {:?}", shape.name(), e);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -137,6 +199,7 @@ mod tests {
     };
     use eyre::Result;
     use pretty_assertions::assert_eq;
+    use rstest::rstest;
     use ui_event::SketchGeometryOperation;
 
     use crate::bevy_app::test_support::WindowOp as _;
@@ -268,6 +331,87 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn completion_adds_rectangle_to_active_sketch() -> Result<()> {
+        // Arrange
+        let mut app = make_geometry_completed_app();
+        let world = app.world_mut();
+        let sketch_id = create_sketch(world);
+        world.resource_mut::<AppActiveSketch>().0 = Some(sketch_id);
+        world.resource_mut::<AppCursorIcon>().0 = Some(IconType::SketchRect);
+
+        // Act
+        world.trigger(GeometryOperationCompletedEvent {
+            operation: SketchGeometryOperation::Rectangle,
+            points: vec![Vec3::new(1.0, 2.0, 0.0), Vec3::new(4.0, 5.0, 0.0)],
+        });
+        world.flush();
+
+        // Assert
+        {
+            let mut engine = world.resource_mut::<EngineState>();
+            let tx = engine.0.begin();
+            let sketch = tx
+                .read::<SketchPerspective>()
+                .unwrap()
+                .get(&sketch_id)
+                .unwrap();
+            let edges = sketch.resolve_edges()?;
+            assert_eq!(edges.len(), 4);
+            assert_eq!(sketch.sketch_constraints().len(), 4);
+        }
+        assert_eq!(world.resource::<AppCursorIcon>().0, None);
+        Ok(())
+    }
+
+    #[rstest]
+    #[case(
+        SketchGeometryOperation::CenterRectangle,
+        vec![Vec3::new(1.0, 2.0, 0.0), Vec3::new(4.0, 5.0, 0.0)],
+        4
+    )]
+    #[case(
+        SketchGeometryOperation::Polygon { sides: 6 },
+        vec![Vec3::new(0.0, 0.0, 0.0), Vec3::new(2.0, 0.0, 0.0)],
+        6
+    )]
+    #[case(
+        SketchGeometryOperation::Slot,
+        vec![Vec3::new(0.0, 0.0, 0.0), Vec3::new(4.0, 0.0, 0.0), Vec3::new(2.0, 1.0, 0.0)],
+        4
+    )]
+    fn completion_adds_shape_to_active_sketch(
+        #[case] operation: SketchGeometryOperation,
+        #[case] points: Vec<Vec3>,
+        #[case] expected: usize,
+    ) -> Result<()> {
+        // Arrange
+        let mut app = make_geometry_completed_app();
+        let world = app.world_mut();
+        let sketch_id = create_sketch(world);
+        world.resource_mut::<AppActiveSketch>().0 = Some(sketch_id);
+        world.resource_mut::<AppCursorIcon>().0 = Some(IconType::SketchRect);
+
+        // Act
+        world.trigger(GeometryOperationCompletedEvent { operation, points });
+        world.flush();
+
+        // Assert
+        {
+            let mut engine = world.resource_mut::<EngineState>();
+            let tx = engine.0.begin();
+            let sketch = tx
+                .read::<SketchPerspective>()
+                .unwrap()
+                .get(&sketch_id)
+                .unwrap();
+            assert_eq!(sketch.resolve_edges()?.len(), expected);
+            assert!(!sketch.sketch_constraints().is_empty());
+        }
+        assert_eq!(world.resource::<AppCursorIcon>().0, None);
+        Ok(())
+    }
+
     #[test]
     fn completion_does_nothing_when_no_active_sketch_exists() -> Result<()> {
         // Arrange
@@ -206,7 +206,10 @@ pub(super) fn on_request_geometry_creation_command(
 
     let icon = match *command.geometry {
         ui_event::SketchGeometryOperation::LineSegment => IconType::SketchLine,
-        ui_event::SketchGeometryOperation::Rectangle => IconType::SketchRect,
+        ui_event::SketchGeometryOperation::Rectangle
+        | ui_event::SketchGeometryOperation::CenterRectangle
+        | ui_event::SketchGeometryOperation::Polygon { .. } => IconType::SketchRect,
+        ui_event::SketchGeometryOperation::Slot => IconType::SketchCircle,
     };
 
     cursor.0 = Some(icon);
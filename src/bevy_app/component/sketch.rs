@@ -65,11 +65,22 @@ impl GeometryOperation {
                 plane,
             )
             .expect("should be able to create operation by event"),
-            SketchGeometryOperation::Rectangle => Self::new(
+            SketchGeometryOperation::Rectangle
+            | SketchGeometryOperation::CenterRectangle
+            | SketchGeometryOperation::Polygon { .. } => Self::new(
                 &[GeometryOperationStep::Point, GeometryOperationStep::Point],
                 plane,
             )
             .expect("should be able to create operation by event"),
+            SketchGeometryOperation::Slot => Self::new(
+                &[
+                    GeometryOperationStep::Point,
+                    GeometryOperationStep::Point,
+                    GeometryOperationStep::Point,
+                ],
+                plane,
+            )
+            .expect("should be able to create operation by event"),
         }
     }
 
@@ -311,4 +322,25 @@ mod tests {
             &[GeometryOperationStep::Point, GeometryOperationStep::Point,]
         );
     }
+
+    #[test]
+    fn from_geometry_slot_creates_three_point_steps() {
+        use ui_event::SketchGeometryOperation;
+
+        // Arrange
+        let plane = default_plane();
+
+        // Act
+        let op = GeometryOperation::from_geometry(SketchGeometryOperation::Slot, &plane);
+
+        // Assert
+        assert_eq!(
+            op.steps.as_slice(),
+            &[
+                GeometryOperationStep::Point,
+                GeometryOperationStep::Point,
+                GeometryOperationStep::Point
+            ]
+        );
+    }
 }
@@ -1,7 +1,9 @@
 use bevy::math::Vec3;
 use cad_base::{point::Point, vector3::Vector3};
 
-/// convenience support for converting Point to Vec3
+/// convenience support for converting Point to Vec3.
+///
+/// CAD values are `f64`, and they are narrowed to `f32` only here for rendering.
 pub trait Vec3Ext {
     fn to_vec3(&self) -> Vec3;
 }
@@ -10,9 +12,9 @@ impl Vec3Ext for Point {
     /// Convert Point to Vec3
     fn to_vec3(&self) -> Vec3 {
         Vec3 {
-            x: *self.x,
-            y: *self.y,
-            z: *self.z,
+            x: *self.x as f32,
+            y: *self.y as f32,
+            z: *self.z as f32,
         }
     }
 }
@@ -21,9 +23,9 @@ impl Vec3Ext for Vector3 {
     /// Convert Point to Vec3
     fn to_vec3(&self) -> Vec3 {
         Vec3 {
-            x: self.x,
-            y: self.y,
-            z: self.z,
+            x: self.x as f32,
+            y: self.y as f32,
+            z: self.z as f32,
         }
     }
 }
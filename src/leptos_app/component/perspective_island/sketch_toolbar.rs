@@ -9,6 +9,9 @@ use ui_event::SketchGeometryOperation;
 use crate::leptos_app::ui_action::SketchGeometryRequestedAction;
 use crate::leptos_app::use_action::{UseActionReturn, use_action};
 
+/// Number of sides of polygons that the toolbar makes
+const DEFAULT_POLYGON_SIDES: usize = 6;
+
 /// Toolbar displayed when the perspective is set to Sketch.
 #[component]
 pub fn SketchToolbar() -> impl IntoView {
@@ -38,6 +41,38 @@ pub fn SketchToolbar() -> impl IntoView {
         );
     };
 
+    let dispatch_center_rectangle = dispatch.clone();
+    let center_rect_on_click = move |_ev: leptos::web_sys::MouseEvent| {
+        dispatch_center_rectangle(
+            SketchGeometryRequestedAction {
+                operation: SketchGeometryOperation::CenterRectangle,
+            }
+            .into(),
+        );
+    };
+
+    let dispatch_polygon = dispatch.clone();
+    let polygon_on_click = move |_ev: leptos::web_sys::MouseEvent| {
+        dispatch_polygon(
+            SketchGeometryRequestedAction {
+                operation: SketchGeometryOperation::Polygon {
+                    sides: DEFAULT_POLYGON_SIDES,
+                },
+            }
+            .into(),
+        );
+    };
+
+    let dispatch_slot = dispatch.clone();
+    let slot_on_click = move |_ev: leptos::web_sys::MouseEvent| {
+        dispatch_slot(
+            SketchGeometryRequestedAction {
+                operation: SketchGeometryOperation::Slot,
+            }
+            .into(),
+        );
+    };
+
     view! {
         <div class="flex flex-row gap-2 p-2">
             <ToolButton
@@ -55,6 +90,21 @@ pub fn SketchToolbar() -> impl IntoView {
                 label="Rectangle"
                 on_click=Callback::new(rect_on_click)
             />
+            <ToolButton
+                icon=IconType::SketchRectangle(IconSize::Medium)
+                label="Center Rectangle"
+                on_click=Callback::new(center_rect_on_click)
+            />
+            <ToolButton
+                icon=IconType::SketchRectangle(IconSize::Medium)
+                label="Polygon"
+                on_click=Callback::new(polygon_on_click)
+            />
+            <ToolButton
+                icon=IconType::SketchCircle(IconSize::Medium)
+                label="Slot"
+                on_click=Callback::new(slot_on_click)
+            />
         </div>
     }
 }